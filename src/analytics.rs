@@ -0,0 +1,262 @@
+//! Engagement analytics over email logs and their events.
+//!
+//! An `EngagementReport` aggregates how many emails sent within a time range
+//! were opened, clicked, or bounced, in total and per template or tag. Build
+//! one from the API with `Api::engagement_report`, or record logs and events
+//! you already have with `EngagementReport::record`.
+
+use crate::models::{EmailLog, EventType, LogEvent};
+use std::collections::BTreeMap;
+
+/// Engagement counts for a group of emails.
+///
+/// Each email is counted at most once per outcome, no matter how often it
+/// was opened or clicked.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EngagementStats {
+  /// Number of emails sent
+  pub sent: u64,
+
+  /// Number of emails opened at least once
+  pub opened: u64,
+
+  /// Number of emails with at least one click
+  pub clicked: u64,
+
+  /// Number of emails that bounced
+  pub bounced: u64,
+}
+
+impl EngagementStats {
+  /// Returns the share of sent emails that were opened, between 0 and 1.
+  pub fn open_rate(&self) -> f64 {
+    self.rate(self.opened)
+  }
+
+  /// Returns the share of sent emails that were clicked, between 0 and 1.
+  pub fn click_rate(&self) -> f64 {
+    self.rate(self.clicked)
+  }
+
+  /// Returns the share of sent emails that bounced, between 0 and 1.
+  pub fn bounce_rate(&self) -> f64 {
+    self.rate(self.bounced)
+  }
+
+  fn rate(&self, count: u64) -> f64 {
+    if self.sent == 0 {
+      0.0
+    } else {
+      count as f64 / self.sent as f64
+    }
+  }
+
+  fn add(&mut self, opened: bool, clicked: bool, bounced: bool) {
+    self.sent += 1;
+    self.opened += u64::from(opened);
+    self.clicked += u64::from(clicked);
+    self.bounced += u64::from(bounced);
+  }
+}
+
+/// Engagement summary for emails sent within a time range.
+///
+/// Only emails whose log was created within the range are counted, and only
+/// events within the range count towards them.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::analytics::EngagementReport;
+/// use send_with_us::models::{EmailLog, LogEvent};
+///
+/// let log: EmailLog = serde_json::from_str(r#"{
+///   "id": "log_abc123",
+///   "created": 1700000000,
+///   "recipient_address": "user@example.com",
+///   "status": "opened",
+///   "email_id": "tem_welcome"
+/// }"#).unwrap();
+/// let events: Vec<LogEvent> = serde_json::from_str(r#"[
+///   {"type": "sent", "created": 1700000000},
+///   {"type": "open", "created": 1700000600}
+/// ]"#).unwrap();
+///
+/// let mut report = EngagementReport::new(1699990000, 1700090000);
+/// report.record(&log, &events, &["onboarding".to_string()]);
+///
+/// assert_eq!(report.total().open_rate(), 1.0);
+/// assert_eq!(report.by_template()["tem_welcome"].opened, 1);
+/// assert_eq!(report.by_tag()["onboarding"].clicked, 0);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EngagementReport {
+  from: i64,
+  to: i64,
+  total: EngagementStats,
+  by_template: BTreeMap<String, EngagementStats>,
+  by_tag: BTreeMap<String, EngagementStats>,
+}
+
+impl EngagementReport {
+  /// Creates an empty report for a time range.
+  ///
+  /// # Arguments
+  /// * `from` - Start of the range (Unix timestamp, inclusive)
+  /// * `to` - End of the range (Unix timestamp, exclusive)
+  ///
+  /// # Returns
+  /// A new EngagementReport
+  pub fn new(from: i64, to: i64) -> Self {
+    Self {
+      from,
+      to,
+      ..Self::default()
+    }
+  }
+
+  /// Adds an email and its events to the report.
+  ///
+  /// Emails sent outside the report's range are ignored. The email is
+  /// grouped under its template ID, if the log has one, and under each of
+  /// `tags`. Email logs don't include the tags an email was sent with, so
+  /// pass the tags you sent it with to group by tag.
+  ///
+  /// # Arguments
+  /// * `log` - The email's log
+  /// * `events` - The log's events, as returned by `log_events`
+  /// * `tags` - Tags the email was sent with
+  pub fn record(&mut self, log: &EmailLog, events: &[LogEvent], tags: &[String]) {
+    if !(self.from..self.to).contains(&log.created) {
+      return;
+    }
+
+    let happened = |event_type: EventType| {
+      events
+        .iter()
+        .any(|event| event.event_type == event_type && event.happened_between(self.from, self.to))
+    };
+    let opened = happened(EventType::Opened);
+    let clicked = happened(EventType::Clicked);
+    let bounced = happened(EventType::Bounced);
+
+    self.total.add(opened, clicked, bounced);
+
+    if let Some(template) = &log.email_id {
+      self
+        .by_template
+        .entry(template.to_string())
+        .or_default()
+        .add(opened, clicked, bounced);
+    }
+
+    for tag in tags {
+      self
+        .by_tag
+        .entry(tag.clone())
+        .or_default()
+        .add(opened, clicked, bounced);
+    }
+  }
+
+  /// Returns the start of the report's range (Unix timestamp, inclusive).
+  pub fn from(&self) -> i64 {
+    self.from
+  }
+
+  /// Returns the end of the report's range (Unix timestamp, exclusive).
+  pub fn to(&self) -> i64 {
+    self.to
+  }
+
+  /// Returns the counts across all recorded emails.
+  pub fn total(&self) -> EngagementStats {
+    self.total
+  }
+
+  /// Returns the counts per template ID.
+  pub fn by_template(&self) -> &BTreeMap<String, EngagementStats> {
+    &self.by_template
+  }
+
+  /// Returns the counts per tag.
+  pub fn by_tag(&self) -> &BTreeMap<String, EngagementStats> {
+    &self.by_tag
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::ids::TemplateId;
+  use crate::testing::fixtures::EmailLogFixture;
+  use serde_json::json;
+
+  fn log(created: i64, template: Option<&str>) -> EmailLog {
+    let mut log = EmailLogFixture::new().with_created(created).build();
+    log.email_id = template.map(|template| TemplateId::new(template).unwrap());
+    log
+  }
+
+  fn events(events: serde_json::Value) -> Vec<LogEvent> {
+    serde_json::from_value(events).unwrap()
+  }
+
+  #[test]
+  fn test_report_groups_and_rates() {
+    let tags = vec!["weekly".to_string()];
+    let mut report = EngagementReport::new(1000, 2000);
+
+    report.record(
+      &log(1100, Some("tem_a")),
+      &events(json!([
+        {"type": "open", "created": 1200},
+        {"type": "open", "created": 1300},
+        {"type": "click", "created": 1400}
+      ])),
+      &tags,
+    );
+    report.record(
+      &log(1500, Some("tem_a")),
+      &events(json!([{"type": "bounce", "created": 1600}])),
+      &tags,
+    );
+    report.record(&log(1700, Some("tem_b")), &[], &[]);
+    report.record(&log(1800, None), &[], &[]);
+
+    let total = report.total();
+    assert_eq!(total.sent, 4);
+    assert_eq!(total.opened, 1);
+    assert_eq!(total.open_rate(), 0.25);
+    assert_eq!(total.bounce_rate(), 0.25);
+
+    let template = report.by_template()["tem_a"];
+    assert_eq!(template.sent, 2);
+    assert_eq!(template.click_rate(), 0.5);
+    assert_eq!(report.by_template()["tem_b"].open_rate(), 0.0);
+    assert_eq!(report.by_template().len(), 2);
+
+    assert_eq!(report.by_tag()["weekly"], template);
+  }
+
+  #[test]
+  fn test_report_ignores_emails_and_events_outside_range() {
+    let mut report = EngagementReport::new(1000, 2000);
+
+    report.record(&log(900, Some("tem_a")), &[], &[]);
+    report.record(
+      &log(1900, Some("tem_a")),
+      &events(json!([{"type": "open", "created": 2100}])),
+      &[],
+    );
+
+    assert_eq!(
+      report.total(),
+      EngagementStats {
+        sent: 1,
+        ..Default::default()
+      }
+    );
+    assert_eq!(EngagementStats::default().open_rate(), 0.0);
+  }
+}
@@ -1,14 +1,37 @@
 use async_trait::async_trait;
+use futures::stream::{self, BoxStream, StreamExt, TryStreamExt};
 use reqwest::{Client, StatusCode};
 use serde::{Serialize, de::DeserializeOwned};
 use serde_json::Value;
-
-use crate::config::Config;
-use crate::error::{Error, Result};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use url::Url;
+
+use crate::analytics::EngagementReport;
+use crate::capture::{self, CapturedExchange};
+use crate::circuit_breaker::CircuitBreaker;
+use crate::config::{Config, ORIGINAL_RECIPIENTS_HEADER, ORIGINAL_RECIPIENTS_KEY};
+use crate::dead_letter::DeadLetter;
+use crate::encoding::{encode_display_name, validate_display_name};
+use crate::endpoint::Endpoint;
+use crate::error::{Error, RequestContext, Result, SwuApiError};
+use crate::etag_cache::EtagCache;
+use crate::ids::{CampaignId, LogId, TemplateId};
+use crate::logging::{self, log_event};
+use crate::models::{
+  DripCampaignCustomer, DripCampaignCustomers, DripCampaignStep, EmailLog, EmailLogs, EventType,
+  LogEvent, SendResponse, Template, TemplateVersion, UnsubscribeStatus,
+};
+use crate::request::{API_KEY_HEADER, CORRELATION_ID_HEADER, PreparedRequest};
+use crate::response::{RawResponse, ResponseEnvelope};
+use crate::template_registry::TemplateRegistry;
 use crate::types::{
-  CustomerOptions, DripCampaignOptions, EmailOptions, Recipient, RenderOptions, Sender,
-  TemplateOptions,
+  CustomerEventOptions, CustomerOptions, CustomerUpdateOptions, DataUpdateMode,
+  DripCampaignOptions, EmailLogQuery, EmailOptions, Recipient, RenderOptions, Sender,
+  TemplateOptions, TemplateUpdateOptions,
 };
+use crate::version_diff::VersionDiff;
 
 /// SendWithUs API client for interacting with the SendWithUs email service.
 ///
@@ -28,10 +51,24 @@ use crate::types::{
 ///   .with_debug(true);
 /// let api = Api::new(config);
 /// ```
+///
+/// Cloning an `Api` is cheap: all clones share the same configuration and
+/// HTTP client, so a clone can be handed to each task that needs to send email.
 #[derive(Debug, Clone)]
 pub struct Api {
+  inner: Arc<ApiInner>,
+  timeout: Option<Duration>,
+  correlation_id: Option<String>,
+}
+
+/// State shared between all clones of an `Api`.
+#[derive(Debug)]
+struct ApiInner {
   config: Config,
   client: Client,
+  circuit_breaker: Option<CircuitBreaker>,
+  etags: Option<EtagCache>,
+  templates: TemplateRegistry,
 }
 
 /// API client trait defining all available SendWithUs operations.
@@ -40,17 +77,58 @@ pub struct Api {
 /// It's implemented by the `Api` struct and can be mocked for testing purposes.
 ///
 /// Each method corresponds to a specific API endpoint and operation in the SendWithUs service.
+///
+/// The trait is object safe, so a client can be shared as `Arc<dyn ApiClient>`.
+/// References, `Box`es, and `Arc`s of a client are clients too.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::{Api, ApiClient};
+/// use std::sync::Arc;
+///
+/// fn register(client: impl ApiClient + 'static) -> Arc<dyn ApiClient> {
+///   Arc::new(client)
+/// }
+///
+/// let shared = register(Api::with_api_key("api-key"));
+/// let _also_a_client: Box<dyn ApiClient> = Box::new(Arc::clone(&shared));
+/// ```
 #[async_trait]
 #[cfg(not(tarpaulin_include))]
-pub trait ApiClient {
+pub trait ApiClient: Send + Sync {
   /// Send an email using a template.
   ///
+  /// The options are checked with `EmailOptions::validate` first, so
+  /// malformed emails fail without a request being made.
+  ///
   /// # Arguments
   /// * `options` - Email sending options including template ID, recipient, data, and attachments
   ///
   /// # Returns
-  /// API response with send status and tracking information
-  async fn send_email(&self, options: EmailOptions) -> Result<Value>;
+  /// The send receipt, including the receipt ID used to track delivery
+  async fn send_email(&self, options: EmailOptions) -> Result<SendResponse>;
+
+  /// Applies the adjustments made to every email before it is sent.
+  ///
+  /// `Api` fills in the configured defaults, checks the options, and
+  /// redirects the recipients when interception is enabled. Use this to
+  /// deliver an email some other way, such as over SMTP, exactly as
+  /// `send_email` would have sent it. Clients without configuration only
+  /// check the options.
+  ///
+  /// # Arguments
+  /// * `options` - Email sending options
+  ///
+  /// # Returns
+  /// The options `send_email` would send
+  ///
+  /// # Errors
+  /// Returns an error if the options are invalid
+  fn prepare_email(&self, options: EmailOptions) -> Result<EmailOptions> {
+    options.validate()?;
+    Ok(options)
+  }
 
   /// List all available email templates.
   ///
@@ -92,7 +170,7 @@ pub trait ApiClient {
   /// API response with activation status
   async fn start_on_drip_campaign(
     &self,
-    campaign_id: &str,
+    campaign_id: &CampaignId,
     options: DripCampaignOptions,
   ) -> Result<Value>;
 
@@ -106,7 +184,7 @@ pub trait ApiClient {
   /// API response with deactivation status
   async fn remove_from_drip_campaign(
     &self,
-    campaign_id: &str,
+    campaign_id: &CampaignId,
     recipient_address: &str,
   ) -> Result<Value>;
 
@@ -117,7 +195,28 @@ pub trait ApiClient {
   ///
   /// # Returns
   /// API response with campaign details and statistics
-  async fn drip_campaign_details(&self, campaign_id: &str) -> Result<Value>;
+  async fn drip_campaign_details(&self, campaign_id: &CampaignId) -> Result<Value>;
+
+  /// List the steps of a drip campaign.
+  ///
+  /// # Arguments
+  /// * `campaign_id` - ID of the drip campaign
+  ///
+  /// # Returns
+  /// The campaign's steps in send order
+  async fn drip_campaign_steps(&self, campaign_id: &CampaignId) -> Result<Vec<DripCampaignStep>>;
+
+  /// List the customers currently active on a drip campaign.
+  ///
+  /// # Arguments
+  /// * `campaign_id` - ID of the drip campaign
+  ///
+  /// # Returns
+  /// The customers on the campaign along with their current step
+  async fn drip_campaign_customers(
+    &self,
+    campaign_id: &CampaignId,
+  ) -> Result<Vec<DripCampaignCustomer>>;
 
   /// Get customer details by email address.
   ///
@@ -137,6 +236,30 @@ pub trait ApiClient {
   /// API response with creation status
   async fn customer_create(&self, options: CustomerOptions) -> Result<Value>;
 
+  /// Update an existing customer record.
+  ///
+  /// Only the fields set in `options` are changed. With the default
+  /// `DataUpdateMode::Merge`, the current customer data is fetched first and
+  /// the provided keys are merged into it, so unrelated keys are preserved.
+  ///
+  /// The merge is a read followed by a write and is not atomic: if another
+  /// update to the same customer lands in between, the keys it changed are
+  /// overwritten with the values read before it. Serialize updates to a
+  /// customer, or use `DataUpdateMode::Replace` with the complete data, when
+  /// concurrent updates are possible.
+  ///
+  /// # Arguments
+  /// * `email` - Customer's email address
+  /// * `options` - Fields to update and how to apply the data map
+  ///
+  /// # Returns
+  /// API response with update status
+  ///
+  /// # Errors
+  /// Returns `Error::CustomerNotFound` when merging data into a customer
+  /// that doesn't exist
+  async fn customer_update(&self, email: &str, options: CustomerUpdateOptions) -> Result<Value>;
+
   /// Delete a customer record.
   ///
   /// # Arguments
@@ -146,23 +269,36 @@ pub trait ApiClient {
   /// API response with deletion status
   async fn customer_delete(&self, email: &str) -> Result<Value>;
 
+  /// Record a behavioral event for a customer.
+  ///
+  /// Events can be used to trigger drip campaigns and segment customers.
+  ///
+  /// # Arguments
+  /// * `email` - Customer's email address
+  /// * `options` - Event options including the event name and optional data
+  ///
+  /// # Returns
+  /// API response with the event status
+  async fn customer_add_event(&self, email: &str, options: CustomerEventOptions) -> Result<Value>;
+
   /// Get email logs for a specific customer.
   ///
   /// # Arguments
   /// * `email` - Customer's email address
-  /// * `count` - Optional maximum number of logs to return
-  /// * `created_gt` - Optional filter for logs created after this date
-  /// * `created_lt` - Optional filter for logs created before this date
+  /// * `query` - Filters such as count, offset, and creation time bounds
   ///
   /// # Returns
-  /// API response with email log history
-  async fn customer_email_log(
-    &self,
-    email: &str,
-    count: Option<u32>,
-    created_gt: Option<String>,
-    created_lt: Option<String>,
-  ) -> Result<Value>;
+  /// The customer's email logs, most recent first
+  async fn customer_email_log(&self, email: &str, query: EmailLogQuery) -> Result<Vec<EmailLog>>;
+
+  /// List email logs across the whole account.
+  ///
+  /// # Arguments
+  /// * `query` - Filters such as count, offset, and creation time bounds
+  ///
+  /// # Returns
+  /// The matching email logs
+  async fn logs(&self, query: EmailLogQuery) -> Result<Vec<EmailLog>>;
 
   /// Get details for a specific email log.
   ///
@@ -170,8 +306,8 @@ pub trait ApiClient {
   /// * `log_id` - Email log ID
   ///
   /// # Returns
-  /// API response with log details
-  async fn log(&self, log_id: &str) -> Result<Value>;
+  /// The email log
+  async fn log(&self, log_id: &LogId) -> Result<EmailLog>;
 
   /// Get events for a specific email log.
   ///
@@ -179,8 +315,31 @@ pub trait ApiClient {
   /// * `log_id` - Email log ID
   ///
   /// # Returns
-  /// API response with events (sent, opened, clicked, etc.)
-  async fn log_events(&self, log_id: &str) -> Result<Value>;
+  /// The events recorded for the email (sent, opened, clicked, etc.)
+  async fn log_events(&self, log_id: &LogId) -> Result<Vec<LogEvent>>;
+
+  /// Get a template's metadata.
+  ///
+  /// # Arguments
+  /// * `template_id` - Template ID
+  ///
+  /// # Returns
+  /// API response with the template name, tags, and versions
+  async fn get_template(&self, template_id: &TemplateId) -> Result<Value>;
+
+  /// Update a template's metadata without changing its versions.
+  ///
+  /// # Arguments
+  /// * `template_id` - Template ID
+  /// * `options` - The metadata fields to change
+  ///
+  /// # Returns
+  /// API response with the updated template
+  async fn update_template(
+    &self,
+    template_id: &TemplateId,
+    options: TemplateUpdateOptions,
+  ) -> Result<Value>;
 
   /// Delete an email template.
   ///
@@ -189,7 +348,7 @@ pub trait ApiClient {
   ///
   /// # Returns
   /// API response with deletion status
-  async fn delete_template(&self, template_id: &str) -> Result<Value>;
+  async fn delete_template(&self, template_id: &TemplateId) -> Result<Value>;
 
   /// List all versions of a template.
   ///
@@ -198,7 +357,7 @@ pub trait ApiClient {
   ///
   /// # Returns
   /// API response with version details
-  async fn list_template_versions(&self, template_id: &str) -> Result<Value>;
+  async fn list_template_versions(&self, template_id: &TemplateId) -> Result<Value>;
 
   /// Get a specific template version.
   ///
@@ -208,7 +367,8 @@ pub trait ApiClient {
   ///
   /// # Returns
   /// API response with version details and content
-  async fn get_template_version(&self, template_id: &str, version_id: &str) -> Result<Value>;
+  async fn get_template_version(&self, template_id: &TemplateId, version_id: &str)
+  -> Result<Value>;
 
   /// Update a template version.
   ///
@@ -221,7 +381,7 @@ pub trait ApiClient {
   /// API response with update status
   async fn update_template_version(
     &self,
-    template_id: &str,
+    template_id: &TemplateId,
     version_id: &str,
     options: TemplateOptions,
   ) -> Result<Value>;
@@ -236,7 +396,7 @@ pub trait ApiClient {
   /// API response with new version details
   async fn create_template_version(
     &self,
-    template_id: &str,
+    template_id: &TemplateId,
     options: TemplateOptions,
   ) -> Result<Value>;
 
@@ -248,6 +408,33 @@ pub trait ApiClient {
   /// # Returns
   /// API response with unsubscribe status
   async fn drips_unsubscribe(&self, email_address: &str) -> Result<Value>;
+
+  /// Get the unsubscribe status of an email address.
+  ///
+  /// # Arguments
+  /// * `email_address` - Email address to check
+  ///
+  /// # Returns
+  /// Whether the address is unsubscribed, and when
+  async fn unsubscribe_status(&self, email_address: &str) -> Result<UnsubscribeStatus>;
+
+  /// Unsubscribe an email address from all emails.
+  ///
+  /// # Arguments
+  /// * `email_address` - Email address to unsubscribe
+  ///
+  /// # Returns
+  /// API response with unsubscribe status
+  async fn unsubscribe(&self, email_address: &str) -> Result<Value>;
+
+  /// Remove an email address from the unsubscribe list.
+  ///
+  /// # Arguments
+  /// * `email_address` - Email address to resubscribe
+  ///
+  /// # Returns
+  /// API response with resubscribe status
+  async fn resubscribe(&self, email_address: &str) -> Result<Value>;
 }
 
 impl Api {
@@ -268,8 +455,68 @@ impl Api {
   /// let api = Api::new(config);
   /// ```
   pub fn new(config: Config) -> Self {
-    let client = Client::new();
-    Self { config, client }
+    let mut builder = Client::builder();
+
+    if let Some(timeout) = config.connect_timeout {
+      builder = builder.connect_timeout(timeout);
+    }
+
+    if let Some(timeout) = config.read_timeout {
+      builder = builder.read_timeout(timeout);
+    }
+
+    #[cfg(any(feature = "native-tls", feature = "rustls"))]
+    for certificate in &config.root_certificates {
+      builder = builder.add_root_certificate(certificate.clone());
+    }
+
+    // Like `Client::new`, this only fails if the TLS backend can't be initialized.
+    let client = builder.build().expect("failed to build HTTP client");
+    Self::with_client(config, client)
+  }
+
+  /// Creates a new API client that sends requests through the given HTTP client.
+  ///
+  /// Use this to share a connection pool with the rest of your application or
+  /// to customize TLS, proxies, and other transport settings. The
+  /// `connect_timeout`, `read_timeout`, and `root_certificates` settings of
+  /// `config` only apply to clients built by `Api::new`; configure them on
+  /// `client` instead.
+  ///
+  /// # Arguments
+  /// * `config` - The SendWithUs API configuration
+  /// * `client` - The HTTP client used to send requests
+  ///
+  /// # Returns
+  /// A new Api instance using the provided client
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::{Api, Config};
+  /// use std::time::Duration;
+  ///
+  /// let client = reqwest::Client::builder()
+  ///   .pool_idle_timeout(Duration::from_secs(30))
+  ///   .build()
+  ///   .unwrap();
+  ///
+  /// let api = Api::with_client(Config::new("api-key"), client);
+  /// ```
+  pub fn with_client(config: Config, client: Client) -> Self {
+    let circuit_breaker = config.circuit_breaker.map(CircuitBreaker::new);
+    let etags = config.conditional_requests.then(EtagCache::default);
+    Self {
+      inner: Arc::new(ApiInner {
+        config,
+        client,
+        circuit_breaker,
+        etags,
+        templates: TemplateRegistry::default(),
+      }),
+      timeout: None,
+      correlation_id: None,
+    }
   }
 
   /// Creates a new API client with just an API key, using default configuration.
@@ -292,1112 +539,5197 @@ impl Api {
     Self::new(config)
   }
 
+  /// Returns a handle whose calls use a different deadline.
+  ///
+  /// The returned handle shares configuration and connections with `self`;
+  /// only calls made through it use the new deadline, overriding
+  /// `Config::timeout`. Calls that don't complete in time fail with
+  /// `Error::Timeout`.
+  ///
+  /// # Arguments
+  /// * `timeout` - Maximum duration of each call made through the returned handle
+  ///
+  /// # Returns
+  /// A new handle with the given deadline
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// use send_with_us::{Api, ApiClient};
+  /// use std::time::Duration;
+  ///
+  /// # async fn run() -> send_with_us::Result<()> {
+  /// let api = Api::with_api_key("api-key");
+  /// let templates = api
+  ///   .with_timeout(Duration::from_secs(2))
+  ///   .list_templates()
+  ///   .await?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn with_timeout(&self, timeout: Duration) -> Self {
+    Self {
+      timeout: Some(timeout),
+      ..self.clone()
+    }
+  }
+
+  /// Returns a handle that tags every call with the given correlation ID.
+  ///
+  /// The ID is sent in the `X-Correlation-ID` header and included in errors
+  /// and debug output, so a call can be traced across services. Calls made
+  /// through a handle without a correlation ID get a freshly generated UUID
+  /// each. The returned handle shares its configuration and connection pool
+  /// with `self`.
+  ///
+  /// # Arguments
+  /// * `correlation_id` - ID to send with each call made through the returned handle
+  ///
+  /// # Returns
+  /// A new handle with the given correlation ID
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// use send_with_us::{Api, ApiClient};
+  /// use send_with_us::types::{EmailOptions, Recipient};
+  ///
+  /// # async fn run() -> send_with_us::Result<()> {
+  /// let api = Api::with_api_key("api-key");
+  /// let options = EmailOptions::new("tem_123", Recipient::new("user@example.com"));
+  ///
+  /// if let Err(error) = api.with_correlation_id("order-1234").send_email(options).await {
+  ///   // Errors from the API name the correlation ID, e.g.
+  ///   // "SendWithUs API rejected POST send (correlation ID order-1234): ..."
+  ///   eprintln!("{}", error);
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn with_correlation_id(&self, correlation_id: impl Into<String>) -> Self {
+    Self {
+      correlation_id: Some(correlation_id.into()),
+      ..self.clone()
+    }
+  }
+
+  /// Returns the correlation ID for the next call: the handle's own, or a new UUID.
+  fn next_correlation_id(&self) -> String {
+    self
+      .correlation_id
+      .clone()
+      .unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
+  }
+
   /// Returns a reference to the API configuration.
   ///
   /// # Returns
   /// Reference to the current Config instance
   pub fn config(&self) -> &Config {
-    &self.config
+    &self.inner.config
   }
 
   /// Builds the full request URL for a given API endpoint.
   ///
+  /// Each segment of the endpoint is percent-encoded on its own, so values
+  /// such as email addresses stay in a single segment even if they contain
+  /// `/` or `?`.
+  ///
   /// # Arguments
-  /// * `endpoint` - The API endpoint path
+  /// * `endpoint` - The API endpoint
   ///
   /// # Returns
   /// The complete URL for the specified endpoint
   ///
   /// # Errors
   /// Returns an error if the base URL is not a valid API URL
-  fn build_url(&self, endpoint: &str) -> Result<String> {
-    let mut base = self.config.url.clone();
+  fn build_url(&self, endpoint: &Endpoint) -> Result<Url> {
+    self.build_url_on(&self.config().url, endpoint)
+  }
+
+  /// Builds the full request URL for an API endpoint on the given base URL.
+  fn build_url_on(&self, base: &Url, endpoint: &Endpoint) -> Result<Url> {
+    let config = self.config();
+    let mut base = base.clone();
 
     base
       .path_segments_mut()
       .map_err(|_| Error::InvalidApiUrl)?
+      .pop_if_empty()
       .push("api")
-      .push(&format!("v{}", self.config.api_version))
-      .push(endpoint);
+      .push(&config.api_version.path_segment()?)
+      .extend(endpoint.segments());
+
+    base.set_query(None);
+    if !endpoint.query().is_empty() {
+      base.query_pairs_mut().extend_pairs(endpoint.query());
+    }
 
-    Ok(base.to_string())
+    Ok(base)
   }
 
-  /// Makes an API request to the SendWithUs API.
+  /// Checks email options and applies configuration-wide adjustments before sending.
   ///
   /// # Arguments
-  /// * `method` - HTTP method (GET, POST, etc.)
-  /// * `endpoint` - API endpoint path
-  /// * `payload` - Optional JSON payload for the request
+  /// * `options` - The email options provided by the caller
   ///
   /// # Returns
-  /// Deserialized response from the API
-  ///
-  /// # Type Parameters
-  /// * `T` - Type of the request payload
-  /// * `R` - Type to deserialize the response into
+  /// The email options that should be sent to the API
   ///
   /// # Errors
-  /// Returns an error if the request fails, authentication is invalid, or the response cannot be deserialized
-  async fn request<T, R>(
-    &self,
-    method: reqwest::Method,
-    endpoint: &str,
-    payload: Option<&T>,
-  ) -> Result<R>
-  where
-    T: Serialize + ?Sized,
-    R: DeserializeOwned,
-  {
-    let url = self.build_url(endpoint)?;
+  /// Returns an error if the template ID is missing or a display name is invalid
+  fn prepare_email(&self, mut options: EmailOptions) -> Result<EmailOptions> {
+    if options.email_id.is_empty() {
+      return Err(Error::MissingTemplateId);
+    }
 
-    let mut request = self
-      .client
-      .request(method, &url)
-      .header("Content-Type", "application/json")
-      .header("X-SWU-API-KEY", &self.config.api_key)
-      .header("X-SWU-API-CLIENT", &self.config.client_stub);
+    let config = self.config();
 
-    if let Some(data) = payload {
-      request = request.json(data);
+    if options.sender.is_none() {
+      options.sender = config.default_sender.clone();
     }
 
-    if self.config.debug {
-      eprintln!("SendWithUs Request: {:?}", request);
+    if options.esp_account.is_none() {
+      options.esp_account = config.default_esp_account.clone();
     }
 
-    let response = request.send().await.map_err(|e| {
-      if e.is_connect() {
-        Error::ConnectionFailed
-      } else {
-        Error::RequestFailed(e)
-      }
-    })?;
-
-    let status = response.status();
-    let body = response.text().await?;
+    self.apply_default_locale(&mut options.locale);
 
-    if self.config.debug {
-      eprintln!("SendWithUs Response: {}", body);
+    if !config.global_tags.is_empty() {
+      let tags = options.tags.get_or_insert_with(Vec::new);
+      for tag in config.global_tags.iter().rev() {
+        if !tags.contains(tag) {
+          tags.insert(0, tag.clone());
+        }
+      }
     }
 
-    match status {
-      StatusCode::OK | StatusCode::CREATED | StatusCode::ACCEPTED => {
-        serde_json::from_str(&body).map_err(Error::SerializationFailed)
+    if !config.global_headers.is_empty() {
+      let headers = options.headers.get_or_insert_with(HashMap::new);
+      for (name, value) in &config.global_headers {
+        headers.entry(name.clone()).or_insert_with(|| value.clone());
       }
-      StatusCode::NOT_FOUND => Err(Error::InvalidEndpoint(endpoint.to_string())),
-      StatusCode::FORBIDDEN => Err(Error::InvalidCredentials),
-      StatusCode::BAD_REQUEST => Err(Error::InvalidRequest(body)),
-      _ => Err(Error::ApiError {
-        status: status.as_u16(),
-        message: body,
-      }),
     }
-  }
-}
 
-#[async_trait]
-#[cfg(not(tarpaulin_include))]
-impl ApiClient for Api {
-  /// Send an email
-  async fn send_email(&self, options: EmailOptions) -> Result<Value> {
-    if options.email_id.is_empty() {
-      return Err(Error::MissingTemplateId);
+    options.validate_with_limits(&config.attachment_limits)?;
+
+    if let Some(address) = &config.intercept_address {
+      intercept_recipients(&mut options, address);
     }
 
-    self
-      .request(reqwest::Method::POST, "send", Some(&options))
-      .await
-  }
+    let encode = config.encode_display_names;
+    let prepare_name = |name: &mut Option<String>| -> Result<()> {
+      if let Some(value) = name {
+        validate_display_name(value)?;
 
-  /// List all templates
-  async fn list_templates(&self) -> Result<Value> {
-    self
-      .request::<(), _>(reqwest::Method::GET, "emails", None)
-      .await
-  }
+        if encode {
+          *value = encode_display_name(value).into_owned();
+        }
+      }
 
-  /// Render a template
-  async fn render(&self, options: RenderOptions) -> Result<Value> {
-    self
-      .request(reqwest::Method::POST, "render", Some(&options))
-      .await
-  }
+      Ok(())
+    };
 
-  /// Create a new template
-  async fn create_template(&self, options: TemplateOptions) -> Result<Value> {
-    self
-      .request(reqwest::Method::POST, "emails", Some(&options))
-      .await
-  }
+    prepare_name(&mut options.recipient.name)?;
 
-  /// List all drip campaigns
-  async fn list_drip_campaigns(&self) -> Result<Value> {
-    self
-      .request::<(), _>(reqwest::Method::GET, "drip_campaigns", None)
-      .await
+    if let Some(sender) = options.sender.as_mut() {
+      prepare_name(&mut sender.name)?;
+    }
+
+    for recipient in options
+      .cc
+      .iter_mut()
+      .chain(options.bcc.iter_mut())
+      .flatten()
+    {
+      prepare_name(&mut recipient.name)?;
+    }
+
+    Ok(options)
   }
 
-  /// Start a recipient on a drip campaign
-  async fn start_on_drip_campaign(
-    &self,
-    campaign_id: &str,
-    options: DripCampaignOptions,
-  ) -> Result<Value> {
-    let endpoint = format!("drip_campaigns/{}/activate", campaign_id);
-    self
-      .request(reqwest::Method::POST, &endpoint, Some(&options))
-      .await
+  /// Fills in the configured default locale when no locale was given.
+  fn apply_default_locale(&self, locale: &mut Option<String>) {
+    if locale.is_none() {
+      *locale = self.config().default_locale.clone();
+    }
   }
 
-  /// Remove a recipient from a drip campaign
-  async fn remove_from_drip_campaign(
+  /// Builds the HTTP request for an API call without sending it.
+  ///
+  /// The returned request contains the real API key and must not be exposed
+  /// to callers without redacting it first.
+  ///
+  /// # Arguments
+  /// * `method` - HTTP method (GET, POST, etc.)
+  /// * `endpoint` - API endpoint path
+  /// * `payload` - Optional JSON payload for the request
+  ///
+  /// # Errors
+  /// Returns an error if the URL cannot be built or the payload cannot be serialized
+  fn prepare<T>(
     &self,
-    campaign_id: &str,
-    recipient_address: &str,
-  ) -> Result<Value> {
-    let endpoint = format!("drip_campaigns/{}/deactivate", campaign_id);
-    let payload = serde_json::json!({ "recipient_address": recipient_address });
-    self
-      .request(reqwest::Method::POST, &endpoint, Some(&payload))
-      .await
+    method: reqwest::Method,
+    endpoint: &Endpoint,
+    payload: Option<&T>,
+  ) -> Result<PreparedRequest>
+  where
+    T: Serialize + ?Sized,
+  {
+    let config = self.config();
+    let url = self.build_url(endpoint)?;
+
+    let headers = vec![
+      ("Content-Type".to_string(), "application/json".to_string()),
+      (API_KEY_HEADER.to_string(), config.api_key.clone()),
+      ("X-SWU-API-CLIENT".to_string(), config.client_stub.clone()),
+    ];
+
+    let body = payload.map(serde_json::to_string).transpose()?;
+
+    Ok(PreparedRequest {
+      method,
+      url,
+      headers,
+      body,
+    })
   }
 
-  /// Get drip campaign details
-  async fn drip_campaign_details(&self, campaign_id: &str) -> Result<Value> {
-    let endpoint = format!("drip_campaigns/{}", campaign_id);
-    self
-      .request::<(), _>(reqwest::Method::GET, &endpoint, None)
-      .await
+  /// Builds the exact HTTP request for an API call and returns it without sending.
+  ///
+  /// This is useful for security reviews, contract tests, and debugging payload
+  /// issues. The API key header is replaced with `[REDACTED]`.
+  ///
+  /// # Arguments
+  /// * `method` - HTTP method (GET, POST, etc.)
+  /// * `endpoint` - API endpoint path, relative to the versioned API root
+  /// * `payload` - Optional JSON payload for the request
+  ///
+  /// # Returns
+  /// The prepared request with the API key redacted
+  ///
+  /// # Errors
+  /// Returns an error if the URL cannot be built or the payload cannot be serialized
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::Api;
+  ///
+  /// let api = Api::with_api_key("your-api-key");
+  /// let prepared = api
+  ///   .simulate::<()>(reqwest::Method::GET, "drip_campaigns/dc_123", None)
+  ///   .unwrap();
+  ///
+  /// assert_eq!(
+  ///   prepared.url.as_str(),
+  ///   "https://api.sendwithus.com/api/v1/drip_campaigns/dc_123"
+  /// );
+  /// ```
+  pub fn simulate<T>(
+    &self,
+    method: reqwest::Method,
+    endpoint: &str,
+    payload: Option<&T>,
+  ) -> Result<PreparedRequest>
+  where
+    T: Serialize + ?Sized,
+  {
+    Ok(
+      self
+        .prepare(method, &Endpoint::parse(endpoint), payload)?
+        .redacted(),
+    )
   }
 
-  /// Get customer details
-  async fn customer_get(&self, email: &str) -> Result<Value> {
-    let endpoint = format!("customers/{}", email);
-    self
-      .request::<(), _>(reqwest::Method::GET, &endpoint, None)
-      .await
+  /// Builds the exact request `send_email` would make, without sending it.
+  ///
+  /// The options go through the same checks and adjustments as `send_email`,
+  /// so the body is precisely what would be transmitted.
+  ///
+  /// # Arguments
+  /// * `options` - Email sending options
+  ///
+  /// # Returns
+  /// The prepared request with the API key redacted
+  ///
+  /// # Errors
+  /// Returns an error if the options are invalid or cannot be serialized
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::Api;
+  /// use send_with_us::types::{EmailOptions, Recipient};
+  ///
+  /// let api = Api::with_api_key("your-api-key");
+  /// let options = EmailOptions::new("tem_123", Recipient::new("user@example.com"));
+  ///
+  /// let prepared = api.simulate_send_email(options).unwrap();
+  /// assert_eq!(prepared.body_json().unwrap()["email_id"], "tem_123");
+  /// ```
+  pub fn simulate_send_email(&self, options: EmailOptions) -> Result<PreparedRequest> {
+    let options = self.prepare_email(options)?;
+    self.simulate(reqwest::Method::POST, "send", Some(&options))
   }
 
-  /// Create a new customer
-  async fn customer_create(&self, options: CustomerOptions) -> Result<Value> {
-    self
-      .request(reqwest::Method::POST, "customers", Some(&options))
-      .await
+  /// Sends a prepared request and returns the raw response.
+  ///
+  /// With the `otel` feature, the call is recorded as an OpenTelemetry span
+  /// and the trace context is propagated in the request headers.
+  ///
+  /// # Errors
+  /// Returns an error if the request cannot be sent or the body cannot be read
+  async fn execute(&self, prepared: PreparedRequest) -> Result<RawResponse> {
+    #[cfg(feature = "otel")]
+    let (prepared, cx) = crate::telemetry::start_span(prepared);
+
+    let captured = self
+      .config()
+      .capture_session
+      .as_ref()
+      .map(|session| (session, prepared.clone(), SystemTime::now(), Instant::now()));
+
+    let result = self.dispatch(prepared).await;
+
+    #[cfg(feature = "otel")]
+    crate::telemetry::end_span(&cx, &result);
+
+    if let Some((session, prepared, started_at, started)) = captured {
+      let exchange = CapturedExchange::new(
+        &prepared,
+        &result,
+        started_at,
+        started.elapsed(),
+        &self.config().redacted_data_keys,
+      );
+
+      if let Err(capture_error) = session.record(exchange).await {
+        log_event!(
+          self.config(),
+          error,
+          "SendWithUs: failed to record captured request: {capture_error}"
+        );
+      }
+    }
+
+    result
   }
 
-  /// Delete a customer
-  async fn customer_delete(&self, email: &str) -> Result<Value> {
-    let endpoint = format!("customers/{}", email);
-    self
-      .request::<(), _>(reqwest::Method::DELETE, &endpoint, None)
-      .await
+  /// Fails a prepared request with an injected fault, answers it from the
+  /// configured cassette, or sends it.
+  #[cfg(feature = "mock")]
+  async fn dispatch(&self, prepared: PreparedRequest) -> Result<RawResponse> {
+    let injected = match &self.config().fault_injector {
+      Some(injector) => injector.inject().await,
+      None => None,
+    };
+    if let Some(injected) = injected {
+      return injected;
+    }
+
+    match &self.config().cassette {
+      Some(cassette) => {
+        cassette
+          .play(prepared, |prepared| self.send_prepared(prepared))
+          .await
+      }
+      None => self.send_prepared(prepared).await,
+    }
   }
 
-  /// Get customer email logs
-  async fn customer_email_log(
-    &self,
-    email: &str,
-    count: Option<u32>,
-    created_gt: Option<String>,
-    created_lt: Option<String>,
-  ) -> Result<Value> {
-    let mut params = Vec::new();
+  #[cfg(not(feature = "mock"))]
+  async fn dispatch(&self, prepared: PreparedRequest) -> Result<RawResponse> {
+    self.send_prepared(prepared).await
+  }
+
+  /// Sends a prepared request over HTTP and reads the full response.
+  async fn send_prepared(&self, prepared: PreparedRequest) -> Result<RawResponse> {
+    let sanitized = self
+      .config()
+      .debug
+      .then(|| prepared.sanitized(&self.config().redacted_data_keys));
+
+    if let Some(sanitized) = &sanitized {
+      log_event!(
+        self.config(),
+        debug,
+        "{}",
+        logging::format_request(sanitized)
+      );
+    }
 
-    if let Some(count) = count {
-      params.push(format!("count={}", count));
+    let mut request = self.inner.client.request(prepared.method, prepared.url);
+
+    if let Some(timeout) = self.timeout.or(self.config().timeout) {
+      request = request.timeout(timeout);
     }
 
-    if let Some(created_gt) = created_gt {
-      params.push(format!("created_gt={}", created_gt));
+    for (name, value) in prepared.headers {
+      request = request.header(name, value);
     }
 
-    if let Some(created_lt) = created_lt {
-      params.push(format!("created_lt={}", created_lt));
+    if let Some(body) = prepared.body {
+      request = request.body(body);
     }
 
-    let query_string = if !params.is_empty() {
-      format!("?{}", params.join("&"))
-    } else {
-      String::new()
+    let started = Instant::now();
+    let response = request.send().await.map_err(transport_error)?;
+
+    let status = response.status();
+    let headers = response.headers().clone();
+    let body = response.bytes().await.map_err(transport_error)?.to_vec();
+    let duration = started.elapsed();
+
+    let raw = RawResponse {
+      status,
+      headers,
+      body,
+      duration,
     };
 
-    let endpoint = format!("customers/{}/logs{}", email, query_string);
-    self
-      .request::<(), _>(reqwest::Method::GET, &endpoint, None)
-      .await
-  }
+    if let Some(sanitized) = &sanitized {
+      log_event!(
+        self.config(),
+        debug,
+        "{}",
+        logging::format_response(sanitized, &raw, &self.config().redacted_data_keys)
+      );
+    }
 
-  /// Get email log
-  async fn log(&self, log_id: &str) -> Result<Value> {
-    let endpoint = format!("logs/{}", log_id);
-    self
-      .request::<(), _>(reqwest::Method::GET, &endpoint, None)
-      .await
+    Ok(raw)
   }
 
-  /// Get email log events
-  async fn log_events(&self, log_id: &str) -> Result<Value> {
-    let endpoint = format!("logs/{}/events", log_id);
-    self
-      .request::<(), _>(reqwest::Method::GET, &endpoint, None)
-      .await
+  /// Sends a prepared request, retrying against the fallback URL if the primary can't be reached.
+  ///
+  /// # Arguments
+  /// * `prepared` - The request to send
+  /// * `endpoint` - The API endpoint path the request was prepared for
+  ///
+  /// # Errors
+  /// Returns an error if the request cannot be sent or the body cannot be read
+  async fn execute_with_failover(
+    &self,
+    prepared: PreparedRequest,
+    endpoint: &Endpoint,
+  ) -> Result<RawResponse> {
+    let fallback = self
+      .config()
+      .fallback_url
+      .as_ref()
+      .map(|fallback_url| (prepared.clone(), fallback_url));
+
+    match (self.execute(prepared).await, fallback) {
+      (Err(Error::ConnectionFailed { .. }), Some((mut prepared, fallback_url))) => {
+        log_event!(
+          self.config(),
+          warn,
+          "SendWithUs: primary URL unreachable, retrying against {fallback_url}"
+        );
+
+        prepared.url = self.build_url_on(fallback_url, endpoint)?;
+        self.execute(prepared).await
+      }
+      (result, _) => result,
+    }
   }
 
-  /// Delete a template
-  async fn delete_template(&self, template_id: &str) -> Result<Value> {
-    let endpoint = format!("templates/{}", template_id);
-    self
-      .request::<(), _>(reqwest::Method::DELETE, &endpoint, None)
-      .await
-  }
+  /// Makes an API request and returns the deserialized response with its metadata.
+  ///
+  /// # Arguments
+  /// * `method` - HTTP method (GET, POST, etc.)
+  /// * `endpoint` - API endpoint path
+  /// * `payload` - Optional JSON payload for the request
+  ///
+  /// # Returns
+  /// The deserialized response wrapped in a `ResponseEnvelope`
+  ///
+  /// # Errors
+  /// Returns an error if the request fails, authentication is invalid, or the response cannot be deserialized
+  async fn request_envelope<T, R>(
+    &self,
+    method: reqwest::Method,
+    endpoint: &Endpoint,
+    payload: Option<&T>,
+  ) -> Result<ResponseEnvelope<R>>
+  where
+    T: Serialize + ?Sized,
+    R: DeserializeOwned,
+  {
+    let mut prepared = self.prepare(method.clone(), endpoint, payload)?;
+    let correlation_id = self.next_correlation_id();
+    prepared
+      .headers
+      .push((CORRELATION_ID_HEADER.to_string(), correlation_id.clone()));
+    let context =
+      RequestContext::new(method.clone(), endpoint.to_string()).with_correlation_id(correlation_id);
+
+    let retry_policy = self.config().retry_policy;
+    let idempotent = !(method == reqwest::Method::POST && endpoint.segments() == ["send"]);
+    let deadline = retry_policy
+      .and_then(|policy| policy.budget)
+      .map(|budget| Instant::now() + budget);
+    let mut attempt = 1;
+    let mut rate_limited_for = Duration::ZERO;
+
+    let result = loop {
+      let call = self.attempt(&context, endpoint, prepared.clone());
+      let result = match deadline {
+        Some(deadline) => tokio::time::timeout_at(deadline.into(), call)
+          .await
+          .unwrap_or(Err(Error::Timeout { context: None })),
+        None => call.await,
+      };
+
+      if let Some(wait) =
+        rate_limit_wait(&result, self.config().rate_limit_max_wait, rate_limited_for)
+      {
+        if deadline.is_some_and(|deadline| Instant::now() + wait >= deadline) {
+          break result;
+        }
 
-  /// List template versions
-  async fn list_template_versions(&self, template_id: &str) -> Result<Value> {
-    let endpoint = format!("templates/{}/versions", template_id);
-    self
-      .request::<(), _>(reqwest::Method::GET, &endpoint, None)
-      .await
-  }
+        log_event!(
+          self.config(),
+          info,
+          "SendWithUs: rate limited on {context}, retrying in {wait:?}"
+        );
 
-  /// Get template version
-  async fn get_template_version(&self, template_id: &str, version_id: &str) -> Result<Value> {
-    let endpoint = format!("templates/{}/versions/{}", template_id, version_id);
-    self
-      .request::<(), _>(reqwest::Method::GET, &endpoint, None)
-      .await
-  }
+        tokio::time::sleep(wait).await;
+        rate_limited_for += wait;
+        continue;
+      }
 
-  /// Update template version
-  async fn update_template_version(
-    &self,
-    template_id: &str,
-    version_id: &str,
-    options: TemplateOptions,
-  ) -> Result<Value> {
-    let endpoint = format!("templates/{}/versions/{}", template_id, version_id);
-    self
-      .request(reqwest::Method::PUT, &endpoint, Some(&options))
-      .await
+      match (&result, retry_policy) {
+        (Err(error), Some(policy))
+          if attempt < policy.max_attempts && policy.should_retry(error, idempotent) =>
+        {
+          let backoff = policy.backoff(attempt);
+
+          if deadline.is_some_and(|deadline| Instant::now() + backoff >= deadline) {
+            break result;
+          }
+
+          log_event!(
+            self.config(),
+            info,
+            "SendWithUs: attempt {attempt} on {context} failed ({error}), retrying"
+          );
+
+          tokio::time::sleep(backoff).await;
+          attempt += 1;
+        }
+        _ => break result,
+      }
+    };
+
+    result.map_err(|error| error.with_context(&context))
   }
 
-  /// Create template version
-  async fn create_template_version(
+  /// Makes a single attempt at an API request, honoring the circuit breaker.
+  ///
+  /// With conditional requests enabled, cached template and drip campaign
+  /// reads are revalidated with `If-None-Match`.
+  async fn attempt<R: DeserializeOwned>(
     &self,
-    template_id: &str,
-    options: TemplateOptions,
-  ) -> Result<Value> {
-    let endpoint = format!("templates/{}/versions", template_id);
-    self
-      .request(reqwest::Method::POST, &endpoint, Some(&options))
-      .await
-  }
+    context: &RequestContext,
+    endpoint: &Endpoint,
+    mut prepared: PreparedRequest,
+  ) -> Result<ResponseEnvelope<R>> {
+    let permit = self
+      .inner
+      .circuit_breaker
+      .as_ref()
+      .map(CircuitBreaker::check)
+      .transpose()?;
+
+    let etags = self
+      .inner
+      .etags
+      .as_ref()
+      .filter(|_| EtagCache::applies_to(&context.method, &context.endpoint));
+    let url = prepared.url.to_string();
+
+    if let Some(etag) = etags.and_then(|etags| etags.etag(&url)) {
+      prepared.headers.push(("If-None-Match".to_string(), etag));
+    }
 
-  /// Unsubscribe from drips
-  async fn drips_unsubscribe(&self, email_address: &str) -> Result<Value> {
-    if email_address.is_empty() {
-      return Err(Error::MissingRecipientAddress);
+    let raw = self.execute_with_failover(prepared, endpoint).await;
+
+    #[cfg(feature = "logging")]
+    crate::logging::log_call(context, &raw);
+
+    let raw = match etags {
+      Some(etags) => raw.map(|raw| etags.resolve(&url, raw)),
+      None => raw,
+    };
+
+    let result = raw.and_then(|raw| map_response(context.clone(), raw));
+
+    if let Some(permit) = permit {
+      permit.record(&result);
     }
 
-    let payload = serde_json::json!({ "email_address": email_address });
+    result
+  }
+
+  /// Makes an API request to the SendWithUs API.
+  ///
+  /// # Arguments
+  /// * `method` - HTTP method (GET, POST, etc.)
+  /// * `endpoint` - API endpoint path
+  /// * `payload` - Optional JSON payload for the request
+  ///
+  /// # Returns
+  /// Deserialized response from the API
+  ///
+  /// # Type Parameters
+  /// * `T` - Type of the request payload
+  /// * `R` - Type to deserialize the response into
+  ///
+  /// # Errors
+  /// Returns an error if the request fails, authentication is invalid, or the response cannot be deserialized
+  async fn request<T, R>(
+    &self,
+    method: reqwest::Method,
+    endpoint: &Endpoint,
+    payload: Option<&T>,
+  ) -> Result<R>
+  where
+    T: Serialize + ?Sized,
+    R: DeserializeOwned,
+  {
     self
-      .request(reqwest::Method::POST, "drips/unsubscribe", Some(&payload))
+      .request_envelope(method, endpoint, payload)
       .await
+      .map(ResponseEnvelope::into_inner)
   }
-}
-
-/// Helper functions to build email options more easily.
-///
-/// This module contains utility functions that simplify the creation of common
-/// email components such as email data, recipients, and senders.
-pub mod helpers {
-  use super::*;
-  use serde_json::Value;
-  use std::collections::HashMap;
 
-  /// Creates a HashMap of email template data from key-value pairs.
+  /// Makes an API request and returns the response together with its HTTP metadata.
   ///
-  /// This helper function simplifies the creation of template data for emails.
-  /// It accepts any iterable collection of key-value pairs and converts them into
-  /// the required HashMap format for email template data.
+  /// This is the metadata-returning counterpart of the typed endpoint
+  /// methods: the envelope carries the HTTP status, headers, request
+  /// duration, and the SendWithUs request ID when present.
   ///
   /// # Arguments
-  /// * `pairs` - An iterable of key-value pairs where keys can be converted to String
-  ///   and values can be converted to serde_json::Value
+  /// * `method` - HTTP method (GET, POST, etc.)
+  /// * `endpoint` - API endpoint path, relative to the versioned API root
+  /// * `payload` - Optional JSON payload for the request
   ///
   /// # Returns
-  /// A HashMap with string keys and JSON values
+  /// The deserialized response wrapped in a `ResponseEnvelope`
+  ///
+  /// # Errors
+  /// Returns an error if the request fails, authentication is invalid, or the response cannot be deserialized
   ///
   /// # Examples
   ///
-  /// ```
-  /// use send_with_us::{ApiClient, api::helpers};
-  /// use serde_json::json;
+  /// ```no_run
+  /// use send_with_us::Api;
+  /// use serde_json::Value;
   ///
-  /// let data = helpers::email_data([
-  ///   ("name", json!("John Doe")),
-  ///   ("order_id", json!("12345")),
-  ///   ("items", json!(["item1", "item2"]))
-  /// ]);
+  /// # async fn run() -> send_with_us::Result<()> {
+  /// let api = Api::with_api_key("api-key");
+  /// let envelope = api
+  ///   .request_with_metadata::<(), Value>(reqwest::Method::GET, "emails", None)
+  ///   .await?;
   ///
-  /// assert_eq!(data["name"], "John Doe");
-  /// assert_eq!(data["order_id"], "12345");
+  /// println!("{} in {:?}", envelope.status, envelope.duration);
+  /// # Ok(())
+  /// # }
   /// ```
-  pub fn email_data<K, V>(pairs: impl IntoIterator<Item = (K, V)>) -> HashMap<String, Value>
+  pub async fn request_with_metadata<T, R>(
+    &self,
+    method: reqwest::Method,
+    endpoint: &str,
+    payload: Option<&T>,
+  ) -> Result<ResponseEnvelope<R>>
   where
-    K: Into<String>,
-    V: Into<Value>,
+    T: Serialize + ?Sized,
+    R: DeserializeOwned,
   {
-    pairs
-      .into_iter()
-      .map(|(k, v)| (k.into(), v.into()))
-      .collect()
+    self
+      .request_envelope(method, &Endpoint::parse(endpoint), payload)
+      .await
   }
 
-  /// Creates a Recipient with an email address and optional name.
+  /// Makes an API request and returns the raw response without interpreting it.
+  ///
+  /// This is an escape hatch for SendWithUs endpoints the crate doesn't
+  /// cover yet. Authentication, the base URL, and the API version are applied
+  /// as for every other call, but the status code is not mapped to an error
+  /// and the body is not deserialized.
   ///
   /// # Arguments
-  /// * `email` - The recipient's email address
-  /// * `name` - Optional recipient name
+  /// * `method` - HTTP method (GET, POST, etc.)
+  /// * `endpoint` - API endpoint path, relative to the versioned API root
+  /// * `payload` - Optional JSON payload for the request
   ///
   /// # Returns
-  /// A configured Recipient instance
-  ///
-  /// # Examples
+  /// The raw status, headers, and body of the response
   ///
-  /// ```
-  /// use send_with_us::api::helpers;
-  ///
-  /// let recipient = helpers::recipient("user@example.com", None::<&str>);
+  /// # Errors
+  /// Returns an error only if the request cannot be built or sent
+  pub async fn request_raw<T>(
+    &self,
+    method: reqwest::Method,
+    endpoint: &str,
+    payload: Option<&T>,
+  ) -> Result<RawResponse>
+  where
+    T: Serialize + ?Sized,
+  {
+    let endpoint = Endpoint::parse(endpoint);
+    let mut prepared = self.prepare(method.clone(), &endpoint, payload)?;
+    let correlation_id = self.next_correlation_id();
+    prepared
+      .headers
+      .push((CORRELATION_ID_HEADER.to_string(), correlation_id.clone()));
+    let context =
+      RequestContext::new(method, endpoint.to_string()).with_correlation_id(correlation_id);
+
+    self
+      .execute_with_failover(prepared, &endpoint)
+      .await
+      .map_err(|error| error.with_context(&context))
+  }
+
+  /// Sends an email and returns the receipt together with its HTTP metadata.
   ///
-  /// let recipient = helpers::recipient("user@example.com", Some("John Doe"));
-  /// ```
-  pub fn recipient(email: impl Into<String>, name: Option<impl Into<String>>) -> Recipient {
-    let mut recipient = Recipient::new(email);
-    if let Some(name) = name {
-      recipient = recipient.with_name(name);
+  /// # Arguments
+  /// * `options` - Email options including template, recipient, and data
+  ///
+  /// # Returns
+  /// The send receipt wrapped in a `ResponseEnvelope`
+  ///
+  /// # Errors
+  /// Returns the same errors as `ApiClient::send_email`
+  pub async fn send_email_with_metadata(
+    &self,
+    options: EmailOptions,
+  ) -> Result<ResponseEnvelope<SendResponse>> {
+    let options = self.prepare_email(options)?;
+    let first_attempted_at = unix_timestamp();
+
+    let result = self
+      .request_envelope(
+        reqwest::Method::POST,
+        &Endpoint::new(["send"]),
+        Some(&options),
+      )
+      .await;
+
+    let retries_exhausted = match &result {
+      Err(error) if error.is_retryable() => Some(error),
+      _ => None,
+    };
+
+    if let (Some(error), Some(sink)) = (retries_exhausted, &self.config().dead_letter_sink) {
+      let letter = DeadLetter::new(options, error.to_string(), first_attempted_at)
+        .with_may_have_been_sent(error.may_have_been_processed());
+
+      if let Err(capture_error) = sink.capture(letter).await {
+        log_event!(
+          self.config(),
+          error,
+          "SendWithUs: failed to capture dead letter: {capture_error}"
+        );
+      }
     }
-    recipient
+
+    result
   }
 
-  /// Creates a Sender with an email address and optional name and reply-to address.
+  /// Sends an email using the template with the given name.
+  ///
+  /// Template IDs are looked up by name in a registry shared by all clones
+  /// of this client. The registry is filled from `list_templates` on first
+  /// use and refreshed whenever a name isn't found, so templates created
+  /// later are picked up.
   ///
   /// # Arguments
-  /// * `email` - The sender's email address
-  /// * `name` - Optional sender name
-  /// * `reply_to` - Optional reply-to email address
+  /// * `template_name` - Name of the template, as shown in the dashboard
+  /// * `options` - Email options; their template ID is replaced and may be left empty
   ///
   /// # Returns
-  /// A configured Sender instance
+  /// The send receipt
   ///
-  /// # Examples
+  /// # Errors
+  /// Returns `Error::TemplateNotFound` if no template has the name, or the
+  /// same errors as `ApiClient::send_email`
   ///
-  /// ```
-  /// use send_with_us::api::helpers;
+  /// # Examples
   ///
-  /// let sender = helpers::sender("support@company.com", None::<&str>, None::<&str>);
+  /// ```no_run
+  /// use send_with_us::Api;
+  /// use send_with_us::types::{EmailOptions, Recipient};
   ///
-  /// let sender = helpers::sender("support@company.com", Some("Support Team"), None::<&str>);
+  /// # async fn run() -> send_with_us::Result<()> {
+  /// let api = Api::with_api_key("api-key");
+  /// let options = EmailOptions::new("", Recipient::new("user@example.com"));
   ///
-  /// let sender = helpers::sender(
-  ///   "noreply@company.com",
-  ///   Some("Company Name"),
-  ///   Some("support@company.com")
-  /// );
+  /// api.send_email_by_name("Welcome Email", options).await?;
+  /// # Ok(())
+  /// # }
   /// ```
-  pub fn sender(
-    email: impl Into<String>,
-    name: Option<impl Into<String>>,
-    reply_to: Option<impl Into<String>>,
-  ) -> Sender {
-    let mut sender = Sender::new(email);
+  pub async fn send_email_by_name(
+    &self,
+    template_name: &str,
+    mut options: EmailOptions,
+  ) -> Result<SendResponse> {
+    options.email_id = self.template_id(template_name).await?.to_string();
+    self.send_email(options).await
+  }
 
-    if let Some(name) = name {
-      sender = sender.with_name(name);
+  /// Looks up a template's ID by name.
+  ///
+  /// Uses the same registry as `send_email_by_name`, refreshing it from
+  /// `list_templates` if the name isn't known. If several templates share a
+  /// name, the first one listed is used.
+  ///
+  /// # Arguments
+  /// * `template_name` - Name of the template
+  ///
+  /// # Returns
+  /// The template's ID
+  ///
+  /// # Errors
+  /// Returns `Error::TemplateNotFound` if no template has the name, or an
+  /// error if the templates cannot be listed
+  pub async fn template_id(&self, template_name: &str) -> Result<TemplateId> {
+    if let Some(id) = self.inner.templates.get(template_name) {
+      return Ok(id);
     }
 
-    if let Some(reply_to) = reply_to {
-      sender = sender.with_reply_to(reply_to);
-    }
+    let templates: Vec<Template> = self.list_templates_as().await?;
+    self.inner.templates.replace(templates);
 
-    sender
+    self
+      .inner
+      .templates
+      .get(template_name)
+      .ok_or_else(|| Error::TemplateNotFound(template_name.to_string()))
   }
-}
-
-#[cfg(test)]
-mod tests {
-  use super::*;
-  use std::collections::HashMap;
 
-  struct MockApiClient;
+  /// Fetches two versions of a template and compares their content.
+  ///
+  /// # Arguments
+  /// * `template_id` - Template the versions belong to
+  /// * `old_version_id` - Version to compare from
+  /// * `new_version_id` - Version to compare to
+  ///
+  /// # Returns
+  /// The subject, HTML, and text fields that differ
+  ///
+  /// # Errors
+  /// Returns an error if either version cannot be fetched
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// use send_with_us::Api;
+  /// use send_with_us::ids::TemplateId;
+  ///
+  /// # async fn run() -> send_with_us::Result<()> {
+  /// let api = Api::with_api_key("api-key");
+  /// let template_id = TemplateId::new("tem_welcome")?;
+  ///
+  /// let diff = api.diff_versions(&template_id, "ver_1", "ver_2").await?;
+  /// print!("{}", diff.unified());
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn diff_versions(
+    &self,
+    template_id: &TemplateId,
+    old_version_id: &str,
+    new_version_id: &str,
+  ) -> Result<VersionDiff> {
+    let (old, new): (TemplateVersion, TemplateVersion) = futures::future::try_join(
+      self.get_template_version_as(template_id, old_version_id),
+      self.get_template_version_as(template_id, new_version_id),
+    )
+    .await?;
+
+    Ok(VersionDiff::new(&old, &new))
+  }
 
-  #[async_trait]
-  impl ApiClient for MockApiClient {
-    async fn send_email(&self, options: EmailOptions) -> Result<Value> {
-      if options.email_id.is_empty() {
-        return Err(Error::MissingTemplateId);
-      }
-      Ok(serde_json::json!({"success": true}))
-    }
+  /// Sends the emails captured by the configured dead-letter sink again.
+  ///
+  /// Letters are removed from the sink once they are sent, or once the API
+  /// rejects them outright. Letters that fail again with a retryable error are
+  /// kept for a later replay.
+  ///
+  /// Letters whose email may already have been sent (`may_have_been_sent`)
+  /// are not replayed, since SendWithUs may have delivered them despite the
+  /// error. They stay in the sink and are reported with
+  /// `Error::SendOutcomeUnknown`; check the email's logs, then remove them
+  /// or replay them with `replay_dead_letters_including_unknown`.
+  ///
+  /// # Returns
+  /// The ID and outcome of each letter; empty if no sink is configured
+  ///
+  /// # Errors
+  /// Returns an error if the sink cannot be read or updated
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// use send_with_us::{Api, Config};
+  /// use send_with_us::dead_letter::FileDeadLetterSink;
+  ///
+  /// # async fn run() -> send_with_us::Result<()> {
+  /// let config = Config::new("api-key")
+  ///   .with_dead_letter_sink(FileDeadLetterSink::new("/var/lib/my-app/dead-letters"));
+  /// let api = Api::new(config);
+  ///
+  /// for (id, result) in api.replay_dead_letters().await? {
+  ///   if let Err(err) = result {
+  ///     eprintln!("dead letter {} failed again: {}", id, err);
+  ///   }
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn replay_dead_letters(&self) -> Result<Vec<(String, Result<SendResponse>)>> {
+    self.replay(false).await
+  }
 
-    async fn list_templates(&self) -> Result<Value> {
-      Ok(serde_json::json!([
-        {"id": "template_1", "name": "Template 1"},
-        {"id": "template_2", "name": "Template 2"}
-      ]))
-    }
+  /// Sends every email captured by the configured dead-letter sink again,
+  /// including those that may already have been sent.
+  ///
+  /// Use this once the emails reported with `Error::SendOutcomeUnknown` by
+  /// `replay_dead_letters` have been checked, as it may deliver them twice.
+  ///
+  /// # Returns
+  /// The ID and outcome of each replayed letter; empty if no sink is configured
+  ///
+  /// # Errors
+  /// Returns an error if the sink cannot be read or updated
+  pub async fn replay_dead_letters_including_unknown(
+    &self,
+  ) -> Result<Vec<(String, Result<SendResponse>)>> {
+    self.replay(true).await
+  }
 
-    async fn render(&self, options: RenderOptions) -> Result<Value> {
-      Ok(serde_json::json!({
-        "template": options.template,
-        "rendered_template": "<html>Rendered template</html>"
-      }))
-    }
+  async fn replay(&self, include_unknown: bool) -> Result<Vec<(String, Result<SendResponse>)>> {
+    let sink = match &self.config().dead_letter_sink {
+      Some(sink) => sink,
+      None => return Ok(Vec::new()),
+    };
 
-    async fn create_template(&self, options: TemplateOptions) -> Result<Value> {
-      Ok(serde_json::json!({
-        "id": "new_template",
-        "name": options.name,
-        "created": true
-      }))
-    }
+    let mut outcomes = Vec::new();
 
-    async fn list_drip_campaigns(&self) -> Result<Value> {
-      Ok(serde_json::json!([
-        {"id": "campaign_1", "name": "Campaign 1"},
-        {"id": "campaign_2", "name": "Campaign 2"}
-      ]))
-    }
+    for letter in sink.letters().await? {
+      if letter.may_have_been_sent && !include_unknown {
+        outcomes.push((letter.id, Err(Error::SendOutcomeUnknown)));
+        continue;
+      }
 
-    async fn start_on_drip_campaign(
-      &self,
-      campaign_id: &str,
-      options: DripCampaignOptions,
-    ) -> Result<Value> {
-      Ok(serde_json::json!({
-        "success": true,
-        "recipient": options.recipient_address,
-        "campaign_id": campaign_id
-      }))
-    }
+      let result = self
+        .request(
+          reqwest::Method::POST,
+          &Endpoint::new(["send"]),
+          Some(&letter.options),
+        )
+        .await;
+
+      match &result {
+        // A failed replay that may have reached SendWithUs is kept as a new
+        // letter marked as possibly sent, so it isn't replayed blindly.
+        Err(error) if error.is_retryable() => {
+          if error.may_have_been_processed() && !letter.may_have_been_sent {
+            let retried = DeadLetter::new(
+              letter.options.clone(),
+              error.to_string(),
+              letter.first_attempted_at,
+            );
+            sink.capture(retried).await?;
+            sink.remove(&letter.id).await?;
+          }
+        }
+        _ => sink.remove(&letter.id).await?,
+      }
 
-    async fn remove_from_drip_campaign(
-      &self,
-      campaign_id: &str,
-      recipient_address: &str,
-    ) -> Result<Value> {
-      Ok(serde_json::json!({
-        "success": true,
-        "recipient": recipient_address,
-        "campaign_id": campaign_id
-      }))
+      outcomes.push((letter.id, result));
     }
 
-    async fn drip_campaign_details(&self, campaign_id: &str) -> Result<Value> {
-      Ok(serde_json::json!({
-        "id": campaign_id,
-        "name": format!("Campaign {}", campaign_id),
-        "details": "Some details"
-      }))
-    }
+    Ok(outcomes)
+  }
 
-    async fn customer_get(&self, email: &str) -> Result<Value> {
-      Ok(serde_json::json!({
-        "email": email,
-        "data": {"name": "Test Customer"}
-      }))
-    }
+  /// Exports the traffic recorded by the configured capture session as a HAR document.
+  ///
+  /// HAR files can be opened in browser developer tools and are the format
+  /// SendWithUs support asks for when investigating API issues.
+  ///
+  /// # Returns
+  /// The HAR document as JSON; it has no entries if no session is configured
+  ///
+  /// # Errors
+  /// Returns an error if the session cannot be read
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// use send_with_us::{Api, ApiClient, Config};
+  /// use send_with_us::capture::MemoryCaptureSession;
+  ///
+  /// # async fn run() -> send_with_us::Result<()> {
+  /// let config = Config::new("api-key").with_capture_session(MemoryCaptureSession::new());
+  /// let api = Api::new(config);
+  ///
+  /// let _ = api.list_templates().await;
+  ///
+  /// let har = api.capture_har().await?;
+  /// std::fs::write("sendwithus.har", serde_json::to_string_pretty(&har)?)?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn capture_har(&self) -> Result<Value> {
+    let exchanges = match &self.config().capture_session {
+      Some(session) => session.exchanges().await?,
+      None => Vec::new(),
+    };
 
-    async fn customer_create(&self, options: CustomerOptions) -> Result<Value> {
-      Ok(serde_json::json!({
-        "success": true,
-        "email": options.email
-      }))
-    }
+    Ok(capture::to_har(&exchanges))
+  }
+}
 
-    async fn customer_delete(&self, email: &str) -> Result<Value> {
-      Ok(serde_json::json!({
-        "success": true,
-        "email": email
-      }))
-    }
+impl Api {
+  /// Streams the account's email logs, following pagination.
+  ///
+  /// Pages are fetched as the stream is consumed. `query.count` sets the
+  /// page size (100 if unset) and `query.offset` where to start; the time
+  /// bounds apply to every page. The stream ends after the first page shorter
+  /// than the page size, or after yielding an error.
+  ///
+  /// # Arguments
+  /// * `query` - Filters and page size
+  ///
+  /// # Returns
+  /// A stream of email logs
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// use futures::StreamExt;
+  /// use send_with_us::Api;
+  /// use send_with_us::types::EmailLogQuery;
+  ///
+  /// # async fn run() -> send_with_us::Result<()> {
+  /// let api = Api::with_api_key("api-key");
+  /// let mut logs = api.logs_stream(EmailLogQuery::new().with_created_gt("1700000000"));
+  ///
+  /// while let Some(log) = logs.next().await {
+  ///   let log = log?;
+  ///   println!("{} {}", log.recipient_address, log.status);
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn logs_stream(&self, query: EmailLogQuery) -> BoxStream<'static, Result<EmailLog>> {
+    self.paginate_logs(None, query)
+  }
 
-    async fn customer_email_log(
-      &self,
-      email: &str,
-      count: Option<u32>,
-      created_gt: Option<String>,
-      created_lt: Option<String>,
-    ) -> Result<Value> {
-      let mut response = serde_json::json!({
-        "email": email,
-        "logs": []
-      });
+  /// Streams a customer's email logs, following pagination.
+  ///
+  /// Paging works as for `logs_stream`.
+  ///
+  /// # Arguments
+  /// * `email` - Customer's email address
+  /// * `query` - Filters and page size
+  ///
+  /// # Returns
+  /// A stream of the customer's email logs
+  pub fn customer_email_log_stream(
+    &self,
+    email: &str,
+    query: EmailLogQuery,
+  ) -> BoxStream<'static, Result<EmailLog>> {
+    self.paginate_logs(Some(email.to_string()), query)
+  }
 
-      if let Some(count) = count {
-        response["count"] = serde_json::json!(count);
-      }
+  /// Fetches the account's email logs across pages, up to `limit` logs.
+  ///
+  /// This collects `logs_stream` into a single `Vec`, which suits small
+  /// accounts and scripts. Unless `query.count` sets the page size, pages are
+  /// no larger than `limit`. (Templates and drip campaigns need no such
+  /// helper: their list endpoints aren't paginated.)
+  ///
+  /// # Arguments
+  /// * `query` - Filters, page size, and starting offset
+  /// * `limit` - Maximum number of logs to return
+  ///
+  /// # Returns
+  /// Up to `limit` email logs
+  ///
+  /// # Errors
+  /// Returns the first error encountered while fetching pages
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// use send_with_us::Api;
+  /// use send_with_us::types::EmailLogQuery;
+  ///
+  /// # async fn run() -> send_with_us::Result<()> {
+  /// let api = Api::with_api_key("api-key");
+  /// let logs = api.logs_all(EmailLogQuery::new(), 1_000).await?;
+  /// println!("fetched {} logs", logs.len());
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn logs_all(&self, query: EmailLogQuery, limit: usize) -> Result<Vec<EmailLog>> {
+    collect_logs(self.logs_stream(capped_page(query, limit)), limit).await
+  }
 
-      if let Some(created_gt) = created_gt {
-        response["created_gt"] = serde_json::json!(created_gt);
-      }
+  /// Fetches a customer's email logs across pages, up to `limit` logs.
+  ///
+  /// Paging works as for `logs_all`.
+  ///
+  /// # Arguments
+  /// * `email` - Customer's email address
+  /// * `query` - Filters, page size, and starting offset
+  /// * `limit` - Maximum number of logs to return
+  ///
+  /// # Returns
+  /// Up to `limit` of the customer's email logs
+  ///
+  /// # Errors
+  /// Returns the first error encountered while fetching pages
+  pub async fn customer_email_log_all(
+    &self,
+    email: &str,
+    query: EmailLogQuery,
+    limit: usize,
+  ) -> Result<Vec<EmailLog>> {
+    let stream = self.customer_email_log_stream(email, capped_page(query, limit));
+    collect_logs(stream, limit).await
+  }
 
-      if let Some(created_lt) = created_lt {
-        response["created_lt"] = serde_json::json!(created_lt);
-      }
+  /// Downloads the account's logs for a time range, fetching windows concurrently.
+  ///
+  /// The range is split into consecutive windows of `window` length, each
+  /// fetched with `logs_stream` and at most `concurrency` windows in flight
+  /// at once. The results are merged oldest first: windows in order, and the
+  /// logs of each window sorted by creation time.
+  ///
+  /// # Arguments
+  /// * `from` - Start of the range (Unix timestamp, inclusive)
+  /// * `to` - End of the range (Unix timestamp, exclusive)
+  /// * `window` - Length of each window, rounded down to whole seconds (at least one)
+  /// * `concurrency` - Maximum number of windows fetched at once (at least one)
+  ///
+  /// # Returns
+  /// All logs created in the range, oldest first
+  ///
+  /// # Errors
+  /// Returns the first error encountered while fetching a window
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// use send_with_us::Api;
+  /// use std::time::Duration;
+  ///
+  /// # async fn run() -> send_with_us::Result<()> {
+  /// let api = Api::with_api_key("api-key");
+  ///
+  /// // Thirty days of logs, one day per window, four days at a time.
+  /// let day = Duration::from_secs(86_400);
+  /// let logs = api.logs_in_range(1698796800, 1701388800, day, 4).await?;
+  /// println!("fetched {} logs", logs.len());
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn logs_in_range(
+    &self,
+    from: i64,
+    to: i64,
+    window: Duration,
+    concurrency: usize,
+  ) -> Result<Vec<EmailLog>> {
+    let step = i64::try_from(window.as_secs()).unwrap_or(i64::MAX).max(1);
+    let windows = (0..)
+      .map(|i: i64| from.saturating_add(step.saturating_mul(i)))
+      .take_while(|start| *start < to)
+      .map(|start| (start, start.saturating_add(step).min(to)));
+
+    let pages: Vec<Vec<EmailLog>> = stream::iter(windows)
+      .map(|(start, end)| async move {
+        let query = EmailLogQuery::new()
+          .with_created_gt((start - 1).to_string())
+          .with_created_lt(end.to_string());
+        let mut logs: Vec<EmailLog> = self.logs_stream(query).try_collect().await?;
+        logs.sort_by_key(|log| log.created);
+        Ok::<_, Error>(logs)
+      })
+      .buffered(concurrency.max(1))
+      .try_collect()
+      .await?;
+
+    Ok(pages.into_iter().flatten().collect())
+  }
 
-      Ok(response)
+  /// Builds an engagement report for the emails sent within a time range.
+  ///
+  /// Streams the account's logs created in the range and fetches the events
+  /// of each, so this makes one request per email on top of the log pages.
+  /// Logs don't include tags, so the report is grouped by template only; use
+  /// `EngagementReport::record` directly to group by tag.
+  ///
+  /// # Arguments
+  /// * `from` - Start of the range (Unix timestamp, inclusive)
+  /// * `to` - End of the range (Unix timestamp, exclusive)
+  ///
+  /// # Returns
+  /// Open, click, and bounce counts in total and per template
+  ///
+  /// # Errors
+  /// Returns the first error encountered while fetching logs or events
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// use send_with_us::Api;
+  ///
+  /// # async fn run() -> send_with_us::Result<()> {
+  /// let api = Api::with_api_key("api-key");
+  /// let report = api.engagement_report(1700000000, 1700604800).await?;
+  ///
+  /// for (template, stats) in report.by_template() {
+  ///   println!("{}: {:.1}% opened", template, stats.open_rate() * 100.0);
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn engagement_report(&self, from: i64, to: i64) -> Result<EngagementReport> {
+    let query = EmailLogQuery::new()
+      .with_created_gt((from - 1).to_string())
+      .with_created_lt(to.to_string());
+    let mut logs = self.logs_stream(query);
+    let mut report = EngagementReport::new(from, to);
+
+    while let Some(log) = logs.next().await {
+      let log = log?;
+      let events = self.log_events(&log.id).await?;
+      report.record(&log, &events, &[]);
     }
 
-    async fn log(&self, log_id: &str) -> Result<Value> {
-      Ok(serde_json::json!({
-        "id": log_id,
-        "status": "delivered"
-      }))
+    Ok(report)
+  }
+
+  /// Waits until an event of the given type is recorded for an email log.
+  ///
+  /// Polls `log_events` with exponential backoff, starting at half a second
+  /// and capped at ten seconds, until a matching event appears or `timeout`
+  /// elapses. The log is checked once more at the deadline. A 404 is treated
+  /// as "not yet", since a log can take a moment to appear after sending.
+  ///
+  /// # Arguments
+  /// * `log_id` - Email log ID
+  /// * `event_type` - Type of event to wait for
+  /// * `timeout` - How long to wait in total
+  ///
+  /// # Returns
+  /// The first matching event
+  ///
+  /// # Errors
+  /// Returns `Error::EventTimeout` if no matching event was recorded in
+  /// time, or the first error fetching the events other than a 404
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// use send_with_us::Api;
+  /// use send_with_us::LogId;
+  /// use send_with_us::models::EventType;
+  /// use std::time::Duration;
+  ///
+  /// # async fn run(log_id: LogId) -> send_with_us::Result<()> {
+  /// let api = Api::with_api_key("api-key");
+  /// let event = api
+  ///   .wait_for_event(&log_id, EventType::Delivered, Duration::from_secs(60))
+  ///   .await?;
+  /// println!("delivered at {}", event.created);
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn wait_for_event(
+    &self,
+    log_id: &LogId,
+    event_type: EventType,
+    timeout: Duration,
+  ) -> Result<LogEvent> {
+    let deadline = Instant::now() + timeout;
+    let mut delay = EVENT_POLL_INITIAL_DELAY;
+
+    loop {
+      let events = match self.log_events(log_id).await {
+        Ok(events) => events,
+        Err(Error::InvalidEndpoint(_)) => Vec::new(),
+        Err(error) => return Err(error),
+      };
+
+      if let Some(event) = events
+        .into_iter()
+        .find(|event| event.event_type == event_type)
+      {
+        return Ok(event);
+      }
+
+      let remaining = deadline.saturating_duration_since(Instant::now());
+      if remaining.is_zero() {
+        return Err(Error::EventTimeout {
+          log_id: log_id.clone(),
+          event_type,
+        });
+      }
+
+      tokio::time::sleep(delay.min(remaining)).await;
+      delay = (delay * 2).min(EVENT_POLL_MAX_DELAY);
     }
+  }
 
-    async fn log_events(&self, log_id: &str) -> Result<Value> {
-      Ok(serde_json::json!({
-        "log_id": log_id,
-        "events": [
-          {"type": "sent", "timestamp": "2023-01-01T12:00:00Z"},
-          {"type": "delivered", "timestamp": "2023-01-01T12:01:00Z"}
-        ]
-      }))
+  /// Pages through account logs, or a customer's logs if `email` is set.
+  fn paginate_logs(
+    &self,
+    email: Option<String>,
+    query: EmailLogQuery,
+  ) -> BoxStream<'static, Result<EmailLog>> {
+    let page_size = query.count.unwrap_or(LOG_PAGE_SIZE).max(1);
+    let state = LogPages {
+      api: self.clone(),
+      email,
+      query: query.with_count(page_size),
+      buffer: VecDeque::new(),
+      done: false,
+    };
+
+    stream::unfold(state, |mut state| async move {
+      loop {
+        if let Some(log) = state.buffer.pop_front() {
+          return Some((Ok(log), state));
+        }
+
+        if state.done {
+          return None;
+        }
+
+        let page = match &state.email {
+          Some(email) => {
+            state
+              .api
+              .customer_email_log(email, state.query.clone())
+              .await
+          }
+          None => state.api.logs(state.query.clone()).await,
+        };
+
+        match page {
+          Ok(page) => {
+            let fetched = page.len() as u32;
+            state.done = fetched < state.query.count.unwrap_or(LOG_PAGE_SIZE);
+            state.query.offset = Some(state.query.offset.unwrap_or(0) + fetched);
+            state.buffer.extend(page);
+          }
+          Err(error) => {
+            state.done = true;
+            return Some((Err(error), state));
+          }
+        }
+      }
+    })
+    .boxed()
+  }
+}
+
+/// Delay before the second poll in `Api::wait_for_event`.
+const EVENT_POLL_INITIAL_DELAY: Duration = Duration::from_millis(500);
+
+/// Longest delay between polls in `Api::wait_for_event`.
+const EVENT_POLL_MAX_DELAY: Duration = Duration::from_secs(10);
+
+/// Number of logs fetched per page when streaming logs.
+const LOG_PAGE_SIZE: u32 = 100;
+
+/// Limits the page size of a query to `limit` unless it sets one itself.
+fn capped_page(query: EmailLogQuery, limit: usize) -> EmailLogQuery {
+  match query.count {
+    Some(_) => query,
+    None => {
+      let count = u32::try_from(limit).unwrap_or(u32::MAX).min(LOG_PAGE_SIZE);
+      query.with_count(count)
     }
+  }
+}
 
-    async fn delete_template(&self, template_id: &str) -> Result<Value> {
-      Ok(serde_json::json!({
-        "success": true,
-        "template_id": template_id
-      }))
+/// Collects up to `limit` logs from a stream, stopping at the first error.
+async fn collect_logs(
+  stream: BoxStream<'static, Result<EmailLog>>,
+  limit: usize,
+) -> Result<Vec<EmailLog>> {
+  stream.take(limit).try_collect().await
+}
+
+/// Progress through a paginated log listing.
+struct LogPages {
+  api: Api,
+  email: Option<String>,
+  query: EmailLogQuery,
+  buffer: VecDeque<EmailLog>,
+  done: bool,
+}
+
+impl Api {
+  /// Lists all templates, deserializing the response into `R`.
+  ///
+  /// Each `Value`-returning method of [`ApiClient`] has an `_as` variant
+  /// like this one, so responses can be read into your own types instead of
+  /// `serde_json::Value`.
+  ///
+  /// # Errors
+  /// Returns `Error::ResponseDecodeFailed` if the response doesn't match `R`
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// use send_with_us::Api;
+  /// use serde::Deserialize;
+  ///
+  /// #[derive(Deserialize)]
+  /// struct Template {
+  ///   id: String,
+  ///   name: String,
+  /// }
+  ///
+  /// # async fn run() -> send_with_us::Result<()> {
+  /// let api = Api::with_api_key("api-key");
+  /// let templates: Vec<Template> = api.list_templates_as().await?;
+  ///
+  /// for template in templates {
+  ///   println!("{}: {}", template.id, template.name);
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn list_templates_as<R: DeserializeOwned>(&self) -> Result<R> {
+    self
+      .request::<(), R>(reqwest::Method::GET, &Endpoint::new(["emails"]), None)
+      .await
+  }
+
+  /// Renders a template, deserializing the response into `R`.
+  pub async fn render_as<R: DeserializeOwned>(&self, mut options: RenderOptions) -> Result<R> {
+    self.apply_default_locale(&mut options.locale);
+    self
+      .request(
+        reqwest::Method::POST,
+        &Endpoint::new(["render"]),
+        Some(&options),
+      )
+      .await
+  }
+
+  /// Creates a new template, deserializing the response into `R`.
+  pub async fn create_template_as<R: DeserializeOwned>(
+    &self,
+    options: TemplateOptions,
+  ) -> Result<R> {
+    self
+      .request(
+        reqwest::Method::POST,
+        &Endpoint::new(["emails"]),
+        Some(&options),
+      )
+      .await
+  }
+
+  /// Lists all drip campaigns, deserializing the response into `R`.
+  pub async fn list_drip_campaigns_as<R: DeserializeOwned>(&self) -> Result<R> {
+    self
+      .request::<(), R>(
+        reqwest::Method::GET,
+        &Endpoint::new(["drip_campaigns"]),
+        None,
+      )
+      .await
+  }
+
+  /// Starts a recipient on a drip campaign, deserializing the response into `R`.
+  pub async fn start_on_drip_campaign_as<R: DeserializeOwned>(
+    &self,
+    campaign_id: &CampaignId,
+    mut options: DripCampaignOptions,
+  ) -> Result<R> {
+    self.apply_default_locale(&mut options.locale);
+    if let Some(address) = &self.config().intercept_address {
+      intercept_drip_recipient(&mut options, address);
     }
+    let endpoint = Endpoint::new(["drip_campaigns", campaign_id.as_str(), "activate"]);
+    self
+      .request(reqwest::Method::POST, &endpoint, Some(&options))
+      .await
+  }
 
-    async fn list_template_versions(&self, template_id: &str) -> Result<Value> {
-      Ok(serde_json::json!({
-        "template_id": template_id,
-        "versions": [
-          {"id": "v1", "name": "Version 1"},
-          {"id": "v2", "name": "Version 2"}
-        ]
-      }))
+  /// Removes a recipient from a drip campaign, deserializing the response into `R`.
+  pub async fn remove_from_drip_campaign_as<R: DeserializeOwned>(
+    &self,
+    campaign_id: &CampaignId,
+    recipient_address: &str,
+  ) -> Result<R> {
+    let recipient_address = self
+      .config()
+      .intercept_address
+      .as_deref()
+      .unwrap_or(recipient_address);
+    let endpoint = Endpoint::new(["drip_campaigns", campaign_id.as_str(), "deactivate"]);
+    let payload = serde_json::json!({ "recipient_address": recipient_address });
+    self
+      .request(reqwest::Method::POST, &endpoint, Some(&payload))
+      .await
+  }
+
+  /// Gets drip campaign details, deserializing the response into `R`.
+  pub async fn drip_campaign_details_as<R: DeserializeOwned>(
+    &self,
+    campaign_id: &CampaignId,
+  ) -> Result<R> {
+    let endpoint = Endpoint::new(["drip_campaigns", campaign_id.as_str()]);
+    self
+      .request::<(), R>(reqwest::Method::GET, &endpoint, None)
+      .await
+  }
+
+  /// Gets customer details, deserializing the response into `R`.
+  pub async fn customer_get_as<R: DeserializeOwned>(&self, email: &str) -> Result<R> {
+    let endpoint = Endpoint::new(["customers", email]);
+    self
+      .request::<(), R>(reqwest::Method::GET, &endpoint, None)
+      .await
+  }
+
+  /// Creates a new customer, deserializing the response into `R`.
+  pub async fn customer_create_as<R: DeserializeOwned>(
+    &self,
+    options: CustomerOptions,
+  ) -> Result<R> {
+    self
+      .request(
+        reqwest::Method::POST,
+        &Endpoint::new(["customers"]),
+        Some(&options),
+      )
+      .await
+  }
+
+  /// Updates a customer, deserializing the response into `R`.
+  ///
+  /// Merging data is a non-atomic read-modify-write; see
+  /// `ApiClient::customer_update`.
+  pub async fn customer_update_as<R: DeserializeOwned>(
+    &self,
+    email: &str,
+    mut options: CustomerUpdateOptions,
+  ) -> Result<R> {
+    if email.is_empty() {
+      return Err(Error::MissingRecipientAddress);
     }
 
-    async fn get_template_version(&self, template_id: &str, version_id: &str) -> Result<Value> {
-      Ok(serde_json::json!({
-        "template_id": template_id,
-        "version_id": version_id,
-        "html": "<html>Template content</html>"
-      }))
+    let merge_updates = match options.data_mode {
+      DataUpdateMode::Merge => options.data.take(),
+      DataUpdateMode::Replace => None,
+    };
+
+    if let Some(updates) = merge_updates {
+      let current = match self.customer_get_as::<Value>(email).await {
+        Err(Error::InvalidEndpoint(_)) => return Err(Error::CustomerNotFound),
+        result => result?,
+      };
+      let customer = current.get("customer").unwrap_or(&current);
+
+      let mut data: HashMap<String, Value> = customer
+        .get("data")
+        .and_then(|data| data.as_object())
+        .map(|data| data.clone().into_iter().collect())
+        .unwrap_or_default();
+
+      data.extend(updates);
+      options.data = Some(data);
     }
 
-    async fn update_template_version(
-      &self,
-      template_id: &str,
-      version_id: &str,
-      options: TemplateOptions,
-    ) -> Result<Value> {
-      Ok(serde_json::json!({
-        "success": true,
-        "template_id": template_id,
-        "version_id": version_id,
-        "name": options.name
-      }))
+    let endpoint = Endpoint::new(["customers", email]);
+    self
+      .request(reqwest::Method::PUT, &endpoint, Some(&options))
+      .await
+  }
+
+  /// Deletes a customer, deserializing the response into `R`.
+  pub async fn customer_delete_as<R: DeserializeOwned>(&self, email: &str) -> Result<R> {
+    let endpoint = Endpoint::new(["customers", email]);
+    self
+      .request::<(), R>(reqwest::Method::DELETE, &endpoint, None)
+      .await
+  }
+
+  /// Records a customer event, deserializing the response into `R`.
+  pub async fn customer_add_event_as<R: DeserializeOwned>(
+    &self,
+    email: &str,
+    options: CustomerEventOptions,
+  ) -> Result<R> {
+    if email.is_empty() {
+      return Err(Error::MissingRecipientAddress);
     }
 
-    async fn create_template_version(
-      &self,
-      template_id: &str,
-      options: TemplateOptions,
-    ) -> Result<Value> {
-      Ok(serde_json::json!({
-        "success": true,
-        "template_id": template_id,
-        "new_version": {
-          "id": "new_version",
-          "name": options.name
-        }
-      }))
+    let endpoint = Endpoint::new(["customers", email, "events"]);
+    self
+      .request(reqwest::Method::POST, &endpoint, Some(&options))
+      .await
+  }
+
+  /// Gets a template, deserializing the response into `R`.
+  pub async fn get_template_as<R: DeserializeOwned>(&self, template_id: &TemplateId) -> Result<R> {
+    let endpoint = Endpoint::new(["templates", template_id.as_str()]);
+    self
+      .request::<(), R>(reqwest::Method::GET, &endpoint, None)
+      .await
+  }
+
+  /// Updates a template, deserializing the response into `R`.
+  pub async fn update_template_as<R: DeserializeOwned>(
+    &self,
+    template_id: &TemplateId,
+    options: TemplateUpdateOptions,
+  ) -> Result<R> {
+    let endpoint = Endpoint::new(["templates", template_id.as_str()]);
+    self
+      .request(reqwest::Method::PUT, &endpoint, Some(&options))
+      .await
+  }
+
+  /// Deletes a template, deserializing the response into `R`.
+  pub async fn delete_template_as<R: DeserializeOwned>(
+    &self,
+    template_id: &TemplateId,
+  ) -> Result<R> {
+    let endpoint = Endpoint::new(["templates", template_id.as_str()]);
+    self
+      .request::<(), R>(reqwest::Method::DELETE, &endpoint, None)
+      .await
+  }
+
+  /// Lists template versions, deserializing the response into `R`.
+  pub async fn list_template_versions_as<R: DeserializeOwned>(
+    &self,
+    template_id: &TemplateId,
+  ) -> Result<R> {
+    let endpoint = Endpoint::new(["templates", template_id.as_str(), "versions"]);
+    self
+      .request::<(), R>(reqwest::Method::GET, &endpoint, None)
+      .await
+  }
+
+  /// Gets a template version, deserializing the response into `R`.
+  pub async fn get_template_version_as<R: DeserializeOwned>(
+    &self,
+    template_id: &TemplateId,
+    version_id: &str,
+  ) -> Result<R> {
+    let endpoint = Endpoint::new(["templates", template_id.as_str(), "versions", version_id]);
+    self
+      .request::<(), R>(reqwest::Method::GET, &endpoint, None)
+      .await
+  }
+
+  /// Updates a template version, deserializing the response into `R`.
+  pub async fn update_template_version_as<R: DeserializeOwned>(
+    &self,
+    template_id: &TemplateId,
+    version_id: &str,
+    options: TemplateOptions,
+  ) -> Result<R> {
+    let endpoint = Endpoint::new(["templates", template_id.as_str(), "versions", version_id]);
+    self
+      .request(reqwest::Method::PUT, &endpoint, Some(&options))
+      .await
+  }
+
+  /// Creates a template version, deserializing the response into `R`.
+  pub async fn create_template_version_as<R: DeserializeOwned>(
+    &self,
+    template_id: &TemplateId,
+    options: TemplateOptions,
+  ) -> Result<R> {
+    let endpoint = Endpoint::new(["templates", template_id.as_str(), "versions"]);
+    self
+      .request(reqwest::Method::POST, &endpoint, Some(&options))
+      .await
+  }
+
+  /// Unsubscribes from drips, deserializing the response into `R`.
+  pub async fn drips_unsubscribe_as<R: DeserializeOwned>(&self, email_address: &str) -> Result<R> {
+    if email_address.is_empty() {
+      return Err(Error::MissingRecipientAddress);
     }
 
-    async fn drips_unsubscribe(&self, email_address: &str) -> Result<Value> {
-      if email_address.is_empty() {
-        return Err(Error::MissingRecipientAddress);
-      }
+    let payload = serde_json::json!({ "email_address": email_address });
+    self
+      .request(
+        reqwest::Method::POST,
+        &Endpoint::new(["drips", "unsubscribe"]),
+        Some(&payload),
+      )
+      .await
+  }
+
+  /// Unsubscribes from all emails, deserializing the response into `R`.
+  pub async fn unsubscribe_as<R: DeserializeOwned>(&self, email_address: &str) -> Result<R> {
+    if email_address.is_empty() {
+      return Err(Error::MissingRecipientAddress);
+    }
+
+    let payload = serde_json::json!({ "email_address": email_address });
+    self
+      .request(
+        reqwest::Method::POST,
+        &Endpoint::new(["unsubscribes"]),
+        Some(&payload),
+      )
+      .await
+  }
+
+  /// Resubscribes to emails, deserializing the response into `R`.
+  pub async fn resubscribe_as<R: DeserializeOwned>(&self, email_address: &str) -> Result<R> {
+    if email_address.is_empty() {
+      return Err(Error::MissingRecipientAddress);
+    }
+
+    let endpoint = Endpoint::new(["unsubscribes", email_address]);
+    self
+      .request::<(), R>(reqwest::Method::DELETE, &endpoint, None)
+      .await
+  }
+}
+
+#[async_trait]
+#[cfg(not(tarpaulin_include))]
+impl ApiClient for Api {
+  fn prepare_email(&self, options: EmailOptions) -> Result<EmailOptions> {
+    Api::prepare_email(self, options)
+  }
+
+  /// Send an email
+  async fn send_email(&self, options: EmailOptions) -> Result<SendResponse> {
+    self
+      .send_email_with_metadata(options)
+      .await
+      .map(ResponseEnvelope::into_inner)
+  }
+
+  /// List all templates
+  async fn list_templates(&self) -> Result<Value> {
+    self.list_templates_as().await
+  }
+
+  /// Render a template
+  async fn render(&self, options: RenderOptions) -> Result<Value> {
+    self.render_as(options).await
+  }
+
+  /// Create a new template
+  async fn create_template(&self, options: TemplateOptions) -> Result<Value> {
+    self.create_template_as(options).await
+  }
+
+  /// List all drip campaigns
+  async fn list_drip_campaigns(&self) -> Result<Value> {
+    self.list_drip_campaigns_as().await
+  }
+
+  /// Start a recipient on a drip campaign
+  async fn start_on_drip_campaign(
+    &self,
+    campaign_id: &CampaignId,
+    options: DripCampaignOptions,
+  ) -> Result<Value> {
+    self.start_on_drip_campaign_as(campaign_id, options).await
+  }
+
+  /// Remove a recipient from a drip campaign
+  async fn remove_from_drip_campaign(
+    &self,
+    campaign_id: &CampaignId,
+    recipient_address: &str,
+  ) -> Result<Value> {
+    self
+      .remove_from_drip_campaign_as(campaign_id, recipient_address)
+      .await
+  }
+
+  /// Get drip campaign details
+  async fn drip_campaign_details(&self, campaign_id: &CampaignId) -> Result<Value> {
+    self.drip_campaign_details_as(campaign_id).await
+  }
+
+  /// List drip campaign steps
+  async fn drip_campaign_steps(&self, campaign_id: &CampaignId) -> Result<Vec<DripCampaignStep>> {
+    let endpoint = Endpoint::new(["drip_campaigns", campaign_id.as_str(), "steps"]);
+    self
+      .request::<(), _>(reqwest::Method::GET, &endpoint, None)
+      .await
+  }
+
+  /// List customers on a drip campaign
+  async fn drip_campaign_customers(
+    &self,
+    campaign_id: &CampaignId,
+  ) -> Result<Vec<DripCampaignCustomer>> {
+    let endpoint = Endpoint::new(["drip_campaigns", campaign_id.as_str(), "customers"]);
+    let response: DripCampaignCustomers = self
+      .request::<(), _>(reqwest::Method::GET, &endpoint, None)
+      .await?;
+
+    Ok(response.customers)
+  }
+
+  /// Get customer details
+  async fn customer_get(&self, email: &str) -> Result<Value> {
+    self.customer_get_as(email).await
+  }
+
+  /// Create a new customer
+  async fn customer_create(&self, options: CustomerOptions) -> Result<Value> {
+    self.customer_create_as(options).await
+  }
+
+  /// Update a customer
+  async fn customer_update(&self, email: &str, options: CustomerUpdateOptions) -> Result<Value> {
+    self.customer_update_as(email, options).await
+  }
+
+  /// Delete a customer
+  async fn customer_delete(&self, email: &str) -> Result<Value> {
+    self.customer_delete_as(email).await
+  }
+
+  /// Record a customer event
+  async fn customer_add_event(&self, email: &str, options: CustomerEventOptions) -> Result<Value> {
+    self.customer_add_event_as(email, options).await
+  }
+
+  /// Get customer email logs
+  async fn customer_email_log(&self, email: &str, query: EmailLogQuery) -> Result<Vec<EmailLog>> {
+    let endpoint = Endpoint::new(["customers", email, "logs"]).with_query(&query);
+    let response: EmailLogs = self
+      .request::<(), _>(reqwest::Method::GET, &endpoint, None)
+      .await?;
+
+    Ok(response.logs)
+  }
+
+  /// List account email logs
+  async fn logs(&self, query: EmailLogQuery) -> Result<Vec<EmailLog>> {
+    let endpoint = Endpoint::new(["logs"]).with_query(&query);
+    self
+      .request::<(), _>(reqwest::Method::GET, &endpoint, None)
+      .await
+  }
+
+  /// Get email log
+  async fn log(&self, log_id: &LogId) -> Result<EmailLog> {
+    let endpoint = Endpoint::new(["logs", log_id.as_str()]);
+    self
+      .request::<(), _>(reqwest::Method::GET, &endpoint, None)
+      .await
+  }
+
+  /// Get email log events
+  async fn log_events(&self, log_id: &LogId) -> Result<Vec<LogEvent>> {
+    let endpoint = Endpoint::new(["logs", log_id.as_str(), "events"]);
+    self
+      .request::<(), _>(reqwest::Method::GET, &endpoint, None)
+      .await
+  }
+
+  /// Get a template
+  async fn get_template(&self, template_id: &TemplateId) -> Result<Value> {
+    self.get_template_as(template_id).await
+  }
+
+  /// Update a template
+  async fn update_template(
+    &self,
+    template_id: &TemplateId,
+    options: TemplateUpdateOptions,
+  ) -> Result<Value> {
+    self.update_template_as(template_id, options).await
+  }
+
+  /// Delete a template
+  async fn delete_template(&self, template_id: &TemplateId) -> Result<Value> {
+    self.delete_template_as(template_id).await
+  }
+
+  /// List template versions
+  async fn list_template_versions(&self, template_id: &TemplateId) -> Result<Value> {
+    self.list_template_versions_as(template_id).await
+  }
+
+  /// Get template version
+  async fn get_template_version(
+    &self,
+    template_id: &TemplateId,
+    version_id: &str,
+  ) -> Result<Value> {
+    self.get_template_version_as(template_id, version_id).await
+  }
+
+  /// Update template version
+  async fn update_template_version(
+    &self,
+    template_id: &TemplateId,
+    version_id: &str,
+    options: TemplateOptions,
+  ) -> Result<Value> {
+    self
+      .update_template_version_as(template_id, version_id, options)
+      .await
+  }
+
+  /// Create template version
+  async fn create_template_version(
+    &self,
+    template_id: &TemplateId,
+    options: TemplateOptions,
+  ) -> Result<Value> {
+    self.create_template_version_as(template_id, options).await
+  }
+
+  /// Unsubscribe from drips
+  async fn drips_unsubscribe(&self, email_address: &str) -> Result<Value> {
+    self.drips_unsubscribe_as(email_address).await
+  }
+
+  /// Get unsubscribe status
+  async fn unsubscribe_status(&self, email_address: &str) -> Result<UnsubscribeStatus> {
+    if email_address.is_empty() {
+      return Err(Error::MissingRecipientAddress);
+    }
+
+    let endpoint = Endpoint::new(["unsubscribes", email_address]);
+    self
+      .request::<(), _>(reqwest::Method::GET, &endpoint, None)
+      .await
+  }
+
+  /// Unsubscribe from all emails
+  async fn unsubscribe(&self, email_address: &str) -> Result<Value> {
+    self.unsubscribe_as(email_address).await
+  }
+
+  /// Resubscribe to emails
+  async fn resubscribe(&self, email_address: &str) -> Result<Value> {
+    self.resubscribe_as(email_address).await
+  }
+}
+
+/// Implements `ApiClient` for smart pointers and references by forwarding to the pointee.
+macro_rules! forward_api_client {
+  ($($client:ty),*) => {$(
+    #[async_trait]
+    impl<T: ApiClient + ?Sized> ApiClient for $client {
+      async fn send_email(&self, options: EmailOptions) -> Result<SendResponse> {
+        (**self).send_email(options).await
+      }
+
+      fn prepare_email(&self, options: EmailOptions) -> Result<EmailOptions> {
+        (**self).prepare_email(options)
+      }
+
+      async fn list_templates(&self) -> Result<Value> {
+        (**self).list_templates().await
+      }
+
+      async fn render(&self, options: RenderOptions) -> Result<Value> {
+        (**self).render(options).await
+      }
+
+      async fn create_template(&self, options: TemplateOptions) -> Result<Value> {
+        (**self).create_template(options).await
+      }
+
+      async fn list_drip_campaigns(&self) -> Result<Value> {
+        (**self).list_drip_campaigns().await
+      }
+
+      async fn start_on_drip_campaign(
+        &self,
+        campaign_id: &CampaignId,
+        options: DripCampaignOptions,
+      ) -> Result<Value> {
+        (**self).start_on_drip_campaign(campaign_id, options).await
+      }
+
+      async fn remove_from_drip_campaign(
+        &self,
+        campaign_id: &CampaignId,
+        recipient_address: &str,
+      ) -> Result<Value> {
+        (**self).remove_from_drip_campaign(campaign_id, recipient_address).await
+      }
+
+      async fn drip_campaign_details(&self, campaign_id: &CampaignId) -> Result<Value> {
+        (**self).drip_campaign_details(campaign_id).await
+      }
+
+      async fn drip_campaign_steps(
+        &self,
+        campaign_id: &CampaignId,
+      ) -> Result<Vec<DripCampaignStep>> {
+        (**self).drip_campaign_steps(campaign_id).await
+      }
+
+      async fn drip_campaign_customers(
+        &self,
+        campaign_id: &CampaignId,
+      ) -> Result<Vec<DripCampaignCustomer>> {
+        (**self).drip_campaign_customers(campaign_id).await
+      }
+
+      async fn customer_get(&self, email: &str) -> Result<Value> {
+        (**self).customer_get(email).await
+      }
+
+      async fn customer_create(&self, options: CustomerOptions) -> Result<Value> {
+        (**self).customer_create(options).await
+      }
+
+      async fn customer_update(
+        &self,
+        email: &str,
+        options: CustomerUpdateOptions,
+      ) -> Result<Value> {
+        (**self).customer_update(email, options).await
+      }
+
+      async fn customer_delete(&self, email: &str) -> Result<Value> {
+        (**self).customer_delete(email).await
+      }
+
+      async fn customer_add_event(
+        &self,
+        email: &str,
+        options: CustomerEventOptions,
+      ) -> Result<Value> {
+        (**self).customer_add_event(email, options).await
+      }
+
+      async fn customer_email_log(
+        &self,
+        email: &str,
+        query: EmailLogQuery,
+      ) -> Result<Vec<EmailLog>> {
+        (**self).customer_email_log(email, query).await
+      }
+
+      async fn logs(&self, query: EmailLogQuery) -> Result<Vec<EmailLog>> {
+        (**self).logs(query).await
+      }
+
+      async fn log(&self, log_id: &LogId) -> Result<EmailLog> {
+        (**self).log(log_id).await
+      }
+
+      async fn log_events(&self, log_id: &LogId) -> Result<Vec<LogEvent>> {
+        (**self).log_events(log_id).await
+      }
+
+      async fn get_template(&self, template_id: &TemplateId) -> Result<Value> {
+        (**self).get_template(template_id).await
+      }
+
+      async fn update_template(
+        &self,
+        template_id: &TemplateId,
+        options: TemplateUpdateOptions,
+      ) -> Result<Value> {
+        (**self).update_template(template_id, options).await
+      }
+
+      async fn delete_template(&self, template_id: &TemplateId) -> Result<Value> {
+        (**self).delete_template(template_id).await
+      }
+
+      async fn list_template_versions(&self, template_id: &TemplateId) -> Result<Value> {
+        (**self).list_template_versions(template_id).await
+      }
+
+      async fn get_template_version(
+        &self,
+        template_id: &TemplateId,
+        version_id: &str,
+      ) -> Result<Value> {
+        (**self).get_template_version(template_id, version_id).await
+      }
+
+      async fn update_template_version(
+        &self,
+        template_id: &TemplateId,
+        version_id: &str,
+        options: TemplateOptions,
+      ) -> Result<Value> {
+        (**self).update_template_version(template_id, version_id, options).await
+      }
+
+      async fn create_template_version(
+        &self,
+        template_id: &TemplateId,
+        options: TemplateOptions,
+      ) -> Result<Value> {
+        (**self).create_template_version(template_id, options).await
+      }
+
+      async fn drips_unsubscribe(&self, email_address: &str) -> Result<Value> {
+        (**self).drips_unsubscribe(email_address).await
+      }
+
+      async fn unsubscribe_status(&self, email_address: &str) -> Result<UnsubscribeStatus> {
+        (**self).unsubscribe_status(email_address).await
+      }
+
+      async fn unsubscribe(&self, email_address: &str) -> Result<Value> {
+        (**self).unsubscribe(email_address).await
+      }
+
+      async fn resubscribe(&self, email_address: &str) -> Result<Value> {
+        (**self).resubscribe(email_address).await
+      }
+    }
+  )*};
+}
+
+forward_api_client!(&T, Box<T>, Arc<T>);
+
+/// Delay used when a rate-limited response has no usable `Retry-After` header.
+const DEFAULT_RATE_LIMIT_WAIT: Duration = Duration::from_secs(1);
+
+/// Shortest delay before retrying a rate-limited call.
+///
+/// A `Retry-After` of zero or a date in the past would otherwise retry
+/// immediately, and since no time counts against the wait limit, forever.
+const MIN_RATE_LIMIT_WAIT: Duration = Duration::from_millis(250);
+
+/// Returns how long to wait before retrying a rate-limited call, if it should be retried.
+///
+/// # Arguments
+/// * `result` - Outcome of the last attempt
+/// * `max_wait` - Longest total time the call may wait out rate limiting
+/// * `waited` - Time already spent waiting out rate limiting
+fn rate_limit_wait<T>(
+  result: &Result<T>,
+  max_wait: Option<Duration>,
+  waited: Duration,
+) -> Option<Duration> {
+  let max_wait = max_wait?;
+
+  match result {
+    Err(Error::RateLimited { retry_after, .. }) => {
+      let wait = retry_after
+        .unwrap_or(DEFAULT_RATE_LIMIT_WAIT)
+        .max(MIN_RATE_LIMIT_WAIT);
+      (waited + wait <= max_wait).then_some(wait)
+    }
+    _ => None,
+  }
+}
+
+/// Returns the current time as a Unix timestamp.
+fn unix_timestamp() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|elapsed| elapsed.as_secs())
+    .unwrap_or_default()
+}
+
+/// Maps an API response to the deserialized body or the matching error.
+fn map_response<R: DeserializeOwned>(
+  context: RequestContext,
+  raw: RawResponse,
+) -> Result<ResponseEnvelope<R>> {
+  match raw.status {
+    StatusCode::OK | StatusCode::CREATED | StatusCode::ACCEPTED => {
+      let data = raw.json()?;
+      Ok(ResponseEnvelope::new(data, raw))
+    }
+    StatusCode::NOT_FOUND => Err(Error::InvalidEndpoint(context.endpoint)),
+    StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(Error::InvalidCredentials),
+    StatusCode::TOO_MANY_REQUESTS => Err(Error::RateLimited {
+      retry_after: raw.retry_after(),
+      context: Some(context),
+    }),
+    StatusCode::BAD_REQUEST => Err(Error::InvalidRequest {
+      context,
+      error: Box::new(SwuApiError::from_body(raw.text())),
+    }),
+    status => Err(Error::ApiError {
+      context,
+      status: status.as_u16(),
+      error: Box::new(SwuApiError::from_body(raw.text())),
+    }),
+  }
+}
+
+/// Redirects every recipient of an email to `address`, recording the originals.
+fn intercept_recipients(options: &mut EmailOptions, address: &str) {
+  let addresses = |recipients: &Option<Vec<Recipient>>| -> Vec<String> {
+    recipients
+      .iter()
+      .flatten()
+      .map(|recipient| recipient.address.clone())
+      .collect()
+  };
+
+  let to = options.recipient.address.clone();
+  let cc = addresses(&options.cc);
+  let bcc = addresses(&options.bcc);
+
+  let all = std::iter::once(to.clone())
+    .chain(cc.iter().cloned())
+    .chain(bcc.iter().cloned())
+    .collect::<Vec<_>>()
+    .join(", ");
+
+  options.data.get_or_insert_with(HashMap::new).insert(
+    ORIGINAL_RECIPIENTS_KEY.to_string(),
+    serde_json::json!({ "to": to, "cc": cc, "bcc": bcc }),
+  );
+  options
+    .headers
+    .get_or_insert_with(HashMap::new)
+    .insert(ORIGINAL_RECIPIENTS_HEADER.to_string(), all);
+
+  for recipient in std::iter::once(&mut options.recipient).chain(
+    options
+      .cc
+      .iter_mut()
+      .chain(options.bcc.iter_mut())
+      .flatten(),
+  ) {
+    recipient.address = address.to_string();
+  }
+}
+
+/// Redirects a drip campaign's recipient to `address`, recording the original.
+///
+/// The original address is kept in the email data like for intercepted
+/// emails; drip activations carry no headers.
+fn intercept_drip_recipient(options: &mut DripCampaignOptions, address: &str) {
+  let to = std::mem::replace(&mut options.recipient_address, address.to_string());
+  options.email_data.get_or_insert_with(HashMap::new).insert(
+    ORIGINAL_RECIPIENTS_KEY.to_string(),
+    serde_json::json!({ "to": to, "cc": [], "bcc": [] }),
+  );
+}
+
+/// Maps an HTTP client error to the matching crate error.
+fn transport_error(error: reqwest::Error) -> Error {
+  if error.is_timeout() {
+    Error::Timeout { context: None }
+  } else if error.is_connect() {
+    Error::ConnectionFailed { context: None }
+  } else {
+    Error::from(error)
+  }
+}
+
+/// Helper functions to build email options more easily.
+///
+/// This module contains utility functions that simplify the creation of common
+/// email components such as email data, recipients, and senders.
+pub mod helpers {
+  use super::*;
+  use serde_json::Value;
+  use std::collections::HashMap;
+
+  /// Creates a HashMap of email template data from key-value pairs.
+  ///
+  /// This helper function simplifies the creation of template data for emails.
+  /// It accepts any iterable collection of key-value pairs and converts them into
+  /// the required HashMap format for email template data.
+  ///
+  /// # Arguments
+  /// * `pairs` - An iterable of key-value pairs where keys can be converted to String
+  ///   and values can be converted to serde_json::Value
+  ///
+  /// # Returns
+  /// A HashMap with string keys and JSON values
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::{ApiClient, api::helpers};
+  /// use serde_json::json;
+  ///
+  /// let data = helpers::email_data([
+  ///   ("name", json!("John Doe")),
+  ///   ("order_id", json!("12345")),
+  ///   ("items", json!(["item1", "item2"]))
+  /// ]);
+  ///
+  /// assert_eq!(data["name"], "John Doe");
+  /// assert_eq!(data["order_id"], "12345");
+  /// ```
+  pub fn email_data<K, V>(pairs: impl IntoIterator<Item = (K, V)>) -> HashMap<String, Value>
+  where
+    K: Into<String>,
+    V: Into<Value>,
+  {
+    pairs
+      .into_iter()
+      .map(|(k, v)| (k.into(), v.into()))
+      .collect()
+  }
+
+  /// Creates a Recipient with an email address and optional name.
+  ///
+  /// # Arguments
+  /// * `email` - The recipient's email address
+  /// * `name` - Optional recipient name
+  ///
+  /// # Returns
+  /// A configured Recipient instance
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::api::helpers;
+  ///
+  /// let recipient = helpers::recipient("user@example.com", None::<&str>);
+  ///
+  /// let recipient = helpers::recipient("user@example.com", Some("John Doe"));
+  /// ```
+  pub fn recipient(email: impl Into<String>, name: Option<impl Into<String>>) -> Recipient {
+    let mut recipient = Recipient::new(email);
+    if let Some(name) = name {
+      recipient = recipient.with_name(name);
+    }
+    recipient
+  }
+
+  /// Creates a Sender with an email address and optional name and reply-to address.
+  ///
+  /// # Arguments
+  /// * `email` - The sender's email address
+  /// * `name` - Optional sender name
+  /// * `reply_to` - Optional reply-to email address
+  ///
+  /// # Returns
+  /// A configured Sender instance
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::api::helpers;
+  ///
+  /// let sender = helpers::sender("support@company.com", None::<&str>, None::<&str>);
+  ///
+  /// let sender = helpers::sender("support@company.com", Some("Support Team"), None::<&str>);
+  ///
+  /// let sender = helpers::sender(
+  ///   "noreply@company.com",
+  ///   Some("Company Name"),
+  ///   Some("support@company.com")
+  /// );
+  /// ```
+  pub fn sender(
+    email: impl Into<String>,
+    name: Option<impl Into<String>>,
+    reply_to: Option<impl Into<String>>,
+  ) -> Sender {
+    let mut sender = Sender::new(email);
+
+    if let Some(name) = name {
+      sender = sender.with_name(name);
+    }
+
+    if let Some(reply_to) = reply_to {
+      sender = sender.with_reply_to(reply_to);
+    }
+
+    sender
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::config::ApiVersion;
+  use crate::ids::ReceiptId;
+  use crate::models::{EmailSummary, EventType};
+  use crate::request::REDACTED;
+
+  struct MockApiClient;
+
+  fn mock_log(email: &str) -> EmailLog {
+    EmailLog {
+      id: LogId::new("log_mock").unwrap(),
+      created: 1672574400,
+      recipient_address: email.to_string(),
+      recipient_name: None,
+      status: "sent".to_string(),
+      message: None,
+      email_id: Some(TemplateId::new("tem_mock").unwrap()),
+      email_name: None,
+      email_version: None,
+    }
+  }
+
+  #[async_trait]
+  impl ApiClient for MockApiClient {
+    async fn send_email(&self, options: EmailOptions) -> Result<SendResponse> {
+      if options.email_id.is_empty() {
+        return Err(Error::MissingTemplateId);
+      }
+      Ok(SendResponse {
+        success: true,
+        status: "OK".to_string(),
+        receipt_id: ReceiptId::new("log_mock")?,
+        email: EmailSummary {
+          name: options.email_id,
+          version_name: None,
+          locale: options.locale,
+        },
+      })
+    }
+
+    async fn list_templates(&self) -> Result<Value> {
+      Ok(serde_json::json!([
+        {"id": "template_1", "name": "Template 1"},
+        {"id": "template_2", "name": "Template 2"}
+      ]))
+    }
+
+    async fn render(&self, options: RenderOptions) -> Result<Value> {
+      Ok(serde_json::json!({
+        "template": options.template,
+        "rendered_template": "<html>Rendered template</html>"
+      }))
+    }
+
+    async fn create_template(&self, options: TemplateOptions) -> Result<Value> {
+      Ok(serde_json::json!({
+        "id": "new_template",
+        "name": options.name,
+        "created": true
+      }))
+    }
+
+    async fn list_drip_campaigns(&self) -> Result<Value> {
+      Ok(serde_json::json!([
+        {"id": "campaign_1", "name": "Campaign 1"},
+        {"id": "campaign_2", "name": "Campaign 2"}
+      ]))
+    }
+
+    async fn start_on_drip_campaign(
+      &self,
+      campaign_id: &CampaignId,
+      options: DripCampaignOptions,
+    ) -> Result<Value> {
+      Ok(serde_json::json!({
+        "success": true,
+        "recipient": options.recipient_address,
+        "campaign_id": campaign_id
+      }))
+    }
+
+    async fn remove_from_drip_campaign(
+      &self,
+      campaign_id: &CampaignId,
+      recipient_address: &str,
+    ) -> Result<Value> {
+      Ok(serde_json::json!({
+        "success": true,
+        "recipient": recipient_address,
+        "campaign_id": campaign_id
+      }))
+    }
+
+    async fn drip_campaign_details(&self, campaign_id: &CampaignId) -> Result<Value> {
+      Ok(serde_json::json!({
+        "id": campaign_id,
+        "name": format!("Campaign {}", campaign_id),
+        "details": "Some details"
+      }))
+    }
+
+    async fn drip_campaign_steps(
+      &self,
+      _campaign_id: &CampaignId,
+    ) -> Result<Vec<DripCampaignStep>> {
+      Ok(vec![DripCampaignStep {
+        id: "dcs_1".to_string(),
+        name: Some("Step 1".to_string()),
+        email_id: Some(TemplateId::new("tem_1").unwrap()),
+        delay_seconds: Some(0),
+      }])
+    }
+
+    async fn drip_campaign_customers(
+      &self,
+      _campaign_id: &CampaignId,
+    ) -> Result<Vec<DripCampaignCustomer>> {
+      Ok(vec![DripCampaignCustomer {
+        email: "customer@example.com".to_string(),
+        step_id: Some("dcs_1".to_string()),
+        status: None,
+        created: None,
+      }])
+    }
+
+    async fn customer_get(&self, email: &str) -> Result<Value> {
+      Ok(serde_json::json!({
+        "email": email,
+        "data": {"name": "Test Customer"}
+      }))
+    }
+
+    async fn customer_create(&self, options: CustomerOptions) -> Result<Value> {
+      Ok(serde_json::json!({
+        "success": true,
+        "email": options.email
+      }))
+    }
+
+    async fn customer_update(&self, email: &str, options: CustomerUpdateOptions) -> Result<Value> {
+      Ok(serde_json::json!({
+        "success": true,
+        "email": email,
+        "data": options.data
+      }))
+    }
+
+    async fn customer_delete(&self, email: &str) -> Result<Value> {
+      Ok(serde_json::json!({
+        "success": true,
+        "email": email
+      }))
+    }
+
+    async fn customer_add_event(
+      &self,
+      email: &str,
+      options: CustomerEventOptions,
+    ) -> Result<Value> {
+      Ok(serde_json::json!({
+        "success": true,
+        "email": email,
+        "event_name": options.event_name
+      }))
+    }
+
+    async fn customer_email_log(&self, email: &str, query: EmailLogQuery) -> Result<Vec<EmailLog>> {
+      let count = query.count.unwrap_or(1) as usize;
+      Ok(vec![mock_log(email); count])
+    }
+
+    async fn logs(&self, query: EmailLogQuery) -> Result<Vec<EmailLog>> {
+      let count = query.count.unwrap_or(1) as usize;
+      Ok(vec![mock_log("test@example.com"); count])
+    }
+
+    async fn log(&self, log_id: &LogId) -> Result<EmailLog> {
+      Ok(EmailLog {
+        id: log_id.clone(),
+        status: "delivered".to_string(),
+        ..mock_log("test@example.com")
+      })
+    }
+
+    async fn log_events(&self, _log_id: &LogId) -> Result<Vec<LogEvent>> {
+      Ok(vec![
+        LogEvent {
+          event_type: EventType::Sent,
+          created: 1672574400,
+          message: None,
+        },
+        LogEvent {
+          event_type: EventType::Delivered,
+          created: 1672574460,
+          message: None,
+        },
+      ])
+    }
+
+    async fn get_template(&self, template_id: &TemplateId) -> Result<Value> {
+      Ok(serde_json::json!({
+        "id": template_id,
+        "name": "Template 1",
+        "tags": []
+      }))
+    }
+
+    async fn update_template(
+      &self,
+      template_id: &TemplateId,
+      options: TemplateUpdateOptions,
+    ) -> Result<Value> {
+      Ok(serde_json::json!({
+        "id": template_id,
+        "name": options.name,
+        "tags": options.tags
+      }))
+    }
+
+    async fn delete_template(&self, template_id: &TemplateId) -> Result<Value> {
+      Ok(serde_json::json!({
+        "success": true,
+        "template_id": template_id
+      }))
+    }
+
+    async fn list_template_versions(&self, template_id: &TemplateId) -> Result<Value> {
+      Ok(serde_json::json!({
+        "template_id": template_id,
+        "versions": [
+          {"id": "v1", "name": "Version 1"},
+          {"id": "v2", "name": "Version 2"}
+        ]
+      }))
+    }
+
+    async fn get_template_version(
+      &self,
+      template_id: &TemplateId,
+      version_id: &str,
+    ) -> Result<Value> {
+      Ok(serde_json::json!({
+        "template_id": template_id,
+        "version_id": version_id,
+        "html": "<html>Template content</html>"
+      }))
+    }
+
+    async fn update_template_version(
+      &self,
+      template_id: &TemplateId,
+      version_id: &str,
+      options: TemplateOptions,
+    ) -> Result<Value> {
+      Ok(serde_json::json!({
+        "success": true,
+        "template_id": template_id,
+        "version_id": version_id,
+        "name": options.name
+      }))
+    }
+
+    async fn create_template_version(
+      &self,
+      template_id: &TemplateId,
+      options: TemplateOptions,
+    ) -> Result<Value> {
+      Ok(serde_json::json!({
+        "success": true,
+        "template_id": template_id,
+        "new_version": {
+          "id": "new_version",
+          "name": options.name
+        }
+      }))
+    }
+
+    async fn drips_unsubscribe(&self, email_address: &str) -> Result<Value> {
+      if email_address.is_empty() {
+        return Err(Error::MissingRecipientAddress);
+      }
+
+      Ok(serde_json::json!({
+        "success": true,
+        "email": email_address
+      }))
+    }
+
+    async fn unsubscribe_status(&self, email_address: &str) -> Result<UnsubscribeStatus> {
+      Ok(UnsubscribeStatus {
+        email_address: email_address.to_string(),
+        unsubscribed: false,
+        unsubscribed_at: None,
+        reason: None,
+      })
+    }
+
+    async fn unsubscribe(&self, email_address: &str) -> Result<Value> {
+      Ok(serde_json::json!({
+        "success": true,
+        "email": email_address
+      }))
+    }
+
+    async fn resubscribe(&self, email_address: &str) -> Result<Value> {
+      Ok(serde_json::json!({
+        "success": true,
+        "email": email_address
+      }))
+    }
+  }
+
+  #[tokio::test]
+  async fn test_api_initialization() {
+    let api = Api::with_api_key("test-api-key");
+    assert_eq!(api.config().api_key, "test-api-key");
+    assert_eq!(api.config().api_version, ApiVersion::V1);
+
+    let custom_config = Config::new("custom-key")
+      .with_api_version("2")
+      .with_debug(true);
+
+    let api_with_config = Api::new(custom_config);
+    assert_eq!(api_with_config.config().api_key, "custom-key");
+    assert_eq!(api_with_config.config().api_version, ApiVersion::V2);
+    assert!(api_with_config.config().debug);
+  }
+
+  #[test]
+  fn test_api_clones_share_state() {
+    let api = Api::with_api_key("test-api-key");
+    let clone = api.clone();
+
+    assert!(Arc::ptr_eq(&api.inner, &clone.inner));
+    assert!(std::ptr::eq(api.config(), clone.config()));
+
+    let scoped = api.with_timeout(Duration::from_secs(1));
+    assert!(Arc::ptr_eq(&api.inner, &scoped.inner));
+    assert_eq!(scoped.timeout, Some(Duration::from_secs(1)));
+    assert_eq!(api.timeout, None);
+  }
+
+  #[test]
+  fn test_prepare_email_validates_and_encodes_names() {
+    let recipient = Recipient::new("soren@example.com").with_name("Søren Ångström");
+    let options = EmailOptions::new("template-id", recipient)
+      .with_sender(Sender::new("noreply@example.com").with_name("Zoë"))
+      .with_cc(vec![
+        Recipient::new("cc@example.com").with_name("Plain Name"),
+      ]);
+
+    let api = Api::with_api_key("api-key");
+    let prepared = api.prepare_email(options.clone()).unwrap();
+    assert_eq!(prepared, options);
+
+    let api = Api::new(Config::new("api-key").with_encoded_display_names(true));
+    let prepared = api.prepare_email(options).unwrap();
+    assert_eq!(
+      prepared.recipient.name.as_deref(),
+      Some("=?UTF-8?B?U8O4cmVuIMOFbmdzdHLDtm0=?=")
+    );
+    assert_eq!(
+      prepared.sender.unwrap().name.as_deref(),
+      Some("=?UTF-8?B?Wm/Dqw==?=")
+    );
+    assert_eq!(prepared.cc.unwrap()[0].name.as_deref(), Some("Plain Name"));
+
+    let recipient = Recipient::new("x@example.com").with_name("Evil\r\nBcc: y@example.com");
+    let result = api.prepare_email(EmailOptions::new("template-id", recipient));
+    assert!(matches!(result, Err(Error::InvalidDisplayName(_))));
+
+    let result = api.prepare_email(EmailOptions::new("", Recipient::new("x@example.com")));
+    assert!(matches!(result, Err(Error::MissingTemplateId)));
+  }
+
+  #[test]
+  fn test_prepare_email_applies_default_sender() {
+    let default_sender = Sender::new("noreply@example.com").with_name("Example");
+    let api = Api::new(Config::new("api-key").with_default_sender(default_sender.clone()));
+
+    let options = EmailOptions::new("template-id", Recipient::new("user@example.com"));
+    let prepared = api.prepare_email(options.clone()).unwrap();
+    assert_eq!(prepared.sender, Some(default_sender));
+
+    let own_sender = Sender::new("billing@example.com");
+    let prepared = api
+      .prepare_email(options.with_sender(own_sender.clone()))
+      .unwrap();
+    assert_eq!(prepared.sender, Some(own_sender));
+  }
+
+  #[test]
+  fn test_prepare_email_applies_default_esp_account() {
+    let api = Api::new(Config::new("api-key").with_default_esp_account("esp_default"));
+
+    let options = EmailOptions::new("template-id", Recipient::new("user@example.com"));
+    let prepared = api.prepare_email(options.clone()).unwrap();
+    assert_eq!(prepared.esp_account.as_deref(), Some("esp_default"));
+
+    let prepared = api
+      .prepare_email(options.with_esp_account("esp_marketing"))
+      .unwrap();
+    assert_eq!(prepared.esp_account.as_deref(), Some("esp_marketing"));
+
+    let api = Api::with_api_key("api-key");
+    let prepared = api
+      .prepare_email(EmailOptions::new(
+        "template-id",
+        Recipient::new("user@example.com"),
+      ))
+      .unwrap();
+    assert_eq!(prepared.esp_account, None);
+  }
+
+  #[test]
+  fn test_prepare_email_applies_default_locale() {
+    let api = Api::new(Config::new("api-key").with_default_locale("fr-CA"));
+
+    let options = EmailOptions::new("template-id", Recipient::new("user@example.com"));
+    let prepared = api.prepare_email(options.clone()).unwrap();
+    assert_eq!(prepared.locale.as_deref(), Some("fr-CA"));
+
+    let prepared = api.prepare_email(options.with_locale("de-DE")).unwrap();
+    assert_eq!(prepared.locale.as_deref(), Some("de-DE"));
+  }
+
+  #[test]
+  fn test_prepare_email_merges_global_tags_and_headers() {
+    let mut global_headers = HashMap::new();
+    global_headers.insert("X-Env".to_string(), "prod".to_string());
+    global_headers.insert("X-Service".to_string(), "billing".to_string());
+
+    let api = Api::new(
+      Config::new("api-key")
+        .with_global_tags(vec!["env:prod".to_string(), "service:billing".to_string()])
+        .with_global_headers(global_headers),
+    );
+
+    let prepared = api
+      .prepare_email(EmailOptions::new(
+        "template-id",
+        Recipient::new("user@example.com"),
+      ))
+      .unwrap();
+    assert_eq!(
+      prepared.tags,
+      Some(vec!["env:prod".to_string(), "service:billing".to_string()])
+    );
+    assert_eq!(prepared.headers.unwrap().len(), 2);
+
+    let mut headers = HashMap::new();
+    headers.insert("X-Service".to_string(), "invoices".to_string());
+    let options = EmailOptions::new("template-id", Recipient::new("user@example.com"))
+      .with_tags(vec!["invoice".to_string(), "env:prod".to_string()])
+      .with_headers(headers);
+
+    let prepared = api.prepare_email(options).unwrap();
+    assert_eq!(
+      prepared.tags,
+      Some(vec![
+        "service:billing".to_string(),
+        "invoice".to_string(),
+        "env:prod".to_string()
+      ])
+    );
+    let headers = prepared.headers.unwrap();
+    assert_eq!(headers["X-Env"], "prod");
+    assert_eq!(headers["X-Service"], "invoices");
+  }
+
+  #[test]
+  fn test_prepare_email_intercepts_recipients() {
+    let api = Api::new(Config::new("api-key").with_recipient_intercept("catch-all@example.com"));
+
+    let options = EmailOptions::new(
+      "template-id",
+      Recipient::new("user@example.com").with_name("User"),
+    )
+    .with_cc(vec![Recipient::new("cc@example.com")])
+    .with_bcc(vec![Recipient::new("bcc@example.com")]);
+
+    let prepared = api.prepare_email(options).unwrap();
+    assert_eq!(prepared.recipient.address, "catch-all@example.com");
+    assert_eq!(prepared.recipient.name.as_deref(), Some("User"));
+    assert_eq!(prepared.cc.unwrap()[0].address, "catch-all@example.com");
+    assert_eq!(prepared.bcc.unwrap()[0].address, "catch-all@example.com");
+    assert_eq!(
+      prepared.data.unwrap()[ORIGINAL_RECIPIENTS_KEY],
+      serde_json::json!({
+        "to": "user@example.com",
+        "cc": ["cc@example.com"],
+        "bcc": ["bcc@example.com"]
+      })
+    );
+    assert_eq!(
+      prepared.headers.unwrap()[ORIGINAL_RECIPIENTS_HEADER],
+      "user@example.com, cc@example.com, bcc@example.com"
+    );
+  }
+
+  #[test]
+  fn test_intercepted_send_is_redacted() {
+    let api = Api::new(Config::new("api-key").with_recipient_intercept("catch-all@example.com"));
+    let options = EmailOptions::new("template-id", Recipient::new("user@example.com"))
+      .with_cc(vec![Recipient::new("cc@example.com")]);
+
+    let prepared = api.simulate_send_email(options).unwrap().sanitized(&[]);
+    let body = prepared.body.unwrap();
+    assert!(!body.contains("@example.com"), "unredacted body: {}", body);
+
+    let body: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(body["email_data"][ORIGINAL_RECIPIENTS_KEY], REDACTED);
+    assert_eq!(body["headers"][ORIGINAL_RECIPIENTS_HEADER], REDACTED);
+  }
+
+  #[tokio::test]
+  async fn test_build_url() {
+    let api = Api::with_api_key("api-key");
+    let url = api
+      .build_url(&Endpoint::new(["test-endpoint"]))
+      .expect("Failed to build URL");
+    assert!(url.as_str().contains("/api/v1/test-endpoint"));
+    assert!(url.as_str().starts_with("https://api.sendwithus.com"));
+
+    let url = api
+      .build_url(&Endpoint::new(["drip_campaigns", "dc_123", "activate"]))
+      .expect("Failed to build URL");
+    assert_eq!(
+      url.as_str(),
+      "https://api.sendwithus.com/api/v1/drip_campaigns/dc_123/activate"
+    );
+
+    let url = api
+      .build_url(&Endpoint::new(["customers", "user+tag@example.com"]))
+      .expect("Failed to build URL");
+    assert_eq!(url.path(), "/api/v1/customers/user+tag@example.com");
+
+    let url = api
+      .build_url(&Endpoint::new(["customers", "a/b?c@example.com", "logs"]))
+      .expect("Failed to build URL");
+    assert_eq!(url.path(), "/api/v1/customers/a%2Fb%3Fc@example.com/logs");
+    assert_eq!(url.query(), None);
+
+    let url = api
+      .build_url(
+        &Endpoint::new(["logs"]).with_query(&EmailLogQuery::new().with_count(10).with_offset(20)),
+      )
+      .expect("Failed to build URL");
+    assert_eq!(url.path(), "/api/v1/logs");
+    assert_eq!(url.query(), Some("count=10&offset=20"));
+
+    let api = Api::new(Config::new("api-key").with_api_version("v2"));
+    let url = api
+      .build_url(&Endpoint::new(["send"]))
+      .expect("Failed to build URL");
+    assert_eq!(url.path(), "/api/v2/send");
+
+    let api = Api::new(Config::new("api-key").with_api_version("1/"));
+    assert!(matches!(
+      api.build_url(&Endpoint::new(["send"])),
+      Err(Error::InvalidConfig(_))
+    ));
+  }
+
+  #[test]
+  fn test_simulate_redacts_api_key() {
+    let api = Api::with_api_key("secret-key");
+    let payload = serde_json::json!({"recipient_address": "user@example.com"});
+
+    let prepared = api
+      .simulate(
+        reqwest::Method::POST,
+        "drip_campaigns/dc_123/deactivate",
+        Some(&payload),
+      )
+      .unwrap();
+
+    assert_eq!(prepared.method, reqwest::Method::POST);
+    assert_eq!(
+      prepared.url.as_str(),
+      "https://api.sendwithus.com/api/v1/drip_campaigns/dc_123/deactivate"
+    );
+    assert_eq!(prepared.header("X-SWU-API-KEY"), Some("[REDACTED]"));
+    assert_eq!(prepared.header("Content-Type"), Some("application/json"));
+    assert_eq!(prepared.body_json().unwrap(), payload);
+  }
+
+  #[test]
+  fn test_simulate_send_email_uses_prepared_options() {
+    let api = Api::new(Config::new("secret-key").with_encoded_display_names(true));
+    let recipient = Recipient::new("user@example.com").with_name("Zoë");
+
+    let prepared = api
+      .simulate_send_email(EmailOptions::new("tem_123", recipient))
+      .unwrap();
+
+    let body = prepared.body_json().unwrap();
+    assert_eq!(prepared.url.path(), "/api/v1/send");
+    assert_eq!(body["email_id"], "tem_123");
+    assert_eq!(body["recipient"]["name"], "=?UTF-8?B?Wm/Dqw==?=");
+
+    let result = api.simulate_send_email(EmailOptions::new("", Recipient::new("x@example.com")));
+    assert!(matches!(result, Err(Error::MissingTemplateId)));
+  }
+
+  #[tokio::test]
+  async fn test_mock_client_send_email() {
+    let mock_client = MockApiClient;
+
+    let recipient = Recipient::new("test@example.com").with_name("Test User");
+    let options = EmailOptions::new("template-id", recipient);
+    let result = mock_client.send_email(options).await;
+    assert!(result.is_ok());
+
+    let recipient = Recipient::new("test@example.com");
+    let invalid_options = EmailOptions::new("", recipient);
+    let result = mock_client.send_email(invalid_options).await;
+    assert!(result.is_err());
+    assert!(matches!(result.unwrap_err(), Error::MissingTemplateId));
+  }
+
+  #[tokio::test]
+  async fn test_client_behind_pointers_and_trait_objects() {
+    async fn template_count(client: impl ApiClient) -> usize {
+      client
+        .list_templates()
+        .await
+        .unwrap()
+        .as_array()
+        .unwrap()
+        .len()
+    }
+
+    let shared: Arc<dyn ApiClient> = Arc::new(MockApiClient);
+    assert_eq!(template_count(&MockApiClient).await, 2);
+    assert_eq!(template_count(Box::new(MockApiClient)).await, 2);
+    assert_eq!(template_count(Arc::clone(&shared)).await, 2);
+    assert_eq!(template_count(shared.as_ref()).await, 2);
+  }
+
+  #[tokio::test]
+  async fn test_mock_client_customer_email_log() {
+    let mock_client = MockApiClient;
+
+    let result = mock_client
+      .customer_email_log("test@example.com", EmailLogQuery::new())
+      .await;
+    assert!(result.is_ok());
+    let logs = result.unwrap();
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0].recipient_address, "test@example.com");
+
+    let result = mock_client
+      .customer_email_log("test@example.com", EmailLogQuery::new().with_count(2))
+      .await;
+    assert!(result.is_ok());
+    let logs = result.unwrap();
+    assert_eq!(logs.len(), 2);
+  }
+
+  #[tokio::test]
+  async fn test_mock_client_log() {
+    let mock_client = MockApiClient;
+
+    let log_id = LogId::new("log_TESTTEST123").unwrap();
+    let result = mock_client.log(&log_id).await;
+    assert!(result.is_ok());
+    let log = result.unwrap();
+    assert_eq!(log.id, log_id);
+    assert_eq!(log.status, "delivered");
+
+    let events = mock_client.log_events(&log_id).await.unwrap();
+    assert_eq!(events[1].event_type, EventType::Delivered);
+  }
+
+  #[tokio::test]
+  async fn test_mock_client_start_on_drip_campaign() {
+    let mock_client = MockApiClient;
+    let email = "some@email.stub";
+    let campaign_id = CampaignId::new("dc_SoMeCampaIGnID").unwrap();
+
+    let mut email_data = HashMap::new();
+    email_data.insert("foo".to_string(), serde_json::json!("bar"));
+
+    let options = DripCampaignOptions {
+      recipient_address: email.to_string(),
+      email_data: Some(email_data),
+      tags: None,
+      locale: None,
+    };
+
+    let result = mock_client
+      .start_on_drip_campaign(&campaign_id, options)
+      .await;
+    assert!(result.is_ok());
+    let value = result.unwrap();
+    assert_eq!(value["recipient"], email);
+    assert_eq!(value["campaign_id"], campaign_id.as_str());
+  }
+
+  #[tokio::test]
+  async fn test_mock_client_render() {
+    let mock_client = MockApiClient;
+    let template_id = "template-id";
+    let version_id = Some("some-version-id".to_string());
+    let locale = Some("fr-CA".to_string());
+
+    let mut template_data = HashMap::new();
+    template_data.insert("foo".to_string(), serde_json::json!("bar"));
+
+    let options = RenderOptions {
+      template: template_id.to_string(),
+      version_id,
+      template_data,
+      strict: true,
+      locale,
+    };
+
+    let result = mock_client.render(options).await;
+    assert!(result.is_ok());
+    let value = result.unwrap();
+    assert_eq!(value["template"], template_id);
+    assert_eq!(value["rendered_template"], "<html>Rendered template</html>");
+  }
+
+  #[tokio::test]
+  async fn test_mock_client_drips_unsubscribe() {
+    let mock_client = MockApiClient;
+
+    let result = mock_client.drips_unsubscribe("test@example.com").await;
+    assert!(result.is_ok());
+    let value = result.unwrap();
+    assert_eq!(value["email"], "test@example.com");
+
+    let result = mock_client.drips_unsubscribe("").await;
+    assert!(result.is_err());
+    assert!(matches!(
+      result.unwrap_err(),
+      Error::MissingRecipientAddress
+    ));
+  }
+
+  #[test]
+  fn test_helpers_email_data() {
+    let data = helpers::email_data([("name", "John"), ("age", "30")]);
+
+    assert_eq!(data["name"], "John");
+    assert_eq!(data["age"], "30");
+  }
+
+  #[test]
+  fn test_helpers_recipient() {
+    let recipient = helpers::recipient("test@example.com", Some("Test User"));
+
+    assert_eq!(recipient.address, "test@example.com");
+    assert_eq!(recipient.name, Some("Test User".to_string()));
+
+    let recipient = helpers::recipient("test@example.com", None::<String>);
+
+    assert_eq!(recipient.address, "test@example.com");
+    assert_eq!(recipient.name, None);
+  }
+
+  #[test]
+  fn test_helpers_sender() {
+    let sender = helpers::sender(
+      "sender@example.com",
+      Some("Sender Name"),
+      Some("reply@example.com"),
+    );
+
+    assert_eq!(sender.address, "sender@example.com");
+    assert_eq!(sender.name, Some("Sender Name".to_string()));
+    assert_eq!(sender.reply_to, Some("reply@example.com".to_string()));
+
+    let sender = helpers::sender("sender@example.com", None::<String>, None::<String>);
+
+    assert_eq!(sender.address, "sender@example.com");
+    assert_eq!(sender.name, None);
+    assert_eq!(sender.reply_to, None);
+  }
+}
+
+#[cfg(test)]
+mod request_tests {
+  use super::*;
+  use crate::testing::fixtures::EmailLogFixture;
+  use mockito::Matcher;
+  use reqwest::Client;
+  use serde_json::{Value, json};
+  use std::net::TcpListener;
+
+  #[tokio::test]
+  async fn test_request_success() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
+
+    let mock = mock_server
+      .mock("GET", "/api/v1/test-endpoint")
+      .match_header("Content-Type", "application/json")
+      .match_header("X-SWU-API-KEY", "test-api-key")
+      .match_header("X-SWU-API-CLIENT", Matcher::Any)
+      .with_status(200)
+      .with_body(r#"{"success": true, "message": "Test response"}"#)
+      .create();
+
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
+
+    let response: Value = api
+      .request(
+        reqwest::Method::GET,
+        &Endpoint::new(["test-endpoint"]),
+        None::<&Value>,
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response["success"], json!(true));
+    assert_eq!(response["message"], json!("Test response"));
+
+    mock.assert();
+  }
+
+  #[tokio::test]
+  async fn test_request_sends_correlation_id() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
+
+    let generated = mock_server
+      .mock("GET", "/api/v1/emails")
+      .match_header(
+        CORRELATION_ID_HEADER,
+        Matcher::Regex(
+          "^[0-9a-f]{8}-[0-9a-f]{4}-4[0-9a-f]{3}-[0-9a-f]{4}-[0-9a-f]{12}$".to_string(),
+        ),
+      )
+      .with_status(200)
+      .with_body("[]")
+      .create();
+
+    let provided = mock_server
+      .mock("POST", "/api/v1/send")
+      .match_header(CORRELATION_ID_HEADER, "order-1234")
+      .with_status(400)
+      .with_body(r#"{"success": false, "error": "Bad request"}"#)
+      .create();
+
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
+
+    api.list_templates().await.unwrap();
+
+    let error = api
+      .with_correlation_id("order-1234")
+      .send_email(EmailOptions::new(
+        "tem_123",
+        Recipient::new("user@example.com"),
+      ))
+      .await
+      .unwrap_err();
+
+    let context = error.context().unwrap();
+    assert_eq!(context.correlation_id.as_deref(), Some("order-1234"));
+    assert!(error.to_string().contains("correlation ID order-1234"));
+
+    generated.assert();
+    provided.assert();
+  }
+
+  #[tokio::test]
+  async fn test_request_with_payload() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
+
+    let mock = mock_server
+      .mock("POST", "/api/v1/test-endpoint")
+      .match_header("Content-Type", "application/json")
+      .match_header("X-SWU-API-KEY", "test-api-key")
+      .match_body(r#"{"data":"test value"}"#)
+      .with_status(201)
+      .with_body(r#"{"success": true, "data_received": true}"#)
+      .create();
+
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
+
+    let payload = json!({"data": "test value"});
+
+    let response: Value = api
+      .request(
+        reqwest::Method::POST,
+        &Endpoint::new(["test-endpoint"]),
+        Some(&payload),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response["success"], json!(true));
+    assert_eq!(response["data_received"], json!(true));
+
+    mock.assert();
+  }
+
+  #[tokio::test]
+  async fn test_request_not_found() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
+
+    let mock = mock_server
+      .mock("GET", "/api/v1/nonexistent-endpoint")
+      .with_status(404)
+      .with_body("Not Found")
+      .create();
+
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
+
+    let result: Result<Value> = api
+      .request(
+        reqwest::Method::GET,
+        &Endpoint::new(["nonexistent-endpoint"]),
+        None::<&Value>,
+      )
+      .await;
+
+    assert!(result.is_err());
+
+    match result.unwrap_err() {
+      Error::InvalidEndpoint(endpoint) => {
+        assert_eq!(endpoint, "nonexistent-endpoint");
+      }
+      err => panic!("Unexpected error: {:?}", err),
+    }
+
+    mock.assert();
+  }
+
+  #[tokio::test]
+  async fn test_request_accepted() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
+
+    let mock = mock_server
+      .mock("POST", "/api/v1/test-endpoint")
+      .match_header("Content-Type", "application/json")
+      .match_header("X-SWU-API-KEY", "test-api-key")
+      .with_status(202)
+      .with_body(r#"{"status": "accepted", "message": "Request accepted"}"#)
+      .create();
+
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
+
+    #[derive(Debug, serde::Deserialize)]
+    struct TestResponse {
+      status: String,
+      message: String,
+    }
+
+    let response: TestResponse = api
+      .request(
+        reqwest::Method::POST,
+        &Endpoint::new(["test-endpoint"]),
+        None::<&Value>,
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status, "accepted");
+    assert_eq!(response.message, "Request accepted");
+
+    mock.assert();
+  }
+
+  #[tokio::test]
+  async fn test_request_forbidden() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
+
+    let mock = mock_server
+      .mock("GET", "/api/v1/test-endpoint")
+      .with_status(403)
+      .with_body("Forbidden")
+      .create();
+
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
+
+    let result: Result<Value> = api
+      .request(
+        reqwest::Method::GET,
+        &Endpoint::new(["test-endpoint"]),
+        None::<&Value>,
+      )
+      .await;
+
+    assert!(result.is_err());
+    assert!(matches!(result.unwrap_err(), Error::InvalidCredentials));
+
+    mock.assert();
+  }
+
+  #[tokio::test]
+  async fn test_request_unauthorized() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
+
+    let mock = mock_server
+      .mock("GET", "/api/v1/test-endpoint")
+      .with_status(401)
+      .with_body("Unauthorized")
+      .create();
+
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
+
+    let result: Result<Value> = api
+      .request(
+        reqwest::Method::GET,
+        &Endpoint::new(["test-endpoint"]),
+        None::<&Value>,
+      )
+      .await;
+
+    let error = result.unwrap_err();
+    assert!(matches!(error, Error::InvalidCredentials));
+    assert!(error.is_auth_error());
+    assert!(!error.is_retryable());
+
+    mock.assert();
+  }
+
+  #[tokio::test]
+  async fn test_request_rate_limited() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
+
+    let mock = mock_server
+      .mock("POST", "/api/v1/send")
+      .with_status(429)
+      .with_header("Retry-After", "7")
+      .with_body("Too Many Requests")
+      .create();
+
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
+
+    let options = EmailOptions::new("tem_123", Recipient::new("user@example.com"));
+    let error = api.send_email(options).await.unwrap_err();
+
+    match error {
+      Error::RateLimited { retry_after, .. } => {
+        assert_eq!(retry_after, Some(std::time::Duration::from_secs(7)));
+      }
+      err => panic!("Unexpected error: {:?}", err),
+    }
+
+    mock.assert();
+  }
+
+  #[tokio::test]
+  async fn test_request_bad_request() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
+    let error_message = "Missing required field";
+
+    let mock = mock_server
+      .mock("POST", "/api/v1/test-endpoint")
+      .with_status(400)
+      .with_body(error_message)
+      .create();
+
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
+
+    let result: Result<Value> = api
+      .request(
+        reqwest::Method::POST,
+        &Endpoint::new(["test-endpoint"]),
+        Some(&json!({})),
+      )
+      .await;
+
+    assert!(result.is_err());
+
+    match result.unwrap_err() {
+      Error::InvalidRequest { context, error } => {
+        assert_eq!(context.method, reqwest::Method::POST);
+        assert_eq!(context.endpoint, "test-endpoint");
+        assert_eq!(error.message, error_message);
+        assert_eq!(error.raw, error_message);
+      }
+      err => panic!("Unexpected error: {:?}", err),
+    }
+
+    mock.assert();
+  }
+
+  #[tokio::test]
+  async fn test_request_bad_request_with_json_body() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
+
+    let mock = mock_server
+      .mock("POST", "/api/v1/send")
+      .with_status(400)
+      .with_body(
+        r#"{"status": "error", "code": "validation_error", "error": "Invalid request", "errors": {"recipient": ["address is invalid"]}}"#,
+      )
+      .create();
+
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
+
+    let options = EmailOptions::new("tem_123", Recipient::new("user@example.invalid"));
+    let result = api.send_email(options).await;
+
+    match result.unwrap_err() {
+      Error::InvalidRequest { error, .. } => {
+        assert_eq!(error.code.as_deref(), Some("validation_error"));
+        assert_eq!(error.message, "Invalid request");
+        assert_eq!(error.field("recipient"), ["address is invalid"]);
+      }
+      err => panic!("Unexpected error: {:?}", err),
+    }
+
+    mock.assert();
+  }
+
+  #[tokio::test]
+  async fn test_send_email_validates_before_sending() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
+
+    let mock = mock_server.mock("POST", "/api/v1/send").expect(0).create();
+
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
+
+    let options = EmailOptions::new("tem_123", Recipient::new("not-an-email")).with_cc(vec![
+      Recipient::new("cc@example.com"),
+      Recipient::new("cc@"),
+    ]);
+
+    match api.send_email(options).await.unwrap_err() {
+      Error::InvalidEmailOptions(issues) => {
+        let fields: Vec<_> = issues.iter().map(|issue| issue.field.as_str()).collect();
+        assert_eq!(fields, ["recipient.address", "cc[1].address"]);
+      }
+      err => panic!("Unexpected error: {:?}", err),
+    }
+
+    mock.assert();
+  }
+
+  #[tokio::test]
+  async fn test_request_api_error() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
+    let error_message = "Internal server error";
+
+    let mock = mock_server
+      .mock("GET", "/api/v1/test-endpoint")
+      .with_status(500)
+      .with_body(error_message)
+      .create();
+
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
+
+    let result: Result<Value> = api
+      .request(
+        reqwest::Method::GET,
+        &Endpoint::new(["test-endpoint"]),
+        None::<&Value>,
+      )
+      .await;
+
+    assert!(result.is_err());
+
+    match result.unwrap_err() {
+      Error::ApiError {
+        context,
+        status,
+        error,
+      } => {
+        assert_eq!(context.method, reqwest::Method::GET);
+        assert_eq!(context.endpoint, "test-endpoint");
+        assert!(context.correlation_id.is_some());
+        assert_eq!(status, 500);
+        assert_eq!(error.message, error_message);
+      }
+      err => panic!("Unexpected error: {:?}", err),
+    }
+
+    mock.assert();
+  }
+
+  #[tokio::test]
+  async fn test_request_with_custom_client() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
+
+    let mock = mock_server
+      .mock("GET", "/api/v1/test-endpoint")
+      .match_header("Content-Type", "application/json")
+      .match_header("X-SWU-API-KEY", "test-api-key")
+      .match_header("X-SWU-API-CLIENT", Matcher::Any)
+      .match_header("User-Agent", "test-agent")
+      .with_status(200)
+      .with_body(r#"{"success": true}"#)
+      .create();
+
+    let custom_client = Client::builder().user_agent("test-agent").build().unwrap();
+
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&url).unwrap();
+
+    let api = Api::with_client(config, custom_client);
+
+    let response: Value = api
+      .request(
+        reqwest::Method::GET,
+        &Endpoint::new(["test-endpoint"]),
+        None::<&Value>,
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response["success"], json!(true));
+
+    mock.assert();
+  }
+
+  #[tokio::test]
+  async fn test_request_debug_mode() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
+
+    let mock = mock_server
+      .mock("GET", "/api/v1/test-endpoint")
+      .match_header("Content-Type", "application/json")
+      .match_header("X-SWU-API-KEY", "test-api-key")
+      .with_status(200)
+      .with_body(r#"{"success": true}"#)
+      .create();
+
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&url).unwrap();
+    config.debug = true;
+    let api = Api::new(config);
+
+    let response: Value = api
+      .request(
+        reqwest::Method::GET,
+        &Endpoint::new(["test-endpoint"]),
+        None::<&Value>,
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response["success"], json!(true));
+
+    mock.assert();
+  }
+
+  #[tokio::test]
+  async fn test_customer_add_event() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
+
+    let mock = mock_server
+      .mock("POST", "/api/v1/customers/user@example.com/events")
+      .match_header("X-SWU-API-KEY", "test-api-key")
+      .match_body(Matcher::Json(json!({
+        "event_name": "completed_checkout",
+        "data": {"order_id": "12345"}
+      })))
+      .with_status(200)
+      .with_body(r#"{"success": true, "status": "OK"}"#)
+      .create();
+
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
+
+    let options = CustomerEventOptions::new("completed_checkout")
+      .with_data(helpers::email_data([("order_id", "12345")]));
+    let response = api
+      .customer_add_event("user@example.com", options)
+      .await
+      .unwrap();
+
+    assert_eq!(response["success"], json!(true));
+    mock.assert();
+
+    let result = api
+      .customer_add_event("", CustomerEventOptions::new("signed_up"))
+      .await;
+    assert!(matches!(result, Err(Error::MissingRecipientAddress)));
+  }
+
+  #[tokio::test]
+  async fn test_logs_stream_follows_pages() {
+    use futures::StreamExt;
+
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
+    let log = |id: &str| EmailLogFixture::new().with_id(id).to_json();
+    let mut page = |offset: &str, logs: Value| {
+      mock_server
+        .mock("GET", "/api/v1/logs")
+        .match_query(Matcher::AllOf(vec![
+          Matcher::UrlEncoded("count".into(), "2".into()),
+          Matcher::UrlEncoded("offset".into(), offset.into()),
+          Matcher::UrlEncoded("created_gt".into(), "1600000000".into()),
+        ]))
+        .with_status(200)
+        .with_body(logs.to_string())
+        .create()
+    };
+
+    let first = page("0", json!([log("log_1"), log("log_2")]));
+    let second = page("2", json!([log("log_3")]));
+
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
+
+    let query = EmailLogQuery::new()
+      .with_count(2)
+      .with_offset(0)
+      .with_created_gt("1600000000");
+    let logs: Vec<_> = api.logs_stream(query).collect().await;
+    let ids: Vec<_> = logs
+      .into_iter()
+      .map(|log| log.unwrap().id.as_str().to_string())
+      .collect();
+
+    assert_eq!(ids, ["log_1", "log_2", "log_3"]);
+    first.assert();
+    second.assert();
+  }
+
+  #[tokio::test]
+  async fn test_logs_all_stops_at_limit() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
+    let log = |id: &str| EmailLogFixture::new().with_id(id).to_json();
+
+    let first = mock_server
+      .mock("GET", "/api/v1/customers/user@example.com/logs")
+      .match_query(Matcher::AllOf(vec![
+        Matcher::UrlEncoded("count".into(), "3".into()),
+        Matcher::UrlEncoded("offset".into(), "0".into()),
+      ]))
+      .with_status(200)
+      .with_body(json!({"logs": [log("log_1"), log("log_2"), log("log_3")]}).to_string())
+      .create();
+    let second = mock_server
+      .mock("GET", "/api/v1/customers/user@example.com/logs")
+      .match_query(Matcher::AllOf(vec![
+        Matcher::UrlEncoded("count".into(), "3".into()),
+        Matcher::UrlEncoded("offset".into(), "3".into()),
+      ]))
+      .with_status(200)
+      .with_body(json!({"logs": [log("log_4"), log("log_5"), log("log_6")]}).to_string())
+      .create();
+
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
+
+    let logs = api
+      .customer_email_log_all(
+        "user@example.com",
+        EmailLogQuery::new().with_count(3).with_offset(0),
+        4,
+      )
+      .await
+      .unwrap();
+
+    let ids: Vec<_> = logs.iter().map(|log| log.id.as_str()).collect();
+    assert_eq!(ids, ["log_1", "log_2", "log_3", "log_4"]);
+    first.assert();
+    second.assert();
+  }
+
+  #[tokio::test]
+  async fn test_logs_in_range_merges_windows_in_order() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
+    let log = |id: &str, created: i64| {
+      EmailLogFixture::new()
+        .with_id(id)
+        .with_created(created)
+        .to_json()
+    };
+    let mut window = |gt: &str, lt: &str, logs: Value| {
+      mock_server
+        .mock("GET", "/api/v1/logs")
+        .match_query(Matcher::AllOf(vec![
+          Matcher::UrlEncoded("created_gt".into(), gt.into()),
+          Matcher::UrlEncoded("created_lt".into(), lt.into()),
+        ]))
+        .with_status(200)
+        .with_body(logs.to_string())
+        .create()
+    };
+
+    let first = window(
+      "999",
+      "1100",
+      json!([log("log_2", 1050), log("log_1", 1000)]),
+    );
+    let second = window("1099", "1200", json!([]));
+    let third = window("1199", "1250", json!([log("log_3", 1200)]));
+
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
+
+    let logs = api
+      .logs_in_range(1000, 1250, Duration::from_secs(100), 2)
+      .await
+      .unwrap();
+
+    let ids: Vec<_> = logs.iter().map(|log| log.id.as_str()).collect();
+    assert_eq!(ids, ["log_1", "log_2", "log_3"]);
+    first.assert();
+    second.assert();
+    third.assert();
+  }
+
+  #[tokio::test]
+  async fn test_engagement_report_fetches_events() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
+
+    let logs = mock_server
+      .mock("GET", "/api/v1/logs")
+      .match_query(Matcher::AllOf(vec![
+        Matcher::UrlEncoded("created_gt".into(), "1699999999".into()),
+        Matcher::UrlEncoded("created_lt".into(), "1700100000".into()),
+      ]))
+      .with_status(200)
+      .with_body(
+        json!([EmailLogFixture::new()
+          .with_id("log_1")
+          .with_created(1700000000)
+          .with_status("opened")
+          .with_template("tem_a", "Welcome")
+          .to_json()])
+        .to_string(),
+      )
+      .create();
+    let events = mock_server
+      .mock("GET", "/api/v1/logs/log_1/events")
+      .with_status(200)
+      .with_body(json!([{"type": "open", "created": 1700000100}]).to_string())
+      .create();
+
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
+
+    let report = api.engagement_report(1700000000, 1700100000).await.unwrap();
+
+    assert_eq!(report.total().sent, 1);
+    assert_eq!(report.by_template()["tem_a"].open_rate(), 1.0);
+    logs.assert();
+    events.assert();
+  }
+
+  #[tokio::test]
+  async fn test_wait_for_event() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
+
+    let events = mock_server
+      .mock("GET", "/api/v1/logs/log_1/events")
+      .with_status(200)
+      .with_body(
+        json!([{"type": "send", "created": 1700000000}, {"type": "deliver", "created": 1700000005}])
+          .to_string(),
+      )
+      .create();
+    let missing = mock_server
+      .mock("GET", "/api/v1/logs/log_2/events")
+      .with_status(404)
+      .with_body(r#"{"error": "Not found"}"#)
+      .expect_at_least(2)
+      .create();
+
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
+
+    let event = api
+      .wait_for_event(
+        &LogId::new("log_1").unwrap(),
+        EventType::Delivered,
+        Duration::from_secs(5),
+      )
+      .await
+      .unwrap();
+    assert_eq!(event.created, 1700000005);
+
+    let result = api
+      .wait_for_event(
+        &LogId::new("log_2").unwrap(),
+        EventType::Opened,
+        Duration::from_millis(50),
+      )
+      .await;
+    assert!(matches!(
+      result,
+      Err(Error::EventTimeout {
+        event_type: EventType::Opened,
+        ..
+      })
+    ));
+
+    events.assert();
+    missing.assert();
+  }
+
+  #[test]
+  fn test_capped_page() {
+    assert_eq!(capped_page(EmailLogQuery::new(), 10).count, Some(10));
+    assert_eq!(capped_page(EmailLogQuery::new(), 5_000).count, Some(100));
+    assert_eq!(
+      capped_page(EmailLogQuery::new().with_count(25), 10).count,
+      Some(25)
+    );
+  }
+
+  #[tokio::test]
+  async fn test_customer_email_log_stream_stops_on_error() {
+    use futures::StreamExt;
+
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
+
+    let mock = mock_server
+      .mock("GET", "/api/v1/customers/user@example.com/logs")
+      .match_query(Matcher::UrlEncoded("count".into(), "100".into()))
+      .with_status(500)
+      .with_body("Internal Server Error")
+      .expect(1)
+      .create();
+
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
+
+    let results: Vec<_> = api
+      .customer_email_log_stream("user@example.com", EmailLogQuery::new())
+      .collect()
+      .await;
+
+    assert_eq!(results.len(), 1);
+    assert!(matches!(
+      results[0],
+      Err(Error::ApiError { status: 500, .. })
+    ));
+    mock.assert();
+  }
+
+  #[tokio::test]
+  async fn test_logs_with_query() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
+
+    let mock = mock_server
+      .mock("GET", "/api/v1/logs")
+      .match_query(Matcher::AllOf(vec![
+        Matcher::UrlEncoded("count".into(), "2".into()),
+        Matcher::UrlEncoded("offset".into(), "4".into()),
+        Matcher::UrlEncoded("created_gt".into(), "1700000000".into()),
+      ]))
+      .with_status(200)
+      .with_body(
+        r#"[
+          {"id": "log_1", "created": 1700000001, "recipient_address": "a@example.com", "status": "sent"},
+          {"id": "log_2", "created": 1700000002, "recipient_address": "b@example.com", "status": "opened"}
+        ]"#,
+      )
+      .create();
+
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
+
+    let query = EmailLogQuery::new()
+      .with_count(2)
+      .with_offset(4)
+      .with_created_gt("1700000000");
+    let logs = api.logs(query).await.unwrap();
+
+    assert_eq!(logs.len(), 2);
+    assert_eq!(logs[1].id.as_str(), "log_2");
+    assert_eq!(logs[1].status, "opened");
+    mock.assert();
+  }
+
+  #[tokio::test]
+  async fn test_customer_email_log_keeps_email_in_one_segment() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
+
+    let mock = mock_server
+      .mock("GET", "/api/v1/customers/a%2Fb%3Fc@example.com/logs")
+      .match_query(Matcher::UrlEncoded("count".into(), "5".into()))
+      .with_status(200)
+      .with_body(r#"{"success": true, "status": "OK", "logs": []}"#)
+      .create();
+
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
+
+    let logs = api
+      .customer_email_log("a/b?c@example.com", EmailLogQuery::new().with_count(5))
+      .await
+      .unwrap();
+
+    assert!(logs.is_empty());
+    mock.assert();
+  }
+
+  #[tokio::test]
+  async fn test_customer_email_log_query() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
+
+    let mock = mock_server
+      .mock("GET", "/api/v1/customers/user@example.com/logs")
+      .match_query(Matcher::AllOf(vec![
+        Matcher::UrlEncoded("count".into(), "5".into()),
+        Matcher::UrlEncoded("offset".into(), "10".into()),
+        Matcher::UrlEncoded("created_lt".into(), "1700000100".into()),
+      ]))
+      .with_status(200)
+      .with_body(
+        r#"{"success": true, "status": "OK", "logs": [
+          {"id": "log_1", "created": 1700000001, "recipient_address": "user@example.com", "status": "sent"}
+        ]}"#,
+      )
+      .create();
+
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
+
+    let logs = api
+      .customer_email_log(
+        "user@example.com",
+        EmailLogQuery::new()
+          .with_count(5)
+          .with_offset(10)
+          .with_created_lt("1700000100"),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0].recipient_address, "user@example.com");
+    mock.assert();
+  }
+
+  #[tokio::test]
+  async fn test_conditional_requests_serve_not_modified_from_cache() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
+
+    let fresh = mock_server
+      .mock("GET", "/api/v1/templates/tem_123")
+      .match_header("If-None-Match", Matcher::Missing)
+      .with_status(200)
+      .with_header("ETag", "\"v1\"")
+      .with_body(r#"{"id": "tem_123", "name": "Welcome"}"#)
+      .create();
+    let not_modified = mock_server
+      .mock("GET", "/api/v1/templates/tem_123")
+      .match_header("If-None-Match", "\"v1\"")
+      .with_status(304)
+      .expect(2)
+      .create();
+
+    let mut config = Config::new("test-api-key").with_conditional_requests(true);
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
+    let template_id = TemplateId::new("tem_123").unwrap();
+
+    let template = api.get_template(&template_id).await.unwrap();
+    assert_eq!(template["name"], json!("Welcome"));
+
+    for _ in 0..2 {
+      let template = api.clone().get_template(&template_id).await.unwrap();
+      assert_eq!(template["name"], json!("Welcome"));
+    }
+
+    fresh.assert();
+    not_modified.assert();
+  }
+
+  #[tokio::test]
+  async fn test_diff_versions() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
+
+    for (id, subject) in [("ver_1", "Welcome"), ("ver_2", "Welcome aboard")] {
+      mock_server
+        .mock(
+          "GET",
+          format!("/api/v1/templates/tem_123/versions/{}", id).as_str(),
+        )
+        .with_status(200)
+        .with_body(
+          json!({"id": id, "name": id, "subject": subject, "html": "<p>Hi</p>"}).to_string(),
+        )
+        .create();
+    }
+
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
+    let template_id = TemplateId::new("tem_123").unwrap();
+
+    let diff = api
+      .diff_versions(&template_id, "ver_1", "ver_2")
+      .await
+      .unwrap();
+    assert_eq!(diff.fields.len(), 1);
+    assert_eq!(diff.fields[0].field, "subject");
+    assert_eq!(diff.fields[0].new, "Welcome aboard");
+  }
+
+  #[tokio::test]
+  async fn test_get_and_update_template() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
+
+    let get_mock = mock_server
+      .mock("GET", "/api/v1/templates/tem_123")
+      .with_status(200)
+      .with_body(r#"{"id": "tem_123", "name": "Welcome", "tags": ["onboarding"]}"#)
+      .create();
+
+    let update_mock = mock_server
+      .mock("PUT", "/api/v1/templates/tem_123")
+      .match_body(Matcher::Json(json!({"name": "Welcome v2"})))
+      .with_status(200)
+      .with_body(r#"{"id": "tem_123", "name": "Welcome v2", "tags": ["onboarding"]}"#)
+      .create();
+
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
+    let template_id = TemplateId::new("tem_123").unwrap();
+
+    let template = api.get_template(&template_id).await.unwrap();
+    assert_eq!(template["name"], json!("Welcome"));
+
+    let update = TemplateUpdateOptions::new().with_name("Welcome v2");
+    let template = api.update_template(&template_id, update).await.unwrap();
+    assert_eq!(template["name"], json!("Welcome v2"));
+
+    get_mock.assert();
+    update_mock.assert();
+  }
+
+  #[tokio::test]
+  async fn test_drip_campaign_steps_and_customers() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
+
+    let steps_mock = mock_server
+      .mock("GET", "/api/v1/drip_campaigns/dc_123/steps")
+      .with_status(200)
+      .with_body(
+        r#"[
+          {"id": "dcs_1", "email_id": "tem_1", "delay_seconds": 0},
+          {"id": "dcs_2", "email_id": "tem_2", "delay_seconds": 86400}
+        ]"#,
+      )
+      .create();
+
+    let customers_mock = mock_server
+      .mock("GET", "/api/v1/drip_campaigns/dc_123/customers")
+      .with_status(200)
+      .with_body(
+        r#"{"id": "dc_123", "customers": [{"email": "user@example.com", "step_id": "dcs_2"}]}"#,
+      )
+      .create();
+
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
+    let campaign_id = CampaignId::new("dc_123").unwrap();
+
+    let steps = api.drip_campaign_steps(&campaign_id).await.unwrap();
+    assert_eq!(steps.len(), 2);
+    assert_eq!(steps[1].delay_seconds, Some(86400));
+
+    let customers = api.drip_campaign_customers(&campaign_id).await.unwrap();
+    assert_eq!(customers.len(), 1);
+    assert_eq!(customers[0].email, "user@example.com");
+    assert_eq!(customers[0].step_id.as_deref(), Some("dcs_2"));
+
+    steps_mock.assert();
+    customers_mock.assert();
+  }
+
+  #[tokio::test]
+  async fn test_customer_update_merges_data() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
+
+    let get_mock = mock_server
+      .mock("GET", "/api/v1/customers/user@example.com")
+      .with_status(200)
+      .with_body(
+        r#"{"success": true, "customer": {"email": "user@example.com", "data": {"plan": "free", "name": "Ann"}}}"#,
+      )
+      .create();
+
+    let put_mock = mock_server
+      .mock("PUT", "/api/v1/customers/user@example.com")
+      .match_body(Matcher::Json(json!({
+        "data": {"plan": "premium", "name": "Ann"}
+      })))
+      .with_status(200)
+      .with_body(r#"{"success": true}"#)
+      .create();
+
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
 
-      Ok(serde_json::json!({
-        "success": true,
-        "email": email_address
-      }))
-    }
+    let update = CustomerUpdateOptions::new().with_data(helpers::email_data([("plan", "premium")]));
+    let response = api
+      .customer_update("user@example.com", update)
+      .await
+      .unwrap();
+
+    assert_eq!(response["success"], json!(true));
+    get_mock.assert();
+    put_mock.assert();
   }
 
   #[tokio::test]
-  async fn test_api_initialization() {
-    let api = Api::with_api_key("test-api-key");
-    assert_eq!(api.config().api_key, "test-api-key");
-    assert_eq!(api.config().api_version, "1");
+  async fn test_customer_update_merge_reports_missing_customer() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
 
-    let custom_config = Config::new("custom-key")
-      .with_api_version("2")
-      .with_debug(true);
+    let get_mock = mock_server
+      .mock("GET", "/api/v1/customers/user@example.com")
+      .with_status(404)
+      .with_body(r#"{"success": false, "error": "Not found"}"#)
+      .create();
+    let put_mock = mock_server
+      .mock("PUT", "/api/v1/customers/user@example.com")
+      .expect(0)
+      .create();
 
-    let api_with_config = Api::new(custom_config);
-    assert_eq!(api_with_config.config().api_key, "custom-key");
-    assert_eq!(api_with_config.config().api_version, "2");
-    assert!(api_with_config.config().debug);
-  }
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
 
-  #[tokio::test]
-  async fn test_build_url() {
-    let api = Api::with_api_key("api-key");
-    let url = api.build_url("test-endpoint").expect("Failed to build URL");
-    assert!(url.contains("/api/v1/test-endpoint"));
-    assert!(url.starts_with("https://api.sendwithus.com"));
+    let update = CustomerUpdateOptions::new().with_data(helpers::email_data([("plan", "premium")]));
+    let result = api.customer_update("user@example.com", update).await;
+
+    assert!(matches!(result, Err(Error::CustomerNotFound)));
+    get_mock.assert();
+    put_mock.assert();
   }
 
   #[tokio::test]
-  async fn test_mock_client_send_email() {
-    let mock_client = MockApiClient;
+  async fn test_customer_update_replaces_data() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
 
-    let recipient = Recipient::new("test@example.com").with_name("Test User");
-    let options = EmailOptions::new("template-id", recipient);
-    let result = mock_client.send_email(options).await;
-    assert!(result.is_ok());
+    let get_mock = mock_server
+      .mock("GET", "/api/v1/customers/user@example.com")
+      .expect(0)
+      .create();
 
-    let recipient = Recipient::new("test@example.com");
-    let invalid_options = EmailOptions::new("", recipient);
-    let result = mock_client.send_email(invalid_options).await;
-    assert!(result.is_err());
-    assert!(matches!(result.unwrap_err(), Error::MissingTemplateId));
+    let put_mock = mock_server
+      .mock("PUT", "/api/v1/customers/user@example.com")
+      .match_body(Matcher::Json(json!({
+        "data": {"plan": "premium"},
+        "locale": "fr-CA"
+      })))
+      .with_status(200)
+      .with_body(r#"{"success": true}"#)
+      .create();
+
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
+
+    let update = CustomerUpdateOptions::new()
+      .with_data(helpers::email_data([("plan", "premium")]))
+      .with_locale("fr-CA")
+      .with_data_mode(DataUpdateMode::Replace);
+    api
+      .customer_update("user@example.com", update)
+      .await
+      .unwrap();
+
+    get_mock.assert();
+    put_mock.assert();
   }
 
   #[tokio::test]
-  async fn test_mock_client_customer_email_log() {
-    let mock_client = MockApiClient;
+  async fn test_unsubscribe_management() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
 
-    let result = mock_client
-      .customer_email_log("test@example.com", None, None, None)
-      .await;
-    assert!(result.is_ok());
-    let value = result.unwrap();
-    assert_eq!(value["email"], "test@example.com");
-    assert!(value.get("count").is_none());
+    let status_mock = mock_server
+      .mock("GET", "/api/v1/unsubscribes/user@example.com")
+      .with_status(200)
+      .with_body(
+        r#"{"email_address": "user@example.com", "unsubscribed": true, "unsubscribed_at": 1700000000}"#,
+      )
+      .create();
 
-    let result = mock_client
-      .customer_email_log("test@example.com", Some(2), None, None)
-      .await;
-    assert!(result.is_ok());
-    let value = result.unwrap();
-    assert_eq!(value["email"], "test@example.com");
-    assert_eq!(value["count"], 2);
+    let unsubscribe_mock = mock_server
+      .mock("POST", "/api/v1/unsubscribes")
+      .match_body(Matcher::Json(json!({"email_address": "user@example.com"})))
+      .with_status(200)
+      .with_body(r#"{"success": true}"#)
+      .create();
+
+    let resubscribe_mock = mock_server
+      .mock("DELETE", "/api/v1/unsubscribes/user@example.com")
+      .with_status(200)
+      .with_body(r#"{"success": true}"#)
+      .create();
+
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
+
+    let status = api.unsubscribe_status("user@example.com").await.unwrap();
+    assert!(status.unsubscribed);
+    assert_eq!(status.unsubscribed_at, Some(1700000000));
+
+    api.unsubscribe("user@example.com").await.unwrap();
+    api.resubscribe("user@example.com").await.unwrap();
+
+    assert!(matches!(
+      api.unsubscribe_status("").await,
+      Err(Error::MissingRecipientAddress)
+    ));
+
+    status_mock.assert();
+    unsubscribe_mock.assert();
+    resubscribe_mock.assert();
   }
 
   #[tokio::test]
-  async fn test_mock_client_log() {
-    let mock_client = MockApiClient;
+  async fn test_send_email_returns_receipt() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
 
-    let log_id = "log_TESTTEST123";
-    let result = mock_client.log(log_id).await;
-    assert!(result.is_ok());
-    let value = result.unwrap();
-    assert_eq!(value["id"], log_id);
+    let mock = mock_server
+      .mock("POST", "/api/v1/send")
+      .match_body(Matcher::PartialJson(json!({
+        "email_id": "tem_123",
+        "recipient": {"address": "user@example.com"}
+      })))
+      .with_status(200)
+      .with_body(
+        r#"{
+          "success": true,
+          "status": "OK",
+          "receipt_id": "log_abc123",
+          "email": {"name": "Welcome", "version_name": "Version A", "locale": "en-US"}
+        }"#,
+      )
+      .create();
+
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
+
+    let options = EmailOptions::new("tem_123", Recipient::new("user@example.com"));
+    let response = api.send_email(options).await.unwrap();
+
+    assert!(response.success);
+    assert_eq!(response.receipt_id.as_str(), "log_abc123");
+    assert_eq!(response.email.name, "Welcome");
+    assert_eq!(response.email.locale.as_deref(), Some("en-US"));
+
+    mock.assert();
   }
 
   #[tokio::test]
-  async fn test_mock_client_start_on_drip_campaign() {
-    let mock_client = MockApiClient;
-    let email = "some@email.stub";
-    let campaign_id = "dc_SoMeCampaIGnID";
+  async fn test_default_locale_applies_to_render_and_drip_campaigns() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
 
-    let mut email_data = HashMap::new();
-    email_data.insert("foo".to_string(), serde_json::json!("bar"));
+    let render_mock = mock_server
+      .mock("POST", "/api/v1/render")
+      .match_body(Matcher::PartialJson(json!({"locale": "fr-CA"})))
+      .with_status(200)
+      .with_body(r#"{"success": true}"#)
+      .create();
 
-    let options = DripCampaignOptions {
-      recipient_address: email.to_string(),
-      email_data: Some(email_data),
+    let drip_mock = mock_server
+      .mock("POST", "/api/v1/drip_campaigns/dc_123/activate")
+      .match_body(Matcher::PartialJson(json!({"locale": "fr-CA"})))
+      .with_status(200)
+      .with_body(r#"{"success": true}"#)
+      .create();
+
+    let mut config = Config::new("test-api-key").with_default_locale("fr-CA");
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
+
+    api.render(RenderOptions::new("tem_123")).await.unwrap();
+
+    let drip_options = DripCampaignOptions {
+      recipient_address: "user@example.com".to_string(),
+      email_data: None,
       tags: None,
       locale: None,
     };
+    let campaign_id = CampaignId::new("dc_123").unwrap();
+    api
+      .start_on_drip_campaign(&campaign_id, drip_options)
+      .await
+      .unwrap();
 
-    let result = mock_client
-      .start_on_drip_campaign(campaign_id, options)
-      .await;
-    assert!(result.is_ok());
-    let value = result.unwrap();
-    assert_eq!(value["recipient"], email);
-    assert_eq!(value["campaign_id"], campaign_id);
+    render_mock.assert();
+    drip_mock.assert();
   }
 
   #[tokio::test]
-  async fn test_mock_client_render() {
-    let mock_client = MockApiClient;
-    let template_id = "template-id";
-    let version_id = Some("some-version-id".to_string());
-    let locale = Some("fr-CA".to_string());
+  async fn test_explicit_locale_overrides_default_locale() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
 
-    let mut template_data = HashMap::new();
-    template_data.insert("foo".to_string(), serde_json::json!("bar"));
+    let render_mock = mock_server
+      .mock("POST", "/api/v1/render")
+      .match_body(Matcher::PartialJson(json!({"locale": "de-DE"})))
+      .with_status(200)
+      .with_body(r#"{"success": true}"#)
+      .create();
 
-    let options = RenderOptions {
-      template: template_id.to_string(),
-      version_id,
-      template_data,
-      strict: true,
-      locale,
+    let drip_mock = mock_server
+      .mock("POST", "/api/v1/drip_campaigns/dc_123/activate")
+      .match_body(Matcher::PartialJson(json!({"locale": "de-DE"})))
+      .with_status(200)
+      .with_body(r#"{"success": true}"#)
+      .create();
+
+    let mut config = Config::new("test-api-key").with_default_locale("fr-CA");
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
+
+    let mut render_options = RenderOptions::new("tem_123");
+    render_options.locale = Some("de-DE".to_string());
+    api.render(render_options).await.unwrap();
+
+    let drip_options = DripCampaignOptions {
+      recipient_address: "user@example.com".to_string(),
+      email_data: None,
+      tags: None,
+      locale: Some("de-DE".to_string()),
     };
+    let campaign_id = CampaignId::new("dc_123").unwrap();
+    api
+      .start_on_drip_campaign(&campaign_id, drip_options)
+      .await
+      .unwrap();
 
-    let result = mock_client.render(options).await;
-    assert!(result.is_ok());
-    let value = result.unwrap();
-    assert_eq!(value["template"], template_id);
-    assert_eq!(value["rendered_template"], "<html>Rendered template</html>");
+    render_mock.assert();
+    drip_mock.assert();
   }
 
   #[tokio::test]
-  async fn test_mock_client_drips_unsubscribe() {
-    let mock_client = MockApiClient;
+  async fn test_recipient_intercept_applies_to_drip_campaigns() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
 
-    let result = mock_client.drips_unsubscribe("test@example.com").await;
-    assert!(result.is_ok());
-    let value = result.unwrap();
-    assert_eq!(value["email"], "test@example.com");
+    let activate_mock = mock_server
+      .mock("POST", "/api/v1/drip_campaigns/dc_123/activate")
+      .match_body(Matcher::PartialJson(json!({
+        "recipient_address": "catch-all@example.com",
+        "email_data": {
+          "name": "User",
+          "original_recipients": { "to": "user@example.com", "cc": [], "bcc": [] }
+        }
+      })))
+      .with_status(200)
+      .with_body(r#"{"success": true}"#)
+      .create();
+    let deactivate_mock = mock_server
+      .mock("POST", "/api/v1/drip_campaigns/dc_123/deactivate")
+      .match_body(Matcher::Json(
+        json!({"recipient_address": "catch-all@example.com"}),
+      ))
+      .with_status(200)
+      .with_body(r#"{"success": true}"#)
+      .create();
 
-    let result = mock_client.drips_unsubscribe("").await;
-    assert!(result.is_err());
-    assert!(matches!(
-      result.unwrap_err(),
-      Error::MissingRecipientAddress
-    ));
-  }
+    let mut config = Config::new("test-api-key").with_recipient_intercept("catch-all@example.com");
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
 
-  #[test]
-  fn test_helpers_email_data() {
-    let data = helpers::email_data([("name", "John"), ("age", "30")]);
+    let campaign_id = CampaignId::new("dc_123").unwrap();
+    let options = DripCampaignOptions::new("user@example.com")
+      .with_data(HashMap::from([("name".to_string(), json!("User"))]));
+    api
+      .start_on_drip_campaign(&campaign_id, options)
+      .await
+      .unwrap();
+    api
+      .remove_from_drip_campaign(&campaign_id, "user@example.com")
+      .await
+      .unwrap();
 
-    assert_eq!(data["name"], "John");
-    assert_eq!(data["age"], "30");
+    activate_mock.assert();
+    deactivate_mock.assert();
   }
 
-  #[test]
-  fn test_helpers_recipient() {
-    let recipient = helpers::recipient("test@example.com", Some("Test User"));
-
-    assert_eq!(recipient.address, "test@example.com");
-    assert_eq!(recipient.name, Some("Test User".to_string()));
+  #[tokio::test]
+  async fn test_request_raw_returns_unmapped_response() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
 
-    let recipient = helpers::recipient("test@example.com", None::<String>);
+    let ok_mock = mock_server
+      .mock("POST", "/api/v1/beta/feature")
+      .match_header("X-SWU-API-KEY", "test-api-key")
+      .match_body(Matcher::Json(json!({"enabled": true})))
+      .with_status(200)
+      .with_header("X-Custom", "yes")
+      .with_body(r#"{"ok": true}"#)
+      .create();
 
-    assert_eq!(recipient.address, "test@example.com");
-    assert_eq!(recipient.name, None);
-  }
+    let error_mock = mock_server
+      .mock("GET", "/api/v1/beta/missing")
+      .with_status(404)
+      .with_body("not here")
+      .create();
 
-  #[test]
-  fn test_helpers_sender() {
-    let sender = helpers::sender(
-      "sender@example.com",
-      Some("Sender Name"),
-      Some("reply@example.com"),
-    );
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
 
-    assert_eq!(sender.address, "sender@example.com");
-    assert_eq!(sender.name, Some("Sender Name".to_string()));
-    assert_eq!(sender.reply_to, Some("reply@example.com".to_string()));
+    let response = api
+      .request_raw(
+        reqwest::Method::POST,
+        "beta/feature",
+        Some(&json!({"enabled": true})),
+      )
+      .await
+      .unwrap();
+    assert_eq!(response.status, StatusCode::OK);
+    assert_eq!(response.headers["x-custom"], "yes");
+    assert_eq!(response.json::<Value>().unwrap()["ok"], json!(true));
 
-    let sender = helpers::sender("sender@example.com", None::<String>, None::<String>);
+    let response = api
+      .request_raw::<()>(reqwest::Method::GET, "beta/missing", None)
+      .await
+      .unwrap();
+    assert_eq!(response.status, StatusCode::NOT_FOUND);
+    assert_eq!(response.text(), "not here");
 
-    assert_eq!(sender.address, "sender@example.com");
-    assert_eq!(sender.name, None);
-    assert_eq!(sender.reply_to, None);
+    ok_mock.assert();
+    error_mock.assert();
   }
-}
-
-#[cfg(test)]
-mod request_tests {
-  use super::*;
-  use mockito::Matcher;
-  use reqwest::Client;
-  use serde_json::{Value, json};
-  use std::net::TcpListener;
 
   #[tokio::test]
-  async fn test_request_success() {
+  async fn test_send_email_with_metadata() {
     let mut mock_server = mockito::Server::new_async().await;
     let url = mock_server.url();
 
     let mock = mock_server
-      .mock("GET", "/api/v1/test-endpoint")
-      .match_header("Content-Type", "application/json")
-      .match_header("X-SWU-API-KEY", "test-api-key")
-      .match_header("X-SWU-API-CLIENT", Matcher::Any)
+      .mock("POST", "/api/v1/send")
       .with_status(200)
-      .with_body(r#"{"success": true, "message": "Test response"}"#)
+      .with_header("X-Request-Id", "req_abc123")
+      .with_body(
+        r#"{"success": true, "status": "OK", "receipt_id": "log_123", "email": {"name": "Welcome"}}"#,
+      )
       .create();
 
     let mut config = Config::new("test-api-key");
     config.url = url::Url::parse(&url).unwrap();
     let api = Api::new(config);
 
-    let response: Value = api
-      .request(reqwest::Method::GET, "test-endpoint", None::<&Value>)
-      .await
-      .unwrap();
+    let options = EmailOptions::new("tem_123", Recipient::new("user@example.com"));
+    let envelope = api.send_email_with_metadata(options).await.unwrap();
 
-    assert_eq!(response["success"], json!(true));
-    assert_eq!(response["message"], json!("Test response"));
+    assert_eq!(envelope.status, StatusCode::OK);
+    assert_eq!(envelope.request_id.as_deref(), Some("req_abc123"));
+    assert_eq!(envelope.headers["x-request-id"], "req_abc123");
+    assert_eq!(envelope.data.receipt_id.as_str(), "log_123");
 
     mock.assert();
   }
 
   #[tokio::test]
-  async fn test_request_with_payload() {
+  async fn test_typed_response_variants() {
+    #[derive(Debug, serde::Deserialize)]
+    struct Template {
+      id: String,
+      name: String,
+    }
+
     let mut mock_server = mockito::Server::new_async().await;
     let url = mock_server.url();
 
-    let mock = mock_server
-      .mock("POST", "/api/v1/test-endpoint")
-      .match_header("Content-Type", "application/json")
-      .match_header("X-SWU-API-KEY", "test-api-key")
-      .match_body(r#"{"data":"test value"}"#)
-      .with_status(201)
-      .with_body(r#"{"success": true, "data_received": true}"#)
+    let list_mock = mock_server
+      .mock("GET", "/api/v1/emails")
+      .with_status(200)
+      .with_body(r#"[{"id": "tem_1", "name": "Welcome", "versions": []}]"#)
+      .create();
+
+    let get_mock = mock_server
+      .mock("GET", "/api/v1/templates/tem_1")
+      .with_status(200)
+      .with_body(r#"{"name": "Welcome"}"#)
       .create();
 
     let mut config = Config::new("test-api-key");
     config.url = url::Url::parse(&url).unwrap();
     let api = Api::new(config);
 
-    let payload = json!({"data": "test value"});
-
-    let response: Value = api
-      .request(reqwest::Method::POST, "test-endpoint", Some(&payload))
-      .await
-      .unwrap();
+    let templates: Vec<Template> = api.list_templates_as().await.unwrap();
+    assert_eq!(templates.len(), 1);
+    assert_eq!(templates[0].id, "tem_1");
+    assert_eq!(templates[0].name, "Welcome");
 
-    assert_eq!(response["success"], json!(true));
-    assert_eq!(response["data_received"], json!(true));
+    let template_id = TemplateId::new("tem_1").unwrap();
+    let result = api.get_template_as::<Template>(&template_id).await;
+    match result {
+      Err(Error::ResponseDecodeFailed { body, .. }) => assert_eq!(body, r#"{"name": "Welcome"}"#),
+      other => panic!("Unexpected result: {:?}", other),
+    }
 
-    mock.assert();
+    list_mock.assert();
+    get_mock.assert();
   }
 
   #[tokio::test]
-  async fn test_request_not_found() {
+  async fn test_log_and_log_events() {
     let mut mock_server = mockito::Server::new_async().await;
     let url = mock_server.url();
 
-    let mock = mock_server
-      .mock("GET", "/api/v1/nonexistent-endpoint")
-      .with_status(404)
-      .with_body("Not Found")
+    let log_mock = mock_server
+      .mock("GET", "/api/v1/logs/log_123")
+      .with_status(200)
+      .with_body(
+        r#"{"id": "log_123", "object": "log", "created": 1700000000, "recipient_address": "user@example.com", "status": "bounced"}"#,
+      )
+      .create();
+
+    let events_mock = mock_server
+      .mock("GET", "/api/v1/logs/log_123/events")
+      .with_status(200)
+      .with_body(
+        r#"[
+          {"object": "event", "type": "send", "created": 1700000000},
+          {"object": "event", "type": "bounce", "created": 1700000060, "message": "Mailbox full"}
+        ]"#,
+      )
       .create();
 
     let mut config = Config::new("test-api-key");
     config.url = url::Url::parse(&url).unwrap();
     let api = Api::new(config);
+    let log_id = LogId::new("log_123").unwrap();
+
+    let log = api.log(&log_id).await.unwrap();
+    assert_eq!(log.status, "bounced");
+
+    let events = api.log_events(&log_id).await.unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].event_type, crate::models::EventType::Sent);
+    assert_eq!(events[1].event_type, crate::models::EventType::Bounced);
+    assert_eq!(events[1].message.as_deref(), Some("Mailbox full"));
+
+    log_mock.assert();
+    events_mock.assert();
+  }
+
+  #[tokio::test]
+  async fn test_request_timeout() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let mut config = Config::new("test-api-key").with_timeout(Duration::from_secs(30));
+    config.url = url::Url::parse(&format!("http://{}", addr)).unwrap();
+    let api = Api::new(config);
 
     let result: Result<Value> = api
-      .request(reqwest::Method::GET, "nonexistent-endpoint", None::<&Value>)
+      .with_timeout(Duration::from_millis(100))
+      .request(
+        reqwest::Method::GET,
+        &Endpoint::new(["test-endpoint"]),
+        None::<&Value>,
+      )
       .await;
 
-    assert!(result.is_err());
+    assert!(matches!(result, Err(Error::Timeout { .. })));
+    drop(listener);
+  }
 
-    match result.unwrap_err() {
-      Error::InvalidEndpoint(endpoint) => {
-        assert_eq!(endpoint, "nonexistent-endpoint");
-      }
-      err => panic!("Unexpected error: {:?}", err),
+  #[tokio::test]
+  async fn test_send_email_by_name() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
+
+    let list = mock_server
+      .mock("GET", "/api/v1/emails")
+      .with_status(200)
+      .with_body(r#"[{"id": "tem_welcome", "name": "Welcome Email"}]"#)
+      .expect(2)
+      .create();
+    let send = mock_server
+      .mock("POST", "/api/v1/send")
+      .match_body(Matcher::PartialJson(json!({"email_id": "tem_welcome"})))
+      .with_status(200)
+      .with_body(
+        r#"{"success": true, "status": "OK", "receipt_id": "log_1", "email": {"name": "Welcome Email"}}"#,
+      )
+      .expect(2)
+      .create();
+
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
+
+    for _ in 0..2 {
+      let options = EmailOptions::new("", Recipient::new("user@example.com"));
+      api
+        .clone()
+        .send_email_by_name("Welcome Email", options)
+        .await
+        .unwrap();
     }
 
-    mock.assert();
+    let options = EmailOptions::new("", Recipient::new("user@example.com"));
+    let result = api.send_email_by_name("Goodbye Email", options).await;
+    assert!(matches!(result, Err(Error::TemplateNotFound(name)) if name == "Goodbye Email"));
+
+    list.assert();
+    send.assert();
   }
 
   #[tokio::test]
-  async fn test_request_accepted() {
+  async fn test_circuit_breaker_fails_fast() {
     let mut mock_server = mockito::Server::new_async().await;
     let url = mock_server.url();
 
     let mock = mock_server
-      .mock("POST", "/api/v1/test-endpoint")
-      .match_header("Content-Type", "application/json")
-      .match_header("X-SWU-API-KEY", "test-api-key")
-      .with_status(202)
-      .with_body(r#"{"status": "accepted", "message": "Request accepted"}"#)
+      .mock("GET", "/api/v1/emails")
+      .with_status(503)
+      .with_body("Service Unavailable")
+      .expect(2)
       .create();
 
-    let mut config = Config::new("test-api-key");
+    let mut config = Config::new("test-api-key").with_circuit_breaker(
+      crate::circuit_breaker::CircuitBreakerConfig::new(2, Duration::from_secs(60)),
+    );
     config.url = url::Url::parse(&url).unwrap();
     let api = Api::new(config);
 
-    #[derive(Debug, serde::Deserialize)]
-    struct TestResponse {
-      status: String,
-      message: String,
+    for _ in 0..2 {
+      let result = api.list_templates().await;
+      assert!(matches!(result, Err(Error::ApiError { status: 503, .. })));
     }
 
-    let response: TestResponse = api
-      .request(reqwest::Method::POST, "test-endpoint", None::<&Value>)
-      .await
+    let result = api.clone().list_templates().await;
+    assert!(matches!(result, Err(Error::CircuitOpen { .. })));
+
+    mock.assert();
+  }
+
+  #[tokio::test]
+  async fn test_request_fails_over_to_fallback_url() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let fallback_url = mock_server.url();
+
+    let mock = mock_server
+      .mock("GET", "/api/v1/customers/user@example.com")
+      .with_status(200)
+      .with_body(r#"{"success": true}"#)
+      .create();
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+
+    let config = Config::new("test-api-key")
+      .try_with_url(format!("http://{}", addr))
+      .unwrap()
+      .try_with_fallback_url(fallback_url)
       .unwrap();
+    let api = Api::new(config);
 
-    assert_eq!(response.status, "accepted");
-    assert_eq!(response.message, "Request accepted");
+    let response = api.customer_get("user@example.com").await.unwrap();
+    assert_eq!(response["success"], json!(true));
 
     mock.assert();
   }
 
   #[tokio::test]
-  async fn test_request_forbidden() {
+  async fn test_retry_policy_retries_server_errors() {
     let mut mock_server = mockito::Server::new_async().await;
     let url = mock_server.url();
 
-    let mock = mock_server
-      .mock("GET", "/api/v1/test-endpoint")
-      .with_status(403)
-      .with_body("Forbidden")
+    let failing = mock_server
+      .mock("GET", "/api/v1/emails")
+      .with_status(502)
+      .with_body("Bad Gateway")
+      .expect(3)
       .create();
 
-    let mut config = Config::new("test-api-key");
+    let mut config = Config::new("test-api-key").with_retry_policy(
+      crate::retry::RetryPolicy::new(3).with_initial_backoff(Duration::from_millis(1)),
+    );
     config.url = url::Url::parse(&url).unwrap();
     let api = Api::new(config);
 
-    let result: Result<Value> = api
-      .request(reqwest::Method::GET, "test-endpoint", None::<&Value>)
-      .await;
+    let result = api.list_templates().await;
+    assert!(matches!(result, Err(Error::ApiError { status: 502, .. })));
+    failing.assert();
+    failing.remove();
 
-    assert!(result.is_err());
-    assert!(matches!(result.unwrap_err(), Error::InvalidCredentials));
+    let rejected = mock_server
+      .mock("GET", "/api/v1/emails")
+      .with_status(400)
+      .with_body("Bad Request")
+      .expect(1)
+      .create();
 
-    mock.assert();
+    let result = api.list_templates().await;
+    assert!(matches!(result, Err(Error::InvalidRequest { .. })));
+    rejected.assert();
   }
 
   #[tokio::test]
-  async fn test_request_bad_request() {
+  async fn test_sends_are_not_retried_after_server_errors_by_default() {
     let mut mock_server = mockito::Server::new_async().await;
     let url = mock_server.url();
-    let error_message = "Missing required field";
 
-    let mock = mock_server
-      .mock("POST", "/api/v1/test-endpoint")
-      .with_status(400)
-      .with_body(error_message)
+    let failing = mock_server
+      .mock("POST", "/api/v1/send")
+      .with_status(502)
+      .with_body("Bad Gateway")
+      .expect(1)
       .create();
 
-    let mut config = Config::new("test-api-key");
+    let mut config = Config::new("test-api-key").with_retry_policy(
+      crate::retry::RetryPolicy::new(3).with_initial_backoff(Duration::from_millis(1)),
+    );
     config.url = url::Url::parse(&url).unwrap();
     let api = Api::new(config);
 
-    let result: Result<Value> = api
-      .request(reqwest::Method::POST, "test-endpoint", Some(&json!({})))
-      .await;
+    let options = EmailOptions::new("tem_123", Recipient::new("user@example.com"));
+    let result = api.send_email(options).await;
+    assert!(matches!(result, Err(Error::ApiError { status: 502, .. })));
+    failing.assert();
+  }
 
-    assert!(result.is_err());
+  #[tokio::test]
+  async fn test_retry_budget_stops_retries() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
 
-    match result.unwrap_err() {
-      Error::InvalidRequest(message) => {
-        assert_eq!(message, error_message);
-      }
-      err => panic!("Unexpected error: {:?}", err),
-    }
+    let failing = mock_server
+      .mock("GET", "/api/v1/emails")
+      .with_status(503)
+      .with_body("Unavailable")
+      .expect(1)
+      .create();
 
-    mock.assert();
+    let mut config = Config::new("test-api-key").with_retry_policy(
+      crate::retry::RetryPolicy::new(5)
+        .with_initial_backoff(Duration::from_secs(10))
+        .with_budget(Duration::from_secs(1)),
+    );
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
+
+    let started = Instant::now();
+    let result = api.list_templates().await;
+
+    assert!(matches!(result, Err(Error::ApiError { status: 503, .. })));
+    assert!(started.elapsed() < Duration::from_secs(1));
+    failing.assert();
   }
 
   #[tokio::test]
-  async fn test_request_api_error() {
+  async fn test_retry_budget_bounds_slow_attempts() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let mut config = Config::new("test-api-key")
+      .with_retry_policy(crate::retry::RetryPolicy::new(5).with_budget(Duration::from_millis(100)));
+    config.url = url::Url::parse(&format!("http://{}", addr)).unwrap();
+    let api = Api::new(config);
+
+    let result = api.list_templates().await;
+    assert!(matches!(result, Err(Error::Timeout { .. })));
+    drop(listener);
+  }
+
+  #[tokio::test]
+  async fn test_rate_limited_calls_are_retried() {
     let mut mock_server = mockito::Server::new_async().await;
     let url = mock_server.url();
-    let error_message = "Internal server error";
 
-    let mock = mock_server
-      .mock("GET", "/api/v1/test-endpoint")
-      .with_status(500)
-      .with_body(error_message)
+    let limited = mock_server
+      .mock("GET", "/api/v1/emails")
+      .with_status(429)
+      .with_header("Retry-After", "0")
+      .expect(1)
+      .create();
+    let ok = mock_server
+      .mock("GET", "/api/v1/emails")
+      .with_status(200)
+      .with_body("[]")
+      .expect(1)
       .create();
 
-    let mut config = Config::new("test-api-key");
+    let mut config = Config::new("test-api-key").with_rate_limit_retry(Duration::from_secs(5));
     config.url = url::Url::parse(&url).unwrap();
     let api = Api::new(config);
 
-    let result: Result<Value> = api
-      .request(reqwest::Method::GET, "test-endpoint", None::<&Value>)
-      .await;
+    assert_eq!(api.list_templates().await.unwrap(), json!([]));
+    limited.assert();
+    ok.assert();
+  }
 
-    assert!(result.is_err());
+  #[tokio::test]
+  async fn test_zero_retry_after_still_exhausts_the_wait_limit() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
 
-    match result.unwrap_err() {
-      Error::ApiError { status, message } => {
-        assert_eq!(status, 500);
-        assert_eq!(message, error_message);
-      }
-      err => panic!("Unexpected error: {:?}", err),
-    }
+    // Waits of MIN_RATE_LIMIT_WAIT fit four times into one second.
+    let limited = mock_server
+      .mock("GET", "/api/v1/emails")
+      .with_status(429)
+      .with_header("Retry-After", "0")
+      .expect(5)
+      .create();
 
-    mock.assert();
+    let mut config = Config::new("test-api-key").with_rate_limit_retry(Duration::from_secs(1));
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
+
+    let result = api.list_templates().await;
+    assert!(matches!(result, Err(Error::RateLimited { .. })));
+    limited.assert();
+  }
+
+  #[test]
+  fn test_rate_limit_wait() {
+    let limited = |retry_after| -> Result<()> {
+      Err(Error::RateLimited {
+        retry_after,
+        context: None,
+      })
+    };
+    let max_wait = Some(Duration::from_secs(10));
+
+    assert_eq!(
+      rate_limit_wait(
+        &limited(Some(Duration::from_secs(3))),
+        max_wait,
+        Duration::ZERO
+      ),
+      Some(Duration::from_secs(3))
+    );
+    assert_eq!(
+      rate_limit_wait(&limited(None), max_wait, Duration::ZERO),
+      Some(DEFAULT_RATE_LIMIT_WAIT)
+    );
+    assert_eq!(
+      rate_limit_wait(
+        &limited(Some(Duration::from_secs(3))),
+        max_wait,
+        Duration::from_secs(8)
+      ),
+      None
+    );
+    assert_eq!(
+      rate_limit_wait(&limited(Some(Duration::ZERO)), max_wait, Duration::ZERO),
+      Some(MIN_RATE_LIMIT_WAIT)
+    );
+    assert_eq!(rate_limit_wait(&limited(None), None, Duration::ZERO), None);
+    assert_eq!(
+      rate_limit_wait::<()>(
+        &Err(Error::Timeout { context: None }),
+        max_wait,
+        Duration::ZERO
+      ),
+      None
+    );
   }
 
   #[tokio::test]
-  async fn test_request_with_custom_client() {
+  async fn test_dead_letters_are_captured_and_replayed() {
     let mut mock_server = mockito::Server::new_async().await;
     let url = mock_server.url();
 
-    let mock = mock_server
-      .mock("GET", "/api/v1/test-endpoint")
-      .match_header("Content-Type", "application/json")
-      .match_header("X-SWU-API-KEY", "test-api-key")
-      .match_header("X-SWU-API-CLIENT", Matcher::Any)
-      .match_header("User-Agent", "test-agent")
-      .with_status(200)
-      .with_body(r#"{"success": true}"#)
+    let failing = mock_server
+      .mock("POST", "/api/v1/send")
+      .with_status(500)
+      .with_body("Internal Server Error")
+      .expect(2)
       .create();
 
-    let custom_client = Client::builder().user_agent("test-agent").build().unwrap();
+    use crate::dead_letter::{DeadLetterSink, MemoryDeadLetterSink};
 
-    let mut config = Config::new("test-api-key");
+    let sink = Arc::new(MemoryDeadLetterSink::new());
+    let mut config = Config::new("test-api-key").with_retry_policy(
+      crate::retry::RetryPolicy::new(2)
+        .with_initial_backoff(Duration::from_millis(1))
+        .with_send_retries(true),
+    );
+    config.dead_letter_sink = Some(sink.clone());
     config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
 
-    let api = Api {
-      config,
-      client: custom_client,
-    };
+    let options = EmailOptions::new("tem_123", Recipient::new("user@example.com"));
+    assert!(api.send_email(options.clone()).await.is_err());
+    failing.assert();
+    failing.remove();
 
-    let response: Value = api
-      .request(reqwest::Method::GET, "test-endpoint", None::<&Value>)
-      .await
-      .unwrap();
+    let letters = sink.letters().await.unwrap();
+    assert_eq!(letters.len(), 1);
+    assert_eq!(letters[0].options, options);
+    assert!(letters[0].error.contains("500"));
+    assert!(letters[0].may_have_been_sent);
 
-    assert_eq!(response["success"], json!(true));
+    let ok = mock_server
+      .mock("POST", "/api/v1/send")
+      .with_status(200)
+      .with_body(
+        r#"{"success": true, "status": "OK", "receipt_id": "log_1", "email": {"name": "Welcome"}}"#,
+      )
+      .expect(2)
+      .create();
 
-    mock.assert();
+    // A 500 leaves open whether the email went out, so it needs a check first.
+    let outcomes = api.replay_dead_letters().await.unwrap();
+    assert_eq!(outcomes.len(), 1);
+    assert!(matches!(outcomes[0].1, Err(Error::SendOutcomeUnknown)));
+    assert_eq!(sink.letters().await.unwrap().len(), 1);
+
+    let outcomes = api.replay_dead_letters_including_unknown().await.unwrap();
+    assert_eq!(outcomes.len(), 1);
+    assert_eq!(outcomes[0].0, letters[0].id);
+    assert!(outcomes[0].1.is_ok());
+    assert!(sink.letters().await.unwrap().is_empty());
+
+    // Letters that never reached SendWithUs are replayed right away.
+    let letter = crate::dead_letter::DeadLetter::new(options, "Connection failed", 1_700_000_000)
+      .with_may_have_been_sent(false);
+    sink.capture(letter).await.unwrap();
+    let outcomes = api.replay_dead_letters().await.unwrap();
+    assert!(outcomes[0].1.is_ok());
+    assert!(sink.letters().await.unwrap().is_empty());
+    ok.assert();
   }
 
   #[tokio::test]
-  async fn test_request_debug_mode() {
+  async fn test_unreachable_sends_are_dead_lettered_as_not_sent() {
+    use crate::dead_letter::{DeadLetterSink, MemoryDeadLetterSink};
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+
+    let sink = Arc::new(MemoryDeadLetterSink::new());
+    let mut config = Config::new("test-api-key");
+    config.dead_letter_sink = Some(sink.clone());
+    config.url = url::Url::parse(&format!("http://{}", addr)).unwrap();
+    let api = Api::new(config);
+
+    let options = EmailOptions::new("tem_123", Recipient::new("user@example.com"));
+    assert!(api.send_email(options).await.is_err());
+
+    let letters = sink.letters().await.unwrap();
+    assert_eq!(letters.len(), 1);
+    assert!(!letters[0].may_have_been_sent);
+  }
+
+  #[tokio::test]
+  async fn test_capture_session_records_sanitized_traffic() {
     let mut mock_server = mockito::Server::new_async().await;
     let url = mock_server.url();
 
     let mock = mock_server
-      .mock("GET", "/api/v1/test-endpoint")
-      .match_header("Content-Type", "application/json")
-      .match_header("X-SWU-API-KEY", "test-api-key")
-      .with_status(200)
-      .with_body(r#"{"success": true}"#)
+      .mock("POST", "/api/v1/send")
+      .with_status(400)
+      .with_header("content-type", "application/json")
+      .with_body(r#"{"success": false, "error": "Bad request"}"#)
       .create();
 
-    let mut config = Config::new("test-api-key");
+    let mut config = Config::new("test-api-key")
+      .with_capture_session(crate::capture::MemoryCaptureSession::new())
+      .with_redacted_data_keys(vec!["reset_token".to_string()]);
     config.url = url::Url::parse(&url).unwrap();
-    config.debug = true;
     let api = Api::new(config);
 
-    let response: Value = api
-      .request(reqwest::Method::GET, "test-endpoint", None::<&Value>)
-      .await
-      .unwrap();
+    let options =
+      EmailOptions::new("tem_123", Recipient::new("user@example.com")).with_data(HashMap::from([
+        ("reset_token".to_string(), json!("s3cret-token")),
+      ]));
+    assert!(api.send_email(options).await.is_err());
 
-    assert_eq!(response["success"], json!(true));
+    let har = api.capture_har().await.unwrap();
+    let entries = har["log"]["entries"].as_array().unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0]["request"]["method"], "POST");
+    assert_eq!(entries[0]["response"]["status"], 400);
+
+    let text = har.to_string();
+    assert!(!text.contains("test-api-key"));
+    assert!(!text.contains("user@example.com"));
+    assert!(!text.contains("s3cret-token"));
 
     mock.assert();
   }
 
+  #[tokio::test]
+  async fn test_capture_har_without_session() {
+    let api = Api::with_api_key("test-api-key");
+    let har = api.capture_har().await.unwrap();
+    assert!(har["log"]["entries"].as_array().unwrap().is_empty());
+  }
+
+  #[tokio::test]
+  async fn test_read_timeout_from_config() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let mut config = Config::new("test-api-key").with_read_timeout(Duration::from_millis(100));
+    config.url = url::Url::parse(&format!("http://{}", addr)).unwrap();
+    let api = Api::new(config);
+
+    let result = api.list_templates().await;
+    assert!(matches!(result, Err(Error::Timeout { .. })));
+    drop(listener);
+  }
+
   #[tokio::test]
   async fn test_request_connection_failed() {
     let mut config = Config::new("test-api-key");
@@ -1412,13 +5744,19 @@ mod request_tests {
     let api = Api::new(config);
 
     let result: Result<Value> = api
-      .request(reqwest::Method::GET, "test-endpoint", None::<&Value>)
+      .request(
+        reqwest::Method::GET,
+        &Endpoint::new(["test-endpoint"]),
+        None::<&Value>,
+      )
       .await;
 
     assert!(result.is_err());
     match result.unwrap_err() {
-      Error::ConnectionFailed => {
-        // This is the expected error
+      Error::ConnectionFailed { context } => {
+        let context = context.expect("transport errors carry the request");
+        assert_eq!(context.endpoint, "test-endpoint");
+        assert!(context.correlation_id.is_some());
       }
       err => panic!("Expected ConnectionFailed error, got: {:?}", err),
     }
@@ -1435,13 +5773,20 @@ mod request_tests {
     let api = Api::new(config);
 
     let result: Result<Value> = api
-      .request(reqwest::Method::GET, "test-endpoint", None::<&Value>)
+      .request(
+        reqwest::Method::GET,
+        &Endpoint::new(["test-endpoint"]),
+        None::<&Value>,
+      )
       .await;
 
     assert!(result.is_err());
     match result.unwrap_err() {
-      Error::RequestFailed(e) => {
-        assert!(!e.is_connect(), "Expected non-connection reqwest error");
+      Error::RequestFailed { source, .. } => {
+        assert!(
+          !source.is_connect(),
+          "Expected non-connection reqwest error"
+        );
       }
       err => panic!("Expected RequestFailed error, got: {:?}", err),
     }
@@ -1,17 +1,274 @@
-use crate::error::Result;
+use crate::error::{Error, Result};
+use base64::display::Base64Display;
 use base64::{Engine as _, engine::general_purpose};
-use serde::{Deserialize, Serialize};
+use bytes::Bytes;
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
 use std::path::Path;
+use std::sync::{Arc, Mutex};
 use tokio::fs;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// Largest attachment SendWithUs accepts, in bytes before base64 encoding.
+pub const MAX_ATTACHMENT_SIZE: usize = 10 * 1024 * 1024;
+
+/// Largest combined size of an email's attachments, in bytes before base64 encoding.
+pub const MAX_TOTAL_ATTACHMENT_SIZE: usize = 25 * 1024 * 1024;
+
+/// Size limits for email attachments, in bytes before base64 encoding.
+///
+/// Defaults to `MAX_ATTACHMENT_SIZE` per attachment and
+/// `MAX_TOTAL_ATTACHMENT_SIZE` for all attachments of an email. Set lower
+/// limits with `Config::with_attachment_limits` to reject large emails before
+/// they are encoded and uploaded.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::Attachment;
+/// use send_with_us::attachment::AttachmentLimits;
+///
+/// let limits = AttachmentLimits::new()
+///   .with_max_size(2 * 1024 * 1024)
+///   .with_max_total_size(5 * 1024 * 1024);
+///
+/// let files = vec![Attachment::from_bytes(b"hello", "hello.txt")];
+/// assert!(limits.check(&files).is_ok());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AttachmentLimits {
+  /// Largest size of a single attachment
+  pub max_size: usize,
+
+  /// Largest combined size of all attachments of an email
+  pub max_total_size: usize,
+}
+
+impl Default for AttachmentLimits {
+  fn default() -> Self {
+    Self {
+      max_size: MAX_ATTACHMENT_SIZE,
+      max_total_size: MAX_TOTAL_ATTACHMENT_SIZE,
+    }
+  }
+}
+
+impl AttachmentLimits {
+  /// Creates the default limits.
+  ///
+  /// # Returns
+  /// Limits of `MAX_ATTACHMENT_SIZE` per attachment and `MAX_TOTAL_ATTACHMENT_SIZE` in total
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Sets the largest size of a single attachment.
+  ///
+  /// # Arguments
+  /// * `max_size` - Limit in bytes
+  ///
+  /// # Returns
+  /// Self with the limit set for method chaining
+  pub fn with_max_size(mut self, max_size: usize) -> Self {
+    self.max_size = max_size;
+    self
+  }
+
+  /// Sets the largest combined size of all attachments of an email.
+  ///
+  /// # Arguments
+  /// * `max_total_size` - Limit in bytes
+  ///
+  /// # Returns
+  /// Self with the limit set for method chaining
+  pub fn with_max_total_size(mut self, max_total_size: usize) -> Self {
+    self.max_total_size = max_total_size;
+    self
+  }
+
+  /// Checks attachments against the limits.
+  ///
+  /// # Arguments
+  /// * `files` - Attachments of an email
+  ///
+  /// # Errors
+  /// Returns `Error::AttachmentTooLarge` for the first attachment over
+  /// `max_size`, or if the attachments together are over `max_total_size`
+  pub fn check(&self, files: &[Attachment]) -> Result<()> {
+    let mut total = 0usize;
+
+    for file in files {
+      let size = file.size();
+      check_size(Some(&file.id), size, self.max_size)?;
+      total = total.saturating_add(size);
+    }
+
+    check_size(None, total, self.max_total_size)
+  }
+}
+
+/// Fails with `Error::AttachmentTooLarge` if `size` is over `limit`.
+fn check_size(filename: Option<&str>, size: usize, limit: usize) -> Result<()> {
+  if size > limit {
+    Err(Error::AttachmentTooLarge {
+      filename: filename.map(str::to_string),
+      size,
+      limit,
+    })
+  } else {
+    Ok(())
+  }
+}
+
+/// How an attachment is presented to the recipient.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Disposition {
+  /// Shown as a separate file
+  Attachment,
+
+  /// Embedded in the email body and referenced by its content ID
+  Inline,
+}
+
+impl Disposition {
+  /// Returns the disposition as sent to the API.
+  pub fn as_str(self) -> &'static str {
+    match self {
+      Disposition::Attachment => "attachment",
+      Disposition::Inline => "inline",
+    }
+  }
+}
 
 /// Represents a file attachment for an email
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+///
+/// The content is kept as raw bytes and only base64 encoded while the email
+/// is serialized, straight into the request body, so a large file is never
+/// held in memory in both forms. Cloning an attachment doesn't copy its
+/// content. Clones share their encoding instead: when the same attachment is
+/// added to many emails, e.g. in a bulk send, it is encoded once and the
+/// encoded data is reused for every email.
+///
+/// With the `mime-detection` feature, constructors set `content_type` from
+/// the content's magic bytes, or failing that from the filename extension.
+/// Use `with_content_type` to override it.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::Attachment;
+/// use send_with_us::attachment::Disposition;
+///
+/// // An image referenced from the template as `<img src="cid:logo">`.
+/// let logo = Attachment::from_bytes(b"...", "logo.png")
+///   .with_content_type("image/png")
+///   .inline("logo");
+///
+/// assert_eq!(logo.content_id.as_deref(), Some("logo"));
+/// assert_eq!(logo.disposition, Some(Disposition::Inline));
+/// ```
+#[derive(Debug, Clone, Deserialize, PartialEq)]
 pub struct Attachment {
   /// Attachment ID/filename
   pub id: String,
 
-  /// Base64 encoded data
-  pub data: String,
+  /// Raw content, base64 encoded when serialized
+  #[serde(deserialize_with = "deserialize_base64")]
+  pub data: Bytes,
+
+  /// MIME type of the content, e.g. `application/pdf` (optional)
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub content_type: Option<String>,
+
+  /// Content ID referenced by `cid:` URLs in the email body (optional)
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub content_id: Option<String>,
+
+  /// How the attachment is presented (optional)
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub disposition: Option<Disposition>,
+
+  #[serde(skip)]
+  encoding: EncodingCache,
+}
+
+impl Serialize for Attachment {
+  fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    let optional = [
+      ("content_type", self.content_type.as_deref()),
+      ("content_id", self.content_id.as_deref()),
+      ("disposition", self.disposition.map(Disposition::as_str)),
+    ];
+
+    let len = 2 + optional.iter().filter(|(_, value)| value.is_some()).count();
+    let mut state = serializer.serialize_struct("Attachment", len)?;
+    state.serialize_field("id", &self.id)?;
+
+    // Only clones benefit from a cached encoding; a lone attachment is
+    // encoded straight into the output to avoid a second copy in memory.
+    if self.encoding.is_shared() {
+      state.serialize_field("data", &*self.encoding.get_or_encode(&self.data))?;
+    } else {
+      state.serialize_field("data", &Base64Data(&self.data))?;
+    }
+
+    for (name, value) in optional {
+      match value {
+        Some(value) => state.serialize_field(name, value)?,
+        None => state.skip_field(name)?,
+      }
+    }
+
+    state.end()
+  }
+}
+
+/// Base64 encoding of an attachment's content, shared by the attachment's clones.
+///
+/// The encoding is kept together with the content it was made from, so it is
+/// redone if `data` is replaced.
+#[derive(Clone, Default)]
+struct EncodingCache(Arc<Mutex<Option<Encoded>>>);
+
+/// Content paired with its base64 encoding.
+type Encoded = (Bytes, Arc<str>);
+
+impl EncodingCache {
+  /// Returns `true` if the attachment has clones sharing this cache.
+  fn is_shared(&self) -> bool {
+    Arc::strong_count(&self.0) > 1
+  }
+
+  /// Returns the encoding of `data`, encoding it on first use.
+  fn get_or_encode(&self, data: &Bytes) -> Arc<str> {
+    let mut cached = self.0.lock().unwrap_or_else(|e| e.into_inner());
+
+    match &*cached {
+      Some((source, encoded)) if source.as_ptr() == data.as_ptr() && source.len() == data.len() => {
+        encoded.clone()
+      }
+      _ => {
+        let encoded: Arc<str> = general_purpose::STANDARD.encode(data).into();
+        *cached = Some((data.clone(), encoded.clone()));
+        encoded
+      }
+    }
+  }
+}
+
+impl PartialEq for EncodingCache {
+  fn eq(&self, _other: &Self) -> bool {
+    // The cache is derived from the content, so it never affects equality.
+    true
+  }
+}
+
+impl fmt::Debug for EncodingCache {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str("EncodingCache")
+  }
 }
 
 impl Attachment {
@@ -19,6 +276,7 @@ impl Attachment {
   ///
   /// This method reads a file from the filesystem, extracts its filename,
   /// and creates an attachment with the file contents encoded in base64.
+  /// Files larger than `MAX_ATTACHMENT_SIZE` are rejected before they are read.
   ///
   /// # Arguments
   /// * `path` - Path to the file to attach
@@ -27,7 +285,8 @@ impl Attachment {
   /// A Result containing the new Attachment if successful
   ///
   /// # Errors
-  /// Returns an error if the file cannot be read
+  /// Returns `Error::AttachmentTooLarge` if the file is too large, or an error
+  /// if the file cannot be read
   ///
   /// # Examples
   ///
@@ -39,6 +298,24 @@ impl Attachment {
   /// # }
   /// ```
   pub async fn from_path(path: impl AsRef<Path>) -> Result<Self> {
+    Self::from_path_with_limit(path, MAX_ATTACHMENT_SIZE).await
+  }
+
+  /// Creates a new attachment from a file, rejecting files over `max_size` bytes.
+  ///
+  /// The size is checked before the file is read.
+  ///
+  /// # Arguments
+  /// * `path` - Path to the file to attach
+  /// * `max_size` - Largest accepted file size in bytes
+  ///
+  /// # Returns
+  /// A Result containing the new Attachment if successful
+  ///
+  /// # Errors
+  /// Returns `Error::AttachmentTooLarge` if the file is too large, or an error
+  /// if the file cannot be read
+  pub async fn from_path_with_limit(path: impl AsRef<Path>, max_size: usize) -> Result<Self> {
     let path = path.as_ref();
     let filename = path
       .file_name()
@@ -46,26 +323,167 @@ impl Attachment {
       .unwrap_or("attachment")
       .to_string();
 
+    let size = usize::try_from(fs::metadata(path).await?.len()).unwrap_or(usize::MAX);
+    check_size(Some(&filename), size, max_size)?;
+
     let content = fs::read(path).await?;
-    let encoded = general_purpose::STANDARD.encode(&content);
+    Ok(Self::from_shared(content, filename))
+  }
 
-    Ok(Self {
-      id: filename,
-      data: encoded,
-    })
+  /// Creates attachments from all files matching a pattern.
+  ///
+  /// The last path component is a glob pattern, e.g. `reports/*.pdf`:
+  /// `*` matches any run of characters, `?` matches any single character,
+  /// and `[...]` matches one of the enclosed characters or ranges (`[!...]`
+  /// negates the set). Matching is case-sensitive, so `*.pdf` doesn't match
+  /// `REPORT.PDF`. The directory part is taken literally. Matching files are
+  /// read concurrently, each checked against `MAX_ATTACHMENT_SIZE` like
+  /// `from_path`, and returned sorted by file name. Subdirectories are
+  /// skipped.
+  ///
+  /// # Arguments
+  /// * `pattern` - Path whose file name is a glob pattern
+  ///
+  /// # Returns
+  /// A Result containing an attachment per matching file, possibly none
+  ///
+  /// # Errors
+  /// Returns an error if the file name isn't a valid pattern, the directory
+  /// part contains a wildcard, the directory cannot be listed, or any
+  /// matching file cannot be attached
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use send_with_us::Attachment;
+  /// # async fn example() -> send_with_us::Result<()> {
+  /// let reports = Attachment::from_glob("output/reports/*.pdf").await?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn from_glob(pattern: impl AsRef<Path>) -> Result<Vec<Self>> {
+    let pattern = pattern.as_ref();
+    let invalid = |message: &str| {
+      std::io::Error::new(
+        std::io::ErrorKind::InvalidInput,
+        format!("{}: {}", message, pattern.display()),
+      )
+    };
+
+    let file_pattern = pattern
+      .file_name()
+      .and_then(|name| name.to_str())
+      .ok_or_else(|| invalid("attachment pattern has no file name"))?;
+    let file_pattern =
+      glob::Pattern::new(file_pattern).map_err(|_| invalid("invalid attachment pattern"))?;
+    let directory = match pattern.parent() {
+      Some(parent) if !parent.as_os_str().is_empty() => parent,
+      _ => Path::new("."),
+    };
+    if directory.to_string_lossy().contains(['*', '?', '[']) {
+      return Err(invalid("wildcards are only supported in the file name").into());
+    }
+
+    let mut paths = Vec::new();
+    let mut entries = fs::read_dir(directory).await?;
+    while let Some(entry) = entries.next_entry().await? {
+      let name = entry.file_name();
+      let matches = name.to_str().is_some_and(|name| file_pattern.matches(name));
+
+      if matches && entry.file_type().await?.is_file() {
+        paths.push(entry.path());
+      }
+    }
+    paths.sort();
+
+    futures::future::try_join_all(paths.into_iter().map(Self::from_path)).await
+  }
+
+  /// Creates a new attachment by reading data from an async reader.
+  ///
+  /// A stream from an object store or HTTP body can be attached this way
+  /// without going through the filesystem first.
+  ///
+  /// # Arguments
+  /// * `reader` - Source of the attachment content, read to the end
+  /// * `filename` - The filename to use for the attachment
+  ///
+  /// Reading stops with `Error::AttachmentTooLarge` once more than
+  /// `MAX_ATTACHMENT_SIZE` bytes have been read.
+  ///
+  /// # Returns
+  /// A Result containing the new Attachment if successful
+  ///
+  /// # Errors
+  /// Returns `Error::AttachmentTooLarge` if the content is too large, or an
+  /// error if reading from `reader` fails
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::Attachment;
+  ///
+  /// # async fn example() -> send_with_us::Result<()> {
+  /// let reader: &[u8] = b"hello world";
+  /// let attachment = Attachment::from_reader(reader, "greeting.txt").await?;
+  /// assert_eq!(attachment.size(), 11);
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn from_reader(
+    reader: impl AsyncRead + Unpin,
+    filename: impl Into<String>,
+  ) -> Result<Self> {
+    Self::from_reader_with_limit(reader, filename, MAX_ATTACHMENT_SIZE).await
+  }
+
+  /// Creates a new attachment from an async reader, rejecting content over `max_size` bytes.
+  ///
+  /// # Arguments
+  /// * `reader` - Source of the attachment content, read to the end
+  /// * `filename` - The filename to use for the attachment
+  /// * `max_size` - Largest accepted content size in bytes
+  ///
+  /// # Returns
+  /// A Result containing the new Attachment if successful
+  ///
+  /// # Errors
+  /// Returns `Error::AttachmentTooLarge` as soon as more than `max_size` bytes
+  /// have been read, or an error if reading from `reader` fails
+  pub async fn from_reader_with_limit(
+    mut reader: impl AsyncRead + Unpin,
+    filename: impl Into<String>,
+    max_size: usize,
+  ) -> Result<Self> {
+    let filename = filename.into();
+    let mut content = Vec::new();
+    let mut buffer = vec![0; 64 * 1024];
+
+    loop {
+      let read = reader.read(&mut buffer).await?;
+      if read == 0 {
+        break;
+      }
+
+      check_size(Some(&filename), content.len() + read, max_size)?;
+      content.extend_from_slice(&buffer[..read]);
+    }
+
+    Ok(Self::from_shared(content, filename))
   }
 
   /// Creates a new attachment from raw bytes with a given filename.
   ///
   /// This method is useful when you already have file data in memory
-  /// and don't need to read from the filesystem.
+  /// and don't need to read from the filesystem. The size isn't checked
+  /// here; `EmailOptions::validate` checks it before sending.
   ///
   /// # Arguments
   /// * `content` - The raw bytes to encode as the attachment content
   /// * `filename` - The filename to use for the attachment
   ///
   /// # Returns
-  /// A new Attachment with the provided content encoded in base64
+  /// A new Attachment with a copy of the provided content
   ///
   /// # Examples
   ///
@@ -76,15 +494,208 @@ impl Attachment {
   /// let attachment = Attachment::from_bytes(content, "greeting.txt");
   /// ```
   pub fn from_bytes(content: &[u8], filename: impl Into<String>) -> Self {
-    let encoded = general_purpose::STANDARD.encode(content);
+    Self::from_shared(Bytes::copy_from_slice(content), filename)
+  }
+
+  /// Creates a new attachment that takes ownership of its content without copying it.
+  ///
+  /// # Arguments
+  /// * `content` - The attachment content, e.g. a `Vec<u8>` or `Bytes`
+  /// * `filename` - The filename to use for the attachment
+  ///
+  /// # Returns
+  /// A new Attachment
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::Attachment;
+  ///
+  /// let report = b"col1,col2\n1,2\n".to_vec();
+  /// let attachment = Attachment::from_shared(report, "report.csv");
+  /// assert_eq!(attachment.size(), 14);
+  /// ```
+  pub fn from_shared(content: impl Into<Bytes>, filename: impl Into<String>) -> Self {
+    let id = filename.into();
+    let data = content.into();
 
     Self {
-      id: filename.into(),
-      data: encoded,
+      content_type: detect_content_type(&id, &data),
+      id,
+      data,
+      content_id: None,
+      disposition: None,
+      encoding: EncodingCache::default(),
+    }
+  }
+
+  /// Sets the attachment ID, the filename the recipient sees.
+  ///
+  /// Constructors use the source file name as the ID; use this when the
+  /// source has a temporary or otherwise meaningless name. With the
+  /// `mime-detection` feature, a content type that couldn't be detected
+  /// before is detected again using the new ID.
+  ///
+  /// # Arguments
+  /// * `id` - Attachment ID/filename
+  ///
+  /// # Returns
+  /// Self with the ID set for method chaining
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use send_with_us::Attachment;
+  /// # async fn example() -> send_with_us::Result<()> {
+  /// let attachment = Attachment::from_path("/tmp/.tmp4f2a9c")
+  ///   .await?
+  ///   .with_id("invoice-2024-01.pdf");
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn with_id(mut self, id: impl Into<String>) -> Self {
+    self.id = id.into();
+    if self.content_type.is_none() {
+      self.content_type = detect_content_type(&self.id, &self.data);
+    }
+    self
+  }
+
+  /// Sets the MIME type of the attachment.
+  ///
+  /// # Arguments
+  /// * `content_type` - MIME type, e.g. `application/pdf`
+  ///
+  /// # Returns
+  /// Self with the content type set for method chaining
+  pub fn with_content_type(mut self, content_type: impl Into<String>) -> Self {
+    self.content_type = Some(content_type.into());
+    self
+  }
+
+  /// Embeds the attachment in the email body under a content ID.
+  ///
+  /// The template references the attachment as `cid:<content_id>`, e.g. in
+  /// the `src` of an image.
+  ///
+  /// # Arguments
+  /// * `content_id` - Content ID to reference the attachment by
+  ///
+  /// # Returns
+  /// Self with the content ID and inline disposition set for method chaining
+  pub fn inline(mut self, content_id: impl Into<String>) -> Self {
+    self.content_id = Some(content_id.into());
+    self.disposition = Some(Disposition::Inline);
+    self
+  }
+
+  /// Bundles attachments into a single zip archive attachment.
+  ///
+  /// Each attachment becomes a compressed entry named after its `id`. The
+  /// bundle has the content type `application/zip`. Requires the `zip`
+  /// feature.
+  ///
+  /// # Arguments
+  /// * `files` - Attachments to bundle
+  /// * `filename` - The filename to use for the archive
+  ///
+  /// # Returns
+  /// A Result containing the archive attachment if successful
+  ///
+  /// # Errors
+  /// Returns an error if the archive cannot be written, e.g. because two
+  /// attachments have the same `id`
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::Attachment;
+  ///
+  /// # fn main() -> send_with_us::Result<()> {
+  /// let bundle = Attachment::zip(
+  ///   &[
+  ///     Attachment::from_bytes(b"%PDF-1.7 ...", "invoice-001.pdf"),
+  ///     Attachment::from_bytes(b"%PDF-1.7 ...", "invoice-002.pdf"),
+  ///   ],
+  ///   "invoices.zip",
+  /// )?;
+  ///
+  /// assert_eq!(bundle.content_type.as_deref(), Some("application/zip"));
+  /// # Ok(())
+  /// # }
+  /// ```
+  #[cfg(feature = "zip")]
+  pub fn zip(files: &[Attachment], filename: impl Into<String>) -> Result<Self> {
+    use std::io::Write;
+    use zip::write::{SimpleFileOptions, ZipWriter};
+
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let mut archive = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+
+    for file in files {
+      archive
+        .start_file(file.id.as_str(), options)
+        .map_err(std::io::Error::from)?;
+      archive.write_all(&file.data)?;
     }
+
+    let content = archive.finish().map_err(std::io::Error::from)?.into_inner();
+    Ok(Self::from_shared(content, filename).with_content_type("application/zip"))
+  }
+
+  /// Returns the size of the attachment content in bytes, before base64 encoding.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::Attachment;
+  ///
+  /// let attachment = Attachment::from_bytes(b"hello world", "greeting.txt");
+  /// assert_eq!(attachment.size(), 11);
+  /// ```
+  pub fn size(&self) -> usize {
+    self.data.len()
+  }
+}
+
+/// Infers a MIME type from magic bytes, falling back to the filename extension.
+#[cfg(feature = "mime-detection")]
+fn detect_content_type(filename: &str, content: &[u8]) -> Option<String> {
+  infer::get(content)
+    .map(|kind| kind.mime_type().to_string())
+    .or_else(|| {
+      mime_guess::from_path(filename)
+        .first()
+        .map(|mime| mime.essence_str().to_string())
+    })
+}
+
+/// Leaves the MIME type unset without the `mime-detection` feature.
+#[cfg(not(feature = "mime-detection"))]
+fn detect_content_type(_filename: &str, _content: &[u8]) -> Option<String> {
+  None
+}
+
+/// Content that serializes as base64 without building the encoded string first.
+struct Base64Data<'a>(&'a Bytes);
+
+impl Serialize for Base64Data<'_> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    serializer.collect_str(&Base64Display::new(self.0, &general_purpose::STANDARD))
   }
 }
 
+/// Deserializes base64 encoded content.
+fn deserialize_base64<'de, D: Deserializer<'de>>(
+  deserializer: D,
+) -> std::result::Result<Bytes, D::Error> {
+  let encoded = String::deserialize(deserializer)?;
+  general_purpose::STANDARD
+    .decode(encoded.trim_end())
+    .map(Bytes::from)
+    .map_err(serde::de::Error::custom)
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -99,7 +710,187 @@ mod tests {
     let attachment = Attachment::from_bytes(content, filename);
 
     assert_eq!(attachment.id, "rawr.txt");
-    assert_eq!(attachment.data, general_purpose::STANDARD.encode(content));
+    assert_eq!(attachment.data.as_ref(), content);
+  }
+
+  #[test]
+  fn test_attachment_serializes_optional_fields_only_when_set() {
+    let plain = Attachment::from_bytes(b"hello", "notes");
+    assert_eq!(
+      serde_json::to_value(&plain).unwrap(),
+      serde_json::json!({"id": "notes", "data": "aGVsbG8="})
+    );
+
+    let inline = plain.with_content_type("image/png").inline("logo");
+    assert_eq!(
+      serde_json::to_value(&inline).unwrap(),
+      serde_json::json!({
+        "id": "notes",
+        "data": "aGVsbG8=",
+        "content_type": "image/png",
+        "content_id": "logo",
+        "disposition": "inline"
+      })
+    );
+  }
+
+  #[tokio::test]
+  async fn test_attachment_from_reader() -> Result<()> {
+    // Larger than the read buffer, and not a multiple of three bytes.
+    let content: Vec<u8> = (0..200_001).map(|i| (i % 251) as u8).collect();
+    let attachment = Attachment::from_reader(content.as_slice(), "blob.bin").await?;
+
+    assert_eq!(attachment.id, "blob.bin");
+    assert_eq!(attachment.data.as_ref(), &content);
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_attachment_construction_enforces_limit() {
+    let content = vec![0u8; 100];
+
+    let result = Attachment::from_reader_with_limit(content.as_slice(), "big.bin", 99).await;
+    assert!(matches!(
+      result,
+      Err(Error::AttachmentTooLarge {
+        size: 100,
+        limit: 99,
+        ..
+      })
+    ));
+    assert!(
+      Attachment::from_reader_with_limit(content.as_slice(), "ok.bin", 100)
+        .await
+        .is_ok()
+    );
+
+    let temp_dir = TempDir::new("attachment_test").unwrap();
+    let file_path = temp_dir.path().join("big.bin");
+    std::fs::write(&file_path, &content).unwrap();
+
+    let result = Attachment::from_path_with_limit(&file_path, 50).await;
+    let Err(Error::AttachmentTooLarge { filename, .. }) = result else {
+      panic!("expected the file to be rejected");
+    };
+    assert_eq!(filename.as_deref(), Some("big.bin"));
+  }
+
+  #[test]
+  fn test_attachment_limits_check() {
+    let files = vec![
+      Attachment::from_bytes(&[0; 60], "a.bin"),
+      Attachment::from_bytes(&[0; 60], "b.bin"),
+    ];
+
+    let limits = AttachmentLimits::new().with_max_size(60);
+    assert!(limits.check(&files).is_ok());
+
+    let result = limits.with_max_size(59).check(&files);
+    assert!(matches!(
+      result,
+      Err(Error::AttachmentTooLarge { filename: Some(ref name), .. }) if name == "a.bin"
+    ));
+
+    let result = limits.with_max_total_size(100).check(&files);
+    assert!(matches!(
+      result,
+      Err(Error::AttachmentTooLarge {
+        filename: None,
+        size: 120,
+        limit: 100
+      })
+    ));
+  }
+
+  #[test]
+  fn test_attachment_with_id() {
+    let attachment = Attachment::from_bytes(b"a,b\n1,2\n", "tmp8f3a2c").with_id("report.csv");
+    assert_eq!(attachment.id, "report.csv");
+    assert_eq!(attachment.data.as_ref(), b"a,b\n1,2\n");
+
+    #[cfg(feature = "mime-detection")]
+    assert_eq!(attachment.content_type.as_deref(), Some("text/csv"));
+  }
+
+  #[test]
+  fn test_attachment_clones_share_encoding() {
+    let attachment = Attachment::from_bytes(b"%PDF-1.7 invoice", "invoice");
+    let expected = serde_json::to_value(&attachment).unwrap();
+
+    let clones: Vec<_> = (0..3).map(|_| attachment.clone()).collect();
+    for clone in &clones {
+      assert_eq!(serde_json::to_value(clone).unwrap(), expected);
+    }
+
+    let first = clones[0].encoding.get_or_encode(&clones[0].data);
+    let second = clones[1].encoding.get_or_encode(&clones[1].data);
+    assert!(Arc::ptr_eq(&first, &second));
+
+    let mut replaced = clones[2].clone();
+    replaced.data = Bytes::from_static(b"hello");
+    assert_eq!(serde_json::to_value(&replaced).unwrap()["data"], "aGVsbG8=");
+  }
+
+  #[test]
+  fn test_attachment_deserializes_base64() {
+    let attachment: Attachment =
+      serde_json::from_str(r#"{"id": "hello.txt", "data": "aGVsbG8="}"#).unwrap();
+    assert_eq!(attachment.data.as_ref(), b"hello");
+
+    let result = serde_json::from_str::<Attachment>(r#"{"id": "bad.txt", "data": "not base64!"}"#);
+    assert!(result.is_err());
+  }
+
+  #[cfg(feature = "zip")]
+  #[test]
+  fn test_attachment_zip() {
+    use std::io::Read;
+
+    let files = [
+      Attachment::from_bytes(b"first", "a.txt"),
+      Attachment::from_bytes(b"second", "b.txt"),
+    ];
+    let bundle = Attachment::zip(&files, "bundle.zip").unwrap();
+    assert_eq!(bundle.id, "bundle.zip");
+
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bundle.data.to_vec())).unwrap();
+    assert_eq!(archive.len(), 2);
+
+    let mut content = String::new();
+    archive
+      .by_name("b.txt")
+      .unwrap()
+      .read_to_string(&mut content)
+      .unwrap();
+    assert_eq!(content, "second");
+
+    let duplicate = [files[0].clone(), files[0].clone()];
+    assert!(Attachment::zip(&duplicate, "bundle.zip").is_err());
+  }
+
+  #[cfg(feature = "mime-detection")]
+  #[test]
+  fn test_attachment_detects_content_type() {
+    let png = Attachment::from_bytes(b"\x89PNG\r\n\x1a\n....", "tmp8f3a2c");
+    assert_eq!(png.content_type.as_deref(), Some("image/png"));
+
+    let csv = Attachment::from_bytes(b"a,b\n1,2\n", "report.csv");
+    assert_eq!(csv.content_type.as_deref(), Some("text/csv"));
+
+    let unknown = Attachment::from_bytes(b"hello", "notes");
+    assert_eq!(unknown.content_type, None);
+
+    let overridden = csv.with_content_type("text/plain");
+    assert_eq!(overridden.content_type.as_deref(), Some("text/plain"));
+  }
+
+  #[test]
+  fn test_attachment_size() {
+    for len in 0..8 {
+      let content = vec![b'x'; len];
+      assert_eq!(Attachment::from_bytes(&content, "file.txt").size(), len);
+    }
   }
 
   #[tokio::test]
@@ -108,7 +899,7 @@ mod tests {
     let attachment = Attachment::from_bytes(content, "path.txt");
 
     assert_eq!(attachment.id, "path.txt");
-    assert_eq!(attachment.data, general_purpose::STANDARD.encode(content));
+    assert_eq!(attachment.data.as_ref(), content);
   }
 
   #[tokio::test]
@@ -125,7 +916,60 @@ mod tests {
     let attachment = Attachment::from_path(&file_path).await?;
 
     assert_eq!(attachment.id, "test_file.txt");
-    assert_eq!(attachment.data, general_purpose::STANDARD.encode(content));
+    assert_eq!(attachment.data.as_ref(), content);
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_attachments_from_glob() -> Result<()> {
+    let temp_dir = TempDir::new("attachment_test")?;
+    for name in [
+      "b.pdf",
+      "a.pdf",
+      "C.PDF",
+      "report-1.pdf",
+      "report-x.pdf",
+      "notes.txt",
+    ] {
+      std::fs::write(temp_dir.path().join(name), name)?;
+    }
+    std::fs::create_dir(temp_dir.path().join("dir.pdf"))?;
+
+    let names = |attachments: Vec<Attachment>| -> Vec<String> {
+      attachments.into_iter().map(|a| a.id).collect()
+    };
+
+    let attachments = Attachment::from_glob(temp_dir.path().join("*.pdf")).await?;
+    assert_eq!(attachments[1].data.as_ref(), b"b.pdf");
+    assert_eq!(
+      names(attachments),
+      ["a.pdf", "b.pdf", "report-1.pdf", "report-x.pdf"]
+    );
+    assert_eq!(
+      names(Attachment::from_glob(temp_dir.path().join("?.pdf")).await?),
+      ["a.pdf", "b.pdf"]
+    );
+    assert_eq!(
+      names(Attachment::from_glob(temp_dir.path().join("report-[0-9].pdf")).await?),
+      ["report-1.pdf"]
+    );
+    assert!(
+      Attachment::from_glob(temp_dir.path().join("[.pdf"))
+        .await
+        .is_err()
+    );
+
+    assert!(
+      Attachment::from_glob(temp_dir.path().join("*.zip"))
+        .await?
+        .is_empty()
+    );
+    assert!(
+      Attachment::from_glob(temp_dir.path().join("*").join("*.pdf"))
+        .await
+        .is_err()
+    );
 
     Ok(())
   }
@@ -144,7 +988,7 @@ mod tests {
     let attachment = Attachment::from_path(&file_path).await?;
 
     assert_eq!(attachment.id, "no_extension");
-    assert_eq!(attachment.data, general_purpose::STANDARD.encode(content));
+    assert_eq!(attachment.data.as_ref(), content);
 
     Ok(())
   }
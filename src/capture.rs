@@ -0,0 +1,388 @@
+//! Recording of API traffic for support escalations.
+//!
+//! With a capture session configured via `Config::with_capture_session`,
+//! every HTTP call to SendWithUs is recorded as a request/response pair. The
+//! recorded traffic is sanitized the same way as debug output, so the API key,
+//! email addresses, and configured data keys never reach the session. Use
+//! `Api::capture_har` to export the session as a HAR document that can be
+//! attached to a support ticket.
+
+use crate::error::Result;
+use crate::redaction;
+use crate::request::PreparedRequest;
+use crate::response::RawResponse;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::io::AsyncWriteExt;
+
+/// A single recorded HTTP call.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CapturedExchange {
+  /// When the request was sent (milliseconds since the Unix epoch)
+  pub started_at: u64,
+
+  /// Time until the full response was read, or until the call failed (milliseconds)
+  pub duration_ms: u64,
+
+  /// HTTP method
+  pub method: String,
+
+  /// Request URL, with email addresses redacted
+  pub url: String,
+
+  /// Request headers, with the API key redacted
+  pub request_headers: Vec<(String, String)>,
+
+  /// Request body, with personal data redacted
+  pub request_body: Option<String>,
+
+  /// Response status code; `None` if no response was received
+  pub status: Option<u16>,
+
+  /// Response headers
+  pub response_headers: Vec<(String, String)>,
+
+  /// Response body, with personal data redacted
+  pub response_body: Option<String>,
+
+  /// Description of the error if no response was received
+  pub error: Option<String>,
+}
+
+impl CapturedExchange {
+  /// Records a call from its request and outcome, sanitizing both.
+  pub(crate) fn new(
+    prepared: &PreparedRequest,
+    result: &Result<RawResponse>,
+    started_at: SystemTime,
+    duration: Duration,
+    redacted_keys: &[String],
+  ) -> Self {
+    let request = prepared.sanitized(redacted_keys);
+    let started_at = started_at
+      .duration_since(UNIX_EPOCH)
+      .unwrap_or_default()
+      .as_millis() as u64;
+
+    let mut exchange = Self {
+      started_at,
+      duration_ms: duration.as_millis() as u64,
+      method: request.method.to_string(),
+      url: request.url.to_string(),
+      request_headers: request.headers,
+      request_body: request.body,
+      status: None,
+      response_headers: Vec::new(),
+      response_body: None,
+      error: None,
+    };
+
+    match result {
+      Ok(raw) => {
+        exchange.status = Some(raw.status.as_u16());
+        exchange.response_headers = raw
+          .headers
+          .iter()
+          .map(|(name, value)| {
+            (
+              name.to_string(),
+              String::from_utf8_lossy(value.as_bytes()).into_owned(),
+            )
+          })
+          .collect();
+        exchange.response_body =
+          Some(redaction::redact_body(&raw.text(), redacted_keys).into_owned());
+      }
+      Err(error) => exchange.error = Some(error.to_string()),
+    }
+
+    exchange
+  }
+}
+
+/// Destination for recorded API traffic.
+///
+/// Configure a session with `Config::with_capture_session` and export it with
+/// `Api::capture_har`.
+#[async_trait]
+pub trait CaptureSession: fmt::Debug + Send + Sync {
+  /// Stores a recorded call.
+  async fn record(&self, exchange: CapturedExchange) -> Result<()>;
+
+  /// Returns all recorded calls, oldest first.
+  async fn exchanges(&self) -> Result<Vec<CapturedExchange>>;
+}
+
+/// A capture session keeping recorded calls in memory.
+///
+/// Recorded calls are lost when the process exits; use `FileCaptureSession`
+/// to keep them across restarts.
+#[derive(Debug, Default)]
+pub struct MemoryCaptureSession {
+  exchanges: Mutex<Vec<CapturedExchange>>,
+}
+
+impl MemoryCaptureSession {
+  /// Creates an empty in-memory session.
+  pub fn new() -> Self {
+    Self::default()
+  }
+}
+
+#[async_trait]
+impl CaptureSession for MemoryCaptureSession {
+  async fn record(&self, exchange: CapturedExchange) -> Result<()> {
+    self
+      .exchanges
+      .lock()
+      .unwrap_or_else(|e| e.into_inner())
+      .push(exchange);
+    Ok(())
+  }
+
+  async fn exchanges(&self) -> Result<Vec<CapturedExchange>> {
+    Ok(
+      self
+        .exchanges
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone(),
+    )
+  }
+}
+
+/// A capture session appending recorded calls to a file, one JSON object per line.
+#[derive(Debug, Clone)]
+pub struct FileCaptureSession {
+  path: PathBuf,
+}
+
+impl FileCaptureSession {
+  /// Creates a session that appends to the file at `path`.
+  ///
+  /// The file and its parent directory are created on first use if they
+  /// don't exist. Calls recorded by earlier sessions using the same file are
+  /// kept.
+  ///
+  /// # Arguments
+  /// * `path` - File for recorded calls
+  ///
+  /// # Returns
+  /// A new FileCaptureSession
+  pub fn new(path: impl Into<PathBuf>) -> Self {
+    Self { path: path.into() }
+  }
+}
+
+#[async_trait]
+impl CaptureSession for FileCaptureSession {
+  async fn record(&self, exchange: CapturedExchange) -> Result<()> {
+    if let Some(parent) = self.path.parent() {
+      tokio::fs::create_dir_all(parent).await?;
+    }
+
+    let mut line = serde_json::to_vec(&exchange)?;
+    line.push(b'\n');
+
+    let mut file = tokio::fs::OpenOptions::new()
+      .create(true)
+      .append(true)
+      .open(&self.path)
+      .await?;
+    file.write_all(&line).await?;
+    file.flush().await?;
+
+    Ok(())
+  }
+
+  async fn exchanges(&self) -> Result<Vec<CapturedExchange>> {
+    let contents = match tokio::fs::read_to_string(&self.path).await {
+      Ok(contents) => contents,
+      Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+      Err(e) => return Err(e.into()),
+    };
+
+    contents
+      .lines()
+      .filter(|line| !line.trim().is_empty())
+      .map(|line| Ok(serde_json::from_str(line)?))
+      .collect()
+  }
+}
+
+/// Converts recorded calls to a HAR 1.2 document.
+///
+/// # Arguments
+/// * `exchanges` - Recorded calls, oldest first
+///
+/// # Returns
+/// The HAR document as JSON
+pub fn to_har(exchanges: &[CapturedExchange]) -> Value {
+  let entries: Vec<Value> = exchanges.iter().map(har_entry).collect();
+
+  json!({
+    "log": {
+      "version": "1.2",
+      "creator": {
+        "name": env!("CARGO_PKG_NAME"),
+        "version": env!("CARGO_PKG_VERSION"),
+      },
+      "entries": entries,
+    }
+  })
+}
+
+fn har_headers(headers: &[(String, String)]) -> Vec<Value> {
+  headers
+    .iter()
+    .map(|(name, value)| json!({"name": name, "value": value}))
+    .collect()
+}
+
+fn har_entry(exchange: &CapturedExchange) -> Value {
+  let mut request = json!({
+    "method": exchange.method,
+    "url": exchange.url,
+    "httpVersion": "HTTP/1.1",
+    "cookies": [],
+    "headers": har_headers(&exchange.request_headers),
+    "queryString": [],
+    "headersSize": -1,
+    "bodySize": exchange.request_body.as_ref().map_or(0, |body| body.len() as i64),
+  });
+
+  if let Some(body) = &exchange.request_body {
+    request["postData"] = json!({"mimeType": "application/json", "text": body});
+  }
+
+  let body = exchange.response_body.as_deref().unwrap_or_default();
+  let mime_type = exchange
+    .response_headers
+    .iter()
+    .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+    .map_or("", |(_, value)| value.as_str());
+
+  let mut entry = json!({
+    "startedDateTime": iso8601(exchange.started_at),
+    "time": exchange.duration_ms,
+    "request": request,
+    "response": {
+      "status": exchange.status.unwrap_or(0),
+      "statusText": exchange
+        .status
+        .and_then(|status| reqwest::StatusCode::from_u16(status).ok())
+        .and_then(|status| status.canonical_reason())
+        .unwrap_or_default(),
+      "httpVersion": "HTTP/1.1",
+      "cookies": [],
+      "headers": har_headers(&exchange.response_headers),
+      "content": {"size": body.len(), "mimeType": mime_type, "text": body},
+      "redirectURL": "",
+      "headersSize": -1,
+      "bodySize": exchange.response_body.as_ref().map_or(-1, |body| body.len() as i64),
+    },
+    "cache": {},
+    "timings": {"send": 0, "wait": exchange.duration_ms, "receive": 0},
+  });
+
+  if let Some(error) = &exchange.error {
+    entry["comment"] = json!(error);
+  }
+
+  entry
+}
+
+/// Formats a Unix timestamp in milliseconds as an ISO 8601 UTC date and time.
+fn iso8601(millis: u64) -> String {
+  let secs = millis / 1000;
+  let days = (secs / 86_400) as i64;
+  let secs_of_day = secs % 86_400;
+
+  // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm).
+  let z = days + 719_468;
+  let era = z.div_euclid(146_097);
+  let doe = z.rem_euclid(146_097);
+  let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+  let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+  let mp = (5 * doy + 2) / 153;
+  let day = doy - (153 * mp + 2) / 5 + 1;
+  let month = if mp < 10 { mp + 3 } else { mp - 9 };
+  let year = yoe + era * 400 + i64::from(month <= 2);
+
+  format!(
+    "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+    year,
+    month,
+    day,
+    secs_of_day / 3600,
+    secs_of_day % 3600 / 60,
+    secs_of_day % 60,
+    millis % 1000
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use tempdir::TempDir;
+
+  fn exchange(status: u16) -> CapturedExchange {
+    CapturedExchange {
+      started_at: 1_700_000_000_123,
+      duration_ms: 42,
+      method: "POST".to_string(),
+      url: "https://api.sendwithus.com/api/v1/send".to_string(),
+      request_headers: vec![("X-SWU-API-KEY".to_string(), "[REDACTED]".to_string())],
+      request_body: Some(r#"{"email_id":"tem_123"}"#.to_string()),
+      status: Some(status),
+      response_headers: vec![("content-type".to_string(), "application/json".to_string())],
+      response_body: Some(r#"{"success":true}"#.to_string()),
+      error: None,
+    }
+  }
+
+  #[test]
+  fn test_iso8601() {
+    assert_eq!(iso8601(0), "1970-01-01T00:00:00.000Z");
+    assert_eq!(iso8601(1_700_000_000_123), "2023-11-14T22:13:20.123Z");
+    assert_eq!(iso8601(951_782_400_000), "2000-02-29T00:00:00.000Z");
+  }
+
+  #[test]
+  fn test_to_har() {
+    let har = to_har(&[exchange(200)]);
+    let entry = &har["log"]["entries"][0];
+
+    assert_eq!(har["log"]["version"], "1.2");
+    assert_eq!(entry["startedDateTime"], "2023-11-14T22:13:20.123Z");
+    assert_eq!(entry["time"], 42);
+    assert_eq!(entry["request"]["method"], "POST");
+    assert_eq!(entry["request"]["headers"][0]["value"], "[REDACTED]");
+    assert_eq!(
+      entry["request"]["postData"]["text"],
+      r#"{"email_id":"tem_123"}"#
+    );
+    assert_eq!(entry["response"]["status"], 200);
+    assert_eq!(entry["response"]["statusText"], "OK");
+    assert_eq!(entry["response"]["content"]["mimeType"], "application/json");
+    assert_eq!(entry["response"]["content"]["text"], r#"{"success":true}"#);
+  }
+
+  #[tokio::test]
+  async fn test_file_session() {
+    let dir = TempDir::new("capture").unwrap();
+    let session = FileCaptureSession::new(dir.path().join("logs").join("session.jsonl"));
+    assert!(session.exchanges().await.unwrap().is_empty());
+
+    session.record(exchange(200)).await.unwrap();
+    session.record(exchange(500)).await.unwrap();
+
+    let exchanges = session.exchanges().await.unwrap();
+    assert_eq!(exchanges, vec![exchange(200), exchange(500)]);
+  }
+}
@@ -0,0 +1,403 @@
+//! Record and replay of API traffic for tests.
+//!
+//! With a cassette configured via `Config::with_cassette`, API calls are
+//! either recorded to a JSON file or replayed from it. Record a cassette
+//! once against a real account, commit the file, and later test runs replay
+//! the recorded responses without network access or credentials. The API key
+//! header is redacted before anything is written.
+
+use crate::error::{Error, Result};
+use crate::request::PreparedRequest;
+use crate::response::RawResponse;
+use reqwest::StatusCode;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+/// Whether a cassette records calls or replays them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+  /// Send calls to SendWithUs and write each one to the cassette
+  Record,
+
+  /// Answer calls from the cassette without sending them
+  Replay,
+}
+
+/// A recorded request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+  /// HTTP method
+  pub method: String,
+
+  /// Request URL
+  pub url: String,
+
+  /// Request headers, with the API key redacted
+  #[serde(default)]
+  pub headers: Vec<(String, String)>,
+
+  /// Request body
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub body: Option<String>,
+}
+
+/// A recorded response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedResponse {
+  /// HTTP status code
+  pub status: u16,
+
+  /// Response headers
+  #[serde(default)]
+  pub headers: Vec<(String, String)>,
+
+  /// Response body
+  #[serde(default)]
+  pub body: String,
+}
+
+/// A request and the response it received.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Interaction {
+  /// The request sent
+  pub request: RecordedRequest,
+
+  /// The response received
+  pub response: RecordedResponse,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CassetteFile {
+  interactions: Vec<Interaction>,
+}
+
+#[derive(Debug, Default)]
+struct Tape {
+  interactions: Vec<Interaction>,
+
+  /// Whether each interaction has been replayed
+  played: Vec<bool>,
+}
+
+/// A file of recorded API calls.
+///
+/// A request is replayed with the first interaction not yet played that has
+/// the same method, URL path and query, and body, so the same call made
+/// twice gets the two responses in the order they were recorded. The host is
+/// ignored, so a cassette recorded against one base URL replays against any
+/// other. While recording, calls are sent one at a time so the file keeps
+/// them in order.
+///
+/// # Examples
+///
+/// ```no_run
+/// use send_with_us::cassette::Cassette;
+/// use send_with_us::{Api, ApiClient, Config};
+///
+/// # async fn run() -> send_with_us::Result<()> {
+/// // Records on the first run (with a real key), replays afterwards.
+/// let api_key = std::env::var("SENDWITHUS_API_KEY").unwrap_or_default();
+/// let config = Config::new(api_key).with_cassette(Cassette::new("tests/cassettes/templates.json"));
+/// let api = Api::new(config);
+///
+/// let templates = api.list_templates().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Cassette {
+  path: PathBuf,
+  mode: CassetteMode,
+  tape: Mutex<Option<Tape>>,
+}
+
+impl Cassette {
+  /// Opens a cassette, replaying it if the file exists and recording it otherwise.
+  ///
+  /// # Arguments
+  /// * `path` - Cassette file
+  ///
+  /// # Returns
+  /// A new Cassette
+  pub fn new(path: impl Into<PathBuf>) -> Self {
+    let path = path.into();
+    let mode = match path.exists() {
+      true => CassetteMode::Replay,
+      false => CassetteMode::Record,
+    };
+    Self::with_mode(path, mode)
+  }
+
+  /// Opens a cassette that records every call, replacing the file if it exists.
+  ///
+  /// # Arguments
+  /// * `path` - Cassette file; it and its parent directory are created on first use
+  ///
+  /// # Returns
+  /// A new Cassette
+  pub fn record(path: impl Into<PathBuf>) -> Self {
+    Self::with_mode(path.into(), CassetteMode::Record)
+  }
+
+  /// Opens a cassette that replays every call.
+  ///
+  /// # Arguments
+  /// * `path` - Cassette file, read on first use
+  ///
+  /// # Returns
+  /// A new Cassette
+  pub fn replay(path: impl Into<PathBuf>) -> Self {
+    Self::with_mode(path.into(), CassetteMode::Replay)
+  }
+
+  fn with_mode(path: PathBuf, mode: CassetteMode) -> Self {
+    Self {
+      path,
+      mode,
+      tape: Mutex::new(None),
+    }
+  }
+
+  /// Returns the cassette file.
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+
+  /// Returns whether the cassette records or replays calls.
+  pub fn mode(&self) -> CassetteMode {
+    self.mode
+  }
+
+  /// Answers a request from the cassette, or sends it with `send` and records it.
+  ///
+  /// # Errors
+  /// When replaying, returns `Error::NoRecordedInteraction` if no unplayed
+  /// interaction matches, or an error if the file cannot be read. When
+  /// recording, returns the error of `send`, or an error if the file cannot
+  /// be written.
+  pub(crate) async fn play<F, Fut>(&self, prepared: PreparedRequest, send: F) -> Result<RawResponse>
+  where
+    F: FnOnce(PreparedRequest) -> Fut,
+    Fut: Future<Output = Result<RawResponse>>,
+  {
+    let mut tape = self.tape.lock().await;
+    if tape.is_none() {
+      *tape = Some(self.load().await?);
+    }
+    let tape = tape.as_mut().expect("cassette tape loaded above");
+
+    match self.mode {
+      CassetteMode::Replay => tape.replay(&prepared),
+      CassetteMode::Record => {
+        let request = RecordedRequest::new(&prepared);
+        let raw = send(prepared).await?;
+
+        tape.interactions.push(Interaction {
+          request,
+          response: RecordedResponse::new(&raw),
+        });
+        self.save(tape).await?;
+
+        Ok(raw)
+      }
+    }
+  }
+
+  async fn load(&self) -> Result<Tape> {
+    let interactions = match self.mode {
+      CassetteMode::Record => Vec::new(),
+      CassetteMode::Replay => {
+        let contents = tokio::fs::read_to_string(&self.path).await?;
+        serde_json::from_str::<CassetteFile>(&contents)?.interactions
+      }
+    };
+
+    Ok(Tape {
+      played: vec![false; interactions.len()],
+      interactions,
+    })
+  }
+
+  async fn save(&self, tape: &Tape) -> Result<()> {
+    if let Some(parent) = self.path.parent() {
+      tokio::fs::create_dir_all(parent).await?;
+    }
+
+    let file = CassetteFile {
+      interactions: tape.interactions.clone(),
+    };
+    let mut contents = serde_json::to_string_pretty(&file)?;
+    contents.push('\n');
+    tokio::fs::write(&self.path, contents).await?;
+
+    Ok(())
+  }
+}
+
+impl Tape {
+  fn replay(&mut self, prepared: &PreparedRequest) -> Result<RawResponse> {
+    let index = self
+      .interactions
+      .iter()
+      .zip(&self.played)
+      .position(|(interaction, played)| !played && interaction.request.matches(prepared))
+      .ok_or_else(|| {
+        Error::NoRecordedInteraction(format!("{} {}", prepared.method, prepared.url.path()))
+      })?;
+
+    self.played[index] = true;
+    self.interactions[index].response.to_raw()
+  }
+}
+
+impl RecordedRequest {
+  fn new(prepared: &PreparedRequest) -> Self {
+    let redacted = prepared.redacted();
+    Self {
+      method: redacted.method.to_string(),
+      url: redacted.url.to_string(),
+      headers: redacted.headers,
+      body: redacted.body,
+    }
+  }
+
+  fn matches(&self, prepared: &PreparedRequest) -> bool {
+    let Ok(url) = url::Url::parse(&self.url) else {
+      return false;
+    };
+
+    self.method == prepared.method.as_str()
+      && url.path() == prepared.url.path()
+      && url.query() == prepared.url.query()
+      && same_body(self.body.as_deref(), prepared.body.as_deref())
+  }
+}
+
+impl RecordedResponse {
+  fn new(raw: &RawResponse) -> Self {
+    Self {
+      status: raw.status.as_u16(),
+      headers: raw
+        .headers
+        .iter()
+        .map(|(name, value)| {
+          (
+            name.to_string(),
+            String::from_utf8_lossy(value.as_bytes()).into_owned(),
+          )
+        })
+        .collect(),
+      body: raw.text().into_owned(),
+    }
+  }
+
+  fn to_raw(&self) -> Result<RawResponse> {
+    let status = StatusCode::from_u16(self.status)
+      .map_err(|_| Error::Unexpected(format!("invalid recorded status {}", self.status)))?;
+
+    let mut headers = HeaderMap::new();
+    for (name, value) in &self.headers {
+      if let (Ok(name), Ok(value)) = (
+        HeaderName::try_from(name.as_str()),
+        HeaderValue::from_str(value),
+      ) {
+        headers.append(name, value);
+      }
+    }
+
+    Ok(RawResponse {
+      status,
+      headers,
+      body: self.body.clone().into_bytes(),
+      duration: Duration::ZERO,
+    })
+  }
+}
+
+/// Compares bodies as JSON when both parse, so key order doesn't matter.
+fn same_body(recorded: Option<&str>, sent: Option<&str>) -> bool {
+  match (recorded, sent) {
+    (Some(recorded), Some(sent)) => {
+      match (
+        serde_json::from_str::<Value>(recorded),
+        serde_json::from_str::<Value>(sent),
+      ) {
+        (Ok(recorded), Ok(sent)) => recorded == sent,
+        _ => recorded == sent,
+      }
+    }
+    (recorded, sent) => recorded == sent,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{Api, ApiClient, Config};
+  use serde_json::json;
+  use tempdir::TempDir;
+
+  #[tokio::test]
+  async fn test_record_then_replay() {
+    let dir = TempDir::new("cassette").unwrap();
+    let path = dir.path().join("cassettes/templates.json");
+
+    let mut mock_server = mockito::Server::new_async().await;
+    mock_server
+      .mock("GET", "/api/v1/emails")
+      .with_status(200)
+      .with_body(json!([{"id": "tem_1"}]).to_string())
+      .expect(1)
+      .create();
+    mock_server
+      .mock("POST", "/api/v1/render")
+      .with_status(200)
+      .with_body(json!({"html": "<p>Hi</p>"}).to_string())
+      .expect(1)
+      .create();
+
+    let mut config = Config::new("secret-key").with_cassette(Cassette::new(&path));
+    config.url = url::Url::parse(&mock_server.url()).unwrap();
+    let api = Api::new(config);
+
+    let templates = api.list_templates().await.unwrap();
+    let rendered: Value = api
+      .render_as(crate::types::RenderOptions::new("tem_1"))
+      .await
+      .unwrap();
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert!(!contents.contains("secret-key"));
+    assert!(contents.contains("[REDACTED]"));
+
+    // Replay against a different host without a server.
+    let mut config = Config::new("other-key").with_cassette(Cassette::new(&path));
+    config.url = url::Url::parse("http://127.0.0.1:9").unwrap();
+    let api = Api::new(config);
+
+    assert_eq!(api.list_templates().await.unwrap(), templates);
+    let replayed: Value = api
+      .render_as(crate::types::RenderOptions::new("tem_1"))
+      .await
+      .unwrap();
+    assert_eq!(replayed, rendered);
+
+    let result = api.list_templates().await;
+    assert!(matches!(result, Err(Error::NoRecordedInteraction(_))));
+  }
+
+  #[test]
+  fn test_same_body_ignores_key_order() {
+    assert!(same_body(
+      Some(r#"{"a":1,"b":2}"#),
+      Some(r#"{"b":2,"a":1}"#)
+    ));
+    assert!(!same_body(Some(r#"{"a":1}"#), Some(r#"{"a":2}"#)));
+    assert!(!same_body(None, Some("{}")));
+  }
+}
@@ -0,0 +1,279 @@
+use crate::error::{Error, Result};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Settings for the client-side circuit breaker.
+///
+/// After `failure_threshold` consecutive failed calls the circuit opens and
+/// calls fail immediately with `Error::CircuitOpen` instead of waiting on an
+/// unhealthy API. Once `cool_down` has elapsed the circuit is half-open: a
+/// single probe call is let through while every other call keeps failing
+/// fast. A successful probe closes the circuit, a failed one opens it for a
+/// new cool-down.
+///
+/// Only failures that indicate an unhealthy service count (connection
+/// failures, timeouts, rate limiting, and 5xx responses). Rejected requests
+/// such as validation errors never open the circuit.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::Config;
+/// use send_with_us::circuit_breaker::CircuitBreakerConfig;
+/// use std::time::Duration;
+///
+/// let config = Config::new("api-key")
+///   .with_circuit_breaker(CircuitBreakerConfig::new(5, Duration::from_secs(30)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitBreakerConfig {
+  /// Number of consecutive failures that opens the circuit
+  pub failure_threshold: u32,
+
+  /// How long the circuit stays open before a probe call is let through
+  pub cool_down: Duration,
+}
+
+impl CircuitBreakerConfig {
+  /// Creates circuit breaker settings.
+  ///
+  /// # Arguments
+  /// * `failure_threshold` - Number of consecutive failures that opens the circuit (at least 1)
+  /// * `cool_down` - How long the circuit stays open
+  ///
+  /// # Returns
+  /// A new CircuitBreakerConfig
+  pub fn new(failure_threshold: u32, cool_down: Duration) -> Self {
+    Self {
+      failure_threshold: failure_threshold.max(1),
+      cool_down,
+    }
+  }
+}
+
+impl Default for CircuitBreakerConfig {
+  fn default() -> Self {
+    Self::new(5, Duration::from_secs(30))
+  }
+}
+
+/// Circuit breaker state shared by all clones of an `Api`.
+#[derive(Debug)]
+pub(crate) struct CircuitBreaker {
+  config: CircuitBreakerConfig,
+  state: Mutex<CircuitState>,
+}
+
+#[derive(Debug, Default)]
+struct CircuitState {
+  consecutive_failures: u32,
+  open_until: Option<Instant>,
+  probing: bool,
+}
+
+/// Permission to make a call, returned by `CircuitBreaker::check`.
+///
+/// The call's outcome is reported with `record`. If the permit is dropped
+/// without recording, e.g. because the call was cancelled, a probe it held
+/// is released so the next call can probe instead.
+#[derive(Debug)]
+#[must_use = "the call's outcome should be recorded"]
+pub(crate) struct CircuitPermit<'a> {
+  breaker: &'a CircuitBreaker,
+  probe: bool,
+}
+
+impl CircuitPermit<'_> {
+  /// Records the outcome of the call.
+  pub fn record<T>(mut self, result: &Result<T>) {
+    let probe = std::mem::replace(&mut self.probe, false);
+    self.breaker.record(result, probe);
+  }
+}
+
+impl Drop for CircuitPermit<'_> {
+  fn drop(&mut self) {
+    if self.probe {
+      let mut state = self.breaker.state.lock().unwrap_or_else(|e| e.into_inner());
+      state.probing = false;
+    }
+  }
+}
+
+impl CircuitBreaker {
+  pub fn new(config: CircuitBreakerConfig) -> Self {
+    Self {
+      config,
+      state: Mutex::new(CircuitState::default()),
+    }
+  }
+
+  /// Fails with `Error::CircuitOpen` while the circuit is open, or while
+  /// it is half-open and another call is already probing.
+  ///
+  /// Calls kept out by a running probe are told to retry after a full
+  /// cool-down, the longest the circuit can stay open once the probe ends.
+  pub fn check(&self) -> Result<CircuitPermit<'_>> {
+    let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+
+    let probe = match state.open_until {
+      Some(open_until) => {
+        let now = Instant::now();
+        if now < open_until {
+          return Err(Error::CircuitOpen {
+            retry_in: open_until - now,
+          });
+        }
+        if state.probing {
+          return Err(Error::CircuitOpen {
+            retry_in: self.config.cool_down,
+          });
+        }
+
+        state.probing = true;
+        true
+      }
+      None => false,
+    };
+
+    Ok(CircuitPermit {
+      breaker: self,
+      probe,
+    })
+  }
+
+  /// Records the outcome of a call.
+  ///
+  /// Only the probe decides whether an open circuit closes. Calls let
+  /// through before the circuit opened may finish while it is open or
+  /// probing; their outcomes are stale and ignored.
+  fn record<T>(&self, result: &Result<T>, probe: bool) {
+    let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+
+    if !probe && state.open_until.is_some() {
+      return;
+    }
+
+    match result {
+      Err(error) if error.is_retryable() => {
+        state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+        if probe || state.consecutive_failures >= self.config.failure_threshold {
+          state.open_until = Some(Instant::now() + self.config.cool_down);
+        }
+        state.probing = false;
+      }
+      _ => *state = CircuitState::default(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn failure() -> Result<()> {
+    Err(Error::ConnectionFailed { context: None })
+  }
+
+  #[test]
+  fn test_opens_after_consecutive_failures() {
+    let breaker = CircuitBreaker::new(CircuitBreakerConfig::new(2, Duration::from_secs(60)));
+
+    breaker.record(&failure(), false);
+    assert!(breaker.check().is_ok());
+
+    breaker.record(&failure(), false);
+    match breaker.check() {
+      Err(Error::CircuitOpen { retry_in }) => assert!(retry_in <= Duration::from_secs(60)),
+      other => panic!("Expected open circuit, got: {:?}", other),
+    }
+  }
+
+  #[test]
+  fn test_success_and_client_errors_reset_failures() {
+    let breaker = CircuitBreaker::new(CircuitBreakerConfig::new(2, Duration::from_secs(60)));
+
+    breaker.record(&failure(), false);
+    breaker.record(&Ok(()), false);
+    breaker.record(&failure(), false);
+    assert!(breaker.check().is_ok());
+
+    breaker.record::<()>(&Err(Error::MissingTemplateId), false);
+    breaker.record(&failure(), false);
+    assert!(breaker.check().is_ok());
+  }
+
+  /// Ends the current cool-down immediately.
+  fn end_cool_down(breaker: &CircuitBreaker) {
+    let mut state = breaker.state.lock().unwrap();
+    state.open_until = Some(Instant::now());
+  }
+
+  #[test]
+  fn test_half_open_lets_a_single_probe_through() {
+    let breaker = CircuitBreaker::new(CircuitBreakerConfig::new(1, Duration::from_secs(60)));
+    breaker.record(&failure(), false);
+    end_cool_down(&breaker);
+
+    let probe = breaker.check().unwrap();
+    match breaker.check() {
+      Err(Error::CircuitOpen { retry_in }) => assert_eq!(retry_in, Duration::from_secs(60)),
+      other => panic!("Expected open circuit, got: {:?}", other),
+    }
+
+    probe.record(&failure());
+    assert!(matches!(breaker.check(), Err(Error::CircuitOpen { .. })));
+
+    end_cool_down(&breaker);
+    let probe = breaker.check().unwrap();
+    probe.record(&Ok(()));
+    assert!(breaker.check().is_ok());
+    assert!(breaker.check().is_ok());
+  }
+
+  #[test]
+  fn test_dropped_probe_is_released() {
+    let breaker = CircuitBreaker::new(CircuitBreakerConfig::new(1, Duration::from_secs(60)));
+    breaker.record(&failure(), false);
+    end_cool_down(&breaker);
+
+    drop(breaker.check().unwrap());
+    let probe = breaker.check().unwrap();
+    assert!(breaker.check().is_err());
+    probe.record(&Ok(()));
+  }
+
+  #[test]
+  fn test_stale_permits_do_not_decide_a_probe() {
+    let breaker = CircuitBreaker::new(CircuitBreakerConfig::new(1, Duration::from_secs(60)));
+    let stale_failure = breaker.check().unwrap();
+    let stale_success = breaker.check().unwrap();
+
+    breaker.record(&failure(), false);
+    end_cool_down(&breaker);
+    let probe = breaker.check().unwrap();
+
+    // Calls admitted before the circuit opened finish while the probe runs.
+    stale_failure.record(&failure());
+    stale_success.record(&Ok(()));
+    match breaker.check() {
+      Err(Error::CircuitOpen { retry_in }) => assert_eq!(retry_in, Duration::from_secs(60)),
+      other => panic!("Expected a running probe, got: {:?}", other),
+    }
+
+    probe.record(&Ok(()));
+    assert!(breaker.check().is_ok());
+  }
+
+  #[test]
+  fn test_closes_after_cool_down_and_success() {
+    let breaker = CircuitBreaker::new(CircuitBreakerConfig::new(1, Duration::ZERO));
+
+    breaker.record(&failure(), false);
+    breaker.check().unwrap().record(&Ok(()));
+
+    let state = breaker.state.lock().unwrap();
+    assert_eq!(state.consecutive_failures, 0);
+    assert!(state.open_until.is_none());
+  }
+}
@@ -1,8 +1,117 @@
+use crate::attachment::AttachmentLimits;
+use crate::capture::CaptureSession;
+#[cfg(feature = "mock")]
+use crate::cassette::Cassette;
+use crate::circuit_breaker::CircuitBreakerConfig;
+use crate::dead_letter::DeadLetterSink;
+use crate::error::{Error, Result};
+#[cfg(feature = "mock")]
+use crate::fault_injection::FaultInjector;
+use crate::logging::DebugSink;
+use crate::retry::RetryPolicy;
+use crate::types::Sender;
+#[cfg(any(feature = "native-tls", feature = "rustls"))]
+use reqwest::Certificate;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
 use url::Url;
 
 /// Current crate version, automatically set from Cargo.toml
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Email data key holding the original recipients of an intercepted email.
+pub const ORIGINAL_RECIPIENTS_KEY: &str = "original_recipients";
+
+/// Header listing the original recipients of an intercepted email.
+pub const ORIGINAL_RECIPIENTS_HEADER: &str = "X-Original-Recipients";
+
+/// Version of the SendWithUs API to talk to.
+///
+/// Converting from a string accepts the version with or without a leading
+/// `v`, so `"2"` and `"v2"` both become `ApiVersion::V2`.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::ApiVersion;
+///
+/// assert_eq!(ApiVersion::from("v2"), ApiVersion::V2);
+/// assert_eq!(ApiVersion::from("3").path_segment().unwrap(), "v3");
+/// assert!(ApiVersion::from("1/").path_segment().is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ApiVersion {
+  /// Version 1 of the API
+  #[default]
+  V1,
+
+  /// Version 2 of the API
+  V2,
+
+  /// Any other version, without the leading `v` (e.g. "3")
+  Custom(String),
+}
+
+impl ApiVersion {
+  /// Returns the version without the leading `v` (e.g. "1").
+  pub fn as_str(&self) -> &str {
+    match self {
+      ApiVersion::V1 => "1",
+      ApiVersion::V2 => "2",
+      ApiVersion::Custom(version) => version,
+    }
+  }
+
+  /// Returns the URL path segment for this version (e.g. "v1").
+  ///
+  /// # Errors
+  /// Returns `Error::InvalidConfig` if a custom version is empty or contains
+  /// characters other than ASCII letters, digits, `.`, `-`, and `_`
+  pub fn path_segment(&self) -> Result<String> {
+    let version = self.as_str();
+    let valid = !version.is_empty()
+      && version
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'));
+
+    if valid {
+      Ok(format!("v{}", version))
+    } else {
+      Err(Error::InvalidConfig(format!(
+        "invalid API version {:?}",
+        version
+      )))
+    }
+  }
+}
+
+impl From<&str> for ApiVersion {
+  fn from(version: &str) -> Self {
+    let version = version.trim();
+    let version = version.strip_prefix(['v', 'V']).unwrap_or(version);
+
+    match version {
+      "1" => ApiVersion::V1,
+      "2" => ApiVersion::V2,
+      other => ApiVersion::Custom(other.to_string()),
+    }
+  }
+}
+
+impl From<String> for ApiVersion {
+  fn from(version: String) -> Self {
+    ApiVersion::from(version.as_str())
+  }
+}
+
+impl fmt::Display for ApiVersion {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
 /// Configuration for the SendWithUs API client.
 ///
 /// This struct contains all the settings needed to connect to and interact
@@ -17,29 +126,106 @@ pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 /// ```
 /// use send_with_us::Config;
 ///
+/// # fn main() -> send_with_us::Result<()> {
 /// let config = Config::new("your-api-key");
 ///
 /// let config = Config::new("your-api-key")
-///   .with_url("https://custom-instance.sendwithus.com")
+///   .try_with_url("https://custom-instance.sendwithus.com")?
 ///   .with_api_version("2")
 ///   .with_debug(true);
+/// # Ok(())
+/// # }
 /// ```
 #[derive(Debug, Clone)]
 pub struct Config {
   /// Base URL for the SendWithUs API
   pub url: Url,
 
+  /// Base URL to retry against when the primary URL can't be reached
+  pub fallback_url: Option<Url>,
+
   /// API key used for authentication
   pub api_key: String,
 
-  /// API version to use (default: "1")
-  pub api_version: String,
+  /// API version to use (default: `ApiVersion::V1`)
+  pub api_version: ApiVersion,
 
   /// Debug mode flag for verbose logging
   pub debug: bool,
 
+  /// Patterns for JSON keys whose values are redacted from debug output (default: none)
+  pub redacted_data_keys: Vec<String>,
+
+  /// Where debug output is written instead of stderr or `tracing` (default: none)
+  pub debug_sink: Option<DebugSink>,
+
   /// Client identifier sent with API requests
   pub client_stub: String,
+
+  /// Whether non-ASCII display names are RFC 2047 encoded before sending
+  pub encode_display_names: bool,
+
+  /// Sender used for emails that don't set one (default: none)
+  pub default_sender: Option<Sender>,
+
+  /// ESP account used for emails that don't set one (default: none)
+  pub default_esp_account: Option<String>,
+
+  /// Locale used for emails, drip campaigns, and renders that don't set one (default: none)
+  pub default_locale: Option<String>,
+
+  /// Tags added to every email sent (default: none)
+  pub global_tags: Vec<String>,
+
+  /// Headers added to every email sent, unless the email sets the same header (default: none)
+  pub global_headers: HashMap<String, String>,
+
+  /// Catch-all address that receives every email instead of its recipients (default: none)
+  pub intercept_address: Option<String>,
+
+  /// Maximum time to wait for each API call to complete (default: no limit)
+  pub timeout: Option<Duration>,
+
+  /// Maximum time to wait for a connection to be established (default: no limit)
+  pub connect_timeout: Option<Duration>,
+
+  /// Maximum time to wait between reads of response data (default: no limit)
+  pub read_timeout: Option<Duration>,
+
+  /// Root certificates trusted in addition to the system roots (default: none)
+  ///
+  /// Only available with a TLS backend, the `native-tls` or `rustls` feature.
+  #[cfg(any(feature = "native-tls", feature = "rustls"))]
+  pub root_certificates: Vec<Certificate>,
+
+  /// Circuit breaker settings (default: disabled)
+  pub circuit_breaker: Option<CircuitBreakerConfig>,
+
+  /// Retry settings for failed calls (default: no retries)
+  pub retry_policy: Option<RetryPolicy>,
+
+  /// Longest total time a call waits out rate limiting before giving up (default: no waiting)
+  pub rate_limit_max_wait: Option<Duration>,
+
+  /// Where sends that fail after exhausting retries are captured (default: none)
+  pub dead_letter_sink: Option<Arc<dyn DeadLetterSink>>,
+
+  /// Where API traffic is recorded for support escalations (default: none)
+  pub capture_session: Option<Arc<dyn CaptureSession>>,
+
+  /// Cassette that API calls are recorded to or replayed from (default: none)
+  #[cfg(feature = "mock")]
+  pub cassette: Option<Arc<Cassette>>,
+
+  /// Faults injected into API calls for resilience testing (default: none)
+  #[cfg(feature = "mock")]
+  pub fault_injector: Option<Arc<FaultInjector>>,
+
+  /// Size limits checked for email attachments before sending (default: 10 MiB each, 25 MiB in total)
+  pub attachment_limits: AttachmentLimits,
+
+  /// Whether template and drip campaign reads are cached and revalidated with ETags (default: false)
+  pub conditional_requests: bool,
 }
 
 impl Config {
@@ -47,9 +233,29 @@ impl Config {
   ///
   /// This method initializes a configuration with sensible defaults:
   /// - URL: <https://api.sendwithus.com>
+  /// - Fallback URL: none
   /// - API version: "1"
   /// - Debug mode: false
+  /// - Redacted data keys: none
+  /// - Debug sink: none
   /// - Client stub: rust-{VERSION}
+  /// - Display name encoding: disabled
+  /// - Default sender: none
+  /// - Default ESP account: none
+  /// - Default locale: none
+  /// - Global tags and headers: none
+  /// - Recipient intercept: disabled
+  /// - Timeouts: none
+  /// - Additional root certificates: none
+  /// - Circuit breaker: disabled
+  /// - Retries: disabled
+  /// - Rate limit retries: disabled
+  /// - Dead-letter sink: none
+  /// - Capture session: none
+  /// - Cassette: none (`mock` feature)
+  /// - Fault injection: none (`mock` feature)
+  /// - Attachment limits: 10 MiB per attachment, 25 MiB in total
+  /// - Conditional requests: disabled
   ///
   /// # Arguments
   /// * `api_key` - Your SendWithUs API key
@@ -69,18 +275,67 @@ impl Config {
 
     Self {
       url: default_url,
+      fallback_url: None,
       api_key: api_key.into(),
-      api_version: "1".to_string(),
+      api_version: ApiVersion::V1,
       debug: false,
+      redacted_data_keys: Vec::new(),
+      debug_sink: None,
       client_stub: format!("rust-{}", VERSION),
+      encode_display_names: false,
+      default_sender: None,
+      default_esp_account: None,
+      default_locale: None,
+      global_tags: Vec::new(),
+      global_headers: HashMap::new(),
+      intercept_address: None,
+      timeout: None,
+      connect_timeout: None,
+      read_timeout: None,
+      #[cfg(any(feature = "native-tls", feature = "rustls"))]
+      root_certificates: Vec::new(),
+      circuit_breaker: None,
+      retry_policy: None,
+      rate_limit_max_wait: None,
+      dead_letter_sink: None,
+      capture_session: None,
+      #[cfg(feature = "mock")]
+      cassette: None,
+      #[cfg(feature = "mock")]
+      fault_injector: None,
+      attachment_limits: AttachmentLimits::default(),
+      conditional_requests: false,
     }
   }
 
-  /// Sets a custom API URL.
+  /// Creates a builder that validates the configuration before use.
+  ///
+  /// # Arguments
+  /// * `api_key` - Your SendWithUs API key
+  ///
+  /// # Returns
+  /// A new ConfigBuilder
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::Config;
+  ///
+  /// # fn main() -> send_with_us::Result<()> {
+  /// let config = Config::builder("api-key")
+  ///   .with_url("https://api.eu.sendwithus.com")
+  ///   .build()?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn builder(api_key: impl Into<String>) -> ConfigBuilder {
+    ConfigBuilder::new(api_key)
+  }
+
+  /// Sets a custom API URL, failing if it isn't a valid http(s) URL.
   ///
   /// Use this method if you need to connect to a custom SendWithUs instance
-  /// or a different region. If the provided URL is invalid, it will fall back
-  /// to the default URL.
+  /// or a different region.
   ///
   /// # Arguments
   /// * `url` - The custom API URL to use
@@ -88,27 +343,105 @@ impl Config {
   /// # Returns
   /// Self with the updated URL for method chaining
   ///
+  /// # Errors
+  /// Returns `Error::InvalidConfig` if the URL can't be parsed, isn't http or
+  /// https, or has no host
+  ///
   /// # Examples
   ///
   /// ```
   /// use send_with_us::Config;
   ///
+  /// # fn main() -> send_with_us::Result<()> {
   /// let config = Config::new("api-key")
-  ///   .with_url("https://api.eu.sendwithus.com");
+  ///   .try_with_url("https://api.eu.sendwithus.com")?;
+  ///
+  /// assert!(Config::new("api-key").try_with_url("htps:/api.sendwithus.com").is_err());
+  /// # Ok(())
+  /// # }
   /// ```
+  pub fn try_with_url(mut self, url: impl Into<String>) -> Result<Self> {
+    self.url = parse_api_url(&url.into())?;
+    Ok(self)
+  }
+
+  /// Sets a custom API URL.
+  ///
+  /// If the provided URL is invalid, it silently falls back to the default
+  /// URL. Prefer `try_with_url`, which reports the mistake instead.
+  ///
+  /// # Arguments
+  /// * `url` - The custom API URL to use
+  ///
+  /// # Returns
+  /// Self with the updated URL for method chaining
+  #[deprecated(note = "invalid URLs silently fall back to the default; use `try_with_url` instead")]
   pub fn with_url(mut self, url: impl Into<String>) -> Self {
     self.url =
       Url::parse(&url.into()).unwrap_or_else(|_| Url::parse("https://api.sendwithus.com").unwrap());
     self
   }
 
+  /// Sets a fallback API URL used when the primary URL can't be reached.
+  ///
+  /// When a call fails to connect to the primary URL, it is retried once
+  /// against the fallback. Other failures, such as error responses, are not
+  /// retried.
+  ///
+  /// # Arguments
+  /// * `url` - The fallback API URL
+  ///
+  /// # Returns
+  /// Self with the updated fallback URL for method chaining
+  ///
+  /// # Errors
+  /// Returns `Error::InvalidConfig` if the URL can't be parsed, isn't http or
+  /// https, or has no host
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::Config;
+  ///
+  /// # fn main() -> send_with_us::Result<()> {
+  /// let config = Config::new("api-key")
+  ///   .try_with_url("https://swu-proxy.internal.example.com")?
+  ///   .try_with_fallback_url("https://api.sendwithus.com")?;
+  ///
+  /// assert!(Config::new("api-key").try_with_fallback_url("api.sendwithus.com").is_err());
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn try_with_fallback_url(mut self, url: impl Into<String>) -> Result<Self> {
+    self.fallback_url = Some(parse_api_url(&url.into())?);
+    Ok(self)
+  }
+
+  /// Sets a fallback API URL used when the primary URL can't be reached.
+  ///
+  /// An invalid URL silently leaves the fallback unset. Prefer
+  /// `try_with_fallback_url`, which reports the mistake instead.
+  ///
+  /// # Arguments
+  /// * `url` - The fallback API URL
+  ///
+  /// # Returns
+  /// Self with the updated fallback URL for method chaining
+  #[deprecated(
+    note = "invalid URLs silently leave the fallback unset; use `try_with_fallback_url` instead"
+  )]
+  pub fn with_fallback_url(mut self, url: impl Into<String>) -> Self {
+    self.fallback_url = Url::parse(&url.into()).ok();
+    self
+  }
+
   /// Sets the API version to use.
   ///
   /// SendWithUs supports multiple API versions. Use this method to specify
   /// which version your application should use.
   ///
   /// # Arguments
-  /// * `version` - The API version as a string (e.g., "1", "2")
+  /// * `version` - The API version, as an `ApiVersion` or a string (e.g., "1", "v2")
   ///
   /// # Returns
   /// Self with the updated API version for method chaining
@@ -116,12 +449,13 @@ impl Config {
   /// # Examples
   ///
   /// ```
-  /// use send_with_us::Config;
+  /// use send_with_us::{ApiVersion, Config};
   ///
   /// let config = Config::new("api-key")
   ///   .with_api_version("2");
+  /// assert_eq!(config.api_version, ApiVersion::V2);
   /// ```
-  pub fn with_api_version(mut self, version: impl Into<String>) -> Self {
+  pub fn with_api_version(mut self, version: impl Into<ApiVersion>) -> Self {
     self.api_version = version.into();
     self
   }
@@ -130,6 +464,10 @@ impl Config {
   ///
   /// When debug mode is enabled, the client will output detailed information
   /// about API requests and responses, which can be helpful for troubleshooting.
+  /// The API key and email addresses are redacted from this output; see
+  /// `with_redacted_data_keys` to hide other values.
+  /// With the `logging` feature the output is emitted as `tracing` events,
+  /// otherwise it is printed to stderr.
   ///
   /// # Arguments
   /// * `debug` - Boolean flag to enable or disable debug mode
@@ -150,86 +488,1035 @@ impl Config {
     self
   }
 
-  /// Gets the protocol (http or https) from the configured URL.
+  /// Sets patterns for data keys whose values are hidden in debug output.
+  ///
+  /// Debug output always redacts the API key and email addresses. Values
+  /// under JSON keys matching any of these patterns are redacted as well,
+  /// wherever they appear in request and response bodies. A `*` in a pattern
+  /// matches any run of characters, and matching ignores case.
+  ///
+  /// # Arguments
+  /// * `keys` - Key patterns to redact (e.g., "ssn", "*_token")
   ///
   /// # Returns
-  /// The URL scheme as a string slice
-  pub fn protocol(&self) -> &str {
-    self.url.scheme()
+  /// Self with the updated redaction patterns for method chaining
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::Config;
+  ///
+  /// let config = Config::new("api-key")
+  ///   .with_debug(true)
+  ///   .with_redacted_data_keys(vec!["ssn".to_string(), "*_token".to_string()]);
+  /// ```
+  pub fn with_redacted_data_keys(mut self, keys: Vec<String>) -> Self {
+    self.redacted_data_keys = keys;
+    self
   }
 
-  /// Gets the host from the configured URL.
+  /// Sets where debug output is written.
+  ///
+  /// By default debug output goes to stderr, or to `tracing` with the
+  /// `logging` feature. With a sink configured, it goes to the sink instead,
+  /// for example a log file or an in-memory buffer in tests. Debug mode must
+  /// still be enabled with `with_debug`.
+  ///
+  /// # Arguments
+  /// * `sink` - Destination for debug output
   ///
   /// # Returns
-  /// The host as a string slice, defaulting to "api.sendwithus.com" if not available
-  pub fn host(&self) -> &str {
-    self.url.host_str().unwrap_or("api.sendwithus.com")
+  /// Self with the debug sink set for method chaining
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::Config;
+  /// use send_with_us::logging::DebugSink;
+  ///
+  /// let config = Config::new("api-key")
+  ///   .with_debug(true)
+  ///   .with_debug_sink(DebugSink::from_writer(std::io::stdout()));
+  /// ```
+  pub fn with_debug_sink(mut self, sink: DebugSink) -> Self {
+    self.debug_sink = Some(sink);
+    self
   }
 
-  /// Gets the port from the configured URL.
+  /// Enables or disables RFC 2047 encoding of display names.
   ///
-  /// If the URL doesn't specify a port, this method returns the default port
-  /// for the protocol (443 for HTTPS, 80 for HTTP).
+  /// Some email providers mangle recipient and sender names containing
+  /// non-ASCII characters (for example "Søren Ångström"). When enabled, such
+  /// names are sent as RFC 2047 encoded words, which every mail client decodes
+  /// back to the original text. ASCII names are never changed.
+  ///
+  /// # Arguments
+  /// * `enabled` - Boolean flag to enable or disable display name encoding
   ///
   /// # Returns
-  /// The port number
-  pub fn port(&self) -> u16 {
+  /// Self with the updated encoding setting for method chaining
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::Config;
+  ///
+  /// let config = Config::new("api-key")
+  ///   .with_encoded_display_names(true);
+  /// ```
+  pub fn with_encoded_display_names(mut self, enabled: bool) -> Self {
+    self.encode_display_names = enabled;
     self
-      .url
-      .port()
-      .unwrap_or_else(|| if self.protocol() == "https" { 443 } else { 80 })
-  }
-}
-
-#[cfg(test)]
-mod tests {
-  use super::*;
-
-  #[test]
-  fn test_default_config() {
-    let config = Config::new("test-api-key");
-    assert_eq!(config.protocol(), "https");
-    assert_eq!(config.host(), "api.sendwithus.com");
-    assert_eq!(config.port(), 443);
-    assert_eq!(config.api_key, "test-api-key");
-    assert_eq!(config.api_version, "1");
-    assert!(!config.debug);
-    assert_eq!(config.client_stub, format!("rust-{}", VERSION));
   }
 
-  #[test]
-  fn test_custom_url_config() {
-    let config = Config::new("test-api-key").with_url("http://example.com");
-    assert_eq!(config.protocol(), "http");
-    assert_eq!(config.host(), "example.com");
-    assert_eq!(config.port(), 80);
+  /// Sets the sender used for emails that don't specify one.
+  ///
+  /// `send_email` applies this sender whenever `EmailOptions.sender` is
+  /// `None`. A sender set on the options always takes precedence.
+  ///
+  /// # Arguments
+  /// * `sender` - The default sender
+  ///
+  /// # Returns
+  /// Self with the updated default sender for method chaining
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::Config;
+  /// use send_with_us::types::Sender;
+  ///
+  /// let config = Config::new("api-key")
+  ///   .with_default_sender(Sender::new("noreply@example.com").with_name("Example"));
+  /// ```
+  pub fn with_default_sender(mut self, sender: Sender) -> Self {
+    self.default_sender = Some(sender);
+    self
   }
 
-  #[test]
-  fn test_invalid_url_fallback() {
-    let config = Config::new("test-api-key").with_url("invalid-url");
-    assert_eq!(config.protocol(), "https");
-    assert_eq!(config.host(), "api.sendwithus.com");
-    assert_eq!(config.port(), 443);
+  /// Sets the ESP account used for emails that don't specify one.
+  ///
+  /// `send_email` applies this account whenever `EmailOptions.esp_account`
+  /// is `None`, so routing between email service providers can be decided in
+  /// one place. An account set on the options always takes precedence.
+  ///
+  /// # Arguments
+  /// * `esp_account` - ID of the default ESP account
+  ///
+  /// # Returns
+  /// Self with the updated default ESP account for method chaining
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::Config;
+  ///
+  /// let config = Config::new("api-key")
+  ///   .with_default_esp_account("esp_transactional");
+  /// ```
+  pub fn with_default_esp_account(mut self, esp_account: impl Into<String>) -> Self {
+    self.default_esp_account = Some(esp_account.into());
+    self
   }
 
-  #[test]
-  fn test_with_api_version() {
-    let config = Config::new("test-api-key").with_api_version("2");
-    assert_eq!(config.api_version, "2");
+  /// Sets the locale used when a call doesn't specify one.
+  ///
+  /// Applied to `EmailOptions` in `send_email`, `DripCampaignOptions` in
+  /// `start_on_drip_campaign`, and `RenderOptions` in `render` whenever
+  /// their `locale` is `None`. A locale set on the options always takes
+  /// precedence.
+  ///
+  /// # Arguments
+  /// * `locale` - The default locale (e.g., "en-US")
+  ///
+  /// # Returns
+  /// Self with the updated default locale for method chaining
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::Config;
+  ///
+  /// let config = Config::new("api-key")
+  ///   .with_default_locale("fr-CA");
+  /// ```
+  pub fn with_default_locale(mut self, locale: impl Into<String>) -> Self {
+    self.default_locale = Some(locale.into());
+    self
   }
 
-  #[test]
-  fn test_with_debug_mode() {
-    let config = Config::new("test-api-key").with_debug(true);
-    assert!(config.debug);
+  /// Sets tags added to every email sent.
+  ///
+  /// `send_email` merges these with the tags set on `EmailOptions`, keeping
+  /// each tag once. Use them for baseline tags such as the environment or
+  /// the sending service.
+  ///
+  /// # Arguments
+  /// * `tags` - Tags to add to every email
+  ///
+  /// # Returns
+  /// Self with the updated global tags for method chaining
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::Config;
+  ///
+  /// let config = Config::new("api-key")
+  ///   .with_global_tags(vec!["env:prod".to_string(), "service:billing".to_string()]);
+  /// ```
+  pub fn with_global_tags(mut self, tags: Vec<String>) -> Self {
+    self.global_tags = tags;
+    self
   }
 
-  #[test]
-  fn test_custom_port() {
-    let config = Config::new("test-api-key").with_url("https://example.com:8443");
-    assert_eq!(config.protocol(), "https");
-    assert_eq!(config.host(), "example.com");
-    assert_eq!(config.port(), 8443);
+  /// Sets headers added to every email sent.
+  ///
+  /// `send_email` merges these with the headers set on `EmailOptions`. When
+  /// both define the same header, the value on the email wins.
+  ///
+  /// # Arguments
+  /// * `headers` - Headers to add to every email
+  ///
+  /// # Returns
+  /// Self with the updated global headers for method chaining
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::Config;
+  /// use std::collections::HashMap;
+  ///
+  /// let mut headers = HashMap::new();
+  /// headers.insert("X-Service".to_string(), "billing".to_string());
+  ///
+  /// let config = Config::new("api-key").with_global_headers(headers);
+  /// ```
+  pub fn with_global_headers(mut self, headers: HashMap<String, String>) -> Self {
+    self.global_headers = headers;
+    self
+  }
+
+  /// Redirects every email to a catch-all address.
+  ///
+  /// Intended for staging and other non-production environments: the
+  /// recipient and every cc and bcc address are replaced with `address`, so
+  /// no real customer receives mail. The original addresses are recorded in
+  /// the `original_recipients` email data key (an object with `to`, `cc`,
+  /// and `bcc`) and in the `X-Original-Recipients` header.
+  ///
+  /// Drip campaigns are intercepted too: they are started and stopped for
+  /// `address`, with the original recipient in the `original_recipients`
+  /// email data key.
+  ///
+  /// # Arguments
+  /// * `address` - The catch-all address that receives every email
+  ///
+  /// # Returns
+  /// Self with recipient interception enabled for method chaining
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::Config;
+  ///
+  /// let config = Config::new("api-key")
+  ///   .with_recipient_intercept("staging-inbox@example.com");
+  /// ```
+  pub fn with_recipient_intercept(mut self, address: impl Into<String>) -> Self {
+    self.intercept_address = Some(address.into());
+    self
+  }
+
+  /// Sets the default deadline for API calls.
+  ///
+  /// Calls that don't complete in time fail with `Error::Timeout`. The
+  /// deadline covers sending the request and reading the whole response.
+  /// Individual calls can use a different deadline with `Api::with_timeout`.
+  ///
+  /// # Arguments
+  /// * `timeout` - Maximum duration of each API call
+  ///
+  /// # Returns
+  /// Self with the updated timeout for method chaining
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::Config;
+  /// use std::time::Duration;
+  ///
+  /// let config = Config::new("api-key")
+  ///   .with_timeout(Duration::from_secs(10));
+  /// ```
+  pub fn with_timeout(mut self, timeout: Duration) -> Self {
+    self.timeout = Some(timeout);
+    self
+  }
+
+  /// Sets the maximum time to wait for a connection to the API.
+  ///
+  /// Applied when the `Api` builds its HTTP client, so it must be set before
+  /// calling `Api::new`. Connections that take longer fail with `Error::Timeout`.
+  ///
+  /// # Arguments
+  /// * `timeout` - Maximum duration of the connection phase
+  ///
+  /// # Returns
+  /// Self with the updated connect timeout for method chaining
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::Config;
+  /// use std::time::Duration;
+  ///
+  /// let config = Config::new("api-key")
+  ///   .with_connect_timeout(Duration::from_secs(2));
+  /// ```
+  pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+    self.connect_timeout = Some(timeout);
+    self
+  }
+
+  /// Sets the maximum time to wait between reads of response data.
+  ///
+  /// Applied when the `Api` builds its HTTP client, so it must be set before
+  /// calling `Api::new`. The timer restarts whenever data arrives, so this
+  /// bounds stalls rather than the duration of the whole call; use
+  /// `with_timeout` for an overall limit.
+  ///
+  /// # Arguments
+  /// * `timeout` - Maximum time without receiving data
+  ///
+  /// # Returns
+  /// Self with the updated read timeout for method chaining
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::Config;
+  /// use std::time::Duration;
+  ///
+  /// let config = Config::new("api-key")
+  ///   .with_read_timeout(Duration::from_secs(5));
+  /// ```
+  pub fn with_read_timeout(mut self, timeout: Duration) -> Self {
+    self.read_timeout = Some(timeout);
+    self
+  }
+
+  /// Adds a root certificate to trust when connecting to the API.
+  ///
+  /// Use this when traffic to SendWithUs passes through a TLS-inspecting
+  /// proxy whose certificate authority isn't in the system trust store. The
+  /// certificate is trusted in addition to the system roots. Applied when the
+  /// `Api` builds its HTTP client, so it must be set before calling
+  /// `Api::new`. Can be called multiple times to add several certificates.
+  ///
+  /// # Arguments
+  /// * `certificate` - The root certificate to trust
+  ///
+  /// # Returns
+  /// Self with the certificate added for method chaining
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// use send_with_us::Config;
+  ///
+  /// # fn run() -> Result<(), Box<dyn std::error::Error>> {
+  /// let pem = std::fs::read("/etc/ssl/certs/corporate-proxy-ca.pem")?;
+  /// let config = Config::new("api-key")
+  ///   .with_root_certificate(reqwest::Certificate::from_pem(&pem)?);
+  /// # Ok(())
+  /// # }
+  /// ```
+  #[cfg(any(feature = "native-tls", feature = "rustls"))]
+  pub fn with_root_certificate(mut self, certificate: Certificate) -> Self {
+    self.root_certificates.push(certificate);
+    self
+  }
+
+  /// Enables the circuit breaker.
+  ///
+  /// With the circuit breaker enabled, repeated failures talking to
+  /// SendWithUs make subsequent calls fail fast with `Error::CircuitOpen`
+  /// for a cool-down period instead of each waiting on a struggling API.
+  ///
+  /// # Arguments
+  /// * `circuit_breaker` - Circuit breaker settings
+  ///
+  /// # Returns
+  /// Self with the circuit breaker enabled for method chaining
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::Config;
+  /// use send_with_us::circuit_breaker::CircuitBreakerConfig;
+  ///
+  /// let config = Config::new("api-key")
+  ///   .with_circuit_breaker(CircuitBreakerConfig::default());
+  /// ```
+  pub fn with_circuit_breaker(mut self, circuit_breaker: CircuitBreakerConfig) -> Self {
+    self.circuit_breaker = Some(circuit_breaker);
+    self
+  }
+
+  /// Enables retrying of failed calls.
+  ///
+  /// Calls that fail with a retryable error, such as a connection failure or
+  /// a 5xx response, are retried with exponential backoff.
+  ///
+  /// # Arguments
+  /// * `retry_policy` - Retry settings
+  ///
+  /// # Returns
+  /// Self with retries enabled for method chaining
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::Config;
+  /// use send_with_us::retry::RetryPolicy;
+  ///
+  /// let config = Config::new("api-key")
+  ///   .with_retry_policy(RetryPolicy::new(3));
+  /// ```
+  pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+    self.retry_policy = Some(retry_policy);
+    self
+  }
+
+  /// Enables waiting out rate limiting (HTTP 429) automatically.
+  ///
+  /// When the API responds with 429, the call sleeps for the delay given in
+  /// the `Retry-After` header (one second if there is none, and at least a
+  /// quarter second) and tries again.
+  /// Once the total time spent waiting would exceed `max_wait`, the call
+  /// fails with `Error::RateLimited` instead.
+  ///
+  /// # Arguments
+  /// * `max_wait` - Longest total time a call may spend waiting out rate limiting
+  ///
+  /// # Returns
+  /// Self with rate limit retries enabled for method chaining
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::Config;
+  /// use std::time::Duration;
+  ///
+  /// let config = Config::new("api-key")
+  ///   .with_rate_limit_retry(Duration::from_secs(60));
+  /// ```
+  pub fn with_rate_limit_retry(mut self, max_wait: Duration) -> Self {
+    self.rate_limit_max_wait = Some(max_wait);
+    self
+  }
+
+  /// Sets where sends that fail after exhausting retries are captured.
+  ///
+  /// Captured sends keep the email options, the last error, and timestamps,
+  /// and can be sent again later with `Api::replay_dead_letters`.
+  ///
+  /// # Arguments
+  /// * `sink` - Destination for failed sends
+  ///
+  /// # Returns
+  /// Self with the dead-letter sink set for method chaining
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::Config;
+  /// use send_with_us::dead_letter::FileDeadLetterSink;
+  ///
+  /// let config = Config::new("api-key")
+  ///   .with_dead_letter_sink(FileDeadLetterSink::new("/var/lib/my-app/dead-letters"));
+  /// ```
+  pub fn with_dead_letter_sink(mut self, sink: impl DeadLetterSink + 'static) -> Self {
+    self.dead_letter_sink = Some(Arc::new(sink));
+    self
+  }
+
+  /// Sets where API traffic is recorded.
+  ///
+  /// Every HTTP call is recorded as a request/response pair, sanitized like
+  /// debug output: the API key, email addresses, and values of keys set with
+  /// `with_redacted_data_keys` are replaced with `[REDACTED]`. Export the
+  /// recording with `Api::capture_har` to share it with SendWithUs support.
+  ///
+  /// # Arguments
+  /// * `session` - Destination for recorded calls
+  ///
+  /// # Returns
+  /// Self with the capture session set for method chaining
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::Config;
+  /// use send_with_us::capture::FileCaptureSession;
+  ///
+  /// let config = Config::new("api-key")
+  ///   .with_capture_session(FileCaptureSession::new("/tmp/sendwithus-capture.jsonl"));
+  /// ```
+  pub fn with_capture_session(mut self, session: impl CaptureSession + 'static) -> Self {
+    self.capture_session = Some(Arc::new(session));
+    self
+  }
+
+  /// Records API calls to a cassette, or replays them from it.
+  ///
+  /// Replayed calls never reach SendWithUs, so tests using a recorded
+  /// cassette need neither network access nor a real API key. Retries,
+  /// rate limiting, and failover still apply to replayed responses.
+  /// Available with the `mock` feature.
+  ///
+  /// # Arguments
+  /// * `cassette` - Cassette to record to or replay from
+  ///
+  /// # Returns
+  /// Self with the cassette set for method chaining
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::Config;
+  /// use send_with_us::cassette::Cassette;
+  ///
+  /// let config = Config::new("api-key").with_cassette(Cassette::replay("tests/cassettes/send.json"));
+  /// ```
+  #[cfg(feature = "mock")]
+  pub fn with_cassette(mut self, cassette: Cassette) -> Self {
+    self.cassette = Some(Arc::new(cassette));
+    self
+  }
+
+  /// Injects latency and failures into API calls, for testing resilience.
+  ///
+  /// Injected faults go through retries, rate limiting, failover, and the
+  /// circuit breaker like real ones. Available with the `mock` feature, so
+  /// it can't be set in production builds that don't enable it.
+  ///
+  /// # Arguments
+  /// * `injector` - Faults to inject and their probabilities
+  ///
+  /// # Returns
+  /// Self with the fault injector set for method chaining
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::Config;
+  /// use send_with_us::fault_injection::FaultInjector;
+  ///
+  /// let config = Config::new("api-key")
+  ///   .with_fault_injector(FaultInjector::new().with_connection_failures(0.1));
+  /// ```
+  #[cfg(feature = "mock")]
+  pub fn with_fault_injector(mut self, injector: FaultInjector) -> Self {
+    self.fault_injector = Some(Arc::new(injector));
+    self
+  }
+
+  /// Sets the size limits checked for email attachments before sending.
+  ///
+  /// Emails with an attachment over `max_size`, or attachments over
+  /// `max_total_size` together, fail with `Error::AttachmentTooLarge` without
+  /// being sent.
+  ///
+  /// # Arguments
+  /// * `limits` - Attachment size limits
+  ///
+  /// # Returns
+  /// Self with the attachment limits set for method chaining
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::Config;
+  /// use send_with_us::attachment::AttachmentLimits;
+  ///
+  /// let config = Config::new("api-key")
+  ///   .with_attachment_limits(AttachmentLimits::new().with_max_total_size(5 * 1024 * 1024));
+  /// ```
+  pub fn with_attachment_limits(mut self, limits: AttachmentLimits) -> Self {
+    self.attachment_limits = limits;
+    self
+  }
+
+  /// Enables or disables conditional requests for template and drip campaign reads.
+  ///
+  /// When enabled, responses to GETs of templates, template versions, and
+  /// drip campaigns are cached along with their `ETag`. Later reads of the
+  /// same URL send `If-None-Match`, and a `304 Not Modified` answer is served
+  /// from the cache, which saves bandwidth when polling for changes. The
+  /// cache is shared by all clones of an `Api`.
+  ///
+  /// # Arguments
+  /// * `enabled` - Whether to use conditional requests
+  ///
+  /// # Returns
+  /// Self with the updated setting for method chaining
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::Config;
+  ///
+  /// let config = Config::new("api-key").with_conditional_requests(true);
+  /// ```
+  pub fn with_conditional_requests(mut self, enabled: bool) -> Self {
+    self.conditional_requests = enabled;
+    self
+  }
+
+  /// Gets the protocol (http or https) from the configured URL.
+  ///
+  /// # Returns
+  /// The URL scheme as a string slice
+  pub fn protocol(&self) -> &str {
+    self.url.scheme()
+  }
+
+  /// Gets the host from the configured URL.
+  ///
+  /// # Returns
+  /// The host as a string slice, defaulting to "api.sendwithus.com" if not available
+  pub fn host(&self) -> &str {
+    self.url.host_str().unwrap_or("api.sendwithus.com")
+  }
+
+  /// Gets the port from the configured URL.
+  ///
+  /// If the URL doesn't specify a port, this method returns the default port
+  /// for the protocol (443 for HTTPS, 80 for HTTP).
+  ///
+  /// # Returns
+  /// The port number
+  pub fn port(&self) -> u16 {
+    self
+      .url
+      .port()
+      .unwrap_or_else(|| if self.protocol() == "https" { 443 } else { 80 })
+  }
+}
+
+/// Builder for a `Config` that is validated before use.
+///
+/// Unlike the `Config::with_*` methods, which accept any value, `build`
+/// checks the settings and fails on misconfiguration, so mistakes surface at
+/// startup instead of on the first send. Settings that can't be invalid,
+/// such as retry policies, can be applied to the built `Config` with its
+/// `with_*` methods.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::Config;
+/// use std::time::Duration;
+///
+/// # fn main() -> send_with_us::Result<()> {
+/// let config = Config::builder("api-key")
+///   .with_url("https://api.eu.sendwithus.com")
+///   .with_api_version("1")
+///   .build()?
+///   .with_timeout(Duration::from_secs(10));
+///
+/// assert!(Config::builder("").build().is_err());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConfigBuilder {
+  api_key: String,
+  url: Option<String>,
+  fallback_url: Option<String>,
+  api_version: ApiVersion,
+  debug: bool,
+}
+
+impl ConfigBuilder {
+  /// Creates a builder with default settings and the provided API key.
+  ///
+  /// # Arguments
+  /// * `api_key` - Your SendWithUs API key
+  ///
+  /// # Returns
+  /// A new ConfigBuilder
+  pub fn new(api_key: impl Into<String>) -> Self {
+    Self {
+      api_key: api_key.into(),
+      url: None,
+      fallback_url: None,
+      api_version: ApiVersion::default(),
+      debug: false,
+    }
+  }
+
+  /// Sets a custom API URL.
+  ///
+  /// # Arguments
+  /// * `url` - The custom API URL to use
+  ///
+  /// # Returns
+  /// Self with the updated URL for method chaining
+  pub fn with_url(mut self, url: impl Into<String>) -> Self {
+    self.url = Some(url.into());
+    self
+  }
+
+  /// Sets a fallback API URL used when the primary URL can't be reached.
+  ///
+  /// # Arguments
+  /// * `url` - The fallback API URL
+  ///
+  /// # Returns
+  /// Self with the updated fallback URL for method chaining
+  pub fn with_fallback_url(mut self, url: impl Into<String>) -> Self {
+    self.fallback_url = Some(url.into());
+    self
+  }
+
+  /// Sets the API version to use.
+  ///
+  /// # Arguments
+  /// * `version` - The API version, as an `ApiVersion` or a string (e.g., "1", "v2")
+  ///
+  /// # Returns
+  /// Self with the updated API version for method chaining
+  pub fn with_api_version(mut self, version: impl Into<ApiVersion>) -> Self {
+    self.api_version = version.into();
+    self
+  }
+
+  /// Enables or disables debug mode.
+  ///
+  /// # Arguments
+  /// * `debug` - Whether to enable debug mode
+  ///
+  /// # Returns
+  /// Self with the updated debug setting for method chaining
+  pub fn with_debug(mut self, debug: bool) -> Self {
+    self.debug = debug;
+    self
+  }
+
+  /// Validates the settings and builds the configuration.
+  ///
+  /// # Returns
+  /// The validated Config
+  ///
+  /// # Errors
+  /// Returns `Error::InvalidConfig` if the API key is empty, a URL isn't a
+  /// valid http(s) URL, or the API version can't be used in a URL
+  pub fn build(self) -> Result<Config> {
+    if self.api_key.trim().is_empty() {
+      return Err(Error::InvalidConfig(
+        "API key must not be empty".to_string(),
+      ));
+    }
+
+    self.api_version.path_segment()?;
+
+    let mut config = Config::new(self.api_key)
+      .with_api_version(self.api_version)
+      .with_debug(self.debug);
+
+    if let Some(url) = self.url {
+      config.url = parse_api_url(&url)?;
+    }
+
+    if let Some(url) = self.fallback_url {
+      config.fallback_url = Some(parse_api_url(&url)?);
+    }
+
+    Ok(config)
+  }
+}
+
+/// Parses a base URL for the API, accepting only http(s) URLs with a host.
+fn parse_api_url(url: &str) -> Result<Url> {
+  let parsed = Url::parse(url)
+    .map_err(|e| Error::InvalidConfig(format!("invalid API URL {:?}: {}", url, e)))?;
+
+  if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+    return Err(Error::InvalidConfig(format!(
+      "invalid API URL {:?}: expected an http or https URL with a host",
+      url
+    )));
+  }
+
+  Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_default_config() {
+    let config = Config::new("test-api-key");
+    assert_eq!(config.protocol(), "https");
+    assert_eq!(config.host(), "api.sendwithus.com");
+    assert_eq!(config.port(), 443);
+    assert_eq!(config.api_key, "test-api-key");
+    assert_eq!(config.api_version, ApiVersion::V1);
+    assert!(!config.debug);
+    assert!(config.redacted_data_keys.is_empty());
+    assert!(config.debug_sink.is_none());
+    assert_eq!(config.client_stub, format!("rust-{}", VERSION));
+    assert!(!config.encode_display_names);
+    assert_eq!(config.default_sender, None);
+    assert_eq!(config.default_esp_account, None);
+    assert_eq!(config.default_locale, None);
+    assert!(config.global_tags.is_empty());
+    assert!(config.global_headers.is_empty());
+    assert_eq!(config.intercept_address, None);
+    assert_eq!(config.timeout, None);
+    assert_eq!(config.connect_timeout, None);
+    assert_eq!(config.read_timeout, None);
+    assert_eq!(config.circuit_breaker, None);
+    assert_eq!(config.retry_policy, None);
+    assert_eq!(config.rate_limit_max_wait, None);
+    assert!(config.dead_letter_sink.is_none());
+    assert!(config.capture_session.is_none());
+    #[cfg(feature = "mock")]
+    assert!(config.cassette.is_none());
+    #[cfg(feature = "mock")]
+    assert!(config.fault_injector.is_none());
+    assert_eq!(config.attachment_limits, AttachmentLimits::default());
+    assert!(!config.conditional_requests);
+  }
+
+  #[test]
+  fn test_with_rate_limit_retry() {
+    let config = Config::new("test-api-key").with_rate_limit_retry(Duration::from_secs(60));
+    assert_eq!(config.rate_limit_max_wait, Some(Duration::from_secs(60)));
+  }
+
+  #[test]
+  fn test_with_retry_policy() {
+    let config = Config::new("test-api-key").with_retry_policy(RetryPolicy::new(4));
+    assert_eq!(
+      config.retry_policy.map(|policy| policy.max_attempts),
+      Some(4)
+    );
+  }
+
+  #[test]
+  fn test_with_timeout() {
+    let config = Config::new("test-api-key")
+      .with_timeout(Duration::from_secs(5))
+      .with_connect_timeout(Duration::from_secs(1))
+      .with_read_timeout(Duration::from_secs(2));
+    assert_eq!(config.timeout, Some(Duration::from_secs(5)));
+    assert_eq!(config.connect_timeout, Some(Duration::from_secs(1)));
+    assert_eq!(config.read_timeout, Some(Duration::from_secs(2)));
+  }
+
+  #[cfg(any(feature = "native-tls", feature = "rustls"))]
+  const TEST_CA_PEM: &[u8] = b"-----BEGIN CERTIFICATE-----
+MIIBiDCCAS2gAwIBAgIUSbe+MFsnSiW5zX1OQZseIVZ3EnEwCgYIKoZIzj0EAwIw
+GDEWMBQGA1UEAwwNVGVzdCBQcm94eSBDQTAgFw0yNjEwMTYxNTMyNDZaGA8yMTI2
+MDkyMjE1MzI0NlowGDEWMBQGA1UEAwwNVGVzdCBQcm94eSBDQTBZMBMGByqGSM49
+AgEGCCqGSM49AwEHA0IABBemGid2GO5RrfT2XRmpApQsGMRSvy5hAqT8ZA4A+NXi
+fUtd4boYVuEzwGEbz3Jf8N8DaMvYGKclO6Zl9hnYQhijUzBRMB0GA1UdDgQWBBS8
+4VCZIhV6Srn42J00HKYO+vZo3TAfBgNVHSMEGDAWgBS84VCZIhV6Srn42J00HKYO
++vZo3TAPBgNVHRMBAf8EBTADAQH/MAoGCCqGSM49BAMCA0kAMEYCIQDoEK8/qhG4
+C4dluQ54FrWiZWCtN0yBqSSPvjnwAocB+wIhALvsUABtRrXMTkTRlF3UpjlurK3+
+wiWArmFAGeJ9625Q
+-----END CERTIFICATE-----
+";
+
+  #[cfg(any(feature = "native-tls", feature = "rustls"))]
+  #[test]
+  fn test_with_root_certificate() {
+    let certificate = Certificate::from_pem(TEST_CA_PEM).unwrap();
+    let config = Config::new("test-api-key")
+      .with_root_certificate(certificate.clone())
+      .with_root_certificate(certificate);
+    assert_eq!(config.root_certificates.len(), 2);
+    assert!(Config::new("test-api-key").root_certificates.is_empty());
+
+    // The HTTP client must accept the certificates.
+    crate::Api::new(config);
+  }
+
+  #[test]
+  fn test_custom_url_config() {
+    let config = Config::new("test-api-key")
+      .try_with_url("http://example.com")
+      .unwrap();
+    assert_eq!(config.protocol(), "http");
+    assert_eq!(config.host(), "example.com");
+    assert_eq!(config.port(), 80);
+  }
+
+  #[test]
+  fn test_try_with_url_rejects_invalid_urls() {
+    for url in [
+      "invalid-url",
+      "htps://api.sendwithus.com",
+      "mailto:user@example.com",
+      "",
+    ] {
+      match Config::new("test-api-key").try_with_url(url) {
+        Err(Error::InvalidConfig(message)) => assert!(message.contains("invalid API URL")),
+        other => panic!("Expected InvalidConfig for {:?}, got: {:?}", url, other),
+      }
+    }
+  }
+
+  #[test]
+  #[allow(deprecated)]
+  fn test_invalid_url_fallback() {
+    let config = Config::new("test-api-key").with_url("invalid-url");
+    assert_eq!(config.protocol(), "https");
+    assert_eq!(config.host(), "api.sendwithus.com");
+    assert_eq!(config.port(), 443);
+  }
+
+  #[test]
+  #[allow(deprecated)]
+  fn test_with_fallback_url() {
+    let config = Config::new("test-api-key").with_fallback_url("https://backup.example.com");
+    assert_eq!(
+      config.fallback_url.as_ref().map(Url::as_str),
+      Some("https://backup.example.com/")
+    );
+
+    let config = Config::new("test-api-key").with_fallback_url("invalid-url");
+    assert!(config.fallback_url.is_none());
+  }
+
+  #[test]
+  fn test_try_with_fallback_url() {
+    let config = Config::new("test-api-key")
+      .try_with_fallback_url("https://backup.example.com")
+      .unwrap();
+    assert_eq!(
+      config.fallback_url.as_ref().map(Url::as_str),
+      Some("https://backup.example.com/")
+    );
+
+    for url in ["invalid-url", "ftp://backup.example.com"] {
+      assert!(matches!(
+        Config::new("test-api-key").try_with_fallback_url(url),
+        Err(Error::InvalidConfig(_))
+      ));
+    }
+  }
+
+  #[test]
+  fn test_with_api_version() {
+    let config = Config::new("test-api-key").with_api_version("2");
+    assert_eq!(config.api_version, ApiVersion::V2);
+
+    let config = Config::new("test-api-key").with_api_version(ApiVersion::Custom("3".into()));
+    assert_eq!(config.api_version.to_string(), "3");
+  }
+
+  #[test]
+  fn test_api_version_from_str() {
+    assert_eq!(ApiVersion::from("1"), ApiVersion::V1);
+    assert_eq!(ApiVersion::from("v1"), ApiVersion::V1);
+    assert_eq!(ApiVersion::from(" V2 "), ApiVersion::V2);
+    assert_eq!(
+      ApiVersion::from("v3".to_string()),
+      ApiVersion::Custom("3".into())
+    );
+  }
+
+  #[test]
+  fn test_api_version_path_segment() {
+    assert_eq!(ApiVersion::V1.path_segment().unwrap(), "v1");
+    assert_eq!(ApiVersion::V2.path_segment().unwrap(), "v2");
+    assert_eq!(
+      ApiVersion::from("2024-01").path_segment().unwrap(),
+      "v2024-01"
+    );
+
+    for version in ["", "v", "1/", "/1", "1 2", "../1"] {
+      assert!(
+        matches!(
+          ApiVersion::from(version).path_segment(),
+          Err(Error::InvalidConfig(_))
+        ),
+        "Expected {:?} to be rejected",
+        version
+      );
+    }
+  }
+
+  #[test]
+  fn test_with_debug_mode() {
+    let config = Config::new("test-api-key").with_debug(true);
+    assert!(config.debug);
+  }
+
+  #[test]
+  fn test_with_encoded_display_names() {
+    let config = Config::new("test-api-key").with_encoded_display_names(true);
+    assert!(config.encode_display_names);
+  }
+
+  #[test]
+  fn test_custom_port() {
+    let config = Config::new("test-api-key")
+      .try_with_url("https://example.com:8443")
+      .unwrap();
+    assert_eq!(config.protocol(), "https");
+    assert_eq!(config.host(), "example.com");
+    assert_eq!(config.port(), 8443);
+  }
+
+  #[test]
+  fn test_builder_builds_valid_config() {
+    let config = Config::builder("test-api-key")
+      .with_url("http://localhost:8080")
+      .with_fallback_url("https://api.sendwithus.com")
+      .with_api_version("v2")
+      .with_debug(true)
+      .build()
+      .unwrap();
+
+    assert_eq!(config.api_key, "test-api-key");
+    assert_eq!(config.host(), "localhost");
+    assert_eq!(config.port(), 8080);
+    assert_eq!(
+      config.fallback_url.unwrap().as_str(),
+      "https://api.sendwithus.com/"
+    );
+    assert_eq!(config.api_version, ApiVersion::V2);
+    assert!(config.debug);
+
+    let config = Config::builder("test-api-key").build().unwrap();
+    assert_eq!(config.host(), "api.sendwithus.com");
+    assert!(config.fallback_url.is_none());
+  }
+
+  #[test]
+  fn test_builder_rejects_invalid_config() {
+    let builders = [
+      Config::builder(""),
+      Config::builder("   "),
+      Config::builder("test-api-key").with_url("ftp://example.com"),
+      Config::builder("test-api-key").with_fallback_url("not a url"),
+      Config::builder("test-api-key").with_api_version("1/"),
+    ];
+
+    for builder in builders {
+      let description = format!("{:?}", builder);
+      assert!(
+        matches!(builder.build(), Err(Error::InvalidConfig(_))),
+        "Expected {} to be rejected",
+        description
+      );
+    }
   }
 }
@@ -0,0 +1,235 @@
+use crate::error::Result;
+use crate::file_store::{JsonFileStore, unique_id};
+use crate::types::EmailOptions;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// A send that failed after exhausting its retries.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DeadLetter {
+  /// Unique identifier of the dead letter
+  pub id: String,
+
+  /// The email that could not be sent
+  pub options: EmailOptions,
+
+  /// Description of the last error
+  pub error: String,
+
+  /// When the send was first attempted (Unix timestamp)
+  pub first_attempted_at: u64,
+
+  /// When the send was given up on (Unix timestamp)
+  pub failed_at: u64,
+
+  /// Whether SendWithUs may have accepted the email despite the error
+  ///
+  /// Letters that may already have been sent are skipped by
+  /// `Api::replay_dead_letters`, since replaying them could deliver the
+  /// email twice. Letters stored before this was recorded count as possibly
+  /// sent.
+  #[serde(default = "possibly_sent")]
+  pub may_have_been_sent: bool,
+}
+
+fn possibly_sent() -> bool {
+  true
+}
+
+impl DeadLetter {
+  /// Creates a dead letter with a new unique ID, failed now.
+  ///
+  /// The letter counts as possibly sent until `with_may_have_been_sent`
+  /// says otherwise.
+  ///
+  /// # Arguments
+  /// * `options` - The email that could not be sent
+  /// * `error` - Description of the last error
+  /// * `first_attempted_at` - When the send was first attempted (Unix timestamp)
+  ///
+  /// # Returns
+  /// A new DeadLetter
+  pub fn new(options: EmailOptions, error: impl Into<String>, first_attempted_at: u64) -> Self {
+    let (id, now) = unique_id();
+
+    Self {
+      id,
+      options,
+      error: error.into(),
+      first_attempted_at,
+      failed_at: now.as_secs(),
+      may_have_been_sent: true,
+    }
+  }
+
+  /// Sets whether SendWithUs may have accepted the email despite the error.
+  ///
+  /// # Returns
+  /// Self with the flag set for method chaining
+  pub fn with_may_have_been_sent(mut self, may_have_been_sent: bool) -> Self {
+    self.may_have_been_sent = may_have_been_sent;
+    self
+  }
+}
+
+/// Destination for sends that failed after exhausting their retries.
+///
+/// Configure a sink with `Config::with_dead_letter_sink` and replay its
+/// contents later with `Api::replay_dead_letters`.
+#[async_trait]
+pub trait DeadLetterSink: fmt::Debug + Send + Sync {
+  /// Stores a failed send.
+  async fn capture(&self, letter: DeadLetter) -> Result<()>;
+
+  /// Returns all stored dead letters, oldest first.
+  async fn letters(&self) -> Result<Vec<DeadLetter>>;
+
+  /// Removes a dead letter once it has been dealt with.
+  async fn remove(&self, id: &str) -> Result<()>;
+}
+
+/// A dead-letter sink keeping letters in memory.
+///
+/// Letters are lost when the process exits; use `FileDeadLetterSink` to keep
+/// them across restarts.
+#[derive(Debug, Default)]
+pub struct MemoryDeadLetterSink {
+  letters: Mutex<Vec<DeadLetter>>,
+}
+
+impl MemoryDeadLetterSink {
+  /// Creates an empty in-memory sink.
+  pub fn new() -> Self {
+    Self::default()
+  }
+}
+
+#[async_trait]
+impl DeadLetterSink for MemoryDeadLetterSink {
+  async fn capture(&self, letter: DeadLetter) -> Result<()> {
+    self
+      .letters
+      .lock()
+      .unwrap_or_else(|e| e.into_inner())
+      .push(letter);
+    Ok(())
+  }
+
+  async fn letters(&self) -> Result<Vec<DeadLetter>> {
+    Ok(
+      self
+        .letters
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone(),
+    )
+  }
+
+  async fn remove(&self, id: &str) -> Result<()> {
+    self
+      .letters
+      .lock()
+      .unwrap_or_else(|e| e.into_inner())
+      .retain(|letter| letter.id != id);
+    Ok(())
+  }
+}
+
+/// A dead-letter sink keeping one JSON file per letter in a directory.
+#[derive(Debug, Clone)]
+pub struct FileDeadLetterSink {
+  files: JsonFileStore,
+}
+
+impl FileDeadLetterSink {
+  /// Creates a sink that keeps letters in `dir`.
+  ///
+  /// The directory is created on first use if it doesn't exist.
+  ///
+  /// # Arguments
+  /// * `dir` - Directory for dead letters
+  ///
+  /// # Returns
+  /// A new FileDeadLetterSink
+  pub fn new(dir: impl Into<PathBuf>) -> Self {
+    Self {
+      files: JsonFileStore::new(dir.into()),
+    }
+  }
+
+  /// Returns the files of letters that couldn't be read, oldest name first.
+  ///
+  /// `letters` moves a letter file it can't parse aside, renaming it with a
+  /// `.corrupt` extension, so one damaged file doesn't hide the others.
+  ///
+  /// # Errors
+  /// Returns an error if the directory can't be read
+  pub async fn quarantined(&self) -> Result<Vec<PathBuf>> {
+    self.files.quarantined().await
+  }
+}
+
+#[async_trait]
+impl DeadLetterSink for FileDeadLetterSink {
+  async fn capture(&self, letter: DeadLetter) -> Result<()> {
+    self.files.write(&letter.id, &letter).await
+  }
+
+  async fn letters(&self) -> Result<Vec<DeadLetter>> {
+    let mut letters: Vec<DeadLetter> = self.files.read_all().await?;
+    letters.sort_by(|a, b| a.failed_at.cmp(&b.failed_at).then(a.id.cmp(&b.id)));
+    Ok(letters)
+  }
+
+  async fn remove(&self, id: &str) -> Result<()> {
+    self.files.remove(id).await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::types::Recipient;
+  use tempdir::TempDir;
+
+  fn letter(email: &str) -> DeadLetter {
+    DeadLetter::new(
+      EmailOptions::new("tem_123", Recipient::new(email)),
+      "Connection to SendWithUs API failed",
+      1_700_000_000,
+    )
+  }
+
+  #[tokio::test]
+  async fn test_memory_sink() {
+    let sink = MemoryDeadLetterSink::new();
+    let first = letter("a@example.com");
+
+    sink.capture(first.clone()).await.unwrap();
+    sink.capture(letter("b@example.com")).await.unwrap();
+    assert_eq!(sink.letters().await.unwrap().len(), 2);
+
+    sink.remove(&first.id).await.unwrap();
+    let letters = sink.letters().await.unwrap();
+    assert_eq!(letters.len(), 1);
+    assert_eq!(letters[0].options.recipient.address, "b@example.com");
+  }
+
+  #[tokio::test]
+  async fn test_file_sink() {
+    let dir = TempDir::new("dead_letters").unwrap();
+    let sink = FileDeadLetterSink::new(dir.path().join("letters"));
+    assert!(sink.letters().await.unwrap().is_empty());
+
+    let first = letter("a@example.com");
+    sink.capture(first.clone()).await.unwrap();
+    assert_eq!(sink.letters().await.unwrap(), vec![first.clone()]);
+
+    sink.remove(&first.id).await.unwrap();
+    sink.remove(&first.id).await.unwrap();
+    assert!(sink.letters().await.unwrap().is_empty());
+  }
+}
@@ -0,0 +1,129 @@
+use crate::error::{Error, Result};
+use base64::{Engine as _, engine::general_purpose};
+use std::borrow::Cow;
+
+/// Maximum number of raw bytes carried by a single encoded word.
+///
+/// RFC 2047 limits an encoded word to 75 characters. `=?UTF-8?B?` and `?=`
+/// take 12 of those, leaving 63 for base64 text, which holds at most 45 bytes.
+const MAX_WORD_BYTES: usize = 45;
+
+/// Checks that a display name is safe to place in an email header.
+///
+/// Any Unicode text is allowed, but control characters such as CR and LF are
+/// rejected because they could be used to inject additional headers.
+///
+/// # Arguments
+/// * `name` - The display name to check
+///
+/// # Errors
+/// Returns `Error::InvalidDisplayName` if the name contains control characters
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::encoding::validate_display_name;
+///
+/// assert!(validate_display_name("Søren Ångström").is_ok());
+/// assert!(validate_display_name("Evil\r\nBcc: victim@example.com").is_err());
+/// ```
+pub fn validate_display_name(name: &str) -> Result<()> {
+  if name.chars().any(char::is_control) {
+    return Err(Error::InvalidDisplayName(name.to_string()));
+  }
+
+  Ok(())
+}
+
+/// Encodes a display name as RFC 2047 encoded words when it isn't plain ASCII.
+///
+/// ASCII names are returned unchanged. Names containing other characters are
+/// encoded as one or more `=?UTF-8?B?...?=` words, split on character
+/// boundaries so each word stays within the RFC 2047 length limit.
+///
+/// # Arguments
+/// * `name` - The display name to encode
+///
+/// # Returns
+/// The name, encoded if necessary
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::encoding::encode_display_name;
+///
+/// assert_eq!(encode_display_name("John Doe"), "John Doe");
+/// assert_eq!(encode_display_name("Søren"), "=?UTF-8?B?U8O4cmVu?=");
+/// ```
+pub fn encode_display_name(name: &str) -> Cow<'_, str> {
+  if name.is_ascii() {
+    return Cow::Borrowed(name);
+  }
+
+  let mut words = Vec::new();
+  let mut start = 0;
+
+  for (index, ch) in name.char_indices() {
+    if index + ch.len_utf8() - start > MAX_WORD_BYTES {
+      words.push(encode_word(&name[start..index]));
+      start = index;
+    }
+  }
+
+  words.push(encode_word(&name[start..]));
+
+  Cow::Owned(words.join(" "))
+}
+
+fn encode_word(chunk: &str) -> String {
+  format!("=?UTF-8?B?{}?=", general_purpose::STANDARD.encode(chunk))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn decode(encoded: &str) -> String {
+    encoded
+      .split(' ')
+      .map(|word| {
+        let payload = word
+          .strip_prefix("=?UTF-8?B?")
+          .and_then(|w| w.strip_suffix("?="))
+          .expect("not an encoded word");
+        String::from_utf8(general_purpose::STANDARD.decode(payload).unwrap()).unwrap()
+      })
+      .collect()
+  }
+
+  #[test]
+  fn test_ascii_names_are_unchanged() {
+    assert!(matches!(encode_display_name("John Doe"), Cow::Borrowed(_)));
+  }
+
+  #[test]
+  fn test_non_ascii_names_are_encoded() {
+    let encoded = encode_display_name("Søren Ångström");
+    assert!(encoded.starts_with("=?UTF-8?B?"));
+    assert_eq!(decode(&encoded), "Søren Ångström");
+  }
+
+  #[test]
+  fn test_long_names_are_split_into_short_words() {
+    let name = "Ångström ".repeat(10);
+    let encoded = encode_display_name(&name);
+
+    assert!(encoded.split(' ').count() > 1);
+    assert!(encoded.split(' ').all(|word| word.len() <= 75));
+    assert_eq!(decode(&encoded), name);
+  }
+
+  #[test]
+  fn test_validate_display_name() {
+    assert!(validate_display_name("Søren Ångström").is_ok());
+    assert!(validate_display_name("山田 太郎").is_ok());
+
+    let err = validate_display_name("Name\nBcc: x@example.com").unwrap_err();
+    assert!(matches!(err, Error::InvalidDisplayName(_)));
+  }
+}
@@ -0,0 +1,126 @@
+//! API endpoints kept as path segments and query pairs.
+//!
+//! Values interpolated into an endpoint, such as email addresses, may
+//! contain `/` or `?`. Keeping the parts separate until the URL is built lets
+//! each segment be percent-encoded on its own, so such a value can't change
+//! which endpoint is called.
+
+use crate::types::EmailLogQuery;
+use percent_encoding::{AsciiSet, CONTROLS, utf8_percent_encode};
+use std::fmt;
+
+/// Characters percent-encoded in a path segment, as `url` encodes them.
+const PATH_SEGMENT: &AsciiSet = &CONTROLS
+  .add(b' ')
+  .add(b'"')
+  .add(b'#')
+  .add(b'<')
+  .add(b'>')
+  .add(b'`')
+  .add(b'?')
+  .add(b'{')
+  .add(b'}')
+  .add(b'/')
+  .add(b'%');
+
+/// An API endpoint, relative to the versioned API root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Endpoint {
+  segments: Vec<String>,
+  query: Vec<(String, String)>,
+}
+
+impl Endpoint {
+  /// Creates an endpoint from its unencoded path segments.
+  pub(crate) fn new<S: Into<String>>(segments: impl IntoIterator<Item = S>) -> Self {
+    Self {
+      segments: segments.into_iter().map(Into::into).collect(),
+      query: Vec::new(),
+    }
+  }
+
+  /// Parses an endpoint path given as a string to the public request methods.
+  ///
+  /// Each `/`-separated part becomes a segment, and anything after a `?` is
+  /// read as an already encoded query string.
+  pub(crate) fn parse(endpoint: &str) -> Self {
+    let (path, query) = endpoint.split_once('?').unwrap_or((endpoint, ""));
+
+    Self {
+      segments: path.split('/').map(str::to_string).collect(),
+      query: url::form_urlencoded::parse(query.as_bytes())
+        .into_owned()
+        .collect(),
+    }
+  }
+
+  /// Adds the filters of a log query.
+  pub(crate) fn with_query(mut self, query: &EmailLogQuery) -> Self {
+    self.query.extend(
+      query
+        .query_pairs()
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value)),
+    );
+    self
+  }
+
+  /// Returns the unencoded path segments.
+  pub(crate) fn segments(&self) -> &[String] {
+    &self.segments
+  }
+
+  /// Returns the unencoded query pairs.
+  pub(crate) fn query(&self) -> &[(String, String)] {
+    &self.query
+  }
+}
+
+/// Formats the endpoint as it appears in the request URL, for logs and
+/// request contexts.
+impl fmt::Display for Endpoint {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for (index, segment) in self.segments.iter().enumerate() {
+      if index > 0 {
+        f.write_str("/")?;
+      }
+      write!(f, "{}", utf8_percent_encode(segment, PATH_SEGMENT))?;
+    }
+
+    if !self.query.is_empty() {
+      let query = url::form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(&self.query)
+        .finish();
+      write!(f, "?{}", query)?;
+    }
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_and_display() {
+    let endpoint = Endpoint::parse("logs?count=10&created_gt=a%20b");
+    assert_eq!(endpoint.segments(), ["logs"]);
+    assert_eq!(
+      endpoint.query(),
+      [
+        ("count".to_string(), "10".to_string()),
+        ("created_gt".to_string(), "a b".to_string())
+      ]
+    );
+    assert_eq!(endpoint.to_string(), "logs?count=10&created_gt=a+b");
+
+    let endpoint = Endpoint::new(["customers", "a/b?c@example.com", "logs"])
+      .with_query(&EmailLogQuery::new().with_count(5));
+    assert_eq!(endpoint.segments().len(), 3);
+    assert_eq!(
+      endpoint.to_string(),
+      "customers/a%2Fb%3Fc@example.com/logs?count=5"
+    );
+  }
+}
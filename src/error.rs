@@ -1,3 +1,10 @@
+use crate::ids::LogId;
+use crate::models::EventType;
+use crate::redaction;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
 use thiserror::Error;
 
 /// Result type for SendWithUs operations
@@ -31,6 +38,64 @@ pub enum Error {
   #[error("Recipient email address is required")]
   MissingRecipientAddress,
 
+  /// A webhook event didn't name the email log it belongs to
+  ///
+  /// Events can only be correlated with their send when the payload carries
+  /// a `log_id`. The value is the ID of the webhook event.
+  #[error("Webhook event {0} has no log ID")]
+  MissingLogId(String),
+
+  /// An identifier did not have the prefix expected for its kind
+  ///
+  /// SendWithUs identifiers are prefixed by kind (`tem_` for templates, `dc_`
+  /// for drip campaigns, `log_` for logs and receipts). This error is returned
+  /// when parsing a value that doesn't match, which usually means an ID of the
+  /// wrong kind was passed.
+  #[error("Invalid {kind} ID: {value}")]
+  InvalidId { kind: &'static str, value: String },
+
+  /// No template has the given name
+  ///
+  /// Returned when sending by template name, after the list of templates
+  /// was refreshed from SendWithUs.
+  #[error("No template named {0:?}")]
+  TemplateNotFound(String),
+
+  /// The customer to update doesn't exist
+  ///
+  /// Returned when merging data into a customer whose current record can't
+  /// be found. Create the customer with `customer_create` instead.
+  #[error("Customer not found")]
+  CustomerNotFound,
+
+  /// A recipient or sender display name contains characters that can't be
+  /// placed in an email header
+  ///
+  /// Display names may contain any Unicode text, but control characters such
+  /// as line breaks are rejected.
+  #[error("Invalid display name: {0:?}")]
+  InvalidDisplayName(String),
+
+  /// Email options failed validation before being sent
+  ///
+  /// Returned by `EmailOptions::validate`, and by `send_email` before any
+  /// request is made. Every problem found is listed, so they can all be
+  /// fixed at once.
+  #[error("Invalid email options: {}", ValidationIssue::join(.0))]
+  InvalidEmailOptions(Vec<ValidationIssue>),
+
+  /// An attachment, or the attachments of an email together, are too large
+  ///
+  /// `filename` names the attachment, or is `None` when the combined size of
+  /// all attachments is over the limit. Sizes are in bytes before base64
+  /// encoding. Limits are set with `Config::with_attachment_limits`.
+  #[error("{} is {size} bytes, over the {limit} byte limit", attachment_label(.filename))]
+  AttachmentTooLarge {
+    filename: Option<String>,
+    size: usize,
+    limit: usize,
+  },
+
   /// The provided API endpoint is invalid or cannot be accessed
   ///
   /// This typically indicates a configuration issue with custom API endpoints.
@@ -40,28 +105,87 @@ pub enum Error {
   /// Failed to establish a connection to the SendWithUs API
   ///
   /// This may indicate network connectivity issues or that the SendWithUs
-  /// service is temporarily unavailable.
-  #[error("Connection to SendWithUs API failed")]
-  ConnectionFailed,
+  /// service is temporarily unavailable. `context` is set for calls made
+  /// through `Api`.
+  #[error("Connection to SendWithUs API failed{}", request_label(.context))]
+  ConnectionFailed { context: Option<RequestContext> },
 
   /// The SendWithUs API rejected the request due to invalid parameters
   ///
-  /// The error message provides additional details about what was invalid.
-  #[error("SendWithUs API rejected request: {0}")]
-  InvalidRequest(String),
+  /// The attached error describes what was invalid, including per-field
+  /// validation failures when the API reports them.
+  #[error("SendWithUs API rejected {context}: {error}")]
+  InvalidRequest {
+    context: RequestContext,
+    error: Box<SwuApiError>,
+  },
 
   /// The SendWithUs API returned an HTTP error
   ///
-  /// This contains the HTTP status code and error message from the API.
-  #[error("SendWithUs API error: {status} - {message}")]
-  ApiError { status: u16, message: String },
+  /// This contains the request that failed, the HTTP status code, and the
+  /// error reported by the API.
+  #[error("SendWithUs API error on {context}: {status} - {error}")]
+  ApiError {
+    context: RequestContext,
+    status: u16,
+    error: Box<SwuApiError>,
+  },
+
+  /// The API call did not complete before its deadline
+  ///
+  /// Deadlines are set with `Config::with_timeout` or `Api::with_timeout`.
+  /// A timeout during an incident is usually worth retrying later.
+  /// `context` is set for calls made through `Api`.
+  #[error("SendWithUs API request timed out{}", request_label(.context))]
+  Timeout { context: Option<RequestContext> },
+
+  /// The awaited event was not recorded before the wait timed out
+  ///
+  /// Returned by `Api::wait_for_event` when the log has no event of the
+  /// expected type once the timeout elapses.
+  #[error("Event {event_type} was not recorded for {log_id} in time")]
+  EventTimeout {
+    log_id: LogId,
+    event_type: EventType,
+  },
+
+  /// The circuit breaker is open, so the call was not attempted
+  ///
+  /// The circuit opens after repeated failures talking to SendWithUs. Once
+  /// `retry_in` has elapsed, a single probe call is let through to decide
+  /// whether it closes again.
+  #[error("SendWithUs API circuit breaker is open")]
+  CircuitOpen { retry_in: Duration },
+
+  /// A dead letter was not replayed because its email may already have been sent
+  ///
+  /// The original send failed in a way that leaves open whether SendWithUs
+  /// accepted it, such as a timeout or a 5xx response. Check the email's
+  /// logs before replaying it with `Api::replay_dead_letters_including_unknown`.
+  #[error("The email may already have been sent; check it before replaying")]
+  SendOutcomeUnknown,
+
+  /// The SendWithUs API is rate limiting requests (HTTP 429)
+  ///
+  /// `retry_after` holds the delay requested by the API's `Retry-After`
+  /// header, when it sent one. Wait at least that long before retrying.
+  /// `context` is set for calls made through `Api`.
+  #[error("Rate limited by SendWithUs API{}", request_label(.context))]
+  RateLimited {
+    retry_after: Option<Duration>,
+    context: Option<RequestContext>,
+  },
 
   /// Error communicating with the SendWithUs API
   ///
   /// This is a lower-level error from the HTTP client, which may indicate
-  /// network, timeout, or other communication issues.
-  #[error("API communication error: {0}")]
-  RequestFailed(#[from] reqwest::Error),
+  /// network, timeout, or other communication issues. `context` is set for
+  /// calls made through `Api`.
+  #[error("API communication error{}: {source}", request_label(.context))]
+  RequestFailed {
+    context: Option<RequestContext>,
+    source: reqwest::Error,
+  },
 
   /// Failed to serialize request or deserialize response data
   ///
@@ -70,6 +194,16 @@ pub enum Error {
   #[error("Data serialization error: {0}")]
   SerializationFailed(#[from] serde_json::Error),
 
+  /// A successful API response could not be decoded into the expected type
+  ///
+  /// `body` holds the response exactly as the API returned it, which usually
+  /// shows whether the API changed shape or returned something unexpected.
+  #[error("Failed to decode SendWithUs API response: {source}")]
+  ResponseDecodeFailed {
+    source: serde_json::Error,
+    body: String,
+  },
+
   /// Error accessing a file, typically when working with attachments
   ///
   /// Check that file paths are correct and that your application has
@@ -77,6 +211,44 @@ pub enum Error {
   #[error("File access error: {0}")]
   FileAccessFailed(#[from] std::io::Error),
 
+  /// A local SQLite database could not be read or written
+  ///
+  /// Only produced by `LogMirror` and `SqliteDedupeStore` with the `sqlite`
+  /// feature.
+  #[cfg(feature = "sqlite")]
+  #[error("SQLite database error: {0}")]
+  MirrorFailed(#[from] rusqlite::Error),
+
+  /// A template export could not be read or written as YAML
+  ///
+  /// Only produced by `TemplateExport` with the `yaml` feature.
+  #[cfg(feature = "yaml")]
+  #[error("YAML error: {0}")]
+  YamlFailed(#[from] serde_yaml::Error),
+
+  /// A template could not be rendered locally
+  ///
+  /// Only produced by `render_preview` with the `preview` feature, for
+  /// syntax errors and, in strict mode, undefined variables.
+  #[cfg(feature = "preview")]
+  #[error("Template preview failed: {0}")]
+  PreviewFailed(#[from] minijinja::Error),
+
+  /// A rendered email could not be converted to an SMTP message
+  ///
+  /// Only produced by the `smtp` module with the `lettre` feature, e.g. for
+  /// invalid addresses or a missing sender.
+  #[cfg(feature = "lettre")]
+  #[error("SMTP message could not be built: {0}")]
+  SmtpMessageFailed(String),
+
+  /// A configuration value was rejected while building the client
+  ///
+  /// The message describes which setting is invalid and why, so
+  /// misconfiguration can be caught at startup.
+  #[error("Invalid configuration: {0}")]
+  InvalidConfig(String),
+
   /// The configured base URL for the SendWithUs API is invalid
   ///
   /// This typically indicates a configuration issue in your application.
@@ -84,6 +256,21 @@ pub enum Error {
   #[error("Invalid SendWithUs API URL")]
   InvalidApiUrl,
 
+  /// A request had no matching interaction in the cassette being replayed
+  ///
+  /// The message names the method and path of the request. Re-record the
+  /// cassette if the requests made have changed.
+  #[cfg(feature = "mock")]
+  #[error("No recorded interaction for {0}")]
+  NoRecordedInteraction(String),
+
+  /// A webhook request didn't carry the expected secret token
+  ///
+  /// The request may not come from SendWithUs and should be rejected
+  /// without processing its events.
+  #[error("Webhook verification failed")]
+  WebhookVerificationFailed,
+
   /// An unexpected error occurred that doesn't match any of the known categories
   ///
   /// The error message provides additional context about what went wrong.
@@ -91,6 +278,452 @@ pub enum Error {
   Unexpected(String),
 }
 
+impl Error {
+  /// Returns `true` if the same request may succeed when retried later.
+  ///
+  /// This covers connection failures, timeouts, rate limiting, and
+  /// server-side errors (HTTP 5xx and 408). Errors caused by the request
+  /// itself are never retryable.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::Error;
+  /// use send_with_us::error::{RequestContext, SwuApiError};
+  ///
+  /// let error = Error::ApiError {
+  ///   context: RequestContext::new(reqwest::Method::POST, "send"),
+  ///   status: 503,
+  ///   error: Box::new(SwuApiError::from_body("Unavailable")),
+  /// };
+  /// assert!(error.is_retryable());
+  /// assert!(!Error::InvalidCredentials.is_retryable());
+  /// ```
+  pub fn is_retryable(&self) -> bool {
+    match self {
+      Error::ConnectionFailed { .. }
+      | Error::Timeout { .. }
+      | Error::RateLimited { .. }
+      | Error::CircuitOpen { .. } => true,
+      Error::ApiError { status, .. } => *status >= 500 || *status == 408 || *status == 429,
+      Error::RequestFailed { source, .. } => source.is_timeout() || source.is_connect(),
+      _ => false,
+    }
+  }
+
+  /// Returns `true` if SendWithUs may have acted on the failed request.
+  ///
+  /// Requests that never reached SendWithUs (connection failures and an
+  /// open circuit) or were turned away (rate limiting and other 4xx
+  /// responses) were not processed. After a timeout or a 5xx response it's
+  /// unknown whether they were, so a send failing this way may already have
+  /// been delivered.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::Error;
+  ///
+  /// assert!(Error::Timeout { context: None }.may_have_been_processed());
+  /// assert!(!Error::ConnectionFailed { context: None }.may_have_been_processed());
+  /// ```
+  pub fn may_have_been_processed(&self) -> bool {
+    match self {
+      Error::Timeout { .. } => true,
+      Error::ApiError { status, .. } => *status >= 500,
+      Error::RequestFailed { source, .. } => !source.is_connect(),
+      _ => false,
+    }
+  }
+
+  /// Returns `true` if the error was caused by the request rather than the service.
+  ///
+  /// This includes HTTP 4xx responses as well as requests rejected locally
+  /// before being sent, such as a missing template ID. Retrying these
+  /// unchanged will fail again.
+  pub fn is_client_error(&self) -> bool {
+    match self {
+      Error::InvalidCredentials
+      | Error::MissingTemplateId
+      | Error::MissingRecipientAddress
+      | Error::InvalidId { .. }
+      | Error::TemplateNotFound(_)
+      | Error::CustomerNotFound
+      | Error::InvalidDisplayName(_)
+      | Error::InvalidEmailOptions(_)
+      | Error::AttachmentTooLarge { .. }
+      | Error::InvalidEndpoint(_)
+      | Error::InvalidRequest { .. } => true,
+      Error::ApiError { status, .. } => (400..500).contains(status),
+      _ => false,
+    }
+  }
+
+  /// Returns the request that failed.
+  ///
+  /// Errors reported by the API and transport failures of calls made
+  /// through `Api` carry the request, including its correlation ID.
+  ///
+  /// # Returns
+  /// The method and endpoint of the failed request, if the error carries them
+  pub fn context(&self) -> Option<&RequestContext> {
+    match self {
+      Error::InvalidRequest { context, .. } | Error::ApiError { context, .. } => Some(context),
+      Error::ConnectionFailed { context }
+      | Error::Timeout { context }
+      | Error::RateLimited { context, .. }
+      | Error::RequestFailed { context, .. } => context.as_ref(),
+      _ => None,
+    }
+  }
+
+  /// Attaches the request to a transport failure that doesn't carry one yet.
+  pub(crate) fn with_context(mut self, request: &RequestContext) -> Self {
+    if let Error::ConnectionFailed { context }
+    | Error::Timeout { context }
+    | Error::RateLimited { context, .. }
+    | Error::RequestFailed { context, .. } = &mut self
+    {
+      context.get_or_insert_with(|| request.clone());
+    }
+
+    self
+  }
+
+  /// Returns `true` if the API rejected the credentials.
+  ///
+  /// Authentication errors usually mean the API key is missing, revoked, or
+  /// lacks permissions, and are worth alerting on rather than retrying.
+  pub fn is_auth_error(&self) -> bool {
+    matches!(self, Error::InvalidCredentials)
+  }
+}
+
+impl From<reqwest::Error> for Error {
+  fn from(source: reqwest::Error) -> Self {
+    Error::RequestFailed {
+      context: None,
+      source,
+    }
+  }
+}
+
+/// Describes the request of a transport failure, if it is known.
+fn request_label(context: &Option<RequestContext>) -> String {
+  match context {
+    Some(context) => format!(" on {}", context),
+    None => String::new(),
+  }
+}
+
+/// Describes the attachment named in an `Error::AttachmentTooLarge`.
+fn attachment_label(filename: &Option<String>) -> String {
+  match filename {
+    Some(filename) => format!("Attachment {:?}", filename),
+    None => "Total attachment size".to_string(),
+  }
+}
+
+/// The kind of problem a `ValidationIssue` describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ValidationIssueKind {
+  /// A required field is missing or blank
+  Required,
+
+  /// The field is not a valid email address
+  InvalidEmailAddress,
+
+  /// The field is not a valid email header name
+  InvalidHeaderName,
+}
+
+/// A single problem found while validating email options.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::error::{ValidationIssue, ValidationIssueKind};
+///
+/// let issue = ValidationIssue::new(
+///   "recipient",
+///   ValidationIssueKind::InvalidEmailAddress,
+///   "is not a valid email address",
+/// )
+/// .with_path("recipient.address");
+/// assert_eq!(issue.to_string(), "recipient.address is not a valid email address");
+/// assert_eq!(issue.top_level_field(), "recipient");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationIssue {
+  /// Path of the offending field, e.g. `cc[1].address`
+  pub field: String,
+
+  /// What kind of problem this is
+  pub kind: ValidationIssueKind,
+
+  /// What is wrong with the field
+  pub message: String,
+
+  top_level_field: &'static str,
+}
+
+impl ValidationIssue {
+  /// Creates a new validation issue for a top-level field.
+  ///
+  /// # Arguments
+  /// * `field` - Name of the offending top-level field, e.g. `cc`
+  /// * `kind` - What kind of problem this is
+  /// * `message` - What is wrong with the field
+  ///
+  /// # Returns
+  /// A new ValidationIssue
+  pub fn new(field: &'static str, kind: ValidationIssueKind, message: impl Into<String>) -> Self {
+    Self {
+      field: field.to_string(),
+      kind,
+      message: message.into(),
+      top_level_field: field,
+    }
+  }
+
+  /// Narrows the issue to a field nested in the top-level field.
+  ///
+  /// # Arguments
+  /// * `path` - Full path of the offending field, e.g. `cc[1].address`
+  ///
+  /// # Returns
+  /// Self with the path set for method chaining
+  pub fn with_path(mut self, path: impl Into<String>) -> Self {
+    self.field = path.into();
+    self
+  }
+
+  /// Returns the top-level field the issue is in, e.g. `cc` for `cc[1].address`.
+  pub fn top_level_field(&self) -> &'static str {
+    self.top_level_field
+  }
+
+  fn join(issues: &[ValidationIssue]) -> String {
+    issues
+      .iter()
+      .map(ToString::to_string)
+      .collect::<Vec<_>>()
+      .join("; ")
+  }
+}
+
+impl fmt::Display for ValidationIssue {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{} {}", self.field, self.message)
+  }
+}
+
+/// The API call an error belongs to.
+///
+/// When displayed, endpoint segments and query values holding an email
+/// address are redacted, so error messages can be logged safely.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::error::RequestContext;
+///
+/// let context = RequestContext::new(reqwest::Method::GET, "templates/tem_123");
+/// assert_eq!(context.to_string(), "GET templates/tem_123");
+///
+/// let context = context.with_correlation_id("req-42");
+/// assert_eq!(context.to_string(), "GET templates/tem_123 (correlation ID req-42)");
+///
+/// let context = RequestContext::new(reqwest::Method::GET, "customers/ada@example.com");
+/// assert_eq!(context.to_string(), "GET customers/[REDACTED]");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestContext {
+  /// HTTP method of the request
+  pub method: reqwest::Method,
+
+  /// API endpoint path, relative to the versioned API root
+  pub endpoint: String,
+
+  /// Correlation ID sent with the request, if any
+  pub correlation_id: Option<String>,
+}
+
+impl RequestContext {
+  /// Creates a request context.
+  ///
+  /// # Arguments
+  /// * `method` - HTTP method of the request
+  /// * `endpoint` - API endpoint path
+  ///
+  /// # Returns
+  /// A new RequestContext
+  pub fn new(method: reqwest::Method, endpoint: impl Into<String>) -> Self {
+    Self {
+      method,
+      endpoint: endpoint.into(),
+      correlation_id: None,
+    }
+  }
+
+  /// Sets the correlation ID the request was sent with.
+  ///
+  /// # Arguments
+  /// * `correlation_id` - Correlation ID of the request
+  ///
+  /// # Returns
+  /// Self with the correlation ID set for method chaining
+  pub fn with_correlation_id(mut self, correlation_id: impl Into<String>) -> Self {
+    self.correlation_id = Some(correlation_id.into());
+    self
+  }
+}
+
+impl fmt::Display for RequestContext {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "{} {}",
+      self.method,
+      redaction::redact_endpoint(&self.endpoint)
+    )?;
+
+    if let Some(correlation_id) = &self.correlation_id {
+      write!(f, " (correlation ID {})", correlation_id)?;
+    }
+
+    Ok(())
+  }
+}
+
+/// An error reported by the SendWithUs API in a response body.
+///
+/// SendWithUs usually describes failures with a JSON body. When it does, the
+/// error code, message, and any per-field validation errors are extracted so
+/// callers can branch on them. Bodies that aren't JSON are kept as the message.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::error::SwuApiError;
+///
+/// let error = SwuApiError::from_body(
+///   r#"{"code": "invalid_request", "error": "Bad data", "errors": {"email_id": ["is required"]}}"#,
+/// );
+///
+/// assert_eq!(error.code.as_deref(), Some("invalid_request"));
+/// assert_eq!(error.message, "Bad data");
+/// assert_eq!(error.field_errors["email_id"], vec!["is required"]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SwuApiError {
+  /// Machine-readable error code, when the API provides one
+  pub code: Option<String>,
+
+  /// Human-readable error message
+  pub message: String,
+
+  /// Validation errors keyed by the name of the offending field
+  pub field_errors: HashMap<String, Vec<String>>,
+
+  /// The response body exactly as returned by the API
+  pub raw: String,
+}
+
+impl SwuApiError {
+  /// Parses an error response body.
+  ///
+  /// # Arguments
+  /// * `body` - The response body text
+  ///
+  /// # Returns
+  /// The structured error; if the body isn't a JSON object the whole body is used as the message
+  pub fn from_body(body: impl Into<String>) -> Self {
+    let raw = body.into();
+
+    let object = match serde_json::from_str::<Value>(&raw) {
+      Ok(Value::Object(object)) => object,
+      _ => {
+        return Self {
+          message: raw.trim().to_string(),
+          raw,
+          ..Self::default()
+        };
+      }
+    };
+
+    let code = object.get("code").and_then(|code| match code {
+      Value::String(code) => Some(code.clone()),
+      Value::Number(code) => Some(code.to_string()),
+      _ => None,
+    });
+
+    let message = ["error", "message", "detail"]
+      .iter()
+      .find_map(|key| object.get(*key).and_then(Value::as_str))
+      .map(str::to_string)
+      .unwrap_or_else(|| raw.clone());
+
+    let field_errors = ["errors", "field_errors"]
+      .iter()
+      .find_map(|key| object.get(*key).and_then(Value::as_object))
+      .map(|errors| {
+        errors
+          .iter()
+          .map(|(field, messages)| (field.clone(), messages_of(messages)))
+          .collect()
+      })
+      .unwrap_or_default();
+
+    Self {
+      code,
+      message,
+      field_errors,
+      raw,
+    }
+  }
+
+  /// Returns the validation errors reported for a field.
+  ///
+  /// # Arguments
+  /// * `field` - Name of the request field
+  ///
+  /// # Returns
+  /// The error messages for the field, or an empty slice if it has none
+  pub fn field(&self, field: &str) -> &[String] {
+    self
+      .field_errors
+      .get(field)
+      .map(Vec::as_slice)
+      .unwrap_or_default()
+  }
+}
+
+/// Collects the messages of a single field error, which may be a string or a list.
+fn messages_of(value: &Value) -> Vec<String> {
+  match value {
+    Value::String(message) => vec![message.clone()],
+    Value::Array(messages) => messages
+      .iter()
+      .map(|message| match message {
+        Value::String(message) => message.clone(),
+        other => other.to_string(),
+      })
+      .collect(),
+    other => vec![other.to_string()],
+  }
+}
+
+impl fmt::Display for SwuApiError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match &self.code {
+      Some(code) => write!(f, "{} ({})", self.message, code),
+      None => f.write_str(&self.message),
+    }
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -106,31 +739,83 @@ mod tests {
     let error = Error::MissingRecipientAddress;
     assert_eq!(error.to_string(), "Recipient email address is required");
 
+    let error = Error::InvalidId {
+      kind: "template",
+      value: "dc_123".to_string(),
+    };
+    assert_eq!(error.to_string(), "Invalid template ID: dc_123");
+
+    let error = Error::InvalidDisplayName("Bad\nName".to_string());
+    assert_eq!(error.to_string(), r#"Invalid display name: "Bad\nName""#);
+
     let error = Error::InvalidEndpoint("custom/endpoint".to_string());
     assert_eq!(error.to_string(), "Invalid API endpoint: custom/endpoint");
 
-    let error = Error::InvalidRequest("Invalid parameter".to_string());
+    let error = Error::InvalidRequest {
+      context: RequestContext::new(reqwest::Method::POST, "send"),
+      error: Box::new(SwuApiError::from_body("Invalid parameter")),
+    };
     assert_eq!(
       error.to_string(),
-      "SendWithUs API rejected request: Invalid parameter"
+      "SendWithUs API rejected POST send: Invalid parameter"
     );
 
     let error = Error::ApiError {
+      context: RequestContext::new(reqwest::Method::GET, "templates/tem_123"),
       status: 500,
-      message: "Server error".to_string(),
+      error: Box::new(SwuApiError::from_body("Server error")),
     };
     assert_eq!(
       error.to_string(),
-      "SendWithUs API error: 500 - Server error"
+      "SendWithUs API error on GET templates/tem_123: 500 - Server error"
+    );
+    assert_eq!(error.context().unwrap().endpoint, "templates/tem_123");
+    assert!(Error::InvalidCredentials.context().is_none());
+
+    let source = serde_json::from_str::<u32>("\"oops\"").unwrap_err();
+    let error = Error::ResponseDecodeFailed {
+      source,
+      body: "\"oops\"".to_string(),
+    };
+    assert!(
+      error
+        .to_string()
+        .starts_with("Failed to decode SendWithUs API response: invalid type")
     );
 
+    let error = Error::Timeout { context: None };
+    assert_eq!(error.to_string(), "SendWithUs API request timed out");
+
+    let error = Error::Timeout { context: None }.with_context(
+      &RequestContext::new(reqwest::Method::GET, "emails").with_correlation_id("req-1"),
+    );
+    assert_eq!(
+      error.to_string(),
+      "SendWithUs API request timed out on GET emails (correlation ID req-1)"
+    );
+    assert_eq!(
+      error.context().and_then(|c| c.correlation_id.as_deref()),
+      Some("req-1")
+    );
+
+    let error = Error::CircuitOpen {
+      retry_in: Duration::from_secs(10),
+    };
+    assert_eq!(error.to_string(), "SendWithUs API circuit breaker is open");
+
+    let error = Error::RateLimited {
+      retry_after: Some(Duration::from_secs(30)),
+      context: None,
+    };
+    assert_eq!(error.to_string(), "Rate limited by SendWithUs API");
+
     let error = Error::Unexpected("Something unexpected".to_string());
     assert_eq!(error.to_string(), "Unexpected error: Something unexpected");
 
     let error = Error::InvalidApiUrl;
     assert_eq!(error.to_string(), "Invalid SendWithUs API URL");
 
-    let error = Error::ConnectionFailed;
+    let error = Error::ConnectionFailed { context: None };
     assert_eq!(error.to_string(), "Connection to SendWithUs API failed");
 
     let error = Error::FileAccessFailed(std::io::Error::new(
@@ -143,4 +828,87 @@ mod tests {
     let error = Error::FileAccessFailed(io_error);
     assert!(error.to_string().contains("File access error"));
   }
+
+  #[test]
+  fn test_swu_api_error_from_json_body() {
+    let error = SwuApiError::from_body(
+      r#"{"status": "error", "code": 400, "error": "Validation failed", "errors": {"email_id": "is required", "recipient.address": ["is invalid", "is too long"]}}"#,
+    );
+
+    assert_eq!(error.code.as_deref(), Some("400"));
+    assert_eq!(error.message, "Validation failed");
+    assert_eq!(error.field("email_id"), ["is required"]);
+    assert_eq!(
+      error.field("recipient.address"),
+      ["is invalid", "is too long"]
+    );
+    assert!(error.field("sender").is_empty());
+    assert_eq!(error.to_string(), "Validation failed (400)");
+  }
+
+  #[test]
+  fn test_swu_api_error_from_text_body() {
+    let error = SwuApiError::from_body("Internal server error\n");
+
+    assert_eq!(error.code, None);
+    assert_eq!(error.message, "Internal server error");
+    assert_eq!(error.raw, "Internal server error\n");
+    assert!(error.field_errors.is_empty());
+
+    let error = SwuApiError::from_body(r#"{"unexpected": true}"#);
+    assert_eq!(error.message, r#"{"unexpected": true}"#);
+  }
+
+  #[test]
+  fn test_request_context_display() {
+    let context = RequestContext::new(reqwest::Method::POST, "send");
+    assert_eq!(context.to_string(), "POST send");
+    assert_eq!(
+      context.with_correlation_id("abc").to_string(),
+      "POST send (correlation ID abc)"
+    );
+  }
+
+  #[test]
+  fn test_error_classification() {
+    let context = RequestContext::new(reqwest::Method::POST, "send");
+    let api_error = |status| Error::ApiError {
+      context: context.clone(),
+      status,
+      error: Box::new(SwuApiError::from_body("error")),
+    };
+
+    assert!(Error::ConnectionFailed { context: None }.is_retryable());
+    assert!(Error::Timeout { context: None }.is_retryable());
+    assert!(api_error(500).is_retryable());
+    assert!(api_error(503).is_retryable());
+    assert!(api_error(429).is_retryable());
+    assert!(api_error(408).is_retryable());
+    assert!(
+      Error::RateLimited {
+        retry_after: None,
+        context: None,
+      }
+      .is_retryable()
+    );
+    assert!(!api_error(422).is_retryable());
+    assert!(!Error::InvalidCredentials.is_retryable());
+    assert!(!Error::MissingTemplateId.is_retryable());
+
+    assert!(api_error(422).is_client_error());
+    assert!(!api_error(500).is_client_error());
+    assert!(
+      Error::InvalidRequest {
+        context: context.clone(),
+        error: Box::new(SwuApiError::from_body("bad")),
+      }
+      .is_client_error()
+    );
+    assert!(Error::MissingRecipientAddress.is_client_error());
+    assert!(!Error::ConnectionFailed { context: None }.is_client_error());
+
+    assert!(Error::InvalidCredentials.is_auth_error());
+    assert!(Error::InvalidCredentials.is_client_error());
+    assert!(!api_error(500).is_auth_error());
+  }
 }
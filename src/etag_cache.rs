@@ -0,0 +1,118 @@
+use crate::response::RawResponse;
+use reqwest::StatusCode;
+use reqwest::header::ETAG;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Endpoint prefixes whose GET responses are cached for conditional requests.
+const CACHED_ENDPOINTS: [&str; 3] = ["emails", "templates", "drip_campaigns"];
+
+/// Responses cached by URL along with their `ETag`, shared by all clones of an `Api`.
+#[derive(Debug, Default)]
+pub(crate) struct EtagCache {
+  responses: Mutex<HashMap<String, (String, RawResponse)>>,
+}
+
+impl EtagCache {
+  /// Returns whether GETs of the endpoint are cached.
+  pub fn applies_to(method: &reqwest::Method, endpoint: &str) -> bool {
+    method == reqwest::Method::GET
+      && CACHED_ENDPOINTS.iter().any(|prefix| {
+        endpoint
+          .strip_prefix(prefix)
+          .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+      })
+  }
+
+  /// Returns the `ETag` of the cached response for a URL.
+  pub fn etag(&self, url: &str) -> Option<String> {
+    let responses = self.responses.lock().unwrap();
+    responses.get(url).map(|(etag, _)| etag.clone())
+  }
+
+  /// Serves `304 Not Modified` from the cache, and caches successful responses with an `ETag`.
+  ///
+  /// A 304 for a URL that isn't cached is returned unchanged.
+  pub fn resolve(&self, url: &str, raw: RawResponse) -> RawResponse {
+    let mut responses = self.responses.lock().unwrap();
+
+    if raw.status == StatusCode::NOT_MODIFIED {
+      return match responses.get(url) {
+        Some((_, cached)) => RawResponse {
+          duration: raw.duration,
+          ..cached.clone()
+        },
+        None => raw,
+      };
+    }
+
+    let etag = raw.headers.get(ETAG).and_then(|etag| etag.to_str().ok());
+    match etag {
+      Some(etag) if raw.status.is_success() => {
+        responses.insert(url.to_string(), (etag.to_string(), raw.clone()));
+      }
+      _ => {
+        responses.remove(url);
+      }
+    }
+
+    raw
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use reqwest::header::HeaderMap;
+  use std::time::Duration;
+
+  fn response(status: u16, etag: Option<&str>, body: &str) -> RawResponse {
+    let mut headers = HeaderMap::new();
+    if let Some(etag) = etag {
+      headers.insert(ETAG, etag.parse().unwrap());
+    }
+
+    RawResponse {
+      status: StatusCode::from_u16(status).unwrap(),
+      headers,
+      body: body.as_bytes().to_vec(),
+      duration: Duration::from_millis(status.into()),
+    }
+  }
+
+  #[test]
+  fn test_applies_to_template_and_campaign_reads() {
+    let get = reqwest::Method::GET;
+    assert!(EtagCache::applies_to(&get, "emails"));
+    assert!(EtagCache::applies_to(
+      &get,
+      "templates/tem_1/versions/ver_1"
+    ));
+    assert!(EtagCache::applies_to(&get, "drip_campaigns/dc_1"));
+    assert!(!EtagCache::applies_to(&get, "logs"));
+    assert!(!EtagCache::applies_to(&get, "emailsx"));
+    assert!(!EtagCache::applies_to(
+      &reqwest::Method::PUT,
+      "templates/tem_1"
+    ));
+  }
+
+  #[test]
+  fn test_resolve_serves_not_modified_from_cache() {
+    let cache = EtagCache::default();
+    let url = "https://api.sendwithus.com/api/v1/emails";
+
+    assert_eq!(cache.etag(url), None);
+    cache.resolve(url, response(200, Some("\"v1\""), "[]"));
+    assert_eq!(cache.etag(url).as_deref(), Some("\"v1\""));
+
+    let served = cache.resolve(url, response(304, None, ""));
+    assert_eq!(served.status, StatusCode::OK);
+    assert_eq!(served.body, b"[]");
+    assert_eq!(served.duration, Duration::from_millis(304));
+
+    cache.resolve(url, response(200, None, "[1]"));
+    assert_eq!(cache.etag(url), None);
+    assert_eq!(cache.resolve(url, response(304, None, "")).status, 304);
+  }
+}
@@ -0,0 +1,372 @@
+//! Export of email logs and events.
+//!
+//! `CsvExport` and `write_json_lines` write logs as they arrive from
+//! `Api::logs_stream` or `Api::customer_email_log_stream`, so large exports
+//! are written page by page instead of being collected in memory first.
+
+use crate::error::Result;
+use crate::ids::TemplateId;
+use crate::models::EmailLog;
+use futures::{Stream, StreamExt};
+use serde::Serialize;
+use std::borrow::Cow;
+use std::io::Write;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+/// A column of an email log export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogColumn {
+  /// Log ID
+  Id,
+
+  /// When the email was sent (Unix timestamp)
+  Created,
+
+  /// Recipient's email address
+  RecipientAddress,
+
+  /// Recipient's name
+  RecipientName,
+
+  /// Latest delivery status
+  Status,
+
+  /// Message returned by the email service provider
+  Message,
+
+  /// ID of the template that was sent
+  EmailId,
+
+  /// Name of the template that was sent
+  EmailName,
+
+  /// Name of the template version that was sent
+  EmailVersion,
+}
+
+impl LogColumn {
+  /// Every column, in the order used by default.
+  pub const ALL: [LogColumn; 9] = [
+    LogColumn::Id,
+    LogColumn::Created,
+    LogColumn::RecipientAddress,
+    LogColumn::RecipientName,
+    LogColumn::Status,
+    LogColumn::Message,
+    LogColumn::EmailId,
+    LogColumn::EmailName,
+    LogColumn::EmailVersion,
+  ];
+
+  /// Returns the column's header, which matches the API field name.
+  pub fn header(&self) -> &'static str {
+    match self {
+      LogColumn::Id => "id",
+      LogColumn::Created => "created",
+      LogColumn::RecipientAddress => "recipient_address",
+      LogColumn::RecipientName => "recipient_name",
+      LogColumn::Status => "status",
+      LogColumn::Message => "message",
+      LogColumn::EmailId => "email_id",
+      LogColumn::EmailName => "email_name",
+      LogColumn::EmailVersion => "email_version",
+    }
+  }
+
+  /// Returns the column's value for a log; missing values are empty.
+  fn value<'a>(&self, log: &'a EmailLog) -> Cow<'a, str> {
+    let optional = |value: &'a Option<String>| Cow::Borrowed(value.as_deref().unwrap_or_default());
+
+    match self {
+      LogColumn::Id => Cow::Borrowed(log.id.as_str()),
+      LogColumn::Created => Cow::Owned(log.created.to_string()),
+      LogColumn::RecipientAddress => Cow::Borrowed(&log.recipient_address),
+      LogColumn::RecipientName => optional(&log.recipient_name),
+      LogColumn::Status => Cow::Borrowed(&log.status),
+      LogColumn::Message => optional(&log.message),
+      LogColumn::EmailId => Cow::Borrowed(
+        log
+          .email_id
+          .as_ref()
+          .map(TemplateId::as_str)
+          .unwrap_or_default(),
+      ),
+      LogColumn::EmailName => optional(&log.email_name),
+      LogColumn::EmailVersion => optional(&log.email_version),
+    }
+  }
+}
+
+/// Writes email logs as CSV (RFC 4180).
+///
+/// # Examples
+///
+/// ```no_run
+/// use send_with_us::Api;
+/// use send_with_us::export::{CsvExport, LogColumn};
+/// use send_with_us::types::EmailLogQuery;
+///
+/// # async fn run() -> send_with_us::Result<()> {
+/// let api = Api::with_api_key("api-key");
+/// let file = std::fs::File::create("logs.csv")?;
+///
+/// let written = CsvExport::new()
+///   .with_columns(vec![LogColumn::Created, LogColumn::RecipientAddress, LogColumn::Status])
+///   .write(api.logs_stream(EmailLogQuery::new()), file)
+///   .await?;
+///
+/// println!("exported {} logs", written);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvExport {
+  columns: Vec<LogColumn>,
+  header: bool,
+}
+
+impl Default for CsvExport {
+  fn default() -> Self {
+    Self {
+      columns: LogColumn::ALL.to_vec(),
+      header: true,
+    }
+  }
+}
+
+impl CsvExport {
+  /// Creates an export of every column, with a header row.
+  ///
+  /// # Returns
+  /// A new CsvExport
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Sets which columns are exported, in order.
+  ///
+  /// # Arguments
+  /// * `columns` - Columns to export
+  ///
+  /// # Returns
+  /// Self with the columns set for method chaining
+  pub fn with_columns(mut self, columns: Vec<LogColumn>) -> Self {
+    self.columns = columns;
+    self
+  }
+
+  /// Leaves out the header row, e.g. when appending to an existing export.
+  ///
+  /// # Returns
+  /// Self without a header row for method chaining
+  pub fn without_header(mut self) -> Self {
+    self.header = false;
+    self
+  }
+
+  /// Writes logs from a stream as CSV rows.
+  ///
+  /// Each log is written as soon as it is received. If the stream yields an
+  /// error, the rows written so far are flushed and the error is returned.
+  ///
+  /// # Arguments
+  /// * `logs` - Stream of logs, such as `Api::logs_stream`
+  /// * `writer` - Destination for the CSV output
+  ///
+  /// # Returns
+  /// The number of logs written
+  ///
+  /// # Errors
+  /// Returns an error if the stream yields one or the output cannot be written
+  pub async fn write<S, W>(&self, mut logs: S, mut writer: W) -> Result<usize>
+  where
+    S: Stream<Item = Result<EmailLog>> + Unpin,
+    W: Write,
+  {
+    if self.header {
+      let headers = self.columns.iter().map(|column| column.header().into());
+      write_row(&mut writer, headers)?;
+    }
+
+    let mut written = 0;
+    let result = loop {
+      match logs.next().await {
+        Some(Ok(log)) => {
+          write_row(
+            &mut writer,
+            self.columns.iter().map(|column| column.value(&log)),
+          )?;
+          written += 1;
+        }
+        Some(Err(error)) => break Err(error),
+        None => break Ok(written),
+      }
+    };
+
+    writer.flush()?;
+    result
+  }
+}
+
+/// Writes items from a stream as newline-delimited JSON (JSON Lines).
+///
+/// Each item is serialized to a single line and written as soon as it is
+/// received, which suits bulk loaders that ingest one record per line. Works
+/// with any serializable model, such as `EmailLog` or `LogEvent`. If the
+/// stream yields an error, the lines written so far are flushed and the error
+/// is returned.
+///
+/// # Arguments
+/// * `items` - Stream of items, such as `Api::logs_stream`
+/// * `writer` - Destination for the output
+///
+/// # Returns
+/// The number of items written
+///
+/// # Errors
+/// Returns an error if the stream yields one, an item cannot be serialized,
+/// or the output cannot be written
+///
+/// # Examples
+///
+/// ```no_run
+/// use send_with_us::Api;
+/// use send_with_us::export::write_json_lines;
+/// use send_with_us::types::EmailLogQuery;
+///
+/// # async fn run() -> send_with_us::Result<()> {
+/// let api = Api::with_api_key("api-key");
+/// let file = tokio::fs::File::create("logs.jsonl").await?;
+///
+/// let written = write_json_lines(api.logs_stream(EmailLogQuery::new()), file).await?;
+/// println!("exported {} logs", written);
+/// # Ok(())
+/// # }
+/// ```
+pub async fn write_json_lines<T, S, W>(mut items: S, mut writer: W) -> Result<usize>
+where
+  T: Serialize,
+  S: Stream<Item = Result<T>> + Unpin,
+  W: AsyncWrite + Unpin,
+{
+  let mut written = 0;
+  let result = loop {
+    match items.next().await {
+      Some(Ok(item)) => {
+        let mut line = serde_json::to_vec(&item)?;
+        line.push(b'\n');
+        writer.write_all(&line).await?;
+        written += 1;
+      }
+      Some(Err(error)) => break Err(error),
+      None => break Ok(written),
+    }
+  };
+
+  writer.flush().await?;
+  result
+}
+
+/// Writes one CSV row, quoting fields where needed.
+fn write_row<'a>(
+  writer: &mut impl Write,
+  fields: impl Iterator<Item = Cow<'a, str>>,
+) -> std::io::Result<()> {
+  let row: Vec<_> = fields.map(|field| escape(&field).into_owned()).collect();
+  write!(writer, "{}\r\n", row.join(","))
+}
+
+/// Quotes a CSV field if it contains a delimiter, quote, or line break.
+fn escape(field: &str) -> Cow<'_, str> {
+  if field.contains([',', '"', '\r', '\n']) {
+    Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+  } else {
+    Cow::Borrowed(field)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::error::Error;
+  use crate::models::LogEvent;
+  use crate::testing::fixtures::EmailLogFixture;
+  use futures::stream;
+  use serde_json::json;
+
+  fn log(id: &str, message: Option<&str>) -> EmailLog {
+    let mut log = EmailLogFixture::new()
+      .with_id(id)
+      .with_created(1700000000)
+      .build();
+    log.message = message.map(str::to_string);
+    log
+  }
+
+  #[tokio::test]
+  async fn test_write_selected_columns() {
+    let logs = stream::iter(vec![
+      Ok(log("log_1", None)),
+      Ok(log("log_2", Some("Queued, \"soft\" bounce"))),
+    ]);
+    let mut output = Vec::new();
+
+    let written = CsvExport::new()
+      .with_columns(vec![
+        LogColumn::Id,
+        LogColumn::Message,
+        LogColumn::EmailName,
+      ])
+      .write(logs, &mut output)
+      .await
+      .unwrap();
+
+    assert_eq!(written, 2);
+    assert_eq!(
+      String::from_utf8(output).unwrap(),
+      "id,message,email_name\r\n\
+       log_1,,Welcome\r\n\
+       log_2,\"Queued, \"\"soft\"\" bounce\",Welcome\r\n"
+    );
+  }
+
+  #[tokio::test]
+  async fn test_write_stops_on_error() {
+    let logs = stream::iter(vec![
+      Ok(log("log_1", None)),
+      Err(Error::Timeout { context: None }),
+    ]);
+    let mut output = Vec::new();
+
+    let result = CsvExport::new()
+      .without_header()
+      .with_columns(vec![LogColumn::Id, LogColumn::Created])
+      .write(logs, &mut output)
+      .await;
+
+    assert!(matches!(result, Err(Error::Timeout { .. })));
+    assert_eq!(String::from_utf8(output).unwrap(), "log_1,1700000000\r\n");
+  }
+
+  #[tokio::test]
+  async fn test_write_json_lines() {
+    let events: Vec<Result<LogEvent>> = vec![
+      Ok(serde_json::from_value(json!({"type": "open", "created": 1700000100})).unwrap()),
+      Ok(serde_json::from_value(json!({"type": "click", "created": 1700000200})).unwrap()),
+    ];
+    let mut output = Vec::new();
+
+    let written = write_json_lines(stream::iter(events), &mut output)
+      .await
+      .unwrap();
+
+    assert_eq!(written, 2);
+    let lines: Vec<LogEvent> = String::from_utf8(output)
+      .unwrap()
+      .lines()
+      .map(|line| serde_json::from_str(line).unwrap())
+      .collect();
+    assert_eq!(lines[0].created, 1700000100);
+    assert_eq!(lines[1].created, 1700000200);
+  }
+}
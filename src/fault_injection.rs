@@ -0,0 +1,319 @@
+//! Injection of network faults for testing resilience.
+//!
+//! With a `FaultInjector` configured via `Config::with_fault_injector`, API
+//! calls are randomly delayed or answered with connection failures, 429s, or
+//! 500s instead of reaching SendWithUs. Faults are injected below retries,
+//! rate limiting, failover, and the circuit breaker, so those behave exactly
+//! as they would against a flaky network. Seed the injector with
+//! `with_seed` to make a run reproducible.
+
+use crate::error::{Error, Result};
+use crate::response::RawResponse;
+use reqwest::StatusCode;
+use reqwest::header::{HeaderMap, HeaderValue, RETRY_AFTER};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Counts of the faults injected so far.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FaultStats {
+  /// Calls seen by the injector
+  pub calls: u64,
+
+  /// Calls delayed by injected latency
+  pub delayed: u64,
+
+  /// Calls failed with `Error::ConnectionFailed`
+  pub connection_failures: u64,
+
+  /// Calls answered with HTTP 429
+  pub rate_limits: u64,
+
+  /// Calls answered with HTTP 500
+  pub server_errors: u64,
+}
+
+/// Injects latency and failures into API calls at configured probabilities.
+///
+/// Each call is first delayed with the latency probability, then fails with
+/// at most one fault: a connection failure, a 429, or a 500, checked in that
+/// order. Probabilities are clamped to between 0 and 1. Calls that aren't
+/// failed are sent as usual.
+///
+/// # Examples
+///
+/// ```no_run
+/// use send_with_us::fault_injection::FaultInjector;
+/// use send_with_us::retry::RetryPolicy;
+/// use send_with_us::{Api, ApiClient, Config};
+/// use std::time::Duration;
+///
+/// # async fn run() -> send_with_us::Result<()> {
+/// let config = Config::new("api-key")
+///   .with_retry_policy(RetryPolicy::new(5))
+///   .with_fault_injector(
+///     FaultInjector::new()
+///       .with_seed(42)
+///       .with_latency(Duration::from_millis(200), 0.5)
+///       .with_server_errors(0.3),
+///   );
+/// let api = Api::new(config);
+///
+/// api.list_templates().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct FaultInjector {
+  latency: Duration,
+  latency_probability: f64,
+  connection_failure_probability: f64,
+  rate_limit_probability: f64,
+  retry_after: Duration,
+  server_error_probability: f64,
+  rng: Mutex<u64>,
+  stats: Mutex<FaultStats>,
+}
+
+impl Default for FaultInjector {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl FaultInjector {
+  /// Creates an injector that injects nothing until configured, with a random seed.
+  pub fn new() -> Self {
+    Self {
+      latency: Duration::ZERO,
+      latency_probability: 0.0,
+      connection_failure_probability: 0.0,
+      rate_limit_probability: 0.0,
+      retry_after: Duration::ZERO,
+      server_error_probability: 0.0,
+      rng: Mutex::new(uuid::Uuid::new_v4().as_u64_pair().0),
+      stats: Mutex::new(FaultStats::default()),
+    }
+  }
+
+  /// Seeds the random choices, so the same calls get the same faults on every run.
+  ///
+  /// # Arguments
+  /// * `seed` - Seed for the random number generator
+  ///
+  /// # Returns
+  /// Self with the seed set for method chaining
+  pub fn with_seed(self, seed: u64) -> Self {
+    *self.rng.lock().unwrap_or_else(|e| e.into_inner()) = seed;
+    self
+  }
+
+  /// Delays calls before they are sent or failed.
+  ///
+  /// # Arguments
+  /// * `latency` - Delay added to affected calls
+  /// * `probability` - Chance of a call being delayed
+  ///
+  /// # Returns
+  /// Self with latency injection set for method chaining
+  pub fn with_latency(mut self, latency: Duration, probability: f64) -> Self {
+    self.latency = latency;
+    self.latency_probability = probability.clamp(0.0, 1.0);
+    self
+  }
+
+  /// Fails calls with `Error::ConnectionFailed`, as if SendWithUs were unreachable.
+  ///
+  /// # Arguments
+  /// * `probability` - Chance of a call failing to connect
+  ///
+  /// # Returns
+  /// Self with connection failures set for method chaining
+  pub fn with_connection_failures(mut self, probability: f64) -> Self {
+    self.connection_failure_probability = probability.clamp(0.0, 1.0);
+    self
+  }
+
+  /// Answers calls with HTTP 429 Too Many Requests.
+  ///
+  /// # Arguments
+  /// * `probability` - Chance of a call being rate limited
+  /// * `retry_after` - Delay sent in the `Retry-After` header, in whole seconds
+  ///
+  /// # Returns
+  /// Self with rate limiting set for method chaining
+  pub fn with_rate_limits(mut self, probability: f64, retry_after: Duration) -> Self {
+    self.rate_limit_probability = probability.clamp(0.0, 1.0);
+    self.retry_after = retry_after;
+    self
+  }
+
+  /// Answers calls with HTTP 500 Internal Server Error.
+  ///
+  /// # Arguments
+  /// * `probability` - Chance of a call failing with a server error
+  ///
+  /// # Returns
+  /// Self with server errors set for method chaining
+  pub fn with_server_errors(mut self, probability: f64) -> Self {
+    self.server_error_probability = probability.clamp(0.0, 1.0);
+    self
+  }
+
+  /// Returns how many faults have been injected so far.
+  pub fn stats(&self) -> FaultStats {
+    *self.stats.lock().unwrap_or_else(|e| e.into_inner())
+  }
+
+  /// Delays the call and picks a fault for it.
+  ///
+  /// # Returns
+  /// The injected outcome, or `None` if the call should be sent as usual
+  pub(crate) async fn inject(&self) -> Option<Result<RawResponse>> {
+    let delay = self.roll() < self.latency_probability;
+    let roll = self.roll();
+
+    let rate_limit_threshold = self.connection_failure_probability + self.rate_limit_probability;
+    let fault = if roll < self.connection_failure_probability {
+      Some(Fault::ConnectionFailure)
+    } else if roll < rate_limit_threshold {
+      Some(Fault::RateLimit)
+    } else if roll < rate_limit_threshold + self.server_error_probability {
+      Some(Fault::ServerError)
+    } else {
+      None
+    };
+
+    {
+      let mut stats = self.stats.lock().unwrap_or_else(|e| e.into_inner());
+      stats.calls += 1;
+      stats.delayed += u64::from(delay);
+      match fault {
+        Some(Fault::ConnectionFailure) => stats.connection_failures += 1,
+        Some(Fault::RateLimit) => stats.rate_limits += 1,
+        Some(Fault::ServerError) => stats.server_errors += 1,
+        None => {}
+      }
+    }
+
+    if delay {
+      tokio::time::sleep(self.latency).await;
+    }
+
+    Some(match fault? {
+      Fault::ConnectionFailure => Err(Error::ConnectionFailed { context: None }),
+      Fault::RateLimit => {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from(self.retry_after.as_secs()));
+        Ok(injected_response(StatusCode::TOO_MANY_REQUESTS, headers))
+      }
+      Fault::ServerError => Ok(injected_response(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        HeaderMap::new(),
+      )),
+    })
+  }
+
+  /// Returns a random number between 0 (inclusive) and 1 (exclusive), using SplitMix64.
+  fn roll(&self) -> f64 {
+    let mut state = self.rng.lock().unwrap_or_else(|e| e.into_inner());
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^= z >> 31;
+
+    (z >> 11) as f64 / (1u64 << 53) as f64
+  }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Fault {
+  ConnectionFailure,
+  RateLimit,
+  ServerError,
+}
+
+fn injected_response(status: StatusCode, headers: HeaderMap) -> RawResponse {
+  let body = format!(
+    r#"{{"success": false, "status": "error", "error": "Injected {} response"}}"#,
+    status.as_u16()
+  );
+
+  RawResponse {
+    status,
+    headers,
+    body: body.into_bytes(),
+    duration: Duration::ZERO,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::retry::RetryPolicy;
+  use crate::{Api, ApiClient, Config};
+  use std::sync::Arc;
+
+  fn api(url: &str, faults: FaultInjector) -> (Api, Arc<FaultInjector>) {
+    let mut config = Config::new("test-api-key")
+      .with_retry_policy(RetryPolicy::new(3).with_initial_backoff(Duration::from_millis(1)))
+      .with_fault_injector(faults);
+    config.url = url::Url::parse(url).unwrap();
+    let faults = config.fault_injector.clone().unwrap();
+    (Api::new(config), faults)
+  }
+
+  async fn run(url: &str, seed: u64) -> FaultStats {
+    let faults = FaultInjector::new()
+      .with_seed(seed)
+      .with_connection_failures(0.2)
+      .with_rate_limits(0.2, Duration::ZERO);
+    let (api, faults) = api(url, faults);
+    for _ in 0..20 {
+      let _ = api.list_templates().await;
+    }
+    faults.stats()
+  }
+
+  #[tokio::test]
+  async fn test_injected_server_errors_are_retried() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let mock = mock_server.mock("GET", "/api/v1/emails").expect(0).create();
+
+    let (api, faults) = api(
+      &mock_server.url(),
+      FaultInjector::new().with_server_errors(1.0),
+    );
+
+    let result = api.list_templates().await;
+    assert!(matches!(result, Err(Error::ApiError { status: 500, .. })));
+    assert_eq!(
+      faults.stats(),
+      FaultStats {
+        calls: 3,
+        server_errors: 3,
+        ..FaultStats::default()
+      }
+    );
+    mock.assert();
+  }
+
+  #[tokio::test]
+  async fn test_faults_follow_probabilities_and_seed() {
+    let mut mock_server = mockito::Server::new_async().await;
+    mock_server
+      .mock("GET", "/api/v1/emails")
+      .with_status(200)
+      .with_body("[]")
+      .create();
+
+    let stats = run(&mock_server.url(), 7).await;
+    assert_eq!(stats, run(&mock_server.url(), 7).await);
+    assert!(stats.calls >= 20);
+    assert!(stats.connection_failures > 0);
+    assert!(stats.rate_limits > 0);
+    assert_eq!(stats.server_errors, 0);
+  }
+}
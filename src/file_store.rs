@@ -0,0 +1,158 @@
+//! Directory-backed storage shared by the outbox and dead-letter sink.
+//!
+//! Records are kept as one JSON file per record, written through a temporary
+//! file and synced so a crash never leaves a partial or lost record. Files
+//! that can't be parsed are moved aside with a `.corrupt` extension instead
+//! of failing every read.
+
+use crate::error::Result;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::io::AsyncWriteExt;
+
+/// Returns the current time and an ID unique within and across processes.
+pub(crate) fn unique_id() -> (String, Duration) {
+  static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+  let now = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .unwrap_or_default();
+  let id = format!(
+    "{:x}-{:x}-{:x}",
+    now.as_nanos(),
+    std::process::id(),
+    COUNTER.fetch_add(1, Ordering::Relaxed)
+  );
+
+  (id, now)
+}
+
+/// A directory holding one JSON file per record, keyed by ID.
+#[derive(Debug, Clone)]
+pub(crate) struct JsonFileStore {
+  dir: PathBuf,
+}
+
+impl JsonFileStore {
+  pub(crate) fn new(dir: PathBuf) -> Self {
+    Self { dir }
+  }
+
+  fn path(&self, id: &str) -> PathBuf {
+    self.dir.join(format!("{}.json", id))
+  }
+
+  /// Durably writes a record, creating the directory if needed.
+  pub(crate) async fn write<T: Serialize + ?Sized>(&self, id: &str, record: &T) -> Result<()> {
+    tokio::fs::create_dir_all(&self.dir).await?;
+
+    // Write to a temporary file first so a crash never leaves a partial
+    // record, and sync both the file and the rename before reporting success.
+    let path = self.path(id);
+    let tmp_path = path.with_extension("json.tmp");
+    let mut file = tokio::fs::File::create(&tmp_path).await?;
+    file.write_all(&serde_json::to_vec(record)?).await?;
+    file.sync_all().await?;
+    drop(file);
+    tokio::fs::rename(&tmp_path, &path).await?;
+    sync_dir(&self.dir).await
+  }
+
+  /// Removes a record; removing a missing record succeeds.
+  pub(crate) async fn remove(&self, id: &str) -> Result<()> {
+    match tokio::fs::remove_file(self.path(id)).await {
+      Ok(()) => Ok(()),
+      Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+      Err(e) => Err(e.into()),
+    }
+  }
+
+  /// Reads every record, quarantining files that can't be parsed.
+  pub(crate) async fn read_all<T: DeserializeOwned>(&self) -> Result<Vec<T>> {
+    let mut records = Vec::new();
+    for path in self.files("json").await? {
+      let contents = tokio::fs::read(&path).await?;
+      match serde_json::from_slice(&contents) {
+        Ok(record) => records.push(record),
+        Err(_) => tokio::fs::rename(&path, path.with_extension("json.corrupt")).await?,
+      }
+    }
+
+    Ok(records)
+  }
+
+  /// Returns the quarantined files, sorted by name.
+  pub(crate) async fn quarantined(&self) -> Result<Vec<PathBuf>> {
+    self.files("corrupt").await
+  }
+
+  /// Returns the files with the given extension, sorted by name; a missing
+  /// directory has none.
+  async fn files(&self, extension: &str) -> Result<Vec<PathBuf>> {
+    let mut dir = match tokio::fs::read_dir(&self.dir).await {
+      Ok(dir) => dir,
+      Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+      Err(e) => return Err(e.into()),
+    };
+
+    let mut files = Vec::new();
+    while let Some(file) = dir.next_entry().await? {
+      let path = file.path();
+      if path.extension().is_some_and(|ext| ext == extension) {
+        files.push(path);
+      }
+    }
+
+    files.sort();
+    Ok(files)
+  }
+}
+
+/// Flushes a directory's entries to disk, so a rename into it survives a crash.
+#[cfg(unix)]
+async fn sync_dir(dir: &Path) -> Result<()> {
+  tokio::fs::File::open(dir).await?.sync_all().await?;
+  Ok(())
+}
+
+#[cfg(not(unix))]
+async fn sync_dir(_dir: &Path) -> Result<()> {
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use tempdir::TempDir;
+
+  #[tokio::test]
+  async fn test_round_trip_and_quarantine() {
+    let dir = TempDir::new("file_store").unwrap();
+    let store = JsonFileStore::new(dir.path().join("nested"));
+    assert!(store.read_all::<u32>().await.unwrap().is_empty());
+    assert!(store.quarantined().await.unwrap().is_empty());
+
+    let (first, _) = unique_id();
+    let (second, _) = unique_id();
+    assert_ne!(first, second);
+
+    store.write(&first, &1u32).await.unwrap();
+    store.write(&second, &2u32).await.unwrap();
+    std::fs::write(dir.path().join("nested/broken.json"), "{not json").unwrap();
+
+    let mut records = store.read_all::<u32>().await.unwrap();
+    records.sort();
+    assert_eq!(records, vec![1, 2]);
+    assert_eq!(
+      store.quarantined().await.unwrap(),
+      vec![dir.path().join("nested/broken.json.corrupt")]
+    );
+
+    store.remove(&first).await.unwrap();
+    store.remove(&first).await.unwrap();
+    assert_eq!(store.read_all::<u32>().await.unwrap(), vec![2]);
+  }
+}
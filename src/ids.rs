@@ -0,0 +1,216 @@
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Defines a string-backed identifier type whose values must start with a
+/// known SendWithUs prefix.
+macro_rules! prefixed_id {
+  ($(#[$meta:meta])* $name:ident, $prefix:literal, $kind:literal) => {
+    $(#[$meta])*
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+    #[serde(try_from = "String", into = "String")]
+    pub struct $name(String);
+
+    impl $name {
+      /// The prefix every identifier of this kind starts with.
+      pub const PREFIX: &'static str = $prefix;
+
+      /// Creates a new identifier after checking its prefix.
+      ///
+      /// # Arguments
+      /// * `value` - The raw identifier string
+      ///
+      /// # Returns
+      /// The validated identifier
+      ///
+      /// # Errors
+      /// Returns `Error::InvalidId` if the value is missing the expected prefix
+      /// or has nothing after it
+      pub fn new(value: impl Into<String>) -> Result<Self> {
+        let value = value.into();
+
+        if value.len() > Self::PREFIX.len() && value.starts_with(Self::PREFIX) {
+          Ok(Self(value))
+        } else {
+          Err(Error::InvalidId {
+            kind: $kind,
+            value,
+          })
+        }
+      }
+
+      /// Returns the identifier as a string slice.
+      pub fn as_str(&self) -> &str {
+        &self.0
+      }
+
+      /// Consumes the identifier and returns the underlying string.
+      pub fn into_inner(self) -> String {
+        self.0
+      }
+    }
+
+    impl fmt::Display for $name {
+      fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+      }
+    }
+
+    impl AsRef<str> for $name {
+      fn as_ref(&self) -> &str {
+        &self.0
+      }
+    }
+
+    impl FromStr for $name {
+      type Err = Error;
+
+      fn from_str(s: &str) -> Result<Self> {
+        Self::new(s)
+      }
+    }
+
+    impl TryFrom<String> for $name {
+      type Error = Error;
+
+      fn try_from(value: String) -> Result<Self> {
+        Self::new(value)
+      }
+    }
+
+    impl TryFrom<&str> for $name {
+      type Error = Error;
+
+      fn try_from(value: &str) -> Result<Self> {
+        Self::new(value)
+      }
+    }
+
+    impl From<$name> for String {
+      fn from(id: $name) -> Self {
+        id.0
+      }
+    }
+  };
+}
+
+prefixed_id!(
+  /// Identifier of an email template (`tem_...`).
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::ids::TemplateId;
+  ///
+  /// let id: TemplateId = "tem_abc123".parse().unwrap();
+  /// assert_eq!(id.as_str(), "tem_abc123");
+  ///
+  /// assert!("dc_abc123".parse::<TemplateId>().is_err());
+  /// ```
+  TemplateId,
+  "tem_",
+  "template"
+);
+
+prefixed_id!(
+  /// Identifier of a drip campaign (`dc_...`).
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::ids::CampaignId;
+  ///
+  /// let id = CampaignId::new("dc_abc123").unwrap();
+  /// assert_eq!(id.to_string(), "dc_abc123");
+  /// ```
+  CampaignId,
+  "dc_",
+  "campaign"
+);
+
+prefixed_id!(
+  /// Identifier of an email log entry (`log_...`).
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::ids::LogId;
+  ///
+  /// let id = LogId::new("log_abc123").unwrap();
+  /// assert_eq!(id.as_ref(), "log_abc123");
+  /// ```
+  LogId,
+  "log_",
+  "log"
+);
+
+prefixed_id!(
+  /// Receipt identifier returned when an email is sent (`log_...`).
+  ///
+  /// A receipt ID refers to the log entry created for the send, so it can be
+  /// converted into a `LogId` to look up the log and its events.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::ids::{LogId, ReceiptId};
+  ///
+  /// let receipt = ReceiptId::new("log_abc123").unwrap();
+  /// let log_id = LogId::from(receipt);
+  /// assert_eq!(log_id.as_str(), "log_abc123");
+  /// ```
+  ReceiptId,
+  "log_",
+  "receipt"
+);
+
+impl From<ReceiptId> for LogId {
+  fn from(receipt: ReceiptId) -> Self {
+    Self(receipt.0)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_valid_ids() {
+    assert_eq!(TemplateId::new("tem_123").unwrap().as_str(), "tem_123");
+    assert_eq!(CampaignId::new("dc_123").unwrap().as_str(), "dc_123");
+    assert_eq!(LogId::new("log_123").unwrap().as_str(), "log_123");
+    assert_eq!(ReceiptId::new("log_123").unwrap().as_str(), "log_123");
+  }
+
+  #[test]
+  fn test_invalid_ids() {
+    let err = TemplateId::new("dc_123").unwrap_err();
+    assert_eq!(err.to_string(), "Invalid template ID: dc_123");
+
+    assert!(CampaignId::new("tem_123").is_err());
+    assert!(LogId::new("").is_err());
+    assert!(LogId::new("log_").is_err());
+    assert!("receipt".parse::<ReceiptId>().is_err());
+  }
+
+  #[test]
+  fn test_id_serde_round_trip() {
+    let id = TemplateId::new("tem_123").unwrap();
+    let json = serde_json::to_string(&id).unwrap();
+    assert_eq!(json, r#""tem_123""#);
+
+    let parsed: TemplateId = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, id);
+
+    let result: std::result::Result<TemplateId, _> = serde_json::from_str(r#""log_123""#);
+    assert!(result.is_err());
+  }
+
+  #[test]
+  fn test_receipt_into_log_id() {
+    let receipt = ReceiptId::new("log_abc").unwrap();
+    let log_id: LogId = receipt.into();
+    assert_eq!(log_id.to_string(), "log_abc");
+  }
+}
@@ -1,14 +1,58 @@
 //!
 #![doc = include_str!("../README.md")]
 
+pub mod analytics;
 pub mod api;
 pub mod attachment;
+pub mod capture;
+#[cfg(feature = "mock")]
+pub mod cassette;
+pub mod circuit_breaker;
 pub mod config;
+pub mod dead_letter;
+pub mod encoding;
+mod endpoint;
 pub mod error;
+mod etag_cache;
+pub mod export;
+#[cfg(feature = "mock")]
+pub mod fault_injection;
+mod file_store;
+pub mod ids;
+pub mod lint;
+pub mod logging;
+pub mod message;
+#[cfg(feature = "sqlite")]
+pub mod mirror;
+#[cfg(feature = "mock")]
+pub mod mock;
+pub mod models;
+pub mod outbox;
+#[cfg(feature = "preview")]
+pub mod preview;
+mod redaction;
+pub mod request;
+pub mod response;
+pub mod retry;
+#[cfg(feature = "lettre")]
+pub mod smtp;
+#[cfg(feature = "otel")]
+mod telemetry;
+pub mod template_export;
+mod template_registry;
+pub mod template_sync;
+#[cfg(any(test, feature = "mock"))]
+pub mod testing;
 pub mod types;
+#[cfg(feature = "validator")]
+mod validation;
+pub mod version_diff;
+pub mod webhooks;
 
 pub use api::Api;
 pub use api::ApiClient;
 pub use attachment::Attachment;
-pub use config::Config;
+pub use config::{ApiVersion, Config, ConfigBuilder};
 pub use error::{Error, Result};
+pub use ids::{CampaignId, LogId, ReceiptId, TemplateId};
+pub use response::{RawResponse, ResponseEnvelope};
@@ -0,0 +1,550 @@
+//! Analysis of the variables templates use.
+//!
+//! SendWithUs templates use Jinja-style syntax: `{{ user.name }}` inserts a
+//! value from `email_data`, and tags such as `{% if coupon %}` or
+//! `{% for item in items %}` read them too. `lint_template` extracts the
+//! variables a template version uses and compares them with the data an email
+//! would be sent with, so typos show up before a recipient sees a blank field.
+//! `variable_schema` describes the shape of the data a template expects.
+
+use crate::models::TemplateVersion;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+/// Words in template expressions that aren't variables.
+const KEYWORDS: [&str; 13] = [
+  "and", "or", "not", "in", "is", "if", "else", "true", "false", "none", "True", "False", "None",
+];
+
+/// Differences between the variables a template uses and the data provided.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LintReport {
+  /// Variables used by the template but missing from the data, as dotted paths
+  pub missing: Vec<String>,
+
+  /// Top-level data keys the template never uses
+  pub unused: Vec<String>,
+}
+
+impl LintReport {
+  /// Returns `true` if every variable is provided and every key is used.
+  pub fn is_clean(&self) -> bool {
+    self.missing.is_empty() && self.unused.is_empty()
+  }
+}
+
+/// Compares the variables used by a template version with email data.
+///
+/// The subject, preheader, HTML, plain text, and AMP content are all
+/// checked. A nested variable such as `user.name` is only reported missing
+/// when the data has `user` as an object without a `name` key, or has no
+/// `user` at all. Variables assigned within the template, such as loop
+/// variables, aren't expected in the data.
+///
+/// # Arguments
+/// * `version` - The template version, e.g. from `Api::get_template_version_as`
+/// * `data` - The email data the template would be rendered with
+///
+/// # Returns
+/// The missing variables and unused keys, each sorted
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::lint::lint_template;
+/// use send_with_us::models::TemplateVersion;
+/// use serde_json::json;
+/// use std::collections::HashMap;
+///
+/// let version: TemplateVersion = serde_json::from_value(json!({
+///   "id": "ver_1",
+///   "name": "Original",
+///   "subject": "Welcome, {{ first_name }}!",
+///   "html": "<p>Your code is {{ coupon.code }}</p>"
+/// })).unwrap();
+///
+/// let data = HashMap::from([
+///   ("frist_name".to_string(), json!("Ada")),
+///   ("coupon".to_string(), json!({"code": "WELCOME10"})),
+/// ]);
+///
+/// let report = lint_template(&version, &data);
+/// assert_eq!(report.missing, vec!["first_name"]);
+/// assert_eq!(report.unused, vec!["frist_name"]);
+/// ```
+pub fn lint_template(version: &TemplateVersion, data: &HashMap<String, Value>) -> LintReport {
+  let variables: BTreeSet<String> = sources(version).flat_map(template_variables).collect();
+
+  let missing = variables
+    .iter()
+    .filter(|path| !is_provided(path, data))
+    .cloned()
+    .collect();
+
+  let roots: HashSet<&str> = variables.iter().map(|path| root(path)).collect();
+  let mut unused: Vec<String> = data
+    .keys()
+    .filter(|key| !roots.contains(key.as_str()))
+    .cloned()
+    .collect();
+  unused.sort();
+
+  LintReport { missing, unused }
+}
+
+/// Extracts the variables a template reads from its data.
+///
+/// Variables are returned as dotted paths, such as `user.name`. Filter and
+/// function names, string literals, and variables assigned within the
+/// template (by `for` and `set`) are left out.
+///
+/// # Arguments
+/// * `source` - Template content
+///
+/// # Returns
+/// The variables used, sorted and without duplicates
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::lint::template_variables;
+///
+/// let variables = template_variables(
+///   "{% for item in order.items %}{{ item.name|upper }}{% endfor %} {{ total }}",
+/// );
+///
+/// assert_eq!(variables.into_iter().collect::<Vec<_>>(), vec!["order.items", "total"]);
+/// ```
+pub fn template_variables(source: &str) -> BTreeSet<String> {
+  scan(source)
+    .into_iter()
+    .map(|path| match path.find(ITEMS_SEGMENT) {
+      Some(end) => path[..end].to_string(),
+      None => path,
+    })
+    .collect()
+}
+
+/// Infers the shape of the data a template version reads.
+///
+/// Every variable read in the subject, preheader, HTML, plain text, or AMP
+/// content is included. Variables whose fields are read become objects, and
+/// variables iterated with `for` become lists, with the schema of their items
+/// inferred from how the loop variable is used. This is enough to generate a
+/// form for filling in a template's data.
+///
+/// # Arguments
+/// * `version` - The template version, e.g. from `Api::get_template_version_as`
+///
+/// # Returns
+/// The schema of each top-level variable, by name
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::lint::{VariableSchema, variable_schema};
+/// use send_with_us::models::TemplateVersion;
+/// use serde_json::json;
+/// use std::collections::BTreeMap;
+///
+/// let version: TemplateVersion = serde_json::from_value(json!({
+///   "id": "ver_1",
+///   "name": "Original",
+///   "subject": "Your order, {{ name }}",
+///   "html": "{% for item in order.items %}{{ item.title }}{% endfor %}"
+/// })).unwrap();
+///
+/// let schema = variable_schema(&version);
+/// assert_eq!(schema["name"], VariableSchema::Value);
+/// assert_eq!(
+///   schema["order"],
+///   VariableSchema::Object {
+///     fields: BTreeMap::from([(
+///       "items".to_string(),
+///       VariableSchema::List {
+///         items: Box::new(VariableSchema::Object {
+///           fields: BTreeMap::from([("title".to_string(), VariableSchema::Value)]),
+///         }),
+///       },
+///     )]),
+///   }
+/// );
+/// ```
+pub fn variable_schema(version: &TemplateVersion) -> BTreeMap<String, VariableSchema> {
+  let mut schema = BTreeMap::new();
+
+  for path in sources(version).flat_map(scan) {
+    let segments: Vec<&str> = path.split('.').collect();
+    if let Some((root, rest)) = segments.split_first() {
+      schema
+        .entry(root.to_string())
+        .or_insert(VariableSchema::Value)
+        .insert(rest);
+    }
+  }
+
+  schema
+}
+
+/// The shape of a variable a template reads, inferred from how it's used.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum VariableSchema {
+  /// A value inserted or tested as is, such as text or a number
+  Value,
+
+  /// An object whose fields are read
+  Object {
+    /// Schema of each field read, by name
+    fields: BTreeMap<String, VariableSchema>,
+  },
+
+  /// A list iterated with `for`
+  List {
+    /// Schema of the list's items
+    items: Box<VariableSchema>,
+  },
+}
+
+impl VariableSchema {
+  /// Adds a path below this variable, turning values into objects or lists as needed.
+  fn insert(&mut self, segments: &[&str]) {
+    let Some((first, rest)) = segments.split_first() else {
+      return;
+    };
+
+    if *first == ITEMS {
+      if *self == VariableSchema::Value {
+        *self = VariableSchema::List {
+          items: Box::new(VariableSchema::Value),
+        };
+      }
+      if let VariableSchema::List { items } = self {
+        items.insert(rest);
+      }
+    } else {
+      if *self == VariableSchema::Value {
+        *self = VariableSchema::Object {
+          fields: BTreeMap::new(),
+        };
+      }
+      if let VariableSchema::Object { fields } = self {
+        fields
+          .entry(first.to_string())
+          .or_insert(VariableSchema::Value)
+          .insert(rest);
+      }
+    }
+  }
+}
+
+/// Path segment standing for the items of a list, e.g. `order.items.[].title`.
+const ITEMS: &str = "[]";
+const ITEMS_SEGMENT: &str = ".[]";
+
+/// Returns the content of a template version that can use variables.
+fn sources(version: &TemplateVersion) -> impl Iterator<Item = &str> {
+  [
+    &version.subject,
+    &version.preheader,
+    &version.html,
+    &version.text,
+    &version.amp_html,
+  ]
+  .into_iter()
+  .flatten()
+  .map(String::as_str)
+}
+
+/// Extracts the variables a template reads, in order.
+///
+/// Paths through loop variables are resolved to the list being iterated,
+/// e.g. `item.title` within `{% for item in order.items %}` becomes
+/// `order.items.[].title`.
+fn scan(source: &str) -> Vec<String> {
+  let mut scope = Scope::default();
+  let mut rest = source;
+
+  while let Some(start) = rest.find('{') {
+    let after = &rest[start + 1..];
+    let close = match after.chars().next() {
+      Some('{') => "}}",
+      Some('%') => "%}",
+      Some('#') => "#}",
+      _ => {
+        rest = after;
+        continue;
+      }
+    };
+
+    let inner = &after[1..];
+    let Some(end) = inner.find(close) else {
+      break;
+    };
+    let body = inner[..end].trim_matches(['-', '+']).trim();
+
+    match close {
+      "}}" => scope.read(body),
+      "%}" => scope.tag(body),
+      _ => {}
+    }
+
+    rest = &inner[end + close.len()..];
+  }
+
+  scope.used
+}
+
+/// Variables read so far, and the names assigned within the template.
+#[derive(Debug, Default)]
+struct Scope {
+  used: Vec<String>,
+
+  /// Loop variables of the enclosing `for` blocks, with the list items they stand for
+  loops: Vec<(Vec<String>, Option<String>)>,
+
+  /// Names assigned with `set`
+  assigned: HashSet<String>,
+}
+
+impl Scope {
+  /// Records the variables read by a `{% ... %}` tag, and the names it assigns.
+  fn tag(&mut self, body: &str) {
+    let (tag, rest) = body.split_once(char::is_whitespace).unwrap_or((body, ""));
+
+    match tag {
+      "if" | "elif" => self.read(rest),
+      "for" => {
+        if let Some((targets, iterable)) = rest.split_once(" in ") {
+          let first = self.used.len();
+          self.read(iterable);
+
+          let items = self
+            .used
+            .get(first)
+            .map(|list| format!("{}.{}", list, ITEMS));
+          let names = targets.split(',').map(|name| name.trim().to_string());
+          self.loops.push((names.collect(), items));
+        }
+      }
+      "endfor" => {
+        self.loops.pop();
+      }
+      "set" => {
+        if let Some((target, value)) = rest.split_once('=') {
+          self.read(value);
+          self.assigned.insert(target.trim().to_string());
+        }
+      }
+      _ => {}
+    }
+  }
+
+  /// Records the variables an expression reads.
+  fn read(&mut self, expression: &str) {
+    let mut paths = Vec::new();
+    expression_paths(expression, &mut paths);
+
+    for path in paths {
+      if let Some(path) = self.resolve(&path) {
+        self.used.push(path);
+      }
+    }
+  }
+
+  /// Resolves a path through loop variables; `None` for names assigned within the template.
+  fn resolve(&self, path: &str) -> Option<String> {
+    let (root, fields) = match path.split_once('.') {
+      Some((root, fields)) => (root, Some(fields)),
+      None => (path, None),
+    };
+
+    // `loop` is provided inside every `for` block.
+    if root == "loop" || self.assigned.contains(root) {
+      return None;
+    }
+
+    let binding = self
+      .loops
+      .iter()
+      .rev()
+      .find(|(names, _)| names.iter().any(|name| name == root));
+
+    match (binding, fields) {
+      (None, _) => Some(path.to_string()),
+      (Some((_, items)), None) => items.clone(),
+      (Some((_, items)), Some(fields)) => {
+        items.as_ref().map(|items| format!("{}.{}", items, fields))
+      }
+    }
+  }
+}
+
+/// Collects the variable paths in an expression, skipping literals, keywords, filters, and calls.
+fn expression_paths(expression: &str, used: &mut Vec<String>) {
+  let chars: Vec<char> = expression.chars().collect();
+  let mut i = 0;
+  let mut after_pipe = false;
+
+  while i < chars.len() {
+    let c = chars[i];
+
+    if c == '"' || c == '\'' {
+      i += 1;
+      while i < chars.len() && chars[i] != c {
+        i += 1;
+      }
+      i += 1;
+    } else if c == '|' {
+      after_pipe = true;
+      i += 1;
+    } else if c.is_ascii_digit() {
+      while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '.') {
+        i += 1;
+      }
+    } else if c.is_alphabetic() || c == '_' {
+      let start = i;
+      while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '.') {
+        i += 1;
+      }
+
+      let path: String = chars[start..i].iter().collect();
+      let path = path.trim_end_matches('.');
+      let called = chars[i..].iter().find(|c| !c.is_whitespace()) == Some(&'(');
+      let preceded_by_dot = start > 0 && chars[start - 1] == '.';
+
+      // A method call such as `order.items()` reads the object it's called on.
+      let path = match called {
+        true => path.rsplit_once('.').map(|(object, _)| object),
+        false => Some(path),
+      };
+
+      if let Some(path) =
+        path.filter(|path| !after_pipe && !preceded_by_dot && !KEYWORDS.contains(path))
+      {
+        used.push(path.to_string());
+      }
+      after_pipe = false;
+    } else {
+      i += 1;
+    }
+  }
+}
+
+/// Returns whether the data provides a variable path.
+fn is_provided(path: &str, data: &HashMap<String, Value>) -> bool {
+  let mut segments = path.split('.');
+  let Some(mut value) = segments.next().and_then(|key| data.get(key)) else {
+    return false;
+  };
+
+  for segment in segments {
+    match value {
+      Value::Object(object) => match object.get(segment) {
+        Some(next) => value = next,
+        None => return false,
+      },
+      // Other values may still have attributes when rendered, e.g. a list's length.
+      _ => return true,
+    }
+  }
+
+  true
+}
+
+fn root(path: &str) -> &str {
+  path.split('.').next().unwrap_or(path)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  fn variables(source: &str) -> Vec<String> {
+    template_variables(source).into_iter().collect()
+  }
+
+  #[test]
+  fn test_template_variables() {
+    assert_eq!(
+      variables(
+        "{{ name }} {{- user.email -}} {{ 'literal' }} {{ total|round(2) }} {{ now() }} \
+         {# {{ commented }} #} {% if coupon and not expired %}{{ coupon.code }}{% endif %} \
+         {% set greeting = salutation ~ name %}{{ greeting }} {{ items[0].sku }}"
+      ),
+      vec![
+        "coupon",
+        "coupon.code",
+        "expired",
+        "items",
+        "name",
+        "salutation",
+        "total",
+        "user.email"
+      ]
+    );
+  }
+
+  #[test]
+  fn test_template_variables_skips_loop_variables() {
+    assert_eq!(
+      variables(
+        "{% for key, value in settings.items() %}{{ key }}={{ value }} {{ loop.index }}{% endfor %}"
+      ),
+      vec!["settings"]
+    );
+  }
+
+  #[test]
+  fn test_variable_schema_follows_nested_loops() {
+    let version: TemplateVersion = serde_json::from_value(json!({
+      "id": "ver_1",
+      "name": "Original",
+      "html": "{% for order in orders %}{{ order.id }}\
+               {% for line in order.lines %}{{ line.sku }} x{{ line.qty }}{% endfor %}{% endfor %}\
+               {% for tag in tags %}{{ tag }}{% endfor %}{{ order }}"
+    }))
+    .unwrap();
+
+    let schema = serde_json::to_value(variable_schema(&version)).unwrap();
+    assert_eq!(
+      schema,
+      json!({
+        "order": {"type": "value"},
+        "orders": {"type": "list", "items": {"type": "object", "fields": {
+          "id": {"type": "value"},
+          "lines": {"type": "list", "items": {"type": "object", "fields": {
+            "qty": {"type": "value"},
+            "sku": {"type": "value"}
+          }}}
+        }}},
+        "tags": {"type": "list", "items": {"type": "value"}}
+      })
+    );
+  }
+
+  #[test]
+  fn test_lint_template() {
+    let version: TemplateVersion = serde_json::from_value(json!({
+      "id": "ver_1",
+      "name": "Original",
+      "subject": "{{ user.first_name }}, your order",
+      "html": "{% for item in order.items %}{{ item.name }}{% endfor %} {{ order.total }} {{ tracking.url }}",
+      "text": "{{ order.totl }}"
+    }))
+    .unwrap();
+    let data = HashMap::from([
+      ("user".to_string(), json!({"first_name": "Ada"})),
+      ("order".to_string(), json!({"items": [], "total": 10})),
+      ("tracking".to_string(), json!("https://example.com")),
+      ("unused".to_string(), json!(true)),
+    ]);
+
+    let report = lint_template(&version, &data);
+    assert_eq!(report.missing, vec!["order.totl"]);
+    assert_eq!(report.unused, vec!["unused"]);
+    assert!(!report.is_clean());
+  }
+}
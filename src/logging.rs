@@ -0,0 +1,357 @@
+//! Diagnostic output for API calls.
+//!
+//! With the `logging` feature enabled, diagnostics are emitted as `tracing`
+//! events and left to the application's subscriber to filter. Without it,
+//! they are printed to stderr when `Config::debug` is set. In either case, a
+//! `DebugSink` configured with `Config::with_debug_sink` receives the debug
+//! output instead.
+
+#[cfg(feature = "logging")]
+use crate::error::{RequestContext, Result};
+use crate::redaction;
+use crate::request::PreparedRequest;
+use crate::response::RawResponse;
+use serde_json::Value;
+use std::fmt;
+use std::io;
+use std::sync::{Arc, Mutex};
+
+/// Destination for debug output.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::Config;
+/// use send_with_us::logging::DebugSink;
+/// use std::sync::{Arc, Mutex};
+///
+/// // Collect debug output in memory, e.g. in tests.
+/// let lines = Arc::new(Mutex::new(Vec::new()));
+/// let collected = lines.clone();
+/// let config = Config::new("api-key")
+///   .with_debug(true)
+///   .with_debug_sink(DebugSink::new(move |line| {
+///     collected.lock().unwrap().push(line.to_string());
+///   }));
+///
+/// // Or append it to a file.
+/// # fn open() -> std::io::Result<()> {
+/// let file = std::fs::File::options()
+///   .create(true)
+///   .append(true)
+///   .open("/var/log/my-app/sendwithus-debug.log")?;
+/// let config = Config::new("api-key")
+///   .with_debug(true)
+///   .with_debug_sink(DebugSink::from_writer(file));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct DebugSink(Arc<dyn Fn(&str) + Send + Sync>);
+
+impl DebugSink {
+  /// Creates a sink that passes each message to a callback.
+  ///
+  /// # Arguments
+  /// * `callback` - Called with each debug message
+  ///
+  /// # Returns
+  /// A new DebugSink
+  pub fn new(callback: impl Fn(&str) + Send + Sync + 'static) -> Self {
+    Self(Arc::new(callback))
+  }
+
+  /// Creates a sink that writes each message as a line to a writer.
+  ///
+  /// Errors writing to `writer` are ignored, so a failing sink never fails
+  /// an API call.
+  ///
+  /// # Arguments
+  /// * `writer` - Destination for debug messages
+  ///
+  /// # Returns
+  /// A new DebugSink
+  pub fn from_writer(writer: impl io::Write + Send + 'static) -> Self {
+    let writer = Mutex::new(writer);
+    Self::new(move |message| {
+      let mut writer = writer.lock().unwrap_or_else(|e| e.into_inner());
+      let _ = writeln!(writer, "{}", message).and_then(|_| writer.flush());
+    })
+  }
+
+  /// Writes a message to the sink.
+  pub fn write(&self, message: &str) {
+    (self.0)(message)
+  }
+}
+
+impl fmt::Debug for DebugSink {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str("DebugSink")
+  }
+}
+
+/// Emits a diagnostic message at the given `tracing` level.
+///
+/// When debug mode is enabled and a debug sink is configured, the message
+/// goes to the sink. Otherwise it becomes a `tracing` event with the
+/// `logging` feature, or is written to stderr in debug mode without it.
+macro_rules! log_event {
+  ($config:expr, $level:ident, $($arg:tt)+) => {{
+    let config = $config;
+    match &config.debug_sink {
+      Some(sink) if config.debug => sink.write(&format!($($arg)+)),
+      _ => {
+        #[cfg(feature = "logging")]
+        tracing::$level!($($arg)+);
+        #[cfg(not(feature = "logging"))]
+        if config.debug {
+          eprintln!($($arg)+);
+        }
+      }
+    }
+  }};
+}
+
+pub(crate) use log_event;
+
+/// Formats a body for debug output, pretty-printing it if it is JSON.
+///
+/// Personal data is redacted from JSON bodies; other bodies are returned as-is.
+fn format_body(body: &str, keys: &[String]) -> String {
+  match serde_json::from_str::<Value>(body) {
+    Ok(mut value) => {
+      redaction::redact_value(&mut value, keys);
+      serde_json::to_string_pretty(&value).unwrap_or_else(|_| body.to_string())
+    }
+    Err(_) => body.to_string(),
+  }
+}
+
+/// Formats a sanitized request for debug output.
+///
+/// The first line names the method and URL, followed by the headers and the
+/// body, if there is one.
+pub(crate) fn format_request(request: &PreparedRequest) -> String {
+  let mut output = format!("SendWithUs Request: {} {}", request.method, request.url);
+
+  for (name, value) in &request.headers {
+    output.push_str(&format!("\n{}: {}", name, value));
+  }
+
+  if let Some(body) = &request.body {
+    output.push_str("\n\n");
+    output.push_str(&format_body(body, &[]));
+  }
+
+  output
+}
+
+/// Formats a response for debug output, annotated with the request it answers.
+///
+/// # Arguments
+/// * `request` - The sanitized request the response belongs to
+/// * `response` - The response
+/// * `keys` - Patterns for JSON keys whose values are redacted from the body
+pub(crate) fn format_response(
+  request: &PreparedRequest,
+  response: &RawResponse,
+  keys: &[String],
+) -> String {
+  let mut output = format!(
+    "SendWithUs Response: {} for {} {} ({} ms)",
+    response.status,
+    request.method,
+    request.url.path(),
+    response.duration.as_millis()
+  );
+
+  let body = response.text();
+  if !body.is_empty() {
+    output.push_str("\n\n");
+    output.push_str(&format_body(&body, keys));
+  }
+
+  output
+}
+
+/// Records the outcome of a single HTTP call with its method, endpoint, status, and latency.
+#[cfg(feature = "logging")]
+pub(crate) fn log_call(context: &RequestContext, result: &Result<RawResponse>) {
+  let method = &context.method;
+  let endpoint = redaction::redact_endpoint(&context.endpoint);
+  let endpoint = endpoint.as_ref();
+  let correlation_id = context.correlation_id.as_deref().unwrap_or_default();
+
+  match result {
+    Ok(raw) => tracing::debug!(
+      method = %method,
+      endpoint,
+      correlation_id,
+      status = raw.status.as_u16(),
+      latency_ms = raw.duration.as_millis() as u64,
+      "SendWithUs API call completed"
+    ),
+    Err(error) => tracing::warn!(
+      method = %method,
+      endpoint,
+      correlation_id,
+      error = %error,
+      "SendWithUs API call failed"
+    ),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{Api, ApiClient, Config};
+
+  #[derive(Clone, Default)]
+  struct Captured(Arc<Mutex<Vec<u8>>>);
+
+  impl io::Write for Captured {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+      self.0.lock().unwrap().extend_from_slice(buf);
+      Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+      Ok(())
+    }
+  }
+
+  impl Captured {
+    fn text(&self) -> String {
+      String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+  }
+
+  #[test]
+  fn test_debug_sink_from_writer() {
+    let output = Captured::default();
+    let sink = DebugSink::from_writer(output.clone());
+
+    sink.write("first");
+    sink.write("second");
+    assert_eq!(output.text(), "first\nsecond\n");
+  }
+
+  #[tokio::test]
+  async fn test_debug_output_goes_to_sink() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let mock = mock_server
+      .mock("GET", "/api/v1/customers/user@example.com")
+      .with_status(200)
+      .with_body(r#"{"success": true, "customer": {"email": "user@example.com"}}"#)
+      .create();
+
+    let lines = Arc::new(Mutex::new(Vec::new()));
+    let collected = lines.clone();
+    let mut config = Config::new("test-api-key")
+      .with_debug(true)
+      .with_debug_sink(DebugSink::new(move |line| {
+        collected.lock().unwrap().push(line.to_string());
+      }));
+    config.url = url::Url::parse(&mock_server.url()).unwrap();
+    let api = Api::new(config);
+
+    api.customer_get("user@example.com").await.unwrap();
+
+    let lines = lines.lock().unwrap();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("SendWithUs Request: GET http://"));
+    assert!(lines[0].contains("\nX-SWU-API-KEY: [REDACTED]"));
+    assert!(
+      lines[1].starts_with("SendWithUs Response: 200 OK for GET /api/v1/customers/[REDACTED] (")
+    );
+    assert!(lines[1].contains("\n\n{\n  \"customer\": {\n    \"email\": \"[REDACTED]\"\n  },"));
+    assert!(lines.iter().all(|line| !line.contains("test-api-key")));
+    assert!(lines.iter().all(|line| !line.contains("user@example.com")));
+
+    mock.assert();
+  }
+
+  #[test]
+  fn test_format_request() {
+    let request = PreparedRequest {
+      method: reqwest::Method::POST,
+      url: url::Url::parse("https://api.sendwithus.com/api/v1/send").unwrap(),
+      headers: vec![("Content-Type".to_string(), "application/json".to_string())],
+      body: Some(r#"{"email_id":"tem_123","tags":["a"]}"#.to_string()),
+    };
+
+    assert_eq!(
+      format_request(&request),
+      "SendWithUs Request: POST https://api.sendwithus.com/api/v1/send\n\
+       Content-Type: application/json\n\n\
+       {\n  \"email_id\": \"tem_123\",\n  \"tags\": [\n    \"a\"\n  ]\n}"
+    );
+  }
+
+  #[test]
+  fn test_format_response_keeps_non_json_bodies() {
+    let request = PreparedRequest {
+      method: reqwest::Method::GET,
+      url: url::Url::parse("https://api.sendwithus.com/api/v1/templates").unwrap(),
+      headers: Vec::new(),
+      body: None,
+    };
+    let response = RawResponse {
+      status: reqwest::StatusCode::BAD_GATEWAY,
+      headers: reqwest::header::HeaderMap::new(),
+      body: b"Bad Gateway".to_vec(),
+      duration: std::time::Duration::from_millis(7),
+    };
+
+    assert_eq!(
+      format_response(&request, &response, &[]),
+      "SendWithUs Response: 502 Bad Gateway for GET /api/v1/templates (7 ms)\n\nBad Gateway"
+    );
+  }
+
+  #[cfg(feature = "logging")]
+  fn capture(f: impl FnOnce()) -> String {
+    let output = Captured::default();
+    let writer = output.clone();
+    let subscriber = tracing_subscriber::fmt()
+      .with_max_level(tracing::Level::DEBUG)
+      .with_ansi(false)
+      .with_writer(move || writer.clone())
+      .finish();
+
+    tracing::subscriber::with_default(subscriber, f);
+    output.text()
+  }
+
+  #[cfg(feature = "logging")]
+  #[test]
+  fn test_log_call_records_fields() {
+    use crate::error::Error;
+    use reqwest::StatusCode;
+    use reqwest::header::HeaderMap;
+    use std::time::Duration;
+
+    let raw = RawResponse {
+      status: StatusCode::OK,
+      headers: HeaderMap::new(),
+      body: Vec::new(),
+      duration: Duration::from_millis(42),
+    };
+
+    let context = RequestContext::new(reqwest::Method::POST, "send").with_correlation_id("req-1");
+    let output = capture(|| log_call(&context, &Ok(raw)));
+    assert!(output.contains("method=POST"));
+    assert!(output.contains("correlation_id=\"req-1\""));
+    assert!(output.contains("endpoint=\"send\""));
+    assert!(output.contains("status=200"));
+    assert!(output.contains("latency_ms=42"));
+
+    let context = RequestContext::new(reqwest::Method::GET, "customers/ada@example.com/logs");
+    let output = capture(|| log_call(&context, &Err(Error::Timeout { context: None })));
+    assert!(output.contains("WARN"));
+    assert!(output.contains("endpoint=\"customers/[REDACTED]/logs\""));
+    assert!(!output.contains("ada@example.com"));
+    assert!(output.contains("error=SendWithUs API request timed out"));
+  }
+}
@@ -0,0 +1,346 @@
+//! Provider-agnostic email messages.
+//!
+//! Applications that want to keep the option of switching email providers,
+//! or of sending through several, can code against `EmailMessage` and the
+//! `EmailProvider` trait instead of `EmailOptions` and `ApiClient`. This
+//! crate provides the SendWithUs backend: every `ApiClient`, including `Api`
+//! and the mock clients, is an `EmailProvider`. `EmailMessage` converts to
+//! and from `EmailOptions`, so existing code building options can move over
+//! one call site at a time with `.into()`.
+
+use crate::api::ApiClient;
+use crate::attachment::Attachment;
+use crate::error::Result;
+use crate::types::{EmailOptions, Recipient, Sender};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Key of the provider option holding the SendWithUs ESP account.
+pub const ESP_ACCOUNT_OPTION: &str = "esp_account";
+
+/// A templated email, independent of the provider sending it.
+///
+/// Settings that only one provider understands go in `provider_options`;
+/// providers ignore keys they don't know. The SendWithUs backend reads
+/// `ESP_ACCOUNT_OPTION`.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::message::EmailMessage;
+/// use send_with_us::types::{EmailOptions, Recipient, Sender};
+///
+/// let message = EmailMessage::new("welcome", Recipient::new("ada@example.com"))
+///   .with_from(Sender::new("noreply@example.com"))
+///   .with_tags(vec!["onboarding".to_string()]);
+///
+/// let options: EmailOptions = message.into();
+/// assert_eq!(options.email_id, "welcome");
+/// assert_eq!(options.tags, Some(vec!["onboarding".to_string()]));
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EmailMessage {
+  /// Template the provider renders the email from
+  pub template: String,
+
+  /// Primary recipient
+  pub to: Recipient,
+
+  /// Sender, or `None` for the template's default sender
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub from: Option<Sender>,
+
+  /// CC recipients
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub cc: Vec<Recipient>,
+
+  /// BCC recipients
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub bcc: Vec<Recipient>,
+
+  /// Values for the template's variables
+  #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+  pub data: HashMap<String, Value>,
+
+  /// File attachments
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub attachments: Vec<Attachment>,
+
+  /// Custom email headers
+  #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+  pub headers: HashMap<String, String>,
+
+  /// Tags for categorizing the email
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub tags: Vec<String>,
+
+  /// Locale to render the template in
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub locale: Option<String>,
+
+  /// Name of the template version to send, or `None` for the active one
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub version: Option<String>,
+
+  /// Settings specific to one provider
+  #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+  pub provider_options: HashMap<String, Value>,
+}
+
+impl EmailMessage {
+  /// Creates a message for a template and recipient.
+  ///
+  /// # Arguments
+  /// * `template` - Template the provider renders the email from
+  /// * `to` - Primary recipient
+  ///
+  /// # Returns
+  /// A new EmailMessage with no data, attachments, or extra recipients
+  pub fn new(template: impl Into<String>, to: Recipient) -> Self {
+    Self {
+      template: template.into(),
+      to,
+      from: None,
+      cc: Vec::new(),
+      bcc: Vec::new(),
+      data: HashMap::new(),
+      attachments: Vec::new(),
+      headers: HashMap::new(),
+      tags: Vec::new(),
+      locale: None,
+      version: None,
+      provider_options: HashMap::new(),
+    }
+  }
+
+  /// Sets the sender.
+  ///
+  /// # Returns
+  /// Self with the sender set for method chaining
+  pub fn with_from(mut self, from: Sender) -> Self {
+    self.from = Some(from);
+    self
+  }
+
+  /// Sets the CC recipients.
+  ///
+  /// # Returns
+  /// Self with the CC recipients set for method chaining
+  pub fn with_cc(mut self, cc: Vec<Recipient>) -> Self {
+    self.cc = cc;
+    self
+  }
+
+  /// Sets the BCC recipients.
+  ///
+  /// # Returns
+  /// Self with the BCC recipients set for method chaining
+  pub fn with_bcc(mut self, bcc: Vec<Recipient>) -> Self {
+    self.bcc = bcc;
+    self
+  }
+
+  /// Sets the values for the template's variables.
+  ///
+  /// # Returns
+  /// Self with the data set for method chaining
+  pub fn with_data(mut self, data: HashMap<String, Value>) -> Self {
+    self.data = data;
+    self
+  }
+
+  /// Sets the file attachments.
+  ///
+  /// # Returns
+  /// Self with the attachments set for method chaining
+  pub fn with_attachments(mut self, attachments: Vec<Attachment>) -> Self {
+    self.attachments = attachments;
+    self
+  }
+
+  /// Sets custom email headers.
+  ///
+  /// # Returns
+  /// Self with the headers set for method chaining
+  pub fn with_headers(mut self, headers: HashMap<String, String>) -> Self {
+    self.headers = headers;
+    self
+  }
+
+  /// Sets the tags for categorizing the email.
+  ///
+  /// # Returns
+  /// Self with the tags set for method chaining
+  pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+    self.tags = tags;
+    self
+  }
+
+  /// Sets the locale to render the template in.
+  ///
+  /// # Returns
+  /// Self with the locale set for method chaining
+  pub fn with_locale(mut self, locale: impl Into<String>) -> Self {
+    self.locale = Some(locale.into());
+    self
+  }
+
+  /// Sends a named template version instead of the active one.
+  ///
+  /// # Returns
+  /// Self with the version set for method chaining
+  pub fn with_version(mut self, version: impl Into<String>) -> Self {
+    self.version = Some(version.into());
+    self
+  }
+
+  /// Sets a provider-specific option.
+  ///
+  /// # Arguments
+  /// * `key` - Option name, e.g. `ESP_ACCOUNT_OPTION`
+  /// * `value` - Option value
+  ///
+  /// # Returns
+  /// Self with the option set for method chaining
+  pub fn with_provider_option(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+    self.provider_options.insert(key.into(), value.into());
+    self
+  }
+}
+
+impl From<EmailOptions> for EmailMessage {
+  fn from(options: EmailOptions) -> Self {
+    let mut provider_options = HashMap::new();
+    if let Some(esp_account) = options.esp_account {
+      provider_options.insert(ESP_ACCOUNT_OPTION.to_string(), Value::String(esp_account));
+    }
+
+    Self {
+      template: options.email_id,
+      to: options.recipient,
+      from: options.sender,
+      cc: options.cc.unwrap_or_default(),
+      bcc: options.bcc.unwrap_or_default(),
+      data: options.data.unwrap_or_default(),
+      attachments: options.files.unwrap_or_default(),
+      headers: options.headers.unwrap_or_default(),
+      tags: options.tags.unwrap_or_default(),
+      locale: options.locale,
+      version: options.version_name,
+      provider_options,
+    }
+  }
+}
+
+impl From<EmailMessage> for EmailOptions {
+  fn from(message: EmailMessage) -> Self {
+    let mut options = EmailOptions::new(message.template, message.to);
+    options.sender = message.from;
+    options.cc = non_empty(message.cc);
+    options.bcc = non_empty(message.bcc);
+    options.data = (!message.data.is_empty()).then_some(message.data);
+    options.files = non_empty(message.attachments);
+    options.headers = (!message.headers.is_empty()).then_some(message.headers);
+    options.tags = non_empty(message.tags);
+    options.locale = message.locale;
+    options.version_name = message.version;
+    options.esp_account = message
+      .provider_options
+      .get(ESP_ACCOUNT_OPTION)
+      .and_then(Value::as_str)
+      .map(str::to_string);
+    options
+  }
+}
+
+fn non_empty<T>(items: Vec<T>) -> Option<Vec<T>> {
+  (!items.is_empty()).then_some(items)
+}
+
+/// Outcome of handing a message to a provider.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Delivery {
+  /// Name of the provider that accepted the message, e.g. `"sendwithus"`
+  pub provider: &'static str,
+
+  /// The provider's ID for the message, for looking it up later
+  pub id: String,
+}
+
+/// A service that delivers `EmailMessage`s.
+///
+/// Implement it for other providers to switch between them, or to fail over
+/// from one to another, behind a single `dyn EmailProvider`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use send_with_us::Api;
+/// use send_with_us::message::{EmailMessage, EmailProvider};
+/// use send_with_us::types::Recipient;
+///
+/// # async fn run() -> send_with_us::Result<()> {
+/// let provider: Box<dyn EmailProvider> = Box::new(Api::with_api_key("api-key"));
+///
+/// let delivery = provider
+///   .deliver(EmailMessage::new("welcome", Recipient::new("ada@example.com")))
+///   .await?;
+/// println!("{} accepted {}", delivery.provider, delivery.id);
+/// # Ok(())
+/// # }
+/// ```
+#[async_trait]
+pub trait EmailProvider: Send + Sync {
+  /// Delivers a message.
+  ///
+  /// # Errors
+  /// Returns the provider's error if the message is rejected or can't be sent
+  async fn deliver(&self, message: EmailMessage) -> Result<Delivery>;
+}
+
+/// Every SendWithUs client delivers messages by sending them as emails.
+#[async_trait]
+impl<T: ApiClient + ?Sized> EmailProvider for T {
+  async fn deliver(&self, message: EmailMessage) -> Result<Delivery> {
+    let response = self.send_email(message.into()).await?;
+
+    Ok(Delivery {
+      provider: "sendwithus",
+      id: response.receipt_id.to_string(),
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  #[test]
+  fn test_options_round_trip_through_message() {
+    let mut data = HashMap::new();
+    data.insert("name".to_string(), json!("Ada"));
+    let options = EmailOptions::new("tem_welcome", Recipient::new("ada@example.com"))
+      .with_sender(Sender::new("noreply@example.com"))
+      .with_cc(vec![Recipient::new("grace@example.com")])
+      .with_data(data)
+      .with_files(vec![Attachment::from_bytes(b"hello", "hello.txt")])
+      .with_esp_account("esp_123")
+      .with_version_name("Version B")
+      .with_tags(vec!["onboarding".to_string()])
+      .with_locale("en-GB");
+
+    let message = EmailMessage::from(options.clone());
+    assert_eq!(message.template, "tem_welcome");
+    assert_eq!(message.version.as_deref(), Some("Version B"));
+    assert_eq!(
+      message.provider_options[ESP_ACCOUNT_OPTION],
+      json!("esp_123")
+    );
+    assert!(message.bcc.is_empty());
+
+    assert_eq!(EmailOptions::from(message), options);
+  }
+}
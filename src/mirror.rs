@@ -0,0 +1,405 @@
+//! Local mirror of email logs and events in SQLite.
+//!
+//! Available with the `sqlite` feature. A `LogMirror` incrementally copies
+//! the account's logs and their events into a SQLite database, so dashboards
+//! and reports can query history locally instead of calling the API. Each
+//! `sync` only fetches logs created since the newest log already mirrored
+//! (the high-water mark).
+
+use crate::api::{Api, ApiClient};
+use crate::error::Result;
+use crate::ids::{LogId, TemplateId};
+use crate::models::{EmailLog, EventType, LogEvent};
+use crate::types::EmailLogQuery;
+use futures::StreamExt;
+use rusqlite::types::Type;
+use rusqlite::{Connection, Row, params};
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+const SCHEMA: &str = "
+  CREATE TABLE IF NOT EXISTS logs (
+    id TEXT PRIMARY KEY,
+    created INTEGER NOT NULL,
+    recipient_address TEXT NOT NULL,
+    recipient_name TEXT,
+    status TEXT NOT NULL,
+    message TEXT,
+    email_id TEXT,
+    email_name TEXT,
+    email_version TEXT
+  );
+  CREATE INDEX IF NOT EXISTS logs_created ON logs (created);
+  CREATE TABLE IF NOT EXISTS events (
+    log_id TEXT NOT NULL REFERENCES logs (id),
+    type TEXT NOT NULL,
+    created INTEGER NOT NULL,
+    message TEXT
+  );
+  CREATE INDEX IF NOT EXISTS events_log_id ON events (log_id);
+";
+
+/// Events of logs this much older than the high-water mark are refreshed on
+/// every sync by default.
+const DEFAULT_EVENT_LOOKBACK: Duration = Duration::from_secs(3 * 86_400);
+
+/// Outcome of a `LogMirror::sync`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncSummary {
+  /// Number of logs fetched and stored
+  pub logs: usize,
+
+  /// Number of logs whose events were fetched and stored
+  pub events_refreshed: usize,
+
+  /// The high-water mark after the sync (Unix timestamp), if any log is mirrored
+  pub high_water_mark: Option<i64>,
+}
+
+/// A local SQLite copy of the account's email logs and their events.
+///
+/// The database has a `logs` table mirroring `EmailLog` and an `events`
+/// table mirroring `LogEvent` with a `log_id` column, which other tools can
+/// query directly.
+///
+/// # Examples
+///
+/// ```no_run
+/// use send_with_us::Api;
+/// use send_with_us::mirror::LogMirror;
+///
+/// # async fn run() -> send_with_us::Result<()> {
+/// let api = Api::with_api_key("api-key");
+/// let mirror = LogMirror::open("/var/lib/my-app/sendwithus.db")?;
+///
+/// let summary = mirror.sync(&api).await?;
+/// println!("mirrored {} new logs", summary.logs);
+///
+/// let recent = mirror.logs_between(1700000000, 1700086400)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct LogMirror {
+  connection: Mutex<Connection>,
+  event_lookback: Duration,
+}
+
+impl LogMirror {
+  /// Opens the mirror stored in the SQLite database at `path`.
+  ///
+  /// The database and its tables are created if they don't exist.
+  ///
+  /// # Arguments
+  /// * `path` - Path to the database file
+  ///
+  /// # Returns
+  /// The mirror
+  ///
+  /// # Errors
+  /// Returns an error if the database cannot be opened or initialized
+  pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+    Self::with_connection(Connection::open(path)?)
+  }
+
+  /// Creates a mirror kept in memory, e.g. for tests.
+  ///
+  /// # Returns
+  /// An empty mirror
+  ///
+  /// # Errors
+  /// Returns an error if the database cannot be initialized
+  pub fn in_memory() -> Result<Self> {
+    Self::with_connection(Connection::open_in_memory()?)
+  }
+
+  fn with_connection(connection: Connection) -> Result<Self> {
+    connection.execute_batch(SCHEMA)?;
+
+    Ok(Self {
+      connection: Mutex::new(connection),
+      event_lookback: DEFAULT_EVENT_LOOKBACK,
+    })
+  }
+
+  /// Sets how far back from the high-water mark events are refreshed.
+  ///
+  /// Opens and clicks keep arriving after an email is sent, so each sync
+  /// refetches the events of mirrored logs created within this period
+  /// before the high-water mark. Defaults to three days.
+  ///
+  /// # Arguments
+  /// * `lookback` - Age of the oldest logs whose events are refreshed
+  ///
+  /// # Returns
+  /// Self with the lookback set for method chaining
+  pub fn with_event_lookback(mut self, lookback: Duration) -> Self {
+    self.event_lookback = lookback;
+    self
+  }
+
+  /// Fetches logs created since the high-water mark, and their events.
+  ///
+  /// Logs created in the same second as the mark are fetched again, since
+  /// more may have been sent in that second after the last sync; storing
+  /// them is idempotent. On error, everything stored so far is kept and the
+  /// next sync picks up where this one stopped.
+  ///
+  /// # Arguments
+  /// * `api` - Client used to fetch logs and events
+  ///
+  /// # Returns
+  /// What was fetched
+  ///
+  /// # Errors
+  /// Returns the first error fetching from the API or writing to the database
+  pub async fn sync(&self, api: &Api) -> Result<SyncSummary> {
+    let mark = self.high_water_mark()?;
+    let mut summary = SyncSummary::default();
+
+    if let Some(mark) = mark {
+      let since = mark.saturating_sub(self.event_lookback.as_secs() as i64);
+      for log_id in self.log_ids_between(since, mark)? {
+        let events = api.log_events(&log_id).await?;
+        self.store_events(&log_id, &events)?;
+        summary.events_refreshed += 1;
+      }
+    }
+
+    let mut query = EmailLogQuery::new();
+    if let Some(mark) = mark {
+      query = query.with_created_gt((mark - 1).to_string());
+    }
+
+    let mut logs = api.logs_stream(query);
+    while let Some(log) = logs.next().await {
+      let log = log?;
+      let events = api.log_events(&log.id).await?;
+      self.store_log(&log)?;
+      self.store_events(&log.id, &events)?;
+      summary.logs += 1;
+      summary.events_refreshed += 1;
+    }
+
+    summary.high_water_mark = self.high_water_mark()?;
+    Ok(summary)
+  }
+
+  /// Returns the creation time of the newest mirrored log.
+  ///
+  /// # Returns
+  /// The high-water mark (Unix timestamp), or `None` if the mirror is empty
+  pub fn high_water_mark(&self) -> Result<Option<i64>> {
+    let mark = self
+      .connection()
+      .query_row("SELECT MAX(created) FROM logs", [], |row| row.get(0))?;
+    Ok(mark)
+  }
+
+  /// Returns the mirrored logs created within a time range, oldest first.
+  ///
+  /// # Arguments
+  /// * `from` - Start of the range (Unix timestamp, inclusive)
+  /// * `to` - End of the range (Unix timestamp, exclusive)
+  ///
+  /// # Returns
+  /// The logs created in the range
+  pub fn logs_between(&self, from: i64, to: i64) -> Result<Vec<EmailLog>> {
+    let connection = self.connection();
+    let mut statement = connection.prepare(
+      "SELECT id, created, recipient_address, recipient_name, status, message, email_id,
+              email_name, email_version
+       FROM logs WHERE created >= ?1 AND created < ?2 ORDER BY created, id",
+    )?;
+
+    let rows = statement.query_map(params![from, to], |row| {
+      Ok(EmailLog {
+        id: log_id(row, 0)?,
+        created: row.get(1)?,
+        recipient_address: row.get(2)?,
+        recipient_name: row.get(3)?,
+        status: row.get(4)?,
+        message: row.get(5)?,
+        email_id: template_id(row, 6)?,
+        email_name: row.get(7)?,
+        email_version: row.get(8)?,
+      })
+    })?;
+
+    Ok(rows.collect::<rusqlite::Result<_>>()?)
+  }
+
+  /// Returns the mirrored events of a log, oldest first.
+  ///
+  /// # Arguments
+  /// * `log_id` - Email log ID
+  ///
+  /// # Returns
+  /// The log's events, empty if the log isn't mirrored
+  pub fn events(&self, log_id: &LogId) -> Result<Vec<LogEvent>> {
+    let connection = self.connection();
+    let mut statement = connection
+      .prepare("SELECT type, created, message FROM events WHERE log_id = ?1 ORDER BY created")?;
+
+    let rows = statement.query_map(params![log_id.as_str()], |row| {
+      Ok(LogEvent {
+        event_type: EventType::from(row.get::<_, String>(0)?),
+        created: row.get(1)?,
+        message: row.get(2)?,
+      })
+    })?;
+
+    Ok(rows.collect::<rusqlite::Result<_>>()?)
+  }
+
+  fn connection(&self) -> MutexGuard<'_, Connection> {
+    self.connection.lock().unwrap_or_else(|e| e.into_inner())
+  }
+
+  fn log_ids_between(&self, from: i64, to: i64) -> Result<Vec<LogId>> {
+    let connection = self.connection();
+    let mut statement =
+      connection.prepare("SELECT id FROM logs WHERE created >= ?1 AND created < ?2")?;
+    let ids = statement
+      .query_map(params![from, to], |row| log_id(row, 0))?
+      .collect::<rusqlite::Result<_>>()?;
+    Ok(ids)
+  }
+
+  fn store_log(&self, log: &EmailLog) -> Result<()> {
+    self.connection().execute(
+      "INSERT OR REPLACE INTO logs (id, created, recipient_address, recipient_name, status,
+                                    message, email_id, email_name, email_version)
+       VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+      params![
+        log.id.as_str(),
+        log.created,
+        log.recipient_address,
+        log.recipient_name,
+        log.status,
+        log.message,
+        log.email_id.as_ref().map(TemplateId::as_str),
+        log.email_name,
+        log.email_version,
+      ],
+    )?;
+    Ok(())
+  }
+
+  fn store_events(&self, log_id: &LogId, events: &[LogEvent]) -> Result<()> {
+    let mut connection = self.connection();
+    let transaction = connection.transaction()?;
+
+    transaction.execute(
+      "DELETE FROM events WHERE log_id = ?1",
+      params![log_id.as_str()],
+    )?;
+    for event in events {
+      transaction.execute(
+        "INSERT INTO events (log_id, type, created, message) VALUES (?1, ?2, ?3, ?4)",
+        params![
+          log_id.as_str(),
+          event.event_type.as_str(),
+          event.created,
+          event.message
+        ],
+      )?;
+    }
+
+    transaction.commit()?;
+    Ok(())
+  }
+}
+
+/// Reads a log ID column, failing the row if the stored value is invalid.
+fn log_id(row: &Row<'_>, index: usize) -> rusqlite::Result<LogId> {
+  LogId::new(row.get::<_, String>(index)?)
+    .map_err(|e| rusqlite::Error::FromSqlConversionFailure(index, Type::Text, Box::new(e)))
+}
+
+fn template_id(row: &Row<'_>, index: usize) -> rusqlite::Result<Option<TemplateId>> {
+  row
+    .get::<_, Option<String>>(index)?
+    .map(TemplateId::new)
+    .transpose()
+    .map_err(|e| rusqlite::Error::FromSqlConversionFailure(index, Type::Text, Box::new(e)))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Config;
+  use crate::testing::fixtures::EmailLogFixture;
+  use mockito::Matcher;
+  use serde_json::json;
+
+  #[tokio::test]
+  async fn test_sync_is_incremental() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
+    let log = |id: &str, created: i64| {
+      EmailLogFixture::new()
+        .with_id(id)
+        .with_created(created)
+        .with_template("tem_a", "Welcome")
+        .to_json()
+    };
+
+    let initial = mock_server
+      .mock("GET", "/api/v1/logs")
+      .match_query(Matcher::Exact("count=100".to_string()))
+      .with_status(200)
+      .with_body(json!([log("log_1", 1000), log("log_2", 2000)]).to_string())
+      .create();
+    let incremental = mock_server
+      .mock("GET", "/api/v1/logs")
+      .match_query(Matcher::UrlEncoded("created_gt".into(), "1999".into()))
+      .with_status(200)
+      .with_body(json!([log("log_2", 2000), log("log_3", 3000)]).to_string())
+      .create();
+    let events = mock_server
+      .mock(
+        "GET",
+        Matcher::Regex(r"^/api/v1/logs/log_\d/events$".to_string()),
+      )
+      .with_status(200)
+      .with_body(json!([{"type": "open", "created": 3500, "message": "Opened"}]).to_string())
+      .expect(5)
+      .create();
+
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
+    let mirror = LogMirror::in_memory()
+      .unwrap()
+      .with_event_lookback(Duration::from_secs(1500));
+
+    let summary = mirror.sync(&api).await.unwrap();
+    assert_eq!(summary.logs, 2);
+    assert_eq!(summary.high_water_mark, Some(2000));
+
+    // log_1 falls within the lookback, so its events are refreshed as well.
+    let summary = mirror.sync(&api).await.unwrap();
+    assert_eq!(summary.logs, 2);
+    assert_eq!(summary.events_refreshed, 3);
+    assert_eq!(summary.high_water_mark, Some(3000));
+
+    let logs = mirror.logs_between(0, 3000).unwrap();
+    let ids: Vec<_> = logs.iter().map(|log| log.id.as_str()).collect();
+    assert_eq!(ids, ["log_1", "log_2"]);
+    assert_eq!(
+      logs[0].email_id.as_ref().map(TemplateId::as_str),
+      Some("tem_a")
+    );
+
+    let log_events = mirror.events(&logs[0].id).unwrap();
+    assert_eq!(log_events.len(), 1);
+    assert_eq!(log_events[0].event_type, EventType::Opened);
+    assert_eq!(log_events[0].message.as_deref(), Some("Opened"));
+
+    initial.assert();
+    incremental.assert();
+    events.assert();
+  }
+}
@@ -0,0 +1,1220 @@
+//! A programmable `ApiClient` for tests.
+//!
+//! Available with the `mock` feature. Code written against the `ApiClient`
+//! trait can be tested with a `MockApi` instead of a real account: give it
+//! canned responses or errors per method, run the code under test, then
+//! inspect the recorded calls or `verify` the expected call counts.
+//!
+//! Responses are given as JSON and converted to each method's return type,
+//! so `send_email` needs a response shaped like a `SendResponse`. When the
+//! responses don't matter, a `RecordingApiClient` succeeds at everything and
+//! records what was sent. `RecordingApiClient::deterministic` derives its
+//! responses from the request alone, for snapshot tests.
+
+use crate::api::ApiClient;
+use crate::endpoint::Endpoint;
+use crate::error::{Error, Result};
+use crate::ids::{CampaignId, LogId, ReceiptId, TemplateId};
+use crate::models::{
+  DripCampaignCustomer, DripCampaignStep, EmailLog, EmailSummary, LogEvent, SendResponse,
+  UnsubscribeStatus,
+};
+use crate::testing::fixtures::FIXTURE_TIMESTAMP;
+use crate::types::{
+  CustomerEventOptions, CustomerOptions, CustomerUpdateOptions, DripCampaignOptions, EmailLogQuery,
+  EmailOptions, RenderOptions, TemplateOptions, TemplateUpdateOptions,
+};
+use async_trait::async_trait;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Names of the `ApiClient` methods a `MockApi` can be programmed for.
+pub const METHODS: [&str; 30] = [
+  "send_email",
+  "list_templates",
+  "render",
+  "create_template",
+  "list_drip_campaigns",
+  "start_on_drip_campaign",
+  "remove_from_drip_campaign",
+  "drip_campaign_details",
+  "drip_campaign_steps",
+  "drip_campaign_customers",
+  "customer_get",
+  "customer_create",
+  "customer_update",
+  "customer_delete",
+  "customer_add_event",
+  "customer_email_log",
+  "logs",
+  "log",
+  "log_events",
+  "get_template",
+  "update_template",
+  "delete_template",
+  "list_template_versions",
+  "get_template_version",
+  "update_template_version",
+  "create_template_version",
+  "drips_unsubscribe",
+  "unsubscribe_status",
+  "unsubscribe",
+  "resubscribe",
+];
+
+/// A call made to a `MockApi`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockCall {
+  /// Name of the `ApiClient` method called
+  pub method: &'static str,
+
+  /// Arguments of the call as JSON, keyed by parameter name
+  pub args: Value,
+}
+
+type ErrorFactory = Arc<dyn Fn() -> Error + Send + Sync>;
+
+#[derive(Clone)]
+enum Canned {
+  Response(Value),
+  Error(ErrorFactory),
+}
+
+impl fmt::Debug for Canned {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Canned::Response(value) => f.debug_tuple("Response").field(value).finish(),
+      Canned::Error(_) => f.write_str("Error(..)"),
+    }
+  }
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+  /// Responses used for every call to a method, unless a one-shot response is queued
+  responses: HashMap<&'static str, Canned>,
+
+  /// Responses used once each, in order
+  queued: HashMap<&'static str, VecDeque<Canned>>,
+
+  /// Number of calls expected per method
+  expected: BTreeMap<&'static str, usize>,
+
+  calls: Vec<MockCall>,
+}
+
+/// An `ApiClient` that returns programmed responses and records every call.
+///
+/// A call to a method with no response programmed fails with
+/// `Error::Unexpected`. `send_email` validates its options first, like
+/// `Api` does, so invalid options fail without using a response.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::ApiClient;
+/// use send_with_us::mock::MockApi;
+/// use send_with_us::types::{EmailOptions, Recipient};
+/// use serde_json::json;
+///
+/// # #[tokio::main]
+/// # async fn main() -> send_with_us::Result<()> {
+/// let api = MockApi::new()
+///   .with_response("send_email", json!({
+///     "success": true,
+///     "status": "OK",
+///     "receipt_id": "log_1",
+///     "email": {"name": "Welcome"}
+///   }))
+///   .expect("send_email", 1);
+///
+/// let options = EmailOptions::new("tem_welcome", Recipient::new("user@example.com"));
+/// let receipt = api.send_email(options).await?;
+/// assert_eq!(receipt.receipt_id.as_str(), "log_1");
+///
+/// let calls = api.calls_to("send_email");
+/// assert_eq!(calls[0].args["options"]["email_id"], "tem_welcome");
+/// api.verify();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct MockApi {
+  state: Mutex<MockState>,
+}
+
+impl MockApi {
+  /// Creates a mock with no responses programmed.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Returns a response for every call to a method.
+  ///
+  /// # Arguments
+  /// * `method` - Name of the `ApiClient` method
+  /// * `response` - The response body, converted to the method's return type
+  ///
+  /// # Panics
+  /// Panics if `method` isn't an `ApiClient` method or the response can't be serialized
+  pub fn with_response(self, method: &str, response: impl Serialize) -> Self {
+    let canned = Canned::Response(to_json(response));
+    self.state().responses.insert(method_name(method), canned);
+    self
+  }
+
+  /// Returns a response for the next call to a method only.
+  ///
+  /// One-shot responses are used in the order they were added, before the
+  /// method's `with_response` response.
+  ///
+  /// # Arguments
+  /// * `method` - Name of the `ApiClient` method
+  /// * `response` - The response body, converted to the method's return type
+  ///
+  /// # Panics
+  /// Panics if `method` isn't an `ApiClient` method or the response can't be serialized
+  pub fn with_response_once(self, method: &str, response: impl Serialize) -> Self {
+    self.queue(method, Canned::Response(to_json(response)))
+  }
+
+  /// Fails every call to a method with an error.
+  ///
+  /// # Arguments
+  /// * `method` - Name of the `ApiClient` method
+  /// * `error` - Creates the error returned by each call
+  ///
+  /// # Panics
+  /// Panics if `method` isn't an `ApiClient` method
+  pub fn with_error(self, method: &str, error: impl Fn() -> Error + Send + Sync + 'static) -> Self {
+    let canned = Canned::Error(Arc::new(error));
+    self.state().responses.insert(method_name(method), canned);
+    self
+  }
+
+  /// Fails the next call to a method with an error.
+  ///
+  /// # Arguments
+  /// * `method` - Name of the `ApiClient` method
+  /// * `error` - The error returned
+  ///
+  /// # Panics
+  /// Panics if `method` isn't an `ApiClient` method
+  pub fn with_error_once(self, method: &str, error: Error) -> Self {
+    let error = Mutex::new(Some(error));
+    self.queue(
+      method,
+      Canned::Error(Arc::new(move || {
+        error
+          .lock()
+          .unwrap()
+          .take()
+          .unwrap_or_else(|| Error::Unexpected("one-shot mock error reused".to_string()))
+      })),
+    )
+  }
+
+  /// Expects a method to be called a number of times, checked by `verify`.
+  ///
+  /// # Arguments
+  /// * `method` - Name of the `ApiClient` method
+  /// * `times` - Number of calls expected
+  ///
+  /// # Panics
+  /// Panics if `method` isn't an `ApiClient` method
+  pub fn expect(self, method: &str, times: usize) -> Self {
+    self.state().expected.insert(method_name(method), times);
+    self
+  }
+
+  /// Returns every call made so far, in order.
+  pub fn calls(&self) -> Vec<MockCall> {
+    self.state().calls.clone()
+  }
+
+  /// Returns the calls made so far to one method, in order.
+  ///
+  /// # Arguments
+  /// * `method` - Name of the `ApiClient` method
+  pub fn calls_to(&self, method: &str) -> Vec<MockCall> {
+    self
+      .state()
+      .calls
+      .iter()
+      .filter(|call| call.method == method)
+      .cloned()
+      .collect()
+  }
+
+  /// Checks that every expected method was called the expected number of times.
+  ///
+  /// # Panics
+  /// Panics listing each method whose call count differs from its expectation
+  pub fn verify(&self) {
+    let state = self.state();
+    let mismatches: Vec<String> = state
+      .expected
+      .iter()
+      .filter_map(|(method, expected)| {
+        let actual = state.calls.iter().filter(|c| c.method == *method).count();
+        (actual != *expected)
+          .then(|| format!("{}: expected {} call(s), got {}", method, expected, actual))
+      })
+      .collect();
+
+    if !mismatches.is_empty() {
+      panic!("MockApi expectations not met:\n{}", mismatches.join("\n"));
+    }
+  }
+
+  fn state(&self) -> std::sync::MutexGuard<'_, MockState> {
+    self.state.lock().unwrap_or_else(|e| e.into_inner())
+  }
+
+  fn queue(self, method: &str, canned: Canned) -> Self {
+    self
+      .state()
+      .queued
+      .entry(method_name(method))
+      .or_default()
+      .push_back(canned);
+    self
+  }
+
+  fn record(&self, method: &'static str, args: Value) {
+    self.state().calls.push(MockCall { method, args });
+  }
+
+  /// Returns the programmed response for a call, without recording it.
+  fn respond<T: DeserializeOwned>(&self, method: &'static str) -> Result<T> {
+    let canned = {
+      let mut state = self.state();
+      let queued = state.queued.get_mut(method).and_then(VecDeque::pop_front);
+      queued.or_else(|| state.responses.get(method).cloned())
+    };
+
+    match canned {
+      Some(Canned::Response(value)) => Ok(serde_json::from_value(value)?),
+      Some(Canned::Error(error)) => Err(error()),
+      None => Err(Error::Unexpected(format!(
+        "no mock response configured for {}",
+        method
+      ))),
+    }
+  }
+
+  /// Records a call and returns its programmed response.
+  fn call<T: DeserializeOwned>(&self, method: &'static str, args: Value) -> Result<T> {
+    self.record(method, args);
+    self.respond(method)
+  }
+}
+
+fn method_name(method: &str) -> &'static str {
+  METHODS
+    .iter()
+    .find(|name| **name == method)
+    .copied()
+    .unwrap_or_else(|| panic!("{:?} is not an ApiClient method", method))
+}
+
+fn to_json(value: impl Serialize) -> Value {
+  serde_json::to_value(value).expect("mock response must serialize to JSON")
+}
+
+fn query_json(query: &EmailLogQuery) -> Value {
+  json!({
+    "count": query.count,
+    "offset": query.offset,
+    "created_gt": query.created_gt,
+    "created_lt": query.created_lt,
+  })
+}
+
+#[async_trait]
+impl ApiClient for MockApi {
+  async fn send_email(&self, options: EmailOptions) -> Result<SendResponse> {
+    self.record("send_email", json!({ "options": options }));
+    options.validate()?;
+    self.respond("send_email")
+  }
+
+  async fn list_templates(&self) -> Result<Value> {
+    self.call("list_templates", json!({}))
+  }
+
+  async fn render(&self, options: RenderOptions) -> Result<Value> {
+    self.call("render", json!({ "options": options }))
+  }
+
+  async fn create_template(&self, options: TemplateOptions) -> Result<Value> {
+    self.call("create_template", json!({ "options": options }))
+  }
+
+  async fn list_drip_campaigns(&self) -> Result<Value> {
+    self.call("list_drip_campaigns", json!({}))
+  }
+
+  async fn start_on_drip_campaign(
+    &self,
+    campaign_id: &CampaignId,
+    options: DripCampaignOptions,
+  ) -> Result<Value> {
+    self.call(
+      "start_on_drip_campaign",
+      json!({ "campaign_id": campaign_id, "options": options }),
+    )
+  }
+
+  async fn remove_from_drip_campaign(
+    &self,
+    campaign_id: &CampaignId,
+    recipient_address: &str,
+  ) -> Result<Value> {
+    self.call(
+      "remove_from_drip_campaign",
+      json!({ "campaign_id": campaign_id, "recipient_address": recipient_address }),
+    )
+  }
+
+  async fn drip_campaign_details(&self, campaign_id: &CampaignId) -> Result<Value> {
+    self.call(
+      "drip_campaign_details",
+      json!({ "campaign_id": campaign_id }),
+    )
+  }
+
+  async fn drip_campaign_steps(&self, campaign_id: &CampaignId) -> Result<Vec<DripCampaignStep>> {
+    self.call("drip_campaign_steps", json!({ "campaign_id": campaign_id }))
+  }
+
+  async fn drip_campaign_customers(
+    &self,
+    campaign_id: &CampaignId,
+  ) -> Result<Vec<DripCampaignCustomer>> {
+    self.call(
+      "drip_campaign_customers",
+      json!({ "campaign_id": campaign_id }),
+    )
+  }
+
+  async fn customer_get(&self, email: &str) -> Result<Value> {
+    self.call("customer_get", json!({ "email": email }))
+  }
+
+  async fn customer_create(&self, options: CustomerOptions) -> Result<Value> {
+    self.call("customer_create", json!({ "options": options }))
+  }
+
+  async fn customer_update(&self, email: &str, options: CustomerUpdateOptions) -> Result<Value> {
+    self.call(
+      "customer_update",
+      json!({ "email": email, "options": options }),
+    )
+  }
+
+  async fn customer_delete(&self, email: &str) -> Result<Value> {
+    self.call("customer_delete", json!({ "email": email }))
+  }
+
+  async fn customer_add_event(&self, email: &str, options: CustomerEventOptions) -> Result<Value> {
+    self.call(
+      "customer_add_event",
+      json!({ "email": email, "options": options }),
+    )
+  }
+
+  async fn customer_email_log(&self, email: &str, query: EmailLogQuery) -> Result<Vec<EmailLog>> {
+    self.call(
+      "customer_email_log",
+      json!({ "email": email, "query": query_json(&query) }),
+    )
+  }
+
+  async fn logs(&self, query: EmailLogQuery) -> Result<Vec<EmailLog>> {
+    self.call("logs", json!({ "query": query_json(&query) }))
+  }
+
+  async fn log(&self, log_id: &LogId) -> Result<EmailLog> {
+    self.call("log", json!({ "log_id": log_id }))
+  }
+
+  async fn log_events(&self, log_id: &LogId) -> Result<Vec<LogEvent>> {
+    self.call("log_events", json!({ "log_id": log_id }))
+  }
+
+  async fn get_template(&self, template_id: &TemplateId) -> Result<Value> {
+    self.call("get_template", json!({ "template_id": template_id }))
+  }
+
+  async fn update_template(
+    &self,
+    template_id: &TemplateId,
+    options: TemplateUpdateOptions,
+  ) -> Result<Value> {
+    self.call(
+      "update_template",
+      json!({ "template_id": template_id, "options": options }),
+    )
+  }
+
+  async fn delete_template(&self, template_id: &TemplateId) -> Result<Value> {
+    self.call("delete_template", json!({ "template_id": template_id }))
+  }
+
+  async fn list_template_versions(&self, template_id: &TemplateId) -> Result<Value> {
+    self.call(
+      "list_template_versions",
+      json!({ "template_id": template_id }),
+    )
+  }
+
+  async fn get_template_version(
+    &self,
+    template_id: &TemplateId,
+    version_id: &str,
+  ) -> Result<Value> {
+    self.call(
+      "get_template_version",
+      json!({ "template_id": template_id, "version_id": version_id }),
+    )
+  }
+
+  async fn update_template_version(
+    &self,
+    template_id: &TemplateId,
+    version_id: &str,
+    options: TemplateOptions,
+  ) -> Result<Value> {
+    self.call(
+      "update_template_version",
+      json!({ "template_id": template_id, "version_id": version_id, "options": options }),
+    )
+  }
+
+  async fn create_template_version(
+    &self,
+    template_id: &TemplateId,
+    options: TemplateOptions,
+  ) -> Result<Value> {
+    self.call(
+      "create_template_version",
+      json!({ "template_id": template_id, "options": options }),
+    )
+  }
+
+  async fn drips_unsubscribe(&self, email_address: &str) -> Result<Value> {
+    self.call(
+      "drips_unsubscribe",
+      json!({ "email_address": email_address }),
+    )
+  }
+
+  async fn unsubscribe_status(&self, email_address: &str) -> Result<UnsubscribeStatus> {
+    self.call(
+      "unsubscribe_status",
+      json!({ "email_address": email_address }),
+    )
+  }
+
+  async fn unsubscribe(&self, email_address: &str) -> Result<Value> {
+    self.call("unsubscribe", json!({ "email_address": email_address }))
+  }
+
+  async fn resubscribe(&self, email_address: &str) -> Result<Value> {
+    self.call("resubscribe", json!({ "email_address": email_address }))
+  }
+}
+
+/// A call made to a `RecordingApiClient`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedCall {
+  /// Name of the `ApiClient` method called
+  pub method: &'static str,
+
+  /// Endpoint the call would have requested, relative to the API version,
+  /// e.g. `customers/user@example.com`
+  pub endpoint: String,
+
+  /// Arguments of the call as JSON, keyed by parameter name
+  pub options: Value,
+
+  /// When the call was made
+  pub timestamp: SystemTime,
+}
+
+/// An `ApiClient` that records every call and always succeeds.
+///
+/// Unlike `MockApi` nothing needs to be programmed: sends return a receipt
+/// for the template sent, lists are empty, and other calls return
+/// `{"success": true}`. Options aren't validated. Use it in application
+/// tests to assert on what would have been sent.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::ApiClient;
+/// use send_with_us::mock::RecordingApiClient;
+/// use send_with_us::types::{EmailOptions, Recipient};
+///
+/// # #[tokio::main]
+/// # async fn main() -> send_with_us::Result<()> {
+/// let api = RecordingApiClient::new();
+///
+/// let options = EmailOptions::new("tem_welcome", Recipient::new("user@example.com"));
+/// api.send_email(options).await?;
+///
+/// let sent = api.sent_to("user@example.com");
+/// assert_eq!(sent.len(), 1);
+/// assert_eq!(sent[0].email_id, "tem_welcome");
+/// assert_eq!(api.calls()[0].endpoint, "send");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct RecordingApiClient {
+  state: Mutex<RecordingState>,
+
+  /// Whether responses depend only on the request
+  deterministic: bool,
+}
+
+#[derive(Debug, Default)]
+struct RecordingState {
+  calls: Vec<RecordedCall>,
+
+  /// Emails sent, with the receipt ID each was given
+  sent: Vec<(ReceiptId, EmailOptions)>,
+}
+
+impl RecordingApiClient {
+  /// Creates a client with no calls recorded.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Creates a client whose responses depend only on the request.
+  ///
+  /// Receipt IDs are a hash of the email options instead of a count of the
+  /// sends so far, and logs are dated `testing::fixtures::FIXTURE_TIMESTAMP`
+  /// instead of now. The same calls then get the same responses on every
+  /// run and in any order, which keeps snapshot tests stable. Sending the
+  /// same options twice yields the same receipt ID.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::ApiClient;
+  /// use send_with_us::mock::RecordingApiClient;
+  /// use send_with_us::types::{EmailOptions, Recipient};
+  ///
+  /// # #[tokio::main]
+  /// # async fn main() -> send_with_us::Result<()> {
+  /// let options = EmailOptions::new("tem_welcome", Recipient::new("user@example.com"));
+  ///
+  /// let first = RecordingApiClient::deterministic().send_email(options.clone()).await?;
+  /// let second = RecordingApiClient::deterministic().send_email(options).await?;
+  /// assert_eq!(first.receipt_id, second.receipt_id);
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn deterministic() -> Self {
+    Self {
+      deterministic: true,
+      ..Self::default()
+    }
+  }
+
+  /// Returns every call made so far, in order.
+  pub fn calls(&self) -> Vec<RecordedCall> {
+    self.state().calls.clone()
+  }
+
+  /// Returns the options of every email sent so far, in order.
+  pub fn sent(&self) -> Vec<EmailOptions> {
+    self
+      .state()
+      .sent
+      .iter()
+      .map(|(_, options)| options.clone())
+      .collect()
+  }
+
+  /// Returns the emails sent to an address, as the recipient or in CC or BCC.
+  ///
+  /// # Arguments
+  /// * `address` - Email address, compared case-insensitively
+  ///
+  /// # Returns
+  /// The options of each matching email, in the order sent
+  pub fn sent_to(&self, address: &str) -> Vec<EmailOptions> {
+    self
+      .sent()
+      .into_iter()
+      .filter(|options| addressed_to(options, address))
+      .collect()
+  }
+
+  /// Returns the emails sent with a template.
+  ///
+  /// # Arguments
+  /// * `template_id` - Template ID the emails were sent with
+  ///
+  /// # Returns
+  /// The options of each matching email, in the order sent
+  pub fn sent_with_template(&self, template_id: &str) -> Vec<EmailOptions> {
+    self
+      .sent()
+      .into_iter()
+      .filter(|options| options.email_id == template_id)
+      .collect()
+  }
+
+  /// Forgets every call recorded so far.
+  pub fn clear(&self) {
+    let mut state = self.state();
+    state.calls.clear();
+    state.sent.clear();
+  }
+
+  fn state(&self) -> std::sync::MutexGuard<'_, RecordingState> {
+    self.state.lock().unwrap_or_else(|e| e.into_inner())
+  }
+
+  fn record(&self, method: &'static str, endpoint: String, options: Value) {
+    self.state().calls.push(RecordedCall {
+      method,
+      endpoint,
+      options,
+      timestamp: SystemTime::now(),
+    });
+  }
+
+  /// Records a call and returns a generic success response.
+  fn succeed(&self, method: &'static str, endpoint: String, options: Value) -> Result<Value> {
+    self.record(method, endpoint, options);
+    Ok(json!({ "success": true }))
+  }
+}
+
+#[async_trait]
+impl ApiClient for RecordingApiClient {
+  async fn send_email(&self, options: EmailOptions) -> Result<SendResponse> {
+    self.record(
+      "send_email",
+      "send".to_string(),
+      json!({ "options": options }),
+    );
+
+    let mut state = self.state();
+    let receipt_id = if self.deterministic {
+      let mut hash = FNV_OFFSET_BASIS;
+      fingerprint(&json!(options), &mut hash);
+      ReceiptId::new(format!("log_{:016x}", hash))?
+    } else {
+      ReceiptId::new(format!("log_recorded_{}", state.sent.len() + 1))?
+    };
+    let response = SendResponse {
+      success: true,
+      status: "OK".to_string(),
+      receipt_id: receipt_id.clone(),
+      email: EmailSummary {
+        name: options.email_id.clone(),
+        version_name: options.version_name.clone(),
+        locale: options.locale.clone(),
+      },
+    };
+    state.sent.push((receipt_id, options));
+
+    Ok(response)
+  }
+
+  async fn list_templates(&self) -> Result<Value> {
+    self.record("list_templates", "emails".to_string(), json!({}));
+    Ok(json!([]))
+  }
+
+  async fn render(&self, options: RenderOptions) -> Result<Value> {
+    self.succeed(
+      "render",
+      "render".to_string(),
+      json!({ "options": options }),
+    )
+  }
+
+  async fn create_template(&self, options: TemplateOptions) -> Result<Value> {
+    self.succeed(
+      "create_template",
+      "emails".to_string(),
+      json!({ "options": options }),
+    )
+  }
+
+  async fn list_drip_campaigns(&self) -> Result<Value> {
+    self.record(
+      "list_drip_campaigns",
+      "drip_campaigns".to_string(),
+      json!({}),
+    );
+    Ok(json!([]))
+  }
+
+  async fn start_on_drip_campaign(
+    &self,
+    campaign_id: &CampaignId,
+    options: DripCampaignOptions,
+  ) -> Result<Value> {
+    self.succeed(
+      "start_on_drip_campaign",
+      format!("drip_campaigns/{}/activate", campaign_id),
+      json!({ "campaign_id": campaign_id, "options": options }),
+    )
+  }
+
+  async fn remove_from_drip_campaign(
+    &self,
+    campaign_id: &CampaignId,
+    recipient_address: &str,
+  ) -> Result<Value> {
+    self.succeed(
+      "remove_from_drip_campaign",
+      format!("drip_campaigns/{}/deactivate", campaign_id),
+      json!({ "campaign_id": campaign_id, "recipient_address": recipient_address }),
+    )
+  }
+
+  async fn drip_campaign_details(&self, campaign_id: &CampaignId) -> Result<Value> {
+    self.succeed(
+      "drip_campaign_details",
+      format!("drip_campaigns/{}", campaign_id),
+      json!({ "campaign_id": campaign_id }),
+    )
+  }
+
+  async fn drip_campaign_steps(&self, campaign_id: &CampaignId) -> Result<Vec<DripCampaignStep>> {
+    self.record(
+      "drip_campaign_steps",
+      format!("drip_campaigns/{}/steps", campaign_id),
+      json!({ "campaign_id": campaign_id }),
+    );
+    Ok(Vec::new())
+  }
+
+  async fn drip_campaign_customers(
+    &self,
+    campaign_id: &CampaignId,
+  ) -> Result<Vec<DripCampaignCustomer>> {
+    self.record(
+      "drip_campaign_customers",
+      format!("drip_campaigns/{}/customers", campaign_id),
+      json!({ "campaign_id": campaign_id }),
+    );
+    Ok(Vec::new())
+  }
+
+  async fn customer_get(&self, email: &str) -> Result<Value> {
+    self.succeed(
+      "customer_get",
+      format!("customers/{}", email),
+      json!({ "email": email }),
+    )
+  }
+
+  async fn customer_create(&self, options: CustomerOptions) -> Result<Value> {
+    self.succeed(
+      "customer_create",
+      "customers".to_string(),
+      json!({ "options": options }),
+    )
+  }
+
+  async fn customer_update(&self, email: &str, options: CustomerUpdateOptions) -> Result<Value> {
+    self.succeed(
+      "customer_update",
+      format!("customers/{}", email),
+      json!({ "email": email, "options": options }),
+    )
+  }
+
+  async fn customer_delete(&self, email: &str) -> Result<Value> {
+    self.succeed(
+      "customer_delete",
+      format!("customers/{}", email),
+      json!({ "email": email }),
+    )
+  }
+
+  async fn customer_add_event(&self, email: &str, options: CustomerEventOptions) -> Result<Value> {
+    self.succeed(
+      "customer_add_event",
+      format!("customers/{}/events", email),
+      json!({ "email": email, "options": options }),
+    )
+  }
+
+  async fn customer_email_log(&self, email: &str, query: EmailLogQuery) -> Result<Vec<EmailLog>> {
+    self.record(
+      "customer_email_log",
+      Endpoint::new(["customers", email, "logs"])
+        .with_query(&query)
+        .to_string(),
+      json!({ "email": email, "query": query_json(&query) }),
+    );
+    Ok(Vec::new())
+  }
+
+  async fn logs(&self, query: EmailLogQuery) -> Result<Vec<EmailLog>> {
+    self.record(
+      "logs",
+      Endpoint::new(["logs"]).with_query(&query).to_string(),
+      json!({ "query": query_json(&query) }),
+    );
+    Ok(Vec::new())
+  }
+
+  async fn log(&self, log_id: &LogId) -> Result<EmailLog> {
+    self.record(
+      "log",
+      format!("logs/{}", log_id),
+      json!({ "log_id": log_id }),
+    );
+
+    // Logs of recorded sends describe the email that was sent.
+    let state = self.state();
+    let sent = state
+      .sent
+      .iter()
+      .find(|(receipt_id, _)| receipt_id.as_str() == log_id.as_str())
+      .map(|(_, options)| options);
+
+    let created = if self.deterministic {
+      FIXTURE_TIMESTAMP
+    } else {
+      SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs() as i64)
+        .unwrap_or_default()
+    };
+
+    Ok(EmailLog {
+      id: log_id.clone(),
+      created,
+      recipient_address: sent
+        .map(|options| options.recipient.address.clone())
+        .unwrap_or_default(),
+      recipient_name: sent.and_then(|options| options.recipient.name.clone()),
+      status: "sent".to_string(),
+      message: None,
+      email_id: sent.and_then(|options| TemplateId::new(options.email_id.clone()).ok()),
+      email_name: None,
+      email_version: sent.and_then(|options| options.version_name.clone()),
+    })
+  }
+
+  async fn log_events(&self, log_id: &LogId) -> Result<Vec<LogEvent>> {
+    self.record(
+      "log_events",
+      format!("logs/{}/events", log_id),
+      json!({ "log_id": log_id }),
+    );
+    Ok(Vec::new())
+  }
+
+  async fn get_template(&self, template_id: &TemplateId) -> Result<Value> {
+    self.succeed(
+      "get_template",
+      format!("templates/{}", template_id),
+      json!({ "template_id": template_id }),
+    )
+  }
+
+  async fn update_template(
+    &self,
+    template_id: &TemplateId,
+    options: TemplateUpdateOptions,
+  ) -> Result<Value> {
+    self.succeed(
+      "update_template",
+      format!("templates/{}", template_id),
+      json!({ "template_id": template_id, "options": options }),
+    )
+  }
+
+  async fn delete_template(&self, template_id: &TemplateId) -> Result<Value> {
+    self.succeed(
+      "delete_template",
+      format!("templates/{}", template_id),
+      json!({ "template_id": template_id }),
+    )
+  }
+
+  async fn list_template_versions(&self, template_id: &TemplateId) -> Result<Value> {
+    self.record(
+      "list_template_versions",
+      format!("templates/{}/versions", template_id),
+      json!({ "template_id": template_id }),
+    );
+    Ok(json!([]))
+  }
+
+  async fn get_template_version(
+    &self,
+    template_id: &TemplateId,
+    version_id: &str,
+  ) -> Result<Value> {
+    self.succeed(
+      "get_template_version",
+      format!("templates/{}/versions/{}", template_id, version_id),
+      json!({ "template_id": template_id, "version_id": version_id }),
+    )
+  }
+
+  async fn update_template_version(
+    &self,
+    template_id: &TemplateId,
+    version_id: &str,
+    options: TemplateOptions,
+  ) -> Result<Value> {
+    self.succeed(
+      "update_template_version",
+      format!("templates/{}/versions/{}", template_id, version_id),
+      json!({ "template_id": template_id, "version_id": version_id, "options": options }),
+    )
+  }
+
+  async fn create_template_version(
+    &self,
+    template_id: &TemplateId,
+    options: TemplateOptions,
+  ) -> Result<Value> {
+    self.succeed(
+      "create_template_version",
+      format!("templates/{}/versions", template_id),
+      json!({ "template_id": template_id, "options": options }),
+    )
+  }
+
+  async fn drips_unsubscribe(&self, email_address: &str) -> Result<Value> {
+    self.succeed(
+      "drips_unsubscribe",
+      "drips/unsubscribe".to_string(),
+      json!({ "email_address": email_address }),
+    )
+  }
+
+  async fn unsubscribe_status(&self, email_address: &str) -> Result<UnsubscribeStatus> {
+    self.record(
+      "unsubscribe_status",
+      format!("unsubscribes/{}", email_address),
+      json!({ "email_address": email_address }),
+    );
+    Ok(UnsubscribeStatus {
+      email_address: email_address.to_string(),
+      unsubscribed: false,
+      unsubscribed_at: None,
+      reason: None,
+    })
+  }
+
+  async fn unsubscribe(&self, email_address: &str) -> Result<Value> {
+    self.succeed(
+      "unsubscribe",
+      "unsubscribes".to_string(),
+      json!({ "email_address": email_address }),
+    )
+  }
+
+  async fn resubscribe(&self, email_address: &str) -> Result<Value> {
+    self.succeed(
+      "resubscribe",
+      format!("unsubscribes/{}", email_address),
+      json!({ "email_address": email_address }),
+    )
+  }
+}
+
+/// Returns whether an email goes to an address, as the recipient or in CC or BCC.
+pub(crate) fn addressed_to(options: &EmailOptions, address: &str) -> bool {
+  std::iter::once(&options.recipient)
+    .chain(options.cc.iter().flatten())
+    .chain(options.bcc.iter().flatten())
+    .any(|recipient| recipient.address.eq_ignore_ascii_case(address))
+}
+
+const FNV_OFFSET_BASIS: u64 = 0xCBF2_9CE4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01B3;
+
+/// Feeds a JSON value into an FNV-1a hash.
+///
+/// Object keys are visited in sorted order, so the hash doesn't depend on
+/// the iteration order of the maps the value was serialized from.
+fn fingerprint(value: &Value, hash: &mut u64) {
+  match value {
+    Value::Object(fields) => {
+      let mut keys: Vec<&String> = fields.keys().collect();
+      keys.sort();
+      feed(hash, b"{");
+      for key in keys {
+        feed(hash, json!(key).to_string().as_bytes());
+        fingerprint(&fields[key], hash);
+      }
+      feed(hash, b"}");
+    }
+    Value::Array(items) => {
+      feed(hash, b"[");
+      for item in items {
+        fingerprint(item, hash);
+      }
+      feed(hash, b"]");
+    }
+    other => feed(hash, other.to_string().as_bytes()),
+  }
+}
+
+fn feed(hash: &mut u64, bytes: &[u8]) {
+  for byte in bytes {
+    *hash ^= u64::from(*byte);
+    *hash = hash.wrapping_mul(FNV_PRIME);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::types::Recipient;
+
+  #[tokio::test]
+  async fn test_responses_are_used_in_order() {
+    let api = MockApi::new()
+      .with_response("list_templates", json!([]))
+      .with_response_once("list_templates", json!([{"id": "tem_1"}]))
+      .with_error_once("list_templates", Error::ConnectionFailed { context: None });
+
+    assert_eq!(
+      api.list_templates().await.unwrap(),
+      json!([{"id": "tem_1"}])
+    );
+    assert!(matches!(
+      api.list_templates().await,
+      Err(Error::ConnectionFailed { context: None })
+    ));
+    assert_eq!(api.list_templates().await.unwrap(), json!([]));
+
+    assert!(matches!(
+      api.unsubscribe("user@example.com").await,
+      Err(Error::Unexpected(_))
+    ));
+  }
+
+  #[tokio::test]
+  async fn test_records_calls_and_verifies_expectations() {
+    let api = MockApi::new()
+      .with_error("customer_delete", || {
+        Error::InvalidEndpoint("customers".to_string())
+      })
+      .expect("customer_delete", 2)
+      .expect("send_email", 0);
+
+    assert!(api.customer_delete("a@example.com").await.is_err());
+    assert!(api.customer_delete("b@example.com").await.is_err());
+
+    let result = api
+      .send_email(EmailOptions::new("", Recipient::new("c@example.com")))
+      .await;
+    assert!(matches!(result, Err(Error::InvalidEmailOptions(_))));
+
+    let emails: Vec<Value> = api
+      .calls_to("customer_delete")
+      .into_iter()
+      .map(|call| call.args["email"].clone())
+      .collect();
+    assert_eq!(emails, vec![json!("a@example.com"), json!("b@example.com")]);
+    assert_eq!(api.calls().len(), 3);
+
+    let verify = std::panic::catch_unwind(|| api.verify());
+    assert!(verify.is_err());
+  }
+
+  #[test]
+  #[should_panic(expected = "not an ApiClient method")]
+  fn test_unknown_method_panics() {
+    let _ = MockApi::new().with_response("send_mail", json!({}));
+  }
+
+  #[tokio::test]
+  async fn test_recording_client_records_sends() {
+    let api = RecordingApiClient::new();
+
+    let mut options = EmailOptions::new("tem_welcome", Recipient::new("ada@example.com"));
+    options.cc = Some(vec![Recipient::new("Grace@Example.com")]);
+    let receipt = api.send_email(options).await.unwrap();
+    api
+      .send_email(EmailOptions::new(
+        "tem_reset",
+        Recipient::new("ada@example.com"),
+      ))
+      .await
+      .unwrap();
+    api.customer_delete("grace@example.com").await.unwrap();
+
+    assert_eq!(api.sent_to("ada@example.com").len(), 2);
+    assert_eq!(api.sent_to("grace@example.com")[0].email_id, "tem_welcome");
+    assert_eq!(api.sent_with_template("tem_reset").len(), 1);
+
+    let endpoints: Vec<String> = api.calls().into_iter().map(|call| call.endpoint).collect();
+    assert_eq!(
+      endpoints,
+      vec!["send", "send", "customers/grace@example.com"]
+    );
+
+    let log_id = LogId::new(receipt.receipt_id.as_str()).unwrap();
+    let log = api.log(&log_id).await.unwrap();
+    assert_eq!(log.recipient_address, "ada@example.com");
+    assert_eq!(
+      log.email_id.as_ref().map(TemplateId::as_str),
+      Some("tem_welcome")
+    );
+
+    api.clear();
+    assert!(api.calls().is_empty());
+    assert!(api.sent().is_empty());
+  }
+
+  #[tokio::test]
+  async fn test_deterministic_responses_depend_only_on_the_request() {
+    let welcome = EmailOptions::new("tem_welcome", Recipient::new("ada@example.com")).with_data(
+      HashMap::from([
+        ("plan".to_string(), json!("pro")),
+        ("seats".to_string(), json!(3)),
+        ("team".to_string(), json!({ "name": "Engines", "size": 4 })),
+      ]),
+    );
+    let reset = EmailOptions::new("tem_reset", Recipient::new("ada@example.com"));
+
+    let first = RecordingApiClient::deterministic();
+    let welcome_receipt = first.send_email(welcome.clone()).await.unwrap();
+    let reset_receipt = first.send_email(reset.clone()).await.unwrap();
+
+    let second = RecordingApiClient::deterministic();
+    assert_eq!(
+      second.send_email(reset).await.unwrap().receipt_id,
+      reset_receipt.receipt_id
+    );
+    assert_eq!(second.send_email(welcome).await.unwrap(), welcome_receipt);
+    assert_ne!(welcome_receipt.receipt_id, reset_receipt.receipt_id);
+    assert_eq!(welcome_receipt.email.name, "tem_welcome");
+
+    let log_id = LogId::new(welcome_receipt.receipt_id.as_str()).unwrap();
+    let log = second.log(&log_id).await.unwrap();
+    assert_eq!(log.created, FIXTURE_TIMESTAMP);
+    assert_eq!(
+      log.email_id.as_ref().map(TemplateId::as_str),
+      Some("tem_welcome")
+    );
+  }
+}
@@ -0,0 +1,781 @@
+use crate::ids::{LogId, ReceiptId, TemplateId};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Converts a Unix timestamp in seconds to a UTC date and time.
+#[cfg(feature = "chrono")]
+fn timestamp_to_datetime(timestamp: i64) -> Option<chrono::DateTime<chrono::Utc>> {
+  chrono::DateTime::from_timestamp(timestamp, 0)
+}
+
+/// Result of sending an email.
+///
+/// The `receipt_id` identifies the log entry created for the send and can be
+/// stored to look up delivery status later.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::models::SendResponse;
+///
+/// let response: SendResponse = serde_json::from_str(r#"{
+///   "success": true,
+///   "status": "OK",
+///   "receipt_id": "log_abc123",
+///   "email": {"name": "Welcome", "version_name": "Version A", "locale": "en-US"}
+/// }"#).unwrap();
+///
+/// assert_eq!(response.receipt_id.as_str(), "log_abc123");
+/// assert_eq!(response.email.name, "Welcome");
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SendResponse {
+  /// Whether the send was accepted
+  pub success: bool,
+
+  /// Status message returned by the API (e.g. "OK")
+  pub status: String,
+
+  /// Receipt identifying the log entry for this send
+  pub receipt_id: ReceiptId,
+
+  /// Summary of the template that was sent
+  pub email: EmailSummary,
+}
+
+/// Summary of the template used for a send.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EmailSummary {
+  /// Template name
+  pub name: String,
+
+  /// Name of the template version that was sent
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub version_name: Option<String>,
+
+  /// Locale of the template version that was sent
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub locale: Option<String>,
+}
+
+/// A log entry recorded for an email sent through SendWithUs.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::models::EmailLog;
+///
+/// let log: EmailLog = serde_json::from_str(r#"{
+///   "id": "log_abc123",
+///   "created": 1700000000,
+///   "recipient_address": "user@example.com",
+///   "status": "sent",
+///   "email_id": "tem_abc123",
+///   "email_name": "Welcome"
+/// }"#).unwrap();
+///
+/// assert_eq!(log.id.as_str(), "log_abc123");
+/// assert_eq!(log.status, "sent");
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EmailLog {
+  /// Log ID
+  pub id: LogId,
+
+  /// When the email was sent (Unix timestamp)
+  pub created: i64,
+
+  /// Recipient's email address
+  pub recipient_address: String,
+
+  /// Recipient's name (optional)
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub recipient_name: Option<String>,
+
+  /// Latest delivery status reported for the email
+  pub status: String,
+
+  /// Message returned by the email service provider
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub message: Option<String>,
+
+  /// ID of the template that was sent
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub email_id: Option<TemplateId>,
+
+  /// Name of the template that was sent
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub email_name: Option<String>,
+
+  /// Name of the template version that was sent
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub email_version: Option<String>,
+}
+
+#[cfg(feature = "chrono")]
+impl EmailLog {
+  /// Returns when the email was sent.
+  ///
+  /// # Returns
+  /// The send time in UTC, or `None` if the timestamp is out of range
+  pub fn created_at(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+    timestamp_to_datetime(self.created)
+  }
+}
+
+/// Type of an event recorded against an email log or reported by a webhook.
+///
+/// SendWithUs reports event types in both present (`open`) and past
+/// (`opened`) tense depending on the ESP; both forms map to the same variant.
+/// Types this crate doesn't know about are preserved in `Other`. Webhooks
+/// use the same type, re-exported as `webhooks::Event`, so webhook events
+/// compare directly with log events.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::models::EventType;
+///
+/// assert_eq!(EventType::from("open"), EventType::Opened);
+/// assert_eq!(EventType::from("bounced"), EventType::Bounced);
+/// assert_eq!(EventType::from("unsubscribe"), EventType::Unsubscribed);
+/// assert_eq!(EventType::from("spam"), EventType::Other("spam".to_string()));
+/// assert_eq!(EventType::Clicked.as_str(), "clicked");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum EventType {
+  /// The email was handed to the email service provider
+  Sent,
+
+  /// The email was accepted by the recipient's mail server
+  Delivered,
+
+  /// The recipient opened the email
+  Opened,
+
+  /// The recipient clicked a link in the email
+  Clicked,
+
+  /// The email bounced
+  Bounced,
+
+  /// The email service provider declined to send the email
+  Dropped,
+
+  /// The recipient unsubscribed
+  Unsubscribed,
+
+  /// An event type not covered by the other variants
+  Other(String),
+}
+
+impl EventType {
+  /// Returns the canonical name of the event type.
+  pub fn as_str(&self) -> &str {
+    match self {
+      EventType::Sent => "sent",
+      EventType::Delivered => "delivered",
+      EventType::Opened => "opened",
+      EventType::Clicked => "clicked",
+      EventType::Bounced => "bounced",
+      EventType::Dropped => "dropped",
+      EventType::Unsubscribed => "unsubscribed",
+      EventType::Other(other) => other,
+    }
+  }
+}
+
+impl From<&str> for EventType {
+  fn from(value: &str) -> Self {
+    match value {
+      "send" | "sent" => EventType::Sent,
+      "deliver" | "delivered" => EventType::Delivered,
+      "open" | "opened" => EventType::Opened,
+      "click" | "clicked" => EventType::Clicked,
+      "bounce" | "bounced" => EventType::Bounced,
+      "drop" | "dropped" => EventType::Dropped,
+      "unsubscribe" | "unsubscribed" => EventType::Unsubscribed,
+      other => EventType::Other(other.to_string()),
+    }
+  }
+}
+
+impl From<String> for EventType {
+  fn from(value: String) -> Self {
+    EventType::from(value.as_str())
+  }
+}
+
+impl From<EventType> for String {
+  fn from(event_type: EventType) -> Self {
+    match event_type {
+      EventType::Other(other) => other,
+      known => known.as_str().to_string(),
+    }
+  }
+}
+
+impl fmt::Display for EventType {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+/// An event recorded against an email log, such as an open or a bounce.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::models::{EventType, LogEvent};
+///
+/// let event: LogEvent = serde_json::from_str(
+///   r#"{"type": "open", "created": 1700000000, "message": "Opened"}"#,
+/// ).unwrap();
+///
+/// assert_eq!(event.event_type, EventType::Opened);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LogEvent {
+  /// What happened
+  #[serde(rename = "type")]
+  pub event_type: EventType,
+
+  /// When the event happened (Unix timestamp)
+  pub created: i64,
+
+  /// Message returned by the email service provider
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub message: Option<String>,
+}
+
+#[cfg(feature = "chrono")]
+impl LogEvent {
+  /// Returns when the event happened.
+  ///
+  /// # Returns
+  /// The event time in UTC, or `None` if the timestamp is out of range
+  pub fn created_at(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+    timestamp_to_datetime(self.created)
+  }
+}
+
+impl LogEvent {
+  /// Returns `true` if the event happened at or after `from` and before `to`.
+  ///
+  /// # Arguments
+  /// * `from` - Start of the window (Unix timestamp, inclusive)
+  /// * `to` - End of the window (Unix timestamp, exclusive)
+  pub fn happened_between(&self, from: i64, to: i64) -> bool {
+    (from..to).contains(&self.created)
+  }
+}
+
+/// Filtering helpers for the events of an email log, as returned by `log_events`.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::models::{EventType, LogEvent, LogEventsExt};
+///
+/// let events: Vec<LogEvent> = serde_json::from_str(r#"[
+///   {"type": "sent", "created": 1700000000},
+///   {"type": "open", "created": 1700000600},
+///   {"type": "click", "created": 1700000900}
+/// ]"#).unwrap();
+///
+/// assert!(events.opened_after(1700000000));
+/// assert!(!events.opened_after(1700000600));
+/// assert_eq!(events.of_type(&EventType::Clicked).len(), 1);
+/// assert_eq!(events.between(1700000500, 1700001000).len(), 2);
+/// ```
+pub trait LogEventsExt {
+  /// Returns the events of the given type, in their original order.
+  fn of_type(&self, event_type: &EventType) -> Vec<&LogEvent>;
+
+  /// Returns the events that happened at or after `from` and before `to`.
+  fn between(&self, from: i64, to: i64) -> Vec<&LogEvent>;
+
+  /// Returns the most recent event of the given type.
+  fn latest(&self, event_type: &EventType) -> Option<&LogEvent>;
+
+  /// Returns `true` if an event of the given type happened after `timestamp`.
+  fn has_after(&self, event_type: &EventType, timestamp: i64) -> bool;
+
+  /// Returns `true` if the email was opened after `timestamp`.
+  fn opened_after(&self, timestamp: i64) -> bool {
+    self.has_after(&EventType::Opened, timestamp)
+  }
+
+  /// Returns `true` if a link in the email was clicked after `timestamp`.
+  fn clicked_after(&self, timestamp: i64) -> bool {
+    self.has_after(&EventType::Clicked, timestamp)
+  }
+
+  /// Returns `true` if the email bounced.
+  fn bounced(&self) -> bool {
+    self.latest(&EventType::Bounced).is_some()
+  }
+}
+
+impl LogEventsExt for [LogEvent] {
+  fn of_type(&self, event_type: &EventType) -> Vec<&LogEvent> {
+    self
+      .iter()
+      .filter(|event| &event.event_type == event_type)
+      .collect()
+  }
+
+  fn between(&self, from: i64, to: i64) -> Vec<&LogEvent> {
+    self
+      .iter()
+      .filter(|event| event.happened_between(from, to))
+      .collect()
+  }
+
+  fn latest(&self, event_type: &EventType) -> Option<&LogEvent> {
+    self
+      .iter()
+      .filter(|event| &event.event_type == event_type)
+      .max_by_key(|event| event.created)
+  }
+
+  fn has_after(&self, event_type: &EventType, timestamp: i64) -> bool {
+    self
+      .iter()
+      .any(|event| &event.event_type == event_type && event.created > timestamp)
+  }
+}
+
+/// Response body of the customer email log endpoint.
+#[derive(Debug, Deserialize)]
+pub(crate) struct EmailLogs {
+  #[serde(default)]
+  pub logs: Vec<EmailLog>,
+}
+
+/// An email template, as returned by `list_templates_as` and `get_template_as`.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::models::Template;
+///
+/// let template: Template = serde_json::from_str(r#"{
+///   "id": "tem_abc123",
+///   "name": "Welcome",
+///   "tags": ["onboarding"],
+///   "versions": [{"id": "ver_abc123", "name": "Original", "published": true}]
+/// }"#).unwrap();
+///
+/// assert_eq!(template.name, "Welcome");
+/// assert!(template.versions[0].published);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Template {
+  /// Template ID
+  pub id: TemplateId,
+
+  /// Template name
+  pub name: String,
+
+  /// Tags assigned to the template
+  #[serde(default)]
+  pub tags: Vec<String>,
+
+  /// The template's versions; listings only include their names and IDs
+  #[serde(default)]
+  pub versions: Vec<TemplateVersion>,
+
+  /// When the template was created (Unix timestamp)
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub created: Option<i64>,
+}
+
+/// A version of an email template.
+///
+/// Content fields are only present when the version is fetched on its own
+/// with `get_template_version_as`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TemplateVersion {
+  /// Version ID
+  pub id: String,
+
+  /// Version name
+  pub name: String,
+
+  /// Whether this is the version sent by default
+  #[serde(default)]
+  pub published: bool,
+
+  /// Email subject line
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub subject: Option<String>,
+
+  /// HTML content
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub html: Option<String>,
+
+  /// Plain text content
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub text: Option<String>,
+
+  /// Preheader text
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub preheader: Option<String>,
+
+  /// AMP HTML content
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub amp_html: Option<String>,
+
+  /// When the version was created (Unix timestamp)
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub created: Option<i64>,
+}
+
+/// A template rendered by SendWithUs, as returned by `render_as`.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::models::RenderedTemplate;
+///
+/// let rendered: RenderedTemplate = serde_json::from_str(r#"{
+///   "success": true,
+///   "status": "OK",
+///   "subject": "Welcome, Ada",
+///   "html": "<p>Hi Ada</p>",
+///   "text": "Hi Ada"
+/// }"#).unwrap();
+///
+/// assert_eq!(rendered.subject, "Welcome, Ada");
+/// assert_eq!(rendered.text.as_deref(), Some("Hi Ada"));
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct RenderedTemplate {
+  /// Rendered subject line
+  #[serde(default)]
+  pub subject: String,
+
+  /// Rendered HTML content
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub html: Option<String>,
+
+  /// Rendered plain text content
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub text: Option<String>,
+}
+
+/// A single step of a drip campaign.
+///
+/// Each step sends one template after a delay relative to the previous step.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::models::DripCampaignStep;
+///
+/// let step: DripCampaignStep = serde_json::from_str(
+///   r#"{"id": "dcs_123", "email_id": "tem_123", "delay_seconds": 86400}"#,
+/// ).unwrap();
+///
+/// assert_eq!(step.delay_seconds, Some(86400));
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DripCampaignStep {
+  /// Step ID
+  pub id: String,
+
+  /// Step name (optional)
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub name: Option<String>,
+
+  /// ID of the template sent by this step
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub email_id: Option<TemplateId>,
+
+  /// Delay before this step is sent, in seconds
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub delay_seconds: Option<u64>,
+}
+
+/// A customer currently active on a drip campaign.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::models::DripCampaignCustomer;
+///
+/// let customer: DripCampaignCustomer = serde_json::from_str(
+///   r#"{"email": "user@example.com", "step_id": "dcs_123"}"#,
+/// ).unwrap();
+///
+/// assert_eq!(customer.email, "user@example.com");
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DripCampaignCustomer {
+  /// Customer's email address
+  pub email: String,
+
+  /// ID of the step the customer is currently on
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub step_id: Option<String>,
+
+  /// Membership status as reported by SendWithUs
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub status: Option<String>,
+
+  /// When the customer was added to the campaign (Unix timestamp)
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub created: Option<i64>,
+}
+
+#[cfg(feature = "chrono")]
+impl DripCampaignCustomer {
+  /// Returns when the customer was added to the campaign.
+  ///
+  /// # Returns
+  /// The time in UTC, or `None` if SendWithUs didn't report it
+  pub fn created_at(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+    self.created.and_then(timestamp_to_datetime)
+  }
+}
+
+/// Response body of the campaign customers endpoint.
+#[derive(Debug, Deserialize)]
+pub(crate) struct DripCampaignCustomers {
+  #[serde(default)]
+  pub customers: Vec<DripCampaignCustomer>,
+}
+
+/// Unsubscribe status of an email address.
+///
+/// SendWithUs will not deliver to an unsubscribed address, so checking the
+/// status first avoids sends that are guaranteed to be dropped.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::models::UnsubscribeStatus;
+///
+/// let status: UnsubscribeStatus = serde_json::from_str(
+///   r#"{"email_address": "user@example.com", "unsubscribed": true, "unsubscribed_at": 1700000000}"#,
+/// ).unwrap();
+///
+/// assert!(status.unsubscribed);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UnsubscribeStatus {
+  /// The email address the status applies to
+  pub email_address: String,
+
+  /// Whether the address is currently unsubscribed
+  pub unsubscribed: bool,
+
+  /// When the address unsubscribed (Unix timestamp)
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub unsubscribed_at: Option<i64>,
+
+  /// Reason recorded for the unsubscribe, if any
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub reason: Option<String>,
+}
+
+#[cfg(feature = "chrono")]
+impl UnsubscribeStatus {
+  /// Returns when the address unsubscribed.
+  ///
+  /// # Returns
+  /// The time in UTC, or `None` if the address isn't unsubscribed
+  pub fn unsubscribed_at(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+    self.unsubscribed_at.and_then(timestamp_to_datetime)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  #[test]
+  fn test_send_response_deserialize() {
+    let response: SendResponse = serde_json::from_value(json!({
+      "success": true,
+      "status": "OK",
+      "receipt_id": "log_123",
+      "email": {"name": "Welcome", "version_name": "Version A"}
+    }))
+    .unwrap();
+
+    assert!(response.success);
+    assert_eq!(response.status, "OK");
+    assert_eq!(response.receipt_id.as_str(), "log_123");
+    assert_eq!(response.email.version_name.as_deref(), Some("Version A"));
+    assert_eq!(response.email.locale, None);
+
+    let result: Result<SendResponse, _> = serde_json::from_value(json!({
+      "success": true,
+      "status": "OK",
+      "receipt_id": "tem_123",
+      "email": {"name": "Welcome"}
+    }));
+    assert!(result.is_err());
+  }
+
+  #[test]
+  fn test_email_log_deserialize() {
+    let log: EmailLog = serde_json::from_value(json!({
+      "id": "log_123",
+      "object": "log",
+      "created": 1700000000,
+      "recipient_name": "",
+      "recipient_address": "user@example.com",
+      "status": "sent",
+      "message": "Message has been successfully delivered",
+      "email_id": "tem_123",
+      "email_name": "Welcome",
+      "email_version": "Original Version"
+    }))
+    .unwrap();
+
+    assert_eq!(log.id.as_str(), "log_123");
+    assert_eq!(log.created, 1700000000);
+    assert_eq!(log.recipient_address, "user@example.com");
+    assert_eq!(log.email_version.as_deref(), Some("Original Version"));
+
+    let logs: EmailLogs = serde_json::from_value(json!({"success": true})).unwrap();
+    assert!(logs.logs.is_empty());
+  }
+
+  #[test]
+  fn test_event_type_mapping() {
+    for (raw, expected) in [
+      ("send", EventType::Sent),
+      ("sent", EventType::Sent),
+      ("delivered", EventType::Delivered),
+      ("open", EventType::Opened),
+      ("click", EventType::Clicked),
+      ("bounce", EventType::Bounced),
+      ("dropped", EventType::Dropped),
+      ("unsubscribe", EventType::Unsubscribed),
+      ("spam", EventType::Other("spam".to_string())),
+    ] {
+      let event: LogEvent = serde_json::from_value(json!({"type": raw, "created": 1})).unwrap();
+      assert_eq!(event.event_type, expected);
+    }
+
+    let event = LogEvent {
+      event_type: EventType::Opened,
+      created: 1,
+      message: None,
+    };
+    assert_eq!(
+      serde_json::to_value(&event).unwrap(),
+      json!({"type": "opened", "created": 1})
+    );
+    assert_eq!(EventType::Other("spam".to_string()).to_string(), "spam");
+  }
+
+  #[test]
+  fn test_drip_campaign_step_deserialize() {
+    let step: DripCampaignStep = serde_json::from_value(json!({
+      "id": "dcs_123",
+      "object": "drip_step",
+      "email_id": "tem_123",
+      "delay_seconds": 0
+    }))
+    .unwrap();
+
+    assert_eq!(step.id, "dcs_123");
+    assert_eq!(step.name, None);
+    assert_eq!(
+      step.email_id.as_ref().map(TemplateId::as_str),
+      Some("tem_123")
+    );
+    assert_eq!(step.delay_seconds, Some(0));
+  }
+
+  #[test]
+  fn test_drip_campaign_customers_deserialize() {
+    let response: DripCampaignCustomers = serde_json::from_value(json!({
+      "id": "dc_123",
+      "customers": [
+        {"email": "a@example.com", "step_id": "dcs_1", "status": "active", "created": 1700000000},
+        {"email": "b@example.com"}
+      ]
+    }))
+    .unwrap();
+
+    assert_eq!(response.customers.len(), 2);
+    assert_eq!(response.customers[0].created, Some(1700000000));
+    assert_eq!(response.customers[1].step_id, None);
+
+    let response: DripCampaignCustomers = serde_json::from_value(json!({"id": "dc_123"})).unwrap();
+    assert!(response.customers.is_empty());
+  }
+
+  #[test]
+  fn test_log_events_ext() {
+    let events: Vec<LogEvent> = serde_json::from_value(json!([
+      {"type": "sent", "created": 100},
+      {"type": "opened", "created": 200},
+      {"type": "open", "created": 300},
+      {"type": "bounce", "created": 400}
+    ]))
+    .unwrap();
+
+    assert_eq!(events.of_type(&EventType::Opened).len(), 2);
+    assert_eq!(events.latest(&EventType::Opened).unwrap().created, 300);
+    assert!(events.latest(&EventType::Clicked).is_none());
+    assert!(events.opened_after(299));
+    assert!(!events.opened_after(300));
+    assert!(!events.clicked_after(0));
+    assert!(events.bounced());
+
+    let window: Vec<_> = events.between(200, 400).iter().map(|e| e.created).collect();
+    assert_eq!(window, [200, 300]);
+    assert!(events[0].happened_between(100, 101));
+    assert!(!events[0].happened_between(101, 200));
+  }
+
+  #[test]
+  fn test_unsubscribe_status_deserialize() {
+    let status: UnsubscribeStatus = serde_json::from_value(json!({
+      "email_address": "user@example.com",
+      "unsubscribed": false
+    }))
+    .unwrap();
+
+    assert!(!status.unsubscribed);
+    assert_eq!(status.unsubscribed_at, None);
+    assert_eq!(status.reason, None);
+  }
+
+  #[cfg(feature = "chrono")]
+  #[test]
+  fn test_timestamps_as_datetimes() {
+    use chrono::{TimeZone, Utc};
+
+    let expected = Utc.with_ymd_and_hms(2023, 11, 14, 22, 13, 20).unwrap();
+
+    let event: LogEvent =
+      serde_json::from_value(json!({"type": "open", "created": 1700000000})).unwrap();
+    assert_eq!(event.created_at(), Some(expected));
+
+    let customer: DripCampaignCustomer =
+      serde_json::from_value(json!({"email": "a@example.com", "created": 1700000000})).unwrap();
+    assert_eq!(customer.created_at(), Some(expected));
+
+    let customer: DripCampaignCustomer =
+      serde_json::from_value(json!({"email": "a@example.com"})).unwrap();
+    assert_eq!(customer.created_at(), None);
+
+    let status: UnsubscribeStatus = serde_json::from_value(json!({
+      "email_address": "user@example.com",
+      "unsubscribed": true,
+      "unsubscribed_at": 1700000000
+    }))
+    .unwrap();
+    assert_eq!(status.unsubscribed_at(), Some(expected));
+  }
+}
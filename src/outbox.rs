@@ -0,0 +1,374 @@
+use crate::api::ApiClient;
+use crate::error::Result;
+use crate::file_store::{JsonFileStore, unique_id};
+use crate::models::SendResponse;
+use crate::types::EmailOptions;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// An email recorded in the outbox before it is sent.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OutboxEntry {
+  /// Unique identifier of the entry
+  pub id: String,
+
+  /// The email to send
+  pub options: EmailOptions,
+
+  /// When the entry was recorded (Unix timestamp)
+  pub created_at: u64,
+}
+
+impl OutboxEntry {
+  /// Creates an entry with a new unique ID.
+  ///
+  /// # Arguments
+  /// * `options` - The email to send
+  ///
+  /// # Returns
+  /// A new OutboxEntry
+  pub fn new(options: EmailOptions) -> Self {
+    let (id, now) = unique_id();
+
+    Self {
+      id,
+      options,
+      created_at: now.as_secs(),
+    }
+  }
+}
+
+/// Storage for outbox entries.
+///
+/// Implement this trait to keep the outbox somewhere other than the local
+/// file system, such as a database table. Entries must survive a process
+/// crash for recovery to work.
+#[async_trait]
+pub trait OutboxStore: Send + Sync {
+  /// Durably records an entry that is about to be sent.
+  async fn put(&self, entry: &OutboxEntry) -> Result<()>;
+
+  /// Marks an entry as sent, removing it from the pending entries.
+  async fn complete(&self, id: &str) -> Result<()>;
+
+  /// Returns all entries that have not been completed, oldest first.
+  ///
+  /// An entry that can't be read shouldn't keep the others from being
+  /// recovered; skip it and make it available for inspection instead.
+  async fn pending(&self) -> Result<Vec<OutboxEntry>>;
+}
+
+/// An outbox store keeping one JSON file per pending entry in a directory.
+///
+/// # Examples
+///
+/// ```no_run
+/// use send_with_us::outbox::FileOutboxStore;
+///
+/// let store = FileOutboxStore::new("/var/lib/my-app/outbox");
+/// ```
+#[derive(Debug, Clone)]
+pub struct FileOutboxStore {
+  files: JsonFileStore,
+}
+
+impl FileOutboxStore {
+  /// Creates a store that keeps entries in `dir`.
+  ///
+  /// The directory is created on first use if it doesn't exist.
+  ///
+  /// # Arguments
+  /// * `dir` - Directory for pending entries
+  ///
+  /// # Returns
+  /// A new FileOutboxStore
+  pub fn new(dir: impl Into<PathBuf>) -> Self {
+    Self {
+      files: JsonFileStore::new(dir.into()),
+    }
+  }
+
+  /// Returns the files of entries that couldn't be read, oldest name first.
+  ///
+  /// `pending` moves an entry file it can't parse aside, renaming it with a
+  /// `.corrupt` extension, so one damaged file doesn't block recovery of
+  /// the others. Inspect these files and delete them once dealt with.
+  ///
+  /// # Errors
+  /// Returns an error if the directory can't be read
+  pub async fn quarantined(&self) -> Result<Vec<PathBuf>> {
+    self.files.quarantined().await
+  }
+}
+
+#[async_trait]
+impl OutboxStore for FileOutboxStore {
+  async fn put(&self, entry: &OutboxEntry) -> Result<()> {
+    self.files.write(&entry.id, entry).await
+  }
+
+  async fn complete(&self, id: &str) -> Result<()> {
+    self.files.remove(id).await
+  }
+
+  async fn pending(&self) -> Result<Vec<OutboxEntry>> {
+    let mut entries: Vec<OutboxEntry> = self.files.read_all().await?;
+    entries.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+    Ok(entries)
+  }
+}
+
+/// Sends emails through an outbox so they can be resumed after a crash.
+///
+/// Every email is recorded in the store before it is sent and marked complete
+/// once SendWithUs accepts it. If the process dies in between, `recover`
+/// sends whatever is still pending. An email may therefore be sent twice if
+/// the crash happens after SendWithUs accepted it but before it was marked
+/// complete, but it is never silently lost.
+///
+/// Sends that fail stay pending for the next recovery, unless the request
+/// itself was rejected (`Error::is_client_error`), since retrying those
+/// can't succeed. That includes failures where it's unknown whether
+/// SendWithUs accepted the email, such as a connection reset while reading
+/// the response, so such an email may be sent twice but is never dropped.
+///
+/// # Examples
+///
+/// ```no_run
+/// use send_with_us::Api;
+/// use send_with_us::outbox::{FileOutboxStore, Outbox};
+/// use send_with_us::types::{EmailOptions, Recipient};
+///
+/// # async fn run() -> send_with_us::Result<()> {
+/// let outbox = Outbox::new(
+///   Api::with_api_key("api-key"),
+///   FileOutboxStore::new("/var/lib/my-app/outbox"),
+/// );
+///
+/// // On startup, finish anything interrupted by a crash.
+/// for (id, result) in outbox.recover().await? {
+///   if let Err(err) = result {
+///     eprintln!("outbox entry {} still pending: {}", id, err);
+///   }
+/// }
+///
+/// let options = EmailOptions::new("tem_123", Recipient::new("user@example.com"));
+/// outbox.send(options).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Outbox<C, S> {
+  client: C,
+  store: S,
+}
+
+impl<C: ApiClient, S: OutboxStore> Outbox<C, S> {
+  /// Creates an outbox sending through `client` and recording entries in `store`.
+  ///
+  /// # Arguments
+  /// * `client` - Client used to send emails
+  /// * `store` - Store for pending entries
+  ///
+  /// # Returns
+  /// A new Outbox
+  pub fn new(client: C, store: S) -> Self {
+    Self { client, store }
+  }
+
+  /// Returns the underlying store.
+  pub fn store(&self) -> &S {
+    &self.store
+  }
+
+  /// Records an email in the outbox, sends it, and marks it complete.
+  ///
+  /// # Arguments
+  /// * `options` - The email to send
+  ///
+  /// # Returns
+  /// The send receipt
+  ///
+  /// # Errors
+  /// Returns an error if the entry cannot be stored or the email cannot be sent
+  pub async fn send(&self, options: EmailOptions) -> Result<SendResponse> {
+    let entry = OutboxEntry::new(options);
+    self.store.put(&entry).await?;
+    self.deliver(entry).await
+  }
+
+  /// Sends every pending entry left over from an earlier run.
+  ///
+  /// # Returns
+  /// The ID and outcome of each pending entry, oldest first
+  ///
+  /// # Errors
+  /// Returns an error only if the pending entries cannot be read
+  pub async fn recover(&self) -> Result<Vec<(String, Result<SendResponse>)>> {
+    let mut outcomes = Vec::new();
+
+    for entry in self.store.pending().await? {
+      let id = entry.id.clone();
+      outcomes.push((id, self.deliver(entry).await));
+    }
+
+    Ok(outcomes)
+  }
+
+  async fn deliver(&self, entry: OutboxEntry) -> Result<SendResponse> {
+    let result = self.client.send_email(entry.options).await;
+
+    let finished = match &result {
+      Ok(_) => true,
+      Err(error) => error.is_client_error() && !error.is_retryable(),
+    };
+    if finished {
+      self.store.complete(&entry.id).await?;
+    }
+
+    result
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::error::Error;
+  use crate::types::Recipient;
+  use crate::{Api, Config};
+  use tempdir::TempDir;
+
+  fn options(email: &str) -> EmailOptions {
+    EmailOptions::new("tem_123", Recipient::new(email))
+  }
+
+  fn api(url: &str) -> Api {
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(url).unwrap();
+    Api::new(config)
+  }
+
+  const SEND_OK: &str =
+    r#"{"success": true, "status": "OK", "receipt_id": "log_1", "email": {"name": "Welcome"}}"#;
+
+  #[tokio::test]
+  async fn test_file_store_round_trip() {
+    let dir = TempDir::new("outbox").unwrap();
+    let store = FileOutboxStore::new(dir.path().join("nested"));
+
+    assert!(store.pending().await.unwrap().is_empty());
+
+    let first = OutboxEntry::new(options("a@example.com"));
+    let second = OutboxEntry::new(options("b@example.com"));
+    assert_ne!(first.id, second.id);
+
+    store.put(&first).await.unwrap();
+    store.put(&second).await.unwrap();
+    assert_eq!(store.pending().await.unwrap(), vec![first.clone(), second]);
+
+    store.complete(&first.id).await.unwrap();
+    store.complete(&first.id).await.unwrap();
+    assert_eq!(store.pending().await.unwrap().len(), 1);
+  }
+
+  #[tokio::test]
+  async fn test_unreadable_entries_are_quarantined() {
+    let dir = TempDir::new("outbox").unwrap();
+    let store = FileOutboxStore::new(dir.path());
+
+    let entry = OutboxEntry::new(options("a@example.com"));
+    store.put(&entry).await.unwrap();
+    std::fs::write(dir.path().join("broken.json"), "{not json").unwrap();
+
+    assert_eq!(store.pending().await.unwrap(), vec![entry]);
+    assert_eq!(
+      store.quarantined().await.unwrap(),
+      vec![dir.path().join("broken.json.corrupt")]
+    );
+    assert_eq!(store.pending().await.unwrap().len(), 1);
+  }
+
+  #[tokio::test]
+  async fn test_send_completes_entry() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let mock = mock_server
+      .mock("POST", "/api/v1/send")
+      .with_status(200)
+      .with_body(SEND_OK)
+      .create();
+
+    let dir = TempDir::new("outbox").unwrap();
+    let outbox = Outbox::new(api(&mock_server.url()), FileOutboxStore::new(dir.path()));
+
+    let response = outbox.send(options("a@example.com")).await.unwrap();
+    assert_eq!(response.receipt_id.as_str(), "log_1");
+    assert!(outbox.store().pending().await.unwrap().is_empty());
+
+    mock.assert();
+  }
+
+  #[tokio::test]
+  async fn test_retryable_failures_stay_pending_until_recovered() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let failing = mock_server
+      .mock("POST", "/api/v1/send")
+      .with_status(503)
+      .with_body("Service Unavailable")
+      .create();
+
+    let dir = TempDir::new("outbox").unwrap();
+    let outbox = Outbox::new(api(&mock_server.url()), FileOutboxStore::new(dir.path()));
+
+    assert!(outbox.send(options("a@example.com")).await.is_err());
+    assert_eq!(outbox.store().pending().await.unwrap().len(), 1);
+    failing.assert();
+    failing.remove();
+
+    let ok = mock_server
+      .mock("POST", "/api/v1/send")
+      .with_status(200)
+      .with_body(SEND_OK)
+      .create();
+
+    let outcomes = outbox.recover().await.unwrap();
+    assert_eq!(outcomes.len(), 1);
+    assert!(outcomes[0].1.is_ok());
+    assert!(outbox.store().pending().await.unwrap().is_empty());
+    ok.assert();
+  }
+
+  #[tokio::test]
+  async fn test_sends_with_unknown_outcome_stay_pending() {
+    // Nothing listens on the port, so the send fails without an answer.
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    drop(listener);
+
+    let dir = TempDir::new("outbox").unwrap();
+    let outbox = Outbox::new(api(&url), FileOutboxStore::new(dir.path()));
+
+    let result = outbox.send(options("a@example.com")).await;
+    assert!(!result.unwrap_err().is_client_error());
+    assert_eq!(outbox.store().pending().await.unwrap().len(), 1);
+  }
+
+  #[tokio::test]
+  async fn test_rejected_sends_are_not_kept() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let mock = mock_server
+      .mock("POST", "/api/v1/send")
+      .with_status(400)
+      .with_body(r#"{"error": "Bad template"}"#)
+      .create();
+
+    let dir = TempDir::new("outbox").unwrap();
+    let outbox = Outbox::new(api(&mock_server.url()), FileOutboxStore::new(dir.path()));
+
+    let result = outbox.send(options("a@example.com")).await;
+    assert!(matches!(result, Err(Error::InvalidRequest { .. })));
+    assert!(outbox.store().pending().await.unwrap().is_empty());
+
+    mock.assert();
+  }
+}
@@ -0,0 +1,148 @@
+//! Local rendering of templates for previews.
+//!
+//! Available with the `preview` feature. `render_preview` renders a template
+//! version with the data from `RenderOptions` using a Jinja-compatible engine,
+//! without calling the render API. SendWithUs' own renderer supports
+//! extensions this engine doesn't (such as snippets and some filters), so
+//! previews are best-effort: use them for fast checks such as template CI,
+//! and `Api::render` when the output must match exactly.
+
+use crate::error::Result;
+use crate::models::TemplateVersion;
+use crate::types::RenderOptions;
+use minijinja::{Environment, UndefinedBehavior};
+
+/// A template version rendered locally.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RenderedPreview {
+  /// Rendered subject line
+  pub subject: String,
+
+  /// Rendered HTML content, with inserted values HTML-escaped
+  pub html: String,
+
+  /// Rendered plain text content
+  pub text: String,
+
+  /// Rendered preheader, if the version has one
+  pub preheader: Option<String>,
+}
+
+/// Renders a template version locally with the data of render options.
+///
+/// `options.template_data` provides the variables. In strict mode
+/// undefined variables are an error; otherwise they render as empty text.
+/// The template and version IDs and the locale in `options` are not used,
+/// since the version's content is passed in directly.
+///
+/// # Arguments
+/// * `version` - Template version to render, e.g. from `Api::get_template_version_as`
+/// * `options` - Render options providing the data and strictness
+///
+/// # Returns
+/// The rendered subject, HTML, text, and preheader
+///
+/// # Errors
+/// Returns `Error::PreviewFailed` if the template has a syntax error, or
+/// uses an undefined variable in strict mode
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::models::TemplateVersion;
+/// use send_with_us::preview::render_preview;
+/// use send_with_us::types::RenderOptions;
+/// use serde_json::json;
+/// use std::collections::HashMap;
+///
+/// let version: TemplateVersion = serde_json::from_value(json!({
+///   "id": "ver_1",
+///   "name": "Original",
+///   "subject": "Welcome, {{ name }}!",
+///   "html": "<p>Hi {{ name }}</p>",
+///   "text": "Hi {{ name|upper }}"
+/// })).unwrap();
+///
+/// let options = RenderOptions::new("tem_welcome")
+///   .with_data(HashMap::from([("name".to_string(), json!("Ada"))]));
+/// let preview = render_preview(&version, &options).unwrap();
+///
+/// assert_eq!(preview.subject, "Welcome, Ada!");
+/// assert_eq!(preview.text, "Hi ADA");
+/// ```
+pub fn render_preview(
+  version: &TemplateVersion,
+  options: &RenderOptions,
+) -> Result<RenderedPreview> {
+  let mut env = Environment::new();
+  env.set_undefined_behavior(if options.strict {
+    UndefinedBehavior::Strict
+  } else {
+    UndefinedBehavior::Lenient
+  });
+
+  // Template names pick the escaping: `.html` values are HTML-escaped.
+  let render = |name: &str, source: &Option<String>| -> Result<String> {
+    let source = source.as_deref().unwrap_or_default();
+    Ok(env.render_named_str(name, source, &options.template_data)?)
+  };
+
+  Ok(RenderedPreview {
+    subject: render("subject.txt", &version.subject)?,
+    html: render("body.html", &version.html)?,
+    text: render("body.txt", &version.text)?,
+    preheader: match &version.preheader {
+      Some(_) => Some(render("preheader.txt", &version.preheader)?),
+      None => None,
+    },
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::error::Error;
+  use serde_json::json;
+  use std::collections::HashMap;
+
+  fn version() -> TemplateVersion {
+    serde_json::from_value(json!({
+      "id": "ver_1",
+      "name": "Original",
+      "subject": "Order {{ order.id }}",
+      "html": "{% for item in order.items %}<li>{{ item }}</li>{% endfor %}{{ note }}",
+      "text": "{{ missing }}done"
+    }))
+    .unwrap()
+  }
+
+  #[test]
+  fn test_render_preview() {
+    let options = RenderOptions::new("tem_1").with_data(HashMap::from([
+      (
+        "order".to_string(),
+        json!({"id": 7, "items": ["Tea & cake"]}),
+      ),
+      ("note".to_string(), json!("<b>")),
+    ]));
+
+    let preview = render_preview(&version(), &options).unwrap();
+    assert_eq!(preview.subject, "Order 7");
+    assert_eq!(preview.html, "<li>Tea &amp; cake</li>&lt;b&gt;");
+    assert_eq!(preview.text, "done");
+    assert_eq!(preview.preheader, None);
+  }
+
+  #[test]
+  fn test_render_preview_strict_rejects_undefined_variables() {
+    let options = RenderOptions::new("tem_1")
+      .with_data(HashMap::from([
+        ("order".to_string(), json!({"id": 7, "items": []})),
+        ("note".to_string(), json!("")),
+      ]))
+      .strict();
+
+    let result = render_preview(&version(), &options);
+    assert!(matches!(result, Err(Error::PreviewFailed(_))));
+  }
+}
@@ -0,0 +1,272 @@
+//! Removal of personal data from diagnostic output.
+//!
+//! Debug output is scrubbed before it is written: email addresses in request
+//! and response bodies, in URL paths and query strings, and the original
+//! recipients recorded by recipient interception are always replaced, as is
+//! the value of any JSON key matching one of the configured patterns.
+
+use crate::config::{ORIGINAL_RECIPIENTS_HEADER, ORIGINAL_RECIPIENTS_KEY};
+use crate::request::REDACTED;
+use serde_json::Value;
+use std::borrow::Cow;
+use url::Url;
+
+/// JSON keys whose string values hold email addresses.
+const ADDRESS_KEYS: [&str; 5] = [
+  "address",
+  "recipient_address",
+  "email",
+  "email_address",
+  "reply_to",
+];
+
+/// JSON keys whose whole value lists recipient addresses.
+const RECIPIENT_LIST_KEYS: [&str; 2] = [ORIGINAL_RECIPIENTS_KEY, ORIGINAL_RECIPIENTS_HEADER];
+
+fn is_address(value: &str) -> bool {
+  value.contains('@') || value.contains("%40")
+}
+
+/// Returns `true` if `key` matches `pattern`, where `*` matches any run of characters.
+///
+/// Matching is case-insensitive, so `*_token` matches both `reset_token`
+/// and `API_TOKEN`.
+pub(crate) fn key_matches(pattern: &str, key: &str) -> bool {
+  let pattern = pattern.to_ascii_lowercase();
+  let key = key.to_ascii_lowercase();
+  let mut parts = pattern.split('*');
+
+  // The first part must be a prefix; `split` always yields at least one part.
+  let first = parts.next().unwrap_or_default();
+  let Some(mut rest) = key.strip_prefix(first) else {
+    return false;
+  };
+
+  let mut parts = parts.peekable();
+  while let Some(part) = parts.next() {
+    if parts.peek().is_none() {
+      return rest.ends_with(part);
+    }
+
+    match rest.find(part) {
+      Some(index) => rest = &rest[index + part.len()..],
+      None => return false,
+    }
+  }
+
+  rest.is_empty()
+}
+
+/// Replaces addresses and values of sensitive keys in a JSON value, in place.
+pub(crate) fn redact_value(value: &mut Value, keys: &[String]) {
+  match value {
+    Value::Object(map) => {
+      for (key, value) in map.iter_mut() {
+        let sensitive = keys.iter().any(|pattern| key_matches(pattern, key))
+          || RECIPIENT_LIST_KEYS
+            .iter()
+            .any(|list_key| list_key.eq_ignore_ascii_case(key))
+          || (value.is_string() && ADDRESS_KEYS.contains(&key.as_str()));
+
+        match value {
+          _ if sensitive => *value = Value::String(REDACTED.to_string()),
+          Value::Array(items) if ADDRESS_KEYS.contains(&key.as_str()) => {
+            for item in items {
+              match item {
+                Value::String(address) if is_address(address) => {
+                  *item = Value::String(REDACTED.to_string());
+                }
+                _ => redact_value(item, keys),
+              }
+            }
+          }
+          _ => redact_value(value, keys),
+        }
+      }
+    }
+    Value::Array(items) => {
+      for item in items {
+        redact_value(item, keys);
+      }
+    }
+    _ => {}
+  }
+}
+
+/// Redacts a body for diagnostic output.
+///
+/// JSON bodies are scrubbed key by key; anything else is returned unchanged.
+pub(crate) fn redact_body<'a>(body: &'a str, keys: &[String]) -> Cow<'a, str> {
+  match serde_json::from_str::<Value>(body) {
+    Ok(mut value) => {
+      redact_value(&mut value, keys);
+      Cow::Owned(value.to_string())
+    }
+    Err(_) => Cow::Borrowed(body),
+  }
+}
+
+/// Replaces path segments and query values of an endpoint that contain an
+/// email address, e.g. `customers/[REDACTED]/logs?count=10`.
+pub(crate) fn redact_endpoint(endpoint: &str) -> Cow<'_, str> {
+  if !is_address(endpoint) {
+    return Cow::Borrowed(endpoint);
+  }
+
+  let (path, query) = match endpoint.split_once('?') {
+    Some((path, query)) => (path, Some(query)),
+    None => (endpoint, None),
+  };
+
+  let mut redacted = path
+    .split('/')
+    .map(|segment| {
+      if is_address(segment) {
+        REDACTED
+      } else {
+        segment
+      }
+    })
+    .collect::<Vec<_>>()
+    .join("/");
+
+  if let Some(query) = query {
+    let pairs = query
+      .split('&')
+      .map(|pair| match pair.split_once('=') {
+        Some((key, value)) if is_address(value) => format!("{}={}", key, REDACTED),
+        _ => pair.to_string(),
+      })
+      .collect::<Vec<_>>();
+    redacted.push('?');
+    redacted.push_str(&pairs.join("&"));
+  }
+
+  Cow::Owned(redacted)
+}
+
+/// Replaces URL path segments and query values that contain an email address.
+pub(crate) fn redact_url(url: &Url) -> Url {
+  let mut redacted = url.clone();
+  let segments = url
+    .path_segments()
+    .map(|segments| {
+      segments
+        .map(|segment| {
+          if is_address(segment) {
+            REDACTED
+          } else {
+            segment
+          }
+        })
+        .collect::<Vec<_>>()
+    })
+    .unwrap_or_default();
+
+  if let Ok(mut path) = redacted.path_segments_mut() {
+    path.clear().extend(segments);
+  }
+
+  if url.query_pairs().any(|(_, value)| is_address(&value)) {
+    let pairs = url
+      .query_pairs()
+      .map(|(key, value)| {
+        if is_address(&value) {
+          (key, Cow::Borrowed(REDACTED))
+        } else {
+          (key, value)
+        }
+      })
+      .collect::<Vec<_>>();
+    redacted.query_pairs_mut().clear().extend_pairs(pairs);
+  }
+
+  redacted
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  #[test]
+  fn test_key_matches() {
+    assert!(key_matches("ssn", "ssn"));
+    assert!(key_matches("ssn", "SSN"));
+    assert!(!key_matches("ssn", "ssn_last4"));
+    assert!(key_matches("*_token", "reset_token"));
+    assert!(!key_matches("*_token", "token"));
+    assert!(key_matches("card_*", "card_number"));
+    assert!(key_matches("*secret*", "client_secret_key"));
+    assert!(key_matches("a*b*c", "a-b-c"));
+    assert!(!key_matches("a*b*c", "a-c"));
+    assert!(key_matches("*", "anything"));
+  }
+
+  #[test]
+  fn test_redact_body() {
+    let body = json!({
+      "email_id": "tem_123",
+      "recipient": {"address": "user@example.com", "name": "User"},
+      "cc": [{"address": "cc@example.com"}],
+      "email_data": {"reset_token": "abc", "ssn": "123-45-6789", "first_name": "Ann"}
+    })
+    .to_string();
+
+    let keys = vec!["*_token".to_string(), "ssn".to_string()];
+    let redacted: Value = serde_json::from_str(&redact_body(&body, &keys)).unwrap();
+
+    assert_eq!(redacted["email_id"], "tem_123");
+    assert_eq!(redacted["recipient"]["address"], REDACTED);
+    assert_eq!(redacted["recipient"]["name"], "User");
+    assert_eq!(redacted["cc"][0]["address"], REDACTED);
+    assert_eq!(redacted["email_data"]["reset_token"], REDACTED);
+    assert_eq!(redacted["email_data"]["ssn"], REDACTED);
+    assert_eq!(redacted["email_data"]["first_name"], "Ann");
+
+    let body = json!({
+      "email": ["a@example.com", "not an address"],
+      "email_data": {"original_recipients": {"to": "user@example.com", "cc": []}},
+      "headers": {"x-original-recipients": "user@example.com, cc@example.com"}
+    })
+    .to_string();
+    let redacted: Value = serde_json::from_str(&redact_body(&body, &[])).unwrap();
+    assert_eq!(redacted["email"], json!([REDACTED, "not an address"]));
+    assert_eq!(redacted["email_data"]["original_recipients"], REDACTED);
+    assert_eq!(redacted["headers"]["x-original-recipients"], REDACTED);
+
+    assert_eq!(redact_body("Bad Gateway", &keys), "Bad Gateway");
+  }
+
+  #[test]
+  fn test_redact_endpoint() {
+    assert_eq!(redact_endpoint("templates/tem_123"), "templates/tem_123");
+    assert_eq!(
+      redact_endpoint("customers/a%2Fb@example.com/logs?count=5"),
+      "customers/[REDACTED]/logs?count=5"
+    );
+    assert_eq!(
+      redact_endpoint("search?q=user%40example.com&n=1"),
+      "search?q=[REDACTED]&n=1"
+    );
+  }
+
+  #[test]
+  fn test_redact_url() {
+    let url = Url::parse("https://api.sendwithus.com/api/v1/customers/user@example.com").unwrap();
+    assert_eq!(
+      redact_url(&url).as_str(),
+      "https://api.sendwithus.com/api/v1/customers/[REDACTED]"
+    );
+
+    let url = Url::parse("https://api.sendwithus.com/api/v1/logs?count=10").unwrap();
+    assert_eq!(redact_url(&url), url);
+
+    let url =
+      Url::parse("https://api.sendwithus.com/api/v1/search?q=user%40example.com&n=1").unwrap();
+    assert_eq!(
+      redact_url(&url).as_str(),
+      "https://api.sendwithus.com/api/v1/search?q=%5BREDACTED%5D&n=1"
+    );
+  }
+}
@@ -0,0 +1,165 @@
+use crate::redaction;
+use reqwest::Method;
+use serde_json::Value;
+use url::Url;
+
+/// Name of the header carrying the SendWithUs API key.
+pub(crate) const API_KEY_HEADER: &str = "X-SWU-API-KEY";
+
+/// Name of the header carrying the correlation ID of an API call.
+pub const CORRELATION_ID_HEADER: &str = "X-Correlation-ID";
+
+/// Placeholder used in place of secret header values.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// A fully prepared HTTP request for the SendWithUs API.
+///
+/// This is exactly what the client puts on the wire for an operation: the
+/// method, the complete URL, every header, and the serialized JSON body.
+/// Prepared requests returned to callers (for example by `Api::simulate`)
+/// always have the API key replaced with `[REDACTED]`.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::Api;
+/// use serde_json::json;
+///
+/// let api = Api::with_api_key("secret-key");
+/// let prepared = api
+///   .simulate(reqwest::Method::POST, "render", Some(&json!({"template": "tem_123"})))
+///   .unwrap();
+///
+/// assert_eq!(prepared.url.as_str(), "https://api.sendwithus.com/api/v1/render");
+/// assert_eq!(prepared.header("X-SWU-API-KEY"), Some("[REDACTED]"));
+/// assert_eq!(prepared.body.as_deref(), Some(r#"{"template":"tem_123"}"#));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreparedRequest {
+  /// HTTP method
+  pub method: Method,
+
+  /// Complete request URL
+  pub url: Url,
+
+  /// Request headers in the order they are sent
+  pub headers: Vec<(String, String)>,
+
+  /// Serialized JSON body, if the request has one
+  pub body: Option<String>,
+}
+
+impl PreparedRequest {
+  /// Looks up a header value by name (case-insensitive).
+  ///
+  /// # Arguments
+  /// * `name` - Header name
+  ///
+  /// # Returns
+  /// The value of the first header with that name, if present
+  pub fn header(&self, name: &str) -> Option<&str> {
+    self
+      .headers
+      .iter()
+      .find(|(key, _)| key.eq_ignore_ascii_case(name))
+      .map(|(_, value)| value.as_str())
+  }
+
+  /// Parses the body as JSON.
+  ///
+  /// # Returns
+  /// The body as a JSON value, or `None` if there is no body or it isn't valid JSON
+  pub fn body_json(&self) -> Option<Value> {
+    self
+      .body
+      .as_deref()
+      .and_then(|body| serde_json::from_str(body).ok())
+  }
+
+  /// Returns a copy of the request with the API key header redacted.
+  pub(crate) fn redacted(&self) -> Self {
+    let mut redacted = self.clone();
+
+    for (key, value) in redacted.headers.iter_mut() {
+      if key.eq_ignore_ascii_case(API_KEY_HEADER) {
+        *value = REDACTED.to_string();
+      }
+    }
+
+    redacted
+  }
+
+  /// Returns a copy of the request that is safe to write to diagnostic output.
+  ///
+  /// Besides the API key, email addresses in the URL and body are redacted,
+  /// as are body values under keys matching any of `keys`.
+  pub(crate) fn sanitized(&self, keys: &[String]) -> Self {
+    let mut sanitized = self.redacted();
+    sanitized.url = redaction::redact_url(&sanitized.url);
+    sanitized.body = sanitized
+      .body
+      .map(|body| redaction::redact_body(&body, keys).into_owned());
+    sanitized
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn request() -> PreparedRequest {
+    PreparedRequest {
+      method: Method::POST,
+      url: Url::parse("https://api.sendwithus.com/api/v1/send").unwrap(),
+      headers: vec![
+        ("Content-Type".to_string(), "application/json".to_string()),
+        (API_KEY_HEADER.to_string(), "secret".to_string()),
+      ],
+      body: Some(r#"{"email_id":"tem_123"}"#.to_string()),
+    }
+  }
+
+  #[test]
+  fn test_header_lookup_is_case_insensitive() {
+    let request = request();
+    assert_eq!(request.header("content-type"), Some("application/json"));
+    assert_eq!(request.header("x-swu-api-key"), Some("secret"));
+    assert_eq!(request.header("missing"), None);
+  }
+
+  #[test]
+  fn test_redacted_hides_api_key() {
+    let redacted = request().redacted();
+    assert_eq!(redacted.header(API_KEY_HEADER), Some(REDACTED));
+    assert_eq!(redacted.header("Content-Type"), Some("application/json"));
+    assert_eq!(redacted.body, request().body);
+  }
+
+  #[test]
+  fn test_sanitized_hides_personal_data() {
+    let mut request = request();
+    request.url =
+      Url::parse("https://api.sendwithus.com/api/v1/customers/user@example.com").unwrap();
+    request.body = Some(
+      r#"{"recipient":{"address":"user@example.com"},"email_data":{"reset_token":"abc"}}"#
+        .to_string(),
+    );
+
+    let sanitized = request.sanitized(&["*_token".to_string()]);
+    assert_eq!(sanitized.header(API_KEY_HEADER), Some(REDACTED));
+    assert!(!sanitized.url.as_str().contains("user@example.com"));
+
+    let body = sanitized.body_json().unwrap();
+    assert_eq!(body["recipient"]["address"], REDACTED);
+    assert_eq!(body["email_data"]["reset_token"], REDACTED);
+  }
+
+  #[test]
+  fn test_body_json() {
+    assert_eq!(request().body_json().unwrap()["email_id"], "tem_123");
+
+    let mut request = request();
+    request.body = None;
+    assert!(request.body_json().is_none());
+  }
+}
@@ -0,0 +1,265 @@
+use crate::error::{Error, Result};
+use reqwest::StatusCode;
+use reqwest::header::HeaderMap;
+use serde::de::DeserializeOwned;
+use std::borrow::Cow;
+use std::time::{Duration, SystemTime};
+
+/// Response headers that may carry the SendWithUs request ID, in lookup order.
+const REQUEST_ID_HEADERS: [&str; 2] = ["x-request-id", "x-swu-request-id"];
+
+/// A raw HTTP response from the SendWithUs API.
+///
+/// Returned by `Api::request_raw` for endpoints this crate doesn't model.
+/// The status code is not interpreted, so error responses are returned as-is.
+///
+/// # Examples
+///
+/// ```no_run
+/// use send_with_us::Api;
+///
+/// # async fn run() -> send_with_us::Result<()> {
+/// let api = Api::with_api_key("api-key");
+/// let response = api
+///   .request_raw::<()>(reqwest::Method::GET, "some/new/endpoint", None)
+///   .await?;
+///
+/// if response.status.is_success() {
+///   let value: serde_json::Value = response.json()?;
+///   println!("{}", value);
+/// } else {
+///   eprintln!("{}: {}", response.status, response.text());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RawResponse {
+  /// HTTP status code
+  pub status: StatusCode,
+
+  /// Response headers
+  pub headers: HeaderMap,
+
+  /// Response body
+  pub body: Vec<u8>,
+
+  /// Time from sending the request until the full response body was read
+  pub duration: Duration,
+}
+
+impl RawResponse {
+  /// Returns the body as text, replacing invalid UTF-8 sequences.
+  pub fn text(&self) -> Cow<'_, str> {
+    String::from_utf8_lossy(&self.body)
+  }
+
+  /// Returns how long the API asked the client to wait before retrying.
+  ///
+  /// The `Retry-After` header may hold either a number of seconds or an
+  /// HTTP date. Dates in the past yield a zero duration.
+  ///
+  /// # Returns
+  /// The requested delay, or `None` if the header is missing or malformed
+  pub fn retry_after(&self) -> Option<Duration> {
+    let value = self
+      .headers
+      .get(reqwest::header::RETRY_AFTER)?
+      .to_str()
+      .ok()?
+      .trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+      return Some(Duration::from_secs(seconds));
+    }
+
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(
+      date
+        .duration_since(SystemTime::now())
+        .unwrap_or(Duration::ZERO),
+    )
+  }
+
+  /// Deserializes the body as JSON.
+  ///
+  /// # Errors
+  /// Returns `Error::ResponseDecodeFailed`, carrying the body text, if the
+  /// body isn't valid JSON for `T`
+  pub fn json<T: DeserializeOwned>(&self) -> Result<T> {
+    serde_json::from_slice(&self.body).map_err(|source| Error::ResponseDecodeFailed {
+      source,
+      body: self.text().into_owned(),
+    })
+  }
+}
+
+/// A deserialized API response together with its HTTP metadata.
+///
+/// Use this when you need more than the response body, for example to quote
+/// the SendWithUs request ID in a support ticket.
+///
+/// # Examples
+///
+/// ```no_run
+/// use send_with_us::Api;
+/// use send_with_us::types::{EmailOptions, Recipient};
+///
+/// # async fn run() -> send_with_us::Result<()> {
+/// let api = Api::with_api_key("api-key");
+/// let options = EmailOptions::new("tem_123", Recipient::new("user@example.com"));
+///
+/// let envelope = api.send_email_with_metadata(options).await?;
+/// println!(
+///   "receipt {} (request {:?}) took {:?}",
+///   envelope.data.receipt_id,
+///   envelope.request_id,
+///   envelope.duration
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ResponseEnvelope<T> {
+  /// Deserialized response body
+  pub data: T,
+
+  /// HTTP status code
+  pub status: StatusCode,
+
+  /// Response headers
+  pub headers: HeaderMap,
+
+  /// Time from sending the request until the full response body was read
+  pub duration: Duration,
+
+  /// SendWithUs request ID, when the response carries one
+  pub request_id: Option<String>,
+}
+
+impl<T> ResponseEnvelope<T> {
+  /// Creates an envelope from a raw response and its deserialized body.
+  pub(crate) fn new(data: T, raw: RawResponse) -> Self {
+    let request_id = REQUEST_ID_HEADERS.iter().find_map(|name| {
+      raw
+        .headers
+        .get(*name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+    });
+
+    Self {
+      data,
+      status: raw.status,
+      headers: raw.headers,
+      duration: raw.duration,
+      request_id,
+    }
+  }
+
+  /// Consumes the envelope and returns the response body.
+  pub fn into_inner(self) -> T {
+    self.data
+  }
+
+  /// Transforms the response body while keeping the metadata.
+  ///
+  /// # Arguments
+  /// * `f` - Function applied to the response body
+  ///
+  /// # Returns
+  /// An envelope with the transformed body and the same metadata
+  pub fn map<U>(self, f: impl FnOnce(T) -> U) -> ResponseEnvelope<U> {
+    ResponseEnvelope {
+      data: f(self.data),
+      status: self.status,
+      headers: self.headers,
+      duration: self.duration,
+      request_id: self.request_id,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use reqwest::header::HeaderValue;
+
+  fn raw(headers: &[(&'static str, &'static str)]) -> RawResponse {
+    let mut map = HeaderMap::new();
+    for (name, value) in headers {
+      map.insert(*name, HeaderValue::from_static(value));
+    }
+
+    RawResponse {
+      status: StatusCode::OK,
+      headers: map,
+      body: Vec::new(),
+      duration: Duration::from_millis(25),
+    }
+  }
+
+  #[test]
+  fn test_request_id_is_read_from_headers() {
+    let envelope = ResponseEnvelope::new((), raw(&[("X-Request-Id", "req_123")]));
+    assert_eq!(envelope.request_id.as_deref(), Some("req_123"));
+
+    let envelope = ResponseEnvelope::new((), raw(&[("X-SWU-Request-Id", "req_456")]));
+    assert_eq!(envelope.request_id.as_deref(), Some("req_456"));
+
+    let envelope = ResponseEnvelope::new((), raw(&[]));
+    assert!(envelope.request_id.is_none());
+  }
+
+  #[test]
+  fn test_raw_response_body_helpers() {
+    let mut response = raw(&[]);
+    response.body = br#"{"id": "tem_123"}"#.to_vec();
+
+    assert_eq!(response.text(), r#"{"id": "tem_123"}"#);
+    assert_eq!(
+      response.json::<serde_json::Value>().unwrap()["id"],
+      "tem_123"
+    );
+
+    response.body = b"not json".to_vec();
+    assert!(matches!(
+      response.json::<serde_json::Value>(),
+      Err(Error::ResponseDecodeFailed { body, .. }) if body == "not json"
+    ));
+  }
+
+  #[test]
+  fn test_retry_after() {
+    assert_eq!(
+      raw(&[("Retry-After", "120")]).retry_after(),
+      Some(Duration::from_secs(120))
+    );
+    assert_eq!(
+      raw(&[("Retry-After", "Wed, 21 Oct 2015 07:28:00 GMT")]).retry_after(),
+      Some(Duration::ZERO)
+    );
+    assert_eq!(raw(&[("Retry-After", "soon")]).retry_after(), None);
+    assert_eq!(raw(&[]).retry_after(), None);
+
+    let later = httpdate::fmt_http_date(SystemTime::now() + Duration::from_secs(600));
+    let mut response = raw(&[]);
+    response.headers.insert(
+      reqwest::header::RETRY_AFTER,
+      HeaderValue::from_str(&later).unwrap(),
+    );
+    let delay = response.retry_after().unwrap();
+    assert!(delay > Duration::from_secs(590) && delay <= Duration::from_secs(600));
+  }
+
+  #[test]
+  fn test_map_keeps_metadata() {
+    let envelope = ResponseEnvelope::new(2, raw(&[("X-Request-Id", "req_123")])).map(|n| n * 2);
+
+    assert_eq!(envelope.data, 4);
+    assert_eq!(envelope.status, StatusCode::OK);
+    assert_eq!(envelope.duration, Duration::from_millis(25));
+    assert_eq!(envelope.request_id.as_deref(), Some("req_123"));
+    assert_eq!(envelope.into_inner(), 4);
+  }
+}
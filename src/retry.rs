@@ -0,0 +1,230 @@
+use crate::error::Error;
+use std::time::Duration;
+
+/// Settings for retrying failed API calls.
+///
+/// Calls that fail with a retryable error (see `Error::is_retryable`) are
+/// retried with exponential backoff until they succeed or `max_attempts`
+/// calls have been made. An optional budget bounds the total time spent on a
+/// call across all attempts and backoff delays.
+///
+/// Sending an email isn't idempotent: after a timeout or a 5xx response the
+/// email may already have been accepted, and sending it again delivers it
+/// twice. Sends are therefore only retried when the connection failed before
+/// the request went out, unless `with_send_retries` opts in to retrying them
+/// like any other call.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::Config;
+/// use send_with_us::retry::RetryPolicy;
+/// use std::time::Duration;
+///
+/// let config = Config::new("api-key").with_retry_policy(
+///   RetryPolicy::new(4)
+///     .with_initial_backoff(Duration::from_millis(500))
+///     .with_max_backoff(Duration::from_secs(10))
+///     .with_budget(Duration::from_secs(30)),
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+  /// Total number of attempts, including the first one
+  pub max_attempts: u32,
+
+  /// Delay before the first retry
+  pub initial_backoff: Duration,
+
+  /// Upper bound for the delay between attempts
+  pub max_backoff: Duration,
+
+  /// Total time allowed for a call across all attempts (default: no limit)
+  pub budget: Option<Duration>,
+
+  /// Whether sends are retried after failures that leave their outcome unknown (default: false)
+  pub retry_sends: bool,
+}
+
+impl RetryPolicy {
+  /// Creates a retry policy with default backoff settings.
+  ///
+  /// # Arguments
+  /// * `max_attempts` - Total number of attempts, including the first one (at least 1)
+  ///
+  /// # Returns
+  /// A new RetryPolicy
+  pub fn new(max_attempts: u32) -> Self {
+    Self {
+      max_attempts: max_attempts.max(1),
+      initial_backoff: Duration::from_millis(200),
+      max_backoff: Duration::from_secs(5),
+      budget: None,
+      retry_sends: false,
+    }
+  }
+
+  /// Sets the delay before the first retry.
+  ///
+  /// # Arguments
+  /// * `backoff` - Delay before the first retry; each later retry waits twice as long
+  ///
+  /// # Returns
+  /// Self with the updated backoff for method chaining
+  pub fn with_initial_backoff(mut self, backoff: Duration) -> Self {
+    self.initial_backoff = backoff;
+    self
+  }
+
+  /// Sets the upper bound for the delay between attempts.
+  ///
+  /// # Arguments
+  /// * `backoff` - Maximum delay between attempts
+  ///
+  /// # Returns
+  /// Self with the updated maximum backoff for method chaining
+  pub fn with_max_backoff(mut self, backoff: Duration) -> Self {
+    self.max_backoff = backoff;
+    self
+  }
+
+  /// Sets the total time allowed for a call across all attempts.
+  ///
+  /// No retry is started if its backoff would run past the budget, and an
+  /// attempt still in flight when the budget runs out fails with
+  /// `Error::Timeout`.
+  ///
+  /// # Arguments
+  /// * `budget` - Total time allowed for a call
+  ///
+  /// # Returns
+  /// Self with the updated budget for method chaining
+  pub fn with_budget(mut self, budget: Duration) -> Self {
+    self.budget = Some(budget);
+    self
+  }
+
+  /// Sets whether sends are retried after failures that leave their outcome unknown.
+  ///
+  /// Enable this only if receiving an email twice is better than not
+  /// receiving it, or if the template deduplicates sends itself.
+  ///
+  /// # Arguments
+  /// * `retry` - `true` to retry sends after timeouts and 5xx responses
+  ///
+  /// # Returns
+  /// Self with the updated setting for method chaining
+  pub fn with_send_retries(mut self, retry: bool) -> Self {
+    self.retry_sends = retry;
+    self
+  }
+
+  /// Returns the delay to wait after the given failed attempt.
+  ///
+  /// # Arguments
+  /// * `attempt` - Number of the attempt that just failed, starting at 1
+  ///
+  /// # Returns
+  /// The delay before the next attempt
+  pub fn backoff(&self, attempt: u32) -> Duration {
+    let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+    self
+      .initial_backoff
+      .saturating_mul(factor)
+      .min(self.max_backoff)
+  }
+
+  /// Returns `true` if a call that failed with `error` should be retried by this policy.
+  ///
+  /// `idempotent` is `false` for sends, which are only retried when the
+  /// request can't have reached SendWithUs unless `retry_sends` is set.
+  pub(crate) fn should_retry(&self, error: &Error, idempotent: bool) -> bool {
+    // An open circuit won't close within a backoff period, and rate limits are
+    // governed by the server's Retry-After rather than our backoff.
+    if !error.is_retryable()
+      || matches!(error, Error::CircuitOpen { .. } | Error::RateLimited { .. })
+    {
+      return false;
+    }
+
+    idempotent || self.retry_sends || !error.may_have_been_processed()
+  }
+}
+
+impl Default for RetryPolicy {
+  fn default() -> Self {
+    Self::new(3)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::error::{RequestContext, SwuApiError};
+
+  #[test]
+  fn test_backoff_is_exponential_and_capped() {
+    let policy = RetryPolicy::new(10)
+      .with_initial_backoff(Duration::from_millis(100))
+      .with_max_backoff(Duration::from_secs(1));
+
+    assert_eq!(policy.backoff(1), Duration::from_millis(100));
+    assert_eq!(policy.backoff(2), Duration::from_millis(200));
+    assert_eq!(policy.backoff(3), Duration::from_millis(400));
+    assert_eq!(policy.backoff(5), Duration::from_secs(1));
+    assert_eq!(policy.backoff(40), Duration::from_secs(1));
+  }
+
+  #[test]
+  fn test_sends_are_only_retried_when_never_sent() {
+    let policy = RetryPolicy::default();
+    let server_error = Error::ApiError {
+      context: RequestContext::new(reqwest::Method::POST, "send"),
+      status: 502,
+      error: Box::new(SwuApiError::from_body("Bad Gateway")),
+    };
+
+    assert!(policy.should_retry(&server_error, true));
+    assert!(!policy.should_retry(&server_error, false));
+    assert!(!policy.should_retry(&Error::Timeout { context: None }, false));
+    assert!(policy.should_retry(&Error::ConnectionFailed { context: None }, false));
+
+    let policy = policy.with_send_retries(true);
+    assert!(policy.should_retry(&server_error, false));
+    assert!(policy.should_retry(&Error::Timeout { context: None }, false));
+  }
+
+  #[test]
+  fn test_with_budget() {
+    assert_eq!(RetryPolicy::default().budget, None);
+    assert_eq!(
+      RetryPolicy::default()
+        .with_budget(Duration::from_secs(30))
+        .budget,
+      Some(Duration::from_secs(30))
+    );
+  }
+
+  #[test]
+  fn test_should_retry() {
+    let policy = RetryPolicy::default();
+
+    assert!(policy.should_retry(&Error::ConnectionFailed { context: None }, true));
+    assert!(policy.should_retry(&Error::Timeout { context: None }, true));
+    assert!(!policy.should_retry(&Error::MissingTemplateId, true));
+    assert!(!policy.should_retry(
+      &Error::RateLimited {
+        retry_after: None,
+        context: None,
+      },
+      true
+    ));
+    assert!(!policy.should_retry(
+      &Error::CircuitOpen {
+        retry_in: Duration::from_secs(1)
+      },
+      true
+    ));
+    assert_eq!(RetryPolicy::new(0).max_attempts, 1);
+  }
+}
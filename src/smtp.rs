@@ -0,0 +1,295 @@
+//! Conversion of rendered emails to SMTP messages.
+//!
+//! Available with the `lettre` feature. When sending through SendWithUs is
+//! degraded but rendering still works, `render_message` renders an email's
+//! template and builds a `lettre::Message` from it and the email's options,
+//! ready to hand to any lettre transport for direct SMTP delivery.
+
+use crate::api::ApiClient;
+use crate::attachment::{Attachment, Disposition};
+use crate::error::{Error, Result};
+use crate::models::RenderedTemplate;
+use crate::types::{EmailOptions, Recipient, RenderOptions};
+use lettre::Message;
+use lettre::message::header::{ContentType, HeaderName, HeaderValue};
+use lettre::message::{Mailbox, MultiPart, SinglePart};
+
+/// Content type of attachments without one.
+const DEFAULT_CONTENT_TYPE: &str = "application/octet-stream";
+
+/// Renders an email's template and builds an SMTP message from it.
+///
+/// The options first go through `ApiClient::prepare_email`, so the message
+/// gets the configured default sender, locale, tags, and headers, and goes
+/// to the intercept address when recipient interception is enabled, just as
+/// a send would. The template is rendered with the email's data and locale.
+/// Template versions are selected by ID when rendering, so `version_name`
+/// is ignored and the active version is used.
+///
+/// # Arguments
+/// * `client` - Client used to render the template
+/// * `options` - The email as it would have been sent through SendWithUs
+///
+/// # Returns
+/// The rendered email as a message for a lettre transport
+///
+/// # Errors
+/// Returns an error if the options are invalid, the error of the render
+/// request, or `Error::SmtpMessageFailed` if the message can't be built
+///
+/// # Examples
+///
+/// ```no_run
+/// use send_with_us::types::{EmailOptions, Recipient, Sender};
+/// use send_with_us::{Api, ApiClient, smtp};
+///
+/// # async fn run() -> send_with_us::Result<()> {
+/// let api = Api::with_api_key("api-key");
+/// let options = EmailOptions::new("template-id", Recipient::new("ada@example.com"))
+///   .with_sender(Sender::new("noreply@example.com"));
+///
+/// if let Err(error) = api.send_email(options.clone()).await {
+///   eprintln!("sending failed, falling back to SMTP: {}", error);
+///   let message = smtp::render_message(&api, &options).await?;
+///   // mailer.send(message).await
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn render_message(client: &impl ApiClient, options: &EmailOptions) -> Result<Message> {
+  let options = client.prepare_email(options.clone())?;
+
+  let mut render = RenderOptions::new(options.email_id.clone());
+  render.template_data = options.data.clone().unwrap_or_default();
+  render.locale = options.locale.clone();
+
+  let rendered: RenderedTemplate = serde_json::from_value(client.render(render).await?)?;
+  message(&rendered, &options)
+}
+
+/// Builds an SMTP message from a rendered template and an email's options.
+///
+/// The subject, HTML, and text come from the rendered template; recipients,
+/// sender, reply-to address, custom headers, and attachments from the
+/// options. Inline attachments are embedded next to the HTML so `cid:` URLs
+/// resolve.
+///
+/// # Arguments
+/// * `rendered` - The email's template, rendered with its data
+/// * `options` - The email as it would have been sent through SendWithUs
+///
+/// # Returns
+/// The email as a message for a lettre transport
+///
+/// # Errors
+/// Returns `Error::SmtpMessageFailed` if the options have no sender, an
+/// address or header is invalid, or the template rendered no content
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::models::RenderedTemplate;
+/// use send_with_us::smtp;
+/// use send_with_us::types::{EmailOptions, Recipient, Sender};
+///
+/// let rendered = RenderedTemplate {
+///   subject: "Welcome".to_string(),
+///   html: Some("<p>Hi Ada</p>".to_string()),
+///   text: Some("Hi Ada".to_string()),
+/// };
+/// let options = EmailOptions::new("template-id", Recipient::new("ada@example.com"))
+///   .with_sender(Sender::new("noreply@example.com"));
+///
+/// let message = smtp::message(&rendered, &options)?;
+/// assert_eq!(message.headers().get_raw("Subject"), Some("Welcome"));
+/// # Ok::<(), send_with_us::Error>(())
+/// ```
+pub fn message(rendered: &RenderedTemplate, options: &EmailOptions) -> Result<Message> {
+  let sender = options
+    .sender
+    .as_ref()
+    .ok_or_else(|| Error::SmtpMessageFailed("the email has no sender".to_string()))?;
+
+  let mut builder = Message::builder()
+    .from(mailbox(&sender.address, sender.name.as_deref())?)
+    .to(recipient(&options.recipient)?)
+    .subject(rendered.subject.as_str());
+
+  if let Some(reply_to) = &sender.reply_to {
+    builder = builder.reply_to(mailbox(reply_to, None)?);
+  }
+  for cc in options.cc.iter().flatten() {
+    builder = builder.cc(recipient(cc)?);
+  }
+  for bcc in options.bcc.iter().flatten() {
+    builder = builder.bcc(recipient(bcc)?);
+  }
+  for (name, value) in options.headers.iter().flatten() {
+    let name = HeaderName::new_from_ascii(name.clone()).map_err(failed)?;
+    builder = builder.raw_header(HeaderValue::new(name, value.clone()));
+  }
+
+  builder.multipart(body(rendered, options)?).map_err(failed)
+}
+
+/// Assembles the MIME tree: alternative text and HTML, wrapped with inline
+/// attachments in `multipart/related` and with attachments in `multipart/mixed`.
+fn body(rendered: &RenderedTemplate, options: &EmailOptions) -> Result<MultiPart> {
+  let text = rendered.text.as_deref().filter(|text| !text.is_empty());
+  let html = rendered.html.as_deref().filter(|html| !html.is_empty());
+
+  let mut content = MultiPart::alternative().build();
+  if let Some(text) = text {
+    content = content.singlepart(SinglePart::plain(text.to_string()));
+  }
+  if let Some(html) = html {
+    content = content.singlepart(SinglePart::html(html.to_string()));
+  }
+  if text.is_none() && html.is_none() {
+    return Err(Error::SmtpMessageFailed(
+      "the template rendered no content".to_string(),
+    ));
+  }
+
+  let files = options.files.as_deref().unwrap_or_default();
+  let (inline, attached): (Vec<&Attachment>, Vec<&Attachment>) = files
+    .iter()
+    .partition(|file| file.disposition == Some(Disposition::Inline));
+
+  if !inline.is_empty() {
+    let mut related = MultiPart::related().multipart(content);
+    for file in inline {
+      related = related.singlepart(part(file)?);
+    }
+    content = related;
+  }
+
+  if !attached.is_empty() {
+    let mut mixed = MultiPart::mixed().multipart(content);
+    for file in attached {
+      mixed = mixed.singlepart(part(file)?);
+    }
+    content = mixed;
+  }
+
+  Ok(content)
+}
+
+fn part(file: &Attachment) -> Result<SinglePart> {
+  let content_type = file.content_type.as_deref().unwrap_or(DEFAULT_CONTENT_TYPE);
+  let content_type = ContentType::parse(content_type).map_err(failed)?;
+
+  let attachment = match (&file.content_id, file.disposition) {
+    (Some(content_id), Some(Disposition::Inline)) => {
+      lettre::message::Attachment::new_inline_with_name(content_id.clone(), file.id.clone())
+    }
+    _ => lettre::message::Attachment::new(file.id.clone()),
+  };
+
+  Ok(attachment.body(file.data.to_vec(), content_type))
+}
+
+fn recipient(recipient: &Recipient) -> Result<Mailbox> {
+  mailbox(&recipient.address, recipient.name.as_deref())
+}
+
+fn mailbox(address: &str, name: Option<&str>) -> Result<Mailbox> {
+  let address = address.parse().map_err(failed)?;
+  Ok(Mailbox::new(name.map(str::to_string), address))
+}
+
+fn failed(error: impl std::fmt::Display) -> Error {
+  Error::SmtpMessageFailed(error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::types::Sender;
+
+  fn options() -> EmailOptions {
+    EmailOptions::new(
+      "tem_welcome",
+      Recipient::new("ada@example.com").with_name("Ada Lovelace"),
+    )
+    .with_sender(Sender::new("noreply@example.com").with_reply_to("support@example.com"))
+  }
+
+  fn rendered() -> RenderedTemplate {
+    RenderedTemplate {
+      subject: "Welcome".to_string(),
+      html: Some("<p>Hi Ada <img src=\"cid:logo\"></p>".to_string()),
+      text: Some("Hi Ada".to_string()),
+    }
+  }
+
+  #[test]
+  fn test_message_carries_content_recipients_and_attachments() {
+    let options = options()
+      .with_cc(vec![Recipient::new("grace@example.com")])
+      .with_files(vec![
+        Attachment::from_bytes(b"%PDF", "invoice.pdf").with_content_type("application/pdf"),
+        Attachment::from_bytes(b"PNG", "logo.png")
+          .with_content_type("image/png")
+          .inline("logo"),
+      ]);
+
+    let message = message(&rendered(), &options).unwrap();
+    let formatted = String::from_utf8(message.formatted()).unwrap();
+
+    assert!(formatted.contains("From: noreply@example.com"));
+    assert!(formatted.contains("To: \"Ada Lovelace\" <ada@example.com>"));
+    assert!(formatted.contains("Cc: grace@example.com"));
+    assert!(formatted.contains("Reply-To: support@example.com"));
+    assert!(formatted.contains("Subject: Welcome"));
+    assert!(formatted.contains("multipart/mixed"));
+    assert!(formatted.contains("multipart/related"));
+    assert!(formatted.contains("multipart/alternative"));
+    assert!(formatted.contains("Hi Ada"));
+    assert!(formatted.contains("filename=\"invoice.pdf\""));
+    assert!(formatted.contains("Content-ID: <logo>"));
+  }
+
+  #[tokio::test]
+  async fn test_render_message_prepares_options_like_a_send() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let mock = mock_server
+      .mock("POST", "/api/v1/render")
+      .with_status(200)
+      .with_body(r#"{"success": true, "subject": "Welcome", "html": "<p>Hi</p>"}"#)
+      .create();
+
+    let mut config = crate::Config::new("test-api-key")
+      .with_default_sender(Sender::new("noreply@example.com"))
+      .with_recipient_intercept("catch-all@example.com");
+    config.url = url::Url::parse(&mock_server.url()).unwrap();
+    let api = crate::Api::new(config);
+
+    let options = EmailOptions::new("tem_welcome", Recipient::new("ada@example.com"))
+      .with_cc(vec![Recipient::new("grace@example.com")]);
+    let message = render_message(&api, &options).await.unwrap();
+    let formatted = String::from_utf8(message.formatted()).unwrap();
+
+    assert!(formatted.contains("From: noreply@example.com"));
+    assert!(formatted.contains("To: catch-all@example.com"));
+    assert!(formatted.contains("Cc: catch-all@example.com"));
+    assert!(formatted.contains("X-Original-Recipients: ada@example.com, grace@example.com"));
+    mock.assert();
+  }
+
+  #[test]
+  fn test_message_requires_sender_and_content() {
+    let mut options = options();
+    let empty = RenderedTemplate::default();
+    assert!(matches!(
+      message(&empty, &options),
+      Err(Error::SmtpMessageFailed(_))
+    ));
+
+    options.sender = None;
+    assert!(matches!(
+      message(&rendered(), &options),
+      Err(Error::SmtpMessageFailed(_))
+    ));
+  }
+}
@@ -0,0 +1,137 @@
+//! OpenTelemetry spans and trace propagation for API calls.
+//!
+//! Each HTTP call to SendWithUs is recorded as a client span whose parent is
+//! the current OpenTelemetry context, and the span's context is injected into
+//! the outgoing request headers using the globally configured propagator.
+
+use crate::error::Result;
+use crate::redaction;
+use crate::request::PreparedRequest;
+use crate::response::RawResponse;
+use opentelemetry::propagation::Injector;
+use opentelemetry::trace::{SpanKind, Status, TraceContextExt, Tracer};
+use opentelemetry::{Context, KeyValue, global};
+
+/// Name of the tracer used for SendWithUs spans.
+const TRACER_NAME: &str = "send_with_us";
+
+/// Adds propagation headers to a prepared request.
+struct HeaderInjector<'a>(&'a mut Vec<(String, String)>);
+
+impl Injector for HeaderInjector<'_> {
+  fn set(&mut self, key: &str, value: String) {
+    self.0.retain(|(name, _)| !name.eq_ignore_ascii_case(key));
+    self.0.push((key.to_string(), value));
+  }
+}
+
+/// Starts a client span for a request and injects its context into the request headers.
+///
+/// # Returns
+/// The request with propagation headers added, and the context holding the new span
+pub(crate) fn start_span(mut prepared: PreparedRequest) -> (PreparedRequest, Context) {
+  let tracer = global::tracer(TRACER_NAME);
+  let span = tracer
+    .span_builder(format!("SendWithUs {}", prepared.method))
+    .with_kind(SpanKind::Client)
+    .with_attributes(span_attributes(&prepared))
+    .start_with_context(&tracer, &Context::current());
+  let cx = Context::current_with_span(span);
+
+  global::get_text_map_propagator(|propagator| {
+    propagator.inject_context(&cx, &mut HeaderInjector(&mut prepared.headers));
+  });
+
+  (prepared, cx)
+}
+
+/// Returns the attributes of a request's span.
+///
+/// Email addresses in the URL path, such as in `customers/{email}`, are
+/// redacted so spans don't export personal data.
+fn span_attributes(prepared: &PreparedRequest) -> Vec<KeyValue> {
+  let mut attributes = vec![
+    KeyValue::new("http.request.method", prepared.method.to_string()),
+    KeyValue::new("url.full", redaction::redact_url(&prepared.url).to_string()),
+  ];
+
+  if let Some(host) = prepared.url.host_str() {
+    attributes.push(KeyValue::new("server.address", host.to_string()));
+  }
+
+  attributes
+}
+
+/// Records the outcome of a request on its span and ends the span.
+pub(crate) fn end_span(cx: &Context, result: &Result<RawResponse>) {
+  let span = cx.span();
+
+  match result {
+    Ok(raw) => {
+      span.set_attribute(KeyValue::new(
+        "http.response.status_code",
+        i64::from(raw.status.as_u16()),
+      ));
+
+      if raw.status.is_server_error() {
+        span.set_status(Status::error(raw.status.to_string()));
+      }
+    }
+    Err(error) => span.set_status(Status::error(error.to_string())),
+  }
+
+  span.end();
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{Api, ApiClient, Config};
+  use mockito::Matcher;
+  use opentelemetry_sdk::propagation::TraceContextPropagator;
+  use opentelemetry_sdk::trace::SdkTracerProvider;
+
+  #[tokio::test]
+  async fn test_requests_carry_trace_context() {
+    global::set_text_map_propagator(TraceContextPropagator::new());
+    global::set_tracer_provider(SdkTracerProvider::builder().build());
+
+    let mut mock_server = mockito::Server::new_async().await;
+    let mock = mock_server
+      .mock("GET", "/api/v1/emails")
+      .match_header(
+        "traceparent",
+        Matcher::Regex("^00-[0-9a-f]{32}-[0-9a-f]{16}-01$".to_string()),
+      )
+      .with_status(200)
+      .with_body("[]")
+      .create();
+
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&mock_server.url()).unwrap();
+    let api = Api::new(config);
+
+    api.list_templates().await.unwrap();
+
+    mock.assert();
+  }
+
+  #[test]
+  fn test_span_url_redacts_email_addresses() {
+    let prepared = PreparedRequest {
+      method: reqwest::Method::GET,
+      url: url::Url::parse("https://api.sendwithus.com/api/v1/customers/ada@example.com").unwrap(),
+      headers: Vec::new(),
+      body: None,
+    };
+
+    let url = span_attributes(&prepared)
+      .into_iter()
+      .find(|attribute| attribute.key.as_str() == "url.full")
+      .unwrap();
+    assert_eq!(
+      url.value.as_str(),
+      "https://api.sendwithus.com/api/v1/customers/[REDACTED]"
+    );
+  }
+}
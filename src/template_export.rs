@@ -0,0 +1,413 @@
+//! Export of templates to files and import into another account.
+//!
+//! A `TemplateExport` holds a template's name, tags, and the content of all
+//! of its versions, without any account-specific IDs. Fetch one from one
+//! account, save it as JSON (or YAML with the `yaml` feature), and create it
+//! in another account, e.g. to promote templates from staging to production.
+
+use crate::api::Api;
+use crate::error::{Error, Result};
+use crate::ids::TemplateId;
+use crate::models::{Template, TemplateVersion};
+use crate::types::{TemplateOptions, TemplateUpdateOptions};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// A template and all of its versions, detached from any account.
+///
+/// # Examples
+///
+/// ```no_run
+/// use send_with_us::Api;
+/// use send_with_us::ids::TemplateId;
+/// use send_with_us::template_export::TemplateExport;
+///
+/// # async fn run() -> send_with_us::Result<()> {
+/// let staging = Api::with_api_key("staging-api-key");
+/// let production = Api::with_api_key("production-api-key");
+///
+/// let export = TemplateExport::fetch(&staging, &TemplateId::new("tem_welcome")?).await?;
+/// export.save("welcome.json").await?;
+///
+/// let export = TemplateExport::load("welcome.json").await?;
+/// let template_id = export.create(&production).await?;
+/// println!("created {}", template_id);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemplateExport {
+  /// Template name
+  pub name: String,
+
+  /// Template tags
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub tags: Vec<String>,
+
+  /// Versions of the template, in the order SendWithUs listed them
+  pub versions: Vec<ExportedVersion>,
+}
+
+/// The content of an exported template version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportedVersion {
+  /// Version name
+  pub name: String,
+
+  /// Whether this was the published version when exported
+  #[serde(default)]
+  pub published: bool,
+
+  /// Email subject line
+  #[serde(default)]
+  pub subject: String,
+
+  /// HTML content
+  #[serde(default)]
+  pub html: String,
+
+  /// Plain text content
+  #[serde(default)]
+  pub text: String,
+
+  /// Preheader text
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub preheader: Option<String>,
+
+  /// AMP HTML content
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub amp_html: Option<String>,
+}
+
+impl From<TemplateVersion> for ExportedVersion {
+  fn from(version: TemplateVersion) -> Self {
+    Self {
+      name: version.name,
+      published: version.published,
+      subject: version.subject.unwrap_or_default(),
+      html: version.html.unwrap_or_default(),
+      text: version.text.unwrap_or_default(),
+      preheader: version.preheader.filter(|value| !value.is_empty()),
+      amp_html: version.amp_html.filter(|value| !value.is_empty()),
+    }
+  }
+}
+
+impl ExportedVersion {
+  fn options(&self) -> TemplateOptions {
+    let mut options = TemplateOptions::new(
+      self.name.clone(),
+      self.subject.clone(),
+      self.html.clone(),
+      self.text.clone(),
+    );
+    options.preheader = self.preheader.clone();
+    options.amp_html = self.amp_html.clone();
+    options
+  }
+}
+
+impl TemplateExport {
+  /// Fetches a template and the content of all its versions.
+  ///
+  /// # Arguments
+  /// * `api` - Client for the account to export from
+  /// * `template_id` - Template to export
+  ///
+  /// # Returns
+  /// The exported template
+  ///
+  /// # Errors
+  /// Returns an error if the template or one of its versions cannot be fetched
+  pub async fn fetch(api: &Api, template_id: &TemplateId) -> Result<Self> {
+    let template: Template = api.get_template_as(template_id).await?;
+    let mut versions = Vec::with_capacity(template.versions.len());
+
+    for version in &template.versions {
+      let version: TemplateVersion = api
+        .get_template_version_as(template_id, &version.id)
+        .await?;
+      versions.push(version.into());
+    }
+
+    Ok(Self {
+      name: template.name,
+      tags: template.tags,
+      versions,
+    })
+  }
+
+  /// Creates the template and its versions in an account.
+  ///
+  /// The template is created with the first version, then the other
+  /// versions are added in order and the tags set. A template with the same
+  /// name that already exists is left alone; a new one is always created.
+  /// Which version is published is up to SendWithUs.
+  ///
+  /// # Arguments
+  /// * `api` - Client for the account to import into
+  ///
+  /// # Returns
+  /// The ID of the new template
+  ///
+  /// # Errors
+  /// Returns `Error::InvalidConfig` if the export has no versions, or an
+  /// error if a request fails. Versions created before the failure are kept.
+  pub async fn create(&self, api: &Api) -> Result<TemplateId> {
+    let Some((first, rest)) = self.versions.split_first() else {
+      return Err(Error::InvalidConfig(format!(
+        "template export {:?} has no versions",
+        self.name
+      )));
+    };
+
+    let mut options = first.options();
+    options.name = self.name.clone();
+    let created: Template = api.create_template_as(options).await?;
+
+    // The first version is named after the template, so it needs renaming back.
+    let renamed = created.versions.first().filter(|v| v.name != first.name);
+    if let Some(version) = renamed {
+      api
+        .update_template_version_as::<serde_json::Value>(&created.id, &version.id, first.options())
+        .await?;
+    }
+
+    for version in rest {
+      api
+        .create_template_version_as::<serde_json::Value>(&created.id, version.options())
+        .await?;
+    }
+
+    if !self.tags.is_empty() {
+      let update = TemplateUpdateOptions::new().with_tags(self.tags.clone());
+      api
+        .update_template_as::<serde_json::Value>(&created.id, update)
+        .await?;
+    }
+
+    Ok(created.id)
+  }
+
+  /// Writes the export to a file.
+  ///
+  /// Files ending in `.yaml` or `.yml` are written as YAML, which requires
+  /// the `yaml` feature; anything else is written as pretty-printed JSON.
+  ///
+  /// # Arguments
+  /// * `path` - File to write
+  ///
+  /// # Errors
+  /// Returns an error if the export cannot be serialized or the file cannot be written
+  pub async fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+    let path = path.as_ref();
+    let contents = if is_yaml(path) {
+      to_yaml(self)?
+    } else {
+      let mut json = serde_json::to_string_pretty(self)?;
+      json.push('\n');
+      json
+    };
+
+    tokio::fs::write(path, contents).await?;
+    Ok(())
+  }
+
+  /// Reads an export from a file written by `save`.
+  ///
+  /// # Arguments
+  /// * `path` - File to read; `.yaml` and `.yml` files are read as YAML
+  ///
+  /// # Returns
+  /// The exported template
+  ///
+  /// # Errors
+  /// Returns an error if the file cannot be read or parsed
+  pub async fn load(path: impl AsRef<Path>) -> Result<Self> {
+    let path = path.as_ref();
+    let contents = tokio::fs::read_to_string(path).await?;
+
+    if is_yaml(path) {
+      from_yaml(&contents)
+    } else {
+      Ok(serde_json::from_str(&contents)?)
+    }
+  }
+}
+
+fn is_yaml(path: &Path) -> bool {
+  path
+    .extension()
+    .is_some_and(|extension| extension == "yaml" || extension == "yml")
+}
+
+#[cfg(feature = "yaml")]
+fn to_yaml(export: &TemplateExport) -> Result<String> {
+  Ok(serde_yaml::to_string(export)?)
+}
+
+#[cfg(feature = "yaml")]
+fn from_yaml(contents: &str) -> Result<TemplateExport> {
+  Ok(serde_yaml::from_str(contents)?)
+}
+
+#[cfg(not(feature = "yaml"))]
+fn to_yaml(_export: &TemplateExport) -> Result<String> {
+  Err(yaml_unsupported())
+}
+
+#[cfg(not(feature = "yaml"))]
+fn from_yaml(_contents: &str) -> Result<TemplateExport> {
+  Err(yaml_unsupported())
+}
+
+#[cfg(not(feature = "yaml"))]
+fn yaml_unsupported() -> Error {
+  std::io::Error::new(
+    std::io::ErrorKind::Unsupported,
+    "YAML template exports require the `yaml` feature",
+  )
+  .into()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Config;
+  use mockito::Matcher;
+  use serde_json::json;
+  use tempdir::TempDir;
+
+  fn export() -> TemplateExport {
+    TemplateExport {
+      name: "Welcome".to_string(),
+      tags: vec!["onboarding".to_string()],
+      versions: vec![
+        ExportedVersion {
+          name: "Original".to_string(),
+          published: true,
+          subject: "Hi {{name}}".to_string(),
+          html: "<p>Hi</p>".to_string(),
+          text: "Hi".to_string(),
+          preheader: None,
+          amp_html: None,
+        },
+        ExportedVersion {
+          name: "Variant B".to_string(),
+          published: false,
+          subject: "Hello {{name}}".to_string(),
+          html: "<p>Hello</p>".to_string(),
+          text: "Hello".to_string(),
+          preheader: Some("Welcome aboard".to_string()),
+          amp_html: None,
+        },
+      ],
+    }
+  }
+
+  #[tokio::test]
+  async fn test_save_and_load() {
+    let dir = TempDir::new("template_export").unwrap();
+    let path = dir.path().join("welcome.json");
+
+    export().save(&path).await.unwrap();
+    assert_eq!(TemplateExport::load(&path).await.unwrap(), export());
+
+    let yaml = dir.path().join("welcome.yaml");
+    let result = export().save(&yaml).await;
+
+    #[cfg(feature = "yaml")]
+    {
+      result.unwrap();
+      assert_eq!(TemplateExport::load(&yaml).await.unwrap(), export());
+    }
+
+    #[cfg(not(feature = "yaml"))]
+    assert!(matches!(result, Err(Error::FileAccessFailed(_))));
+  }
+
+  #[tokio::test]
+  async fn test_fetch_and_create() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
+
+    mock_server
+      .mock("GET", "/api/v1/templates/tem_1")
+      .with_status(200)
+      .with_body(
+        json!({
+          "id": "tem_1",
+          "name": "Welcome",
+          "tags": ["onboarding"],
+          "versions": [{"id": "ver_1", "name": "Original"}, {"id": "ver_2", "name": "Variant B"}]
+        })
+        .to_string(),
+      )
+      .create();
+    mock_server
+      .mock("GET", "/api/v1/templates/tem_1/versions/ver_1")
+      .with_status(200)
+      .with_body(
+        json!({"id": "ver_1", "name": "Original", "published": true, "subject": "Hi {{name}}", "html": "<p>Hi</p>", "text": "Hi", "preheader": ""})
+          .to_string(),
+      )
+      .create();
+    mock_server
+      .mock("GET", "/api/v1/templates/tem_1/versions/ver_2")
+      .with_status(200)
+      .with_body(
+        json!({"id": "ver_2", "name": "Variant B", "subject": "Hello {{name}}", "html": "<p>Hello</p>", "text": "Hello", "preheader": "Welcome aboard"})
+          .to_string(),
+      )
+      .create();
+
+    let create = mock_server
+      .mock("POST", "/api/v1/emails")
+      .match_body(Matcher::PartialJson(
+        json!({"name": "Welcome", "html": "<p>Hi</p>"}),
+      ))
+      .with_status(200)
+      .with_body(
+        json!({"id": "tem_9", "name": "Welcome", "versions": [{"id": "ver_9", "name": "Welcome"}]})
+          .to_string(),
+      )
+      .create();
+    let rename = mock_server
+      .mock("PUT", "/api/v1/templates/tem_9/versions/ver_9")
+      .match_body(Matcher::PartialJson(json!({"name": "Original"})))
+      .with_status(200)
+      .with_body("{}")
+      .create();
+    let version = mock_server
+      .mock("POST", "/api/v1/templates/tem_9/versions")
+      .match_body(Matcher::PartialJson(
+        json!({"name": "Variant B", "preheader": "Welcome aboard"}),
+      ))
+      .with_status(200)
+      .with_body("{}")
+      .create();
+    let tags = mock_server
+      .mock("PUT", "/api/v1/templates/tem_9")
+      .match_body(Matcher::Json(json!({"tags": ["onboarding"]})))
+      .with_status(200)
+      .with_body("{}")
+      .create();
+
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&url).unwrap();
+    let api = Api::new(config);
+
+    let exported = TemplateExport::fetch(&api, &TemplateId::new("tem_1").unwrap())
+      .await
+      .unwrap();
+    assert_eq!(exported, export());
+
+    let template_id = exported.create(&api).await.unwrap();
+    assert_eq!(template_id.as_str(), "tem_9");
+
+    create.assert();
+    rename.assert();
+    version.assert();
+    tags.assert();
+  }
+}
@@ -0,0 +1,54 @@
+use crate::ids::TemplateId;
+use crate::models::Template;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Template IDs by name, shared by all clones of an `Api`.
+///
+/// Filled from the template list on the first lookup by name, and refilled
+/// whenever a name isn't found, so templates created since are picked up.
+#[derive(Debug, Default)]
+pub(crate) struct TemplateRegistry {
+  ids: Mutex<HashMap<String, TemplateId>>,
+}
+
+impl TemplateRegistry {
+  /// Returns the ID of the template with the given name, if known.
+  pub fn get(&self, name: &str) -> Option<TemplateId> {
+    self.ids.lock().unwrap().get(name).cloned()
+  }
+
+  /// Replaces the known templates. If several share a name, the first wins.
+  pub fn replace(&self, templates: Vec<Template>) {
+    let mut ids = HashMap::with_capacity(templates.len());
+    for template in templates {
+      ids.entry(template.name).or_insert(template.id);
+    }
+
+    *self.ids.lock().unwrap() = ids;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  #[test]
+  fn test_replace_keeps_first_of_duplicate_names() {
+    let registry = TemplateRegistry::default();
+    assert_eq!(registry.get("Welcome"), None);
+
+    registry.replace(
+      serde_json::from_value(json!([
+        {"id": "tem_1", "name": "Welcome"},
+        {"id": "tem_2", "name": "Welcome"},
+        {"id": "tem_3", "name": "Receipt"}
+      ]))
+      .unwrap(),
+    );
+
+    assert_eq!(registry.get("Welcome").unwrap().as_str(), "tem_1");
+    assert_eq!(registry.get("Receipt").unwrap().as_str(), "tem_3");
+  }
+}
@@ -0,0 +1,953 @@
+//! Synchronization of templates with a local directory.
+//!
+//! `TemplateSync` pulls every template and version into a directory that can
+//! be kept in version control, reports how local edits differ from
+//! SendWithUs, and pushes them back. Each template gets a directory, and each
+//! of its versions a subdirectory:
+//!
+//! ```text
+//! templates/
+//!   welcome/
+//!     template.json     {"id": "tem_...", "name": "Welcome"}
+//!     original/
+//!       version.json    {"id": "ver_...", "name": "Original", "subject": "...", "preheader": "..."}
+//!       body.html
+//!       body.txt
+//!       body.amp.html   (only if the version has AMP content)
+//! ```
+//!
+//! New templates and versions are added by creating directories without an
+//! `id` in their JSON file; pushing creates them and writes the new IDs back.
+//! `TemplateSync::upload` does the same concurrently, reporting a result per
+//! directory, for directories generated by a build. Syncing never deletes
+//! anything on either side.
+
+use crate::api::Api;
+use crate::error::{Error, Result};
+use crate::ids::TemplateId;
+use crate::models::{Template, TemplateVersion};
+use crate::types::TemplateOptions;
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+const TEMPLATE_FILE: &str = "template.json";
+const VERSION_FILE: &str = "version.json";
+const HTML_FILE: &str = "body.html";
+const TEXT_FILE: &str = "body.txt";
+const AMP_FILE: &str = "body.amp.html";
+
+/// A difference between a local template directory and SendWithUs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateChange {
+  /// A local template that doesn't exist on SendWithUs yet
+  NewTemplate {
+    /// Template name
+    name: String,
+  },
+
+  /// A local version of an existing template that doesn't exist on SendWithUs yet
+  NewVersion {
+    /// Template ID
+    template_id: TemplateId,
+
+    /// Version name
+    name: String,
+  },
+
+  /// A version whose local content differs from SendWithUs
+  ModifiedVersion {
+    /// Template ID
+    template_id: TemplateId,
+
+    /// Version ID
+    version_id: String,
+
+    /// Version name
+    name: String,
+
+    /// Names of the fields that differ, e.g. `html` or `subject`
+    fields: Vec<&'static str>,
+  },
+}
+
+/// Outcome of a `TemplateSync::pull`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PullSummary {
+  /// Number of templates written
+  pub templates: usize,
+
+  /// Number of versions written
+  pub versions: usize,
+}
+
+/// Outcome of uploading one template or version directory with `TemplateSync::upload`.
+#[derive(Debug)]
+pub struct UploadResult {
+  /// The template or version directory
+  pub dir: PathBuf,
+
+  /// The change applied, `None` if the directory already matched SendWithUs
+  pub outcome: Result<Option<TemplateChange>>,
+}
+
+impl UploadResult {
+  fn new(dir: PathBuf, outcome: Result<Option<TemplateChange>>) -> Self {
+    Self { dir, outcome }
+  }
+}
+
+/// Progress of a `TemplateSync::upload`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UploadProgress {
+  /// Number of directories uploaded so far, including failures
+  pub completed: usize,
+
+  /// Number of directories to upload
+  pub total: usize,
+}
+
+/// Keeps a local directory of templates in sync with SendWithUs.
+///
+/// # Examples
+///
+/// ```no_run
+/// use send_with_us::Api;
+/// use send_with_us::template_sync::TemplateSync;
+///
+/// # async fn run() -> send_with_us::Result<()> {
+/// let sync = TemplateSync::new(Api::with_api_key("api-key"), "templates");
+///
+/// // Bring the directory up to date, e.g. before committing it.
+/// sync.pull().await?;
+///
+/// // After editing files locally, review and publish the changes.
+/// for change in sync.diff().await? {
+///   println!("{:?}", change);
+/// }
+/// sync.push().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TemplateSync {
+  api: Api,
+  root: PathBuf,
+}
+
+impl TemplateSync {
+  /// Creates a sync between SendWithUs and a local directory.
+  ///
+  /// # Arguments
+  /// * `api` - Client used to read and write templates
+  /// * `root` - Directory holding the templates; created on pull if missing
+  ///
+  /// # Returns
+  /// A new TemplateSync
+  pub fn new(api: Api, root: impl Into<PathBuf>) -> Self {
+    Self {
+      api,
+      root: root.into(),
+    }
+  }
+
+  /// Writes every template and version on SendWithUs to the directory.
+  ///
+  /// Templates and versions already in the directory keep their
+  /// subdirectories, matched by ID, and their files are overwritten with
+  /// the remote content. Others get new subdirectories named after them.
+  ///
+  /// # Returns
+  /// How many templates and versions were written
+  ///
+  /// # Errors
+  /// Returns the first error fetching templates or writing files
+  pub async fn pull(&self) -> Result<PullSummary> {
+    let templates: Vec<Template> = self.api.list_templates_as().await?;
+    let local = read_local(&self.root).await?;
+    let mut summary = PullSummary::default();
+    let mut taken: HashSet<PathBuf> = local.iter().map(|t| t.dir.clone()).collect();
+
+    for template in templates {
+      let existing = local
+        .iter()
+        .find(|t| t.meta.id.as_ref() == Some(&template.id));
+      let dir = match existing {
+        Some(existing) => existing.dir.clone(),
+        None => unique_dir(&self.root, &template.name, &mut taken),
+      };
+
+      let meta = TemplateFile {
+        id: Some(template.id.clone()),
+        name: template.name.clone(),
+      };
+      write_json(&dir.join(TEMPLATE_FILE), &meta).await?;
+      summary.templates += 1;
+
+      let mut taken_versions: HashSet<PathBuf> = existing
+        .map(|t| t.versions.iter().map(|v| v.dir.clone()).collect())
+        .unwrap_or_default();
+
+      for summary_version in &template.versions {
+        let version: TemplateVersion = self
+          .api
+          .get_template_version_as(&template.id, &summary_version.id)
+          .await?;
+
+        let existing_version = existing.and_then(|t| {
+          t.versions
+            .iter()
+            .find(|v| v.meta.id.as_deref() == Some(version.id.as_str()))
+        });
+        let version_dir = match existing_version {
+          Some(existing) => existing.dir.clone(),
+          None => unique_dir(&dir, &version.name, &mut taken_versions),
+        };
+
+        LocalVersion::from_remote(version_dir, version)
+          .write()
+          .await?;
+        summary.versions += 1;
+      }
+    }
+
+    Ok(summary)
+  }
+
+  /// Compares the directory with SendWithUs.
+  ///
+  /// Templates and versions that only exist on SendWithUs aren't reported.
+  ///
+  /// # Returns
+  /// The local changes that `push` would apply
+  ///
+  /// # Errors
+  /// Returns the first error reading files or fetching versions
+  pub async fn diff(&self) -> Result<Vec<TemplateChange>> {
+    let mut changes = Vec::new();
+
+    for template in read_local(&self.root).await? {
+      let Some(template_id) = &template.meta.id else {
+        changes.push(TemplateChange::NewTemplate {
+          name: template.meta.name.clone(),
+        });
+        continue;
+      };
+
+      for version in &template.versions {
+        if let Some(change) = self.version_change(template_id, version).await? {
+          changes.push(change);
+        }
+      }
+    }
+
+    Ok(changes)
+  }
+
+  /// Applies local changes to SendWithUs.
+  ///
+  /// New templates and versions are created and their IDs written to their
+  /// JSON files; modified versions are updated.
+  ///
+  /// # Returns
+  /// The changes that were applied
+  ///
+  /// # Errors
+  /// Returns the first error reading or writing files or calling the API.
+  /// Changes applied before the error are kept, with their IDs written back.
+  pub async fn push(&self) -> Result<Vec<TemplateChange>> {
+    let mut applied = Vec::new();
+
+    for mut template in read_local(&self.root).await? {
+      let template_id = match template.meta.id.clone() {
+        Some(id) => id,
+        None => match self.create_template(&mut template).await? {
+          Some((id, change)) => {
+            applied.push(change);
+            id
+          }
+          None => continue,
+        },
+      };
+
+      for version in &mut template.versions {
+        if let Some(change) = self.apply_version(&template_id, version).await? {
+          applied.push(change);
+        }
+      }
+    }
+
+    Ok(applied)
+  }
+
+  /// Applies local changes to SendWithUs, uploading versions concurrently.
+  ///
+  /// Suited to directories generated by a build, where many versions change
+  /// at once. New templates are created first, one at a time; then versions
+  /// are created or updated up to `concurrency` at a time. A failure only
+  /// affects its own template or version: every other one is still uploaded,
+  /// and the versions of a template that couldn't be created fail too.
+  ///
+  /// # Arguments
+  /// * `concurrency` - Maximum number of versions uploaded at once (at least 1)
+  /// * `on_progress` - Called with each result as it completes, and the progress so far
+  ///
+  /// # Returns
+  /// One result per new template and per version directory, in completion order
+  ///
+  /// # Errors
+  /// Returns an error only if the directory cannot be read
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// use send_with_us::Api;
+  /// use send_with_us::template_sync::TemplateSync;
+  ///
+  /// # async fn run() -> send_with_us::Result<()> {
+  /// let sync = TemplateSync::new(Api::with_api_key("api-key"), "build/templates");
+  ///
+  /// let results = sync
+  ///   .upload(8, |result, progress| {
+  ///     println!("[{}/{}] {}", progress.completed, progress.total, result.dir.display());
+  ///   })
+  ///   .await?;
+  ///
+  /// let failed = results.iter().filter(|result| result.outcome.is_err()).count();
+  /// println!("{} failed", failed);
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn upload<F>(&self, concurrency: usize, mut on_progress: F) -> Result<Vec<UploadResult>>
+  where
+    F: FnMut(&UploadResult, UploadProgress),
+  {
+    let templates = read_local(&self.root).await?;
+    let total = templates
+      .iter()
+      .map(|t| t.versions.len() + usize::from(t.meta.id.is_none()))
+      .sum();
+    let mut results = Vec::with_capacity(total);
+    let mut report = |result: UploadResult, results: &mut Vec<UploadResult>| {
+      let progress = UploadProgress {
+        completed: results.len() + 1,
+        total,
+      };
+      on_progress(&result, progress);
+      results.push(result);
+    };
+
+    let mut queue = Vec::new();
+    for mut template in templates {
+      let template_id = match template.meta.id.clone() {
+        Some(id) => id,
+        None => {
+          let dir = template.dir.clone();
+          match self.create_template(&mut template).await {
+            Ok(Some((id, change))) => {
+              report(UploadResult::new(dir, Ok(Some(change))), &mut results);
+              id
+            }
+            Ok(None) => {
+              report(UploadResult::new(dir, Ok(None)), &mut results);
+              continue;
+            }
+            Err(error) => {
+              report(UploadResult::new(dir, Err(error)), &mut results);
+              for version in template.versions {
+                let error = Error::Unexpected(format!(
+                  "template {:?} could not be created",
+                  template.meta.name
+                ));
+                report(UploadResult::new(version.dir, Err(error)), &mut results);
+              }
+              continue;
+            }
+          }
+        }
+      };
+
+      queue.extend(
+        template
+          .versions
+          .into_iter()
+          .map(|v| (template_id.clone(), v)),
+      );
+    }
+
+    let mut uploads = stream::iter(queue)
+      .map(|(template_id, mut version)| async move {
+        let outcome = self.apply_version(&template_id, &mut version).await;
+        UploadResult::new(version.dir, outcome)
+      })
+      .buffer_unordered(concurrency.max(1));
+
+    while let Some(result) = uploads.next().await {
+      report(result, &mut results);
+    }
+
+    Ok(results)
+  }
+
+  /// Creates a local template on SendWithUs from its first version and writes its ID back.
+  ///
+  /// Returns `None` for a template without versions, which can't be created.
+  async fn create_template(
+    &self,
+    template: &mut LocalTemplate,
+  ) -> Result<Option<(TemplateId, TemplateChange)>> {
+    let Some(first) = template.versions.first_mut() else {
+      return Ok(None);
+    };
+
+    let mut options = first.options();
+    options.name = template.meta.name.clone();
+    let created: Template = self.api.create_template_as(options).await?;
+
+    template.meta.id = Some(created.id.clone());
+    write_json(&template.dir.join(TEMPLATE_FILE), &template.meta).await?;
+
+    // The first version is created along with the template; any differences,
+    // such as its name, are applied as an update afterwards.
+    if let Some(version) = created.versions.first() {
+      first.meta.id = Some(version.id.clone());
+      write_json(&first.dir.join(VERSION_FILE), &first.meta).await?;
+    }
+
+    let change = TemplateChange::NewTemplate {
+      name: template.meta.name.clone(),
+    };
+    Ok(Some((created.id, change)))
+  }
+
+  /// Creates or updates a version of an existing template if it differs from SendWithUs.
+  async fn apply_version(
+    &self,
+    template_id: &TemplateId,
+    version: &mut LocalVersion,
+  ) -> Result<Option<TemplateChange>> {
+    let Some(change) = self.version_change(template_id, version).await? else {
+      return Ok(None);
+    };
+
+    match &change {
+      TemplateChange::NewVersion { .. } => {
+        let created: TemplateVersion = self
+          .api
+          .create_template_version_as(template_id, version.options())
+          .await?;
+        version.meta.id = Some(created.id);
+        write_json(&version.dir.join(VERSION_FILE), &version.meta).await?;
+      }
+      TemplateChange::ModifiedVersion { version_id, .. } => {
+        self
+          .api
+          .update_template_version_as::<serde_json::Value>(
+            template_id,
+            version_id,
+            version.options(),
+          )
+          .await?;
+      }
+      TemplateChange::NewTemplate { .. } => {}
+    }
+
+    Ok(Some(change))
+  }
+
+  /// Compares a local version of an existing template with SendWithUs.
+  async fn version_change(
+    &self,
+    template_id: &TemplateId,
+    version: &LocalVersion,
+  ) -> Result<Option<TemplateChange>> {
+    let Some(version_id) = &version.meta.id else {
+      return Ok(Some(TemplateChange::NewVersion {
+        template_id: template_id.clone(),
+        name: version.meta.name.clone(),
+      }));
+    };
+
+    let remote: TemplateVersion = self
+      .api
+      .get_template_version_as(template_id, version_id)
+      .await?;
+    let fields = version.changed_fields(&remote);
+
+    Ok(
+      (!fields.is_empty()).then(|| TemplateChange::ModifiedVersion {
+        template_id: template_id.clone(),
+        version_id: version_id.clone(),
+        name: version.meta.name.clone(),
+        fields,
+      }),
+    )
+  }
+}
+
+/// Contents of `template.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TemplateFile {
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  id: Option<TemplateId>,
+  name: String,
+}
+
+/// Contents of `version.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct VersionFile {
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  id: Option<String>,
+  name: String,
+  #[serde(default)]
+  subject: String,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  preheader: Option<String>,
+}
+
+/// A template directory.
+#[derive(Debug)]
+struct LocalTemplate {
+  dir: PathBuf,
+  meta: TemplateFile,
+  versions: Vec<LocalVersion>,
+}
+
+/// A version directory.
+#[derive(Debug)]
+struct LocalVersion {
+  dir: PathBuf,
+  meta: VersionFile,
+  html: String,
+  text: String,
+  amp_html: Option<String>,
+}
+
+impl LocalVersion {
+  fn from_remote(dir: PathBuf, version: TemplateVersion) -> Self {
+    Self {
+      dir,
+      meta: VersionFile {
+        id: Some(version.id),
+        name: version.name,
+        subject: version.subject.unwrap_or_default(),
+        preheader: non_empty(version.preheader),
+      },
+      html: version.html.unwrap_or_default(),
+      text: version.text.unwrap_or_default(),
+      amp_html: non_empty(version.amp_html),
+    }
+  }
+
+  async fn read(dir: PathBuf) -> Result<Self> {
+    let meta: VersionFile =
+      serde_json::from_str(&fs::read_to_string(dir.join(VERSION_FILE)).await?)?;
+
+    Ok(Self {
+      html: read_optional(&dir.join(HTML_FILE))
+        .await?
+        .unwrap_or_default(),
+      text: read_optional(&dir.join(TEXT_FILE))
+        .await?
+        .unwrap_or_default(),
+      amp_html: non_empty(read_optional(&dir.join(AMP_FILE)).await?),
+      dir,
+      meta,
+    })
+  }
+
+  async fn write(&self) -> Result<()> {
+    write_json(&self.dir.join(VERSION_FILE), &self.meta).await?;
+    fs::write(self.dir.join(HTML_FILE), &self.html).await?;
+    fs::write(self.dir.join(TEXT_FILE), &self.text).await?;
+
+    match &self.amp_html {
+      Some(amp_html) => fs::write(self.dir.join(AMP_FILE), amp_html).await?,
+      None => remove_if_exists(&self.dir.join(AMP_FILE)).await?,
+    }
+
+    Ok(())
+  }
+
+  fn options(&self) -> TemplateOptions {
+    let mut options = TemplateOptions::new(
+      self.meta.name.clone(),
+      self.meta.subject.clone(),
+      self.html.clone(),
+      self.text.clone(),
+    );
+    options.preheader = self.meta.preheader.clone();
+    options.amp_html = self.amp_html.clone();
+    options
+  }
+
+  /// Names the fields whose local content differs from `remote`.
+  fn changed_fields(&self, remote: &TemplateVersion) -> Vec<&'static str> {
+    let text = |value: &Option<String>| value.clone().unwrap_or_default();
+    let optional = |value: &Option<String>| non_empty(value.clone());
+
+    [
+      ("name", self.meta.name == remote.name),
+      ("subject", self.meta.subject == text(&remote.subject)),
+      ("html", self.html == text(&remote.html)),
+      ("text", self.text == text(&remote.text)),
+      (
+        "preheader",
+        non_empty(self.meta.preheader.clone()) == optional(&remote.preheader),
+      ),
+      ("amp_html", self.amp_html == optional(&remote.amp_html)),
+    ]
+    .into_iter()
+    .filter(|(_, same)| !same)
+    .map(|(field, _)| field)
+    .collect()
+  }
+}
+
+/// Reads every template directory under `root`, sorted by directory name.
+async fn read_local(root: &Path) -> Result<Vec<LocalTemplate>> {
+  let mut templates = Vec::new();
+
+  for dir in subdirectories(root).await? {
+    let Some(meta) = read_optional(&dir.join(TEMPLATE_FILE)).await? else {
+      continue;
+    };
+    let meta: TemplateFile = serde_json::from_str(&meta)?;
+
+    let mut versions = Vec::new();
+    for version_dir in subdirectories(&dir).await? {
+      if fs::try_exists(version_dir.join(VERSION_FILE)).await? {
+        versions.push(LocalVersion::read(version_dir).await?);
+      }
+    }
+
+    templates.push(LocalTemplate {
+      dir,
+      meta,
+      versions,
+    });
+  }
+
+  Ok(templates)
+}
+
+/// Lists the subdirectories of `dir`, sorted; a missing `dir` has none.
+async fn subdirectories(dir: &Path) -> Result<Vec<PathBuf>> {
+  let mut entries = match fs::read_dir(dir).await {
+    Ok(entries) => entries,
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+    Err(e) => return Err(e.into()),
+  };
+
+  let mut dirs = Vec::new();
+  while let Some(entry) = entries.next_entry().await? {
+    if entry.file_type().await?.is_dir() {
+      dirs.push(entry.path());
+    }
+  }
+
+  dirs.sort();
+  Ok(dirs)
+}
+
+/// Picks an unused subdirectory of `parent` named after `name`.
+fn unique_dir(parent: &Path, name: &str, taken: &mut HashSet<PathBuf>) -> PathBuf {
+  let slug = slugify(name);
+  let mut dir = parent.join(&slug);
+  let mut suffix = 2;
+
+  while taken.contains(&dir) || dir.exists() {
+    dir = parent.join(format!("{}-{}", slug, suffix));
+    suffix += 1;
+  }
+
+  taken.insert(dir.clone());
+  dir
+}
+
+/// Turns a name into a directory name of lowercase letters, digits, and dashes.
+fn slugify(name: &str) -> String {
+  let mut slug = String::new();
+
+  for c in name.chars() {
+    if c.is_ascii_alphanumeric() {
+      slug.push(c.to_ascii_lowercase());
+    } else if !slug.is_empty() && !slug.ends_with('-') {
+      slug.push('-');
+    }
+  }
+
+  let slug = slug.trim_end_matches('-');
+  if slug.is_empty() {
+    "untitled".to_string()
+  } else {
+    slug.to_string()
+  }
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+  value.filter(|value| !value.is_empty())
+}
+
+async fn read_optional(path: &Path) -> Result<Option<String>> {
+  match fs::read_to_string(path).await {
+    Ok(contents) => Ok(Some(contents)),
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+    Err(e) => Err(e.into()),
+  }
+}
+
+async fn remove_if_exists(path: &Path) -> Result<()> {
+  match fs::remove_file(path).await {
+    Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+    _ => Ok(()),
+  }
+}
+
+async fn write_json(path: &Path, value: &impl Serialize) -> Result<()> {
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent).await?;
+  }
+
+  let mut json = serde_json::to_string_pretty(value)?;
+  json.push('\n');
+  fs::write(path, json).await?;
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Config;
+  use serde_json::json;
+  use tempdir::TempDir;
+
+  #[test]
+  fn test_slugify() {
+    assert_eq!(slugify("Welcome Email"), "welcome-email");
+    assert_eq!(slugify("  Order #123 -- Receipt!"), "order-123-receipt");
+    assert_eq!(slugify("¡¿?!"), "untitled");
+  }
+
+  #[tokio::test]
+  async fn test_pull_diff_push() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
+    let version = json!({
+      "id": "ver_1",
+      "name": "Original",
+      "published": true,
+      "subject": "Welcome!",
+      "html": "<p>Hi</p>",
+      "text": "Hi"
+    });
+
+    let list = mock_server
+      .mock("GET", "/api/v1/emails")
+      .with_status(200)
+      .with_body(
+        json!([{"id": "tem_1", "name": "Welcome Email", "versions": [{"id": "ver_1", "name": "Original"}]}])
+          .to_string(),
+      )
+      .create();
+    let get = mock_server
+      .mock("GET", "/api/v1/templates/tem_1/versions/ver_1")
+      .with_status(200)
+      .with_body(version.to_string())
+      .expect(3)
+      .create();
+    let update = mock_server
+      .mock("PUT", "/api/v1/templates/tem_1/versions/ver_1")
+      .match_body(mockito::Matcher::PartialJson(
+        json!({"html": "<p>Hello</p>"}),
+      ))
+      .with_status(200)
+      .with_body(version.to_string())
+      .create();
+    let create = mock_server
+      .mock("POST", "/api/v1/templates/tem_1/versions")
+      .match_body(mockito::Matcher::PartialJson(json!({"name": "Variant B"})))
+      .with_status(200)
+      .with_body(json!({"id": "ver_2", "name": "Variant B"}).to_string())
+      .create();
+
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&url).unwrap();
+    let dir = TempDir::new("template_sync").unwrap();
+    let sync = TemplateSync::new(Api::new(config), dir.path());
+
+    let summary = sync.pull().await.unwrap();
+    assert_eq!(
+      summary,
+      PullSummary {
+        templates: 1,
+        versions: 1
+      }
+    );
+
+    let version_dir = dir.path().join("welcome-email").join("original");
+    assert_eq!(
+      std::fs::read_to_string(version_dir.join(HTML_FILE)).unwrap(),
+      "<p>Hi</p>"
+    );
+    assert!(sync.diff().await.unwrap().is_empty());
+
+    std::fs::write(version_dir.join(HTML_FILE), "<p>Hello</p>").unwrap();
+    let new_dir = dir.path().join("welcome-email").join("variant-b");
+    std::fs::create_dir(&new_dir).unwrap();
+    std::fs::write(
+      new_dir.join(VERSION_FILE),
+      r#"{"name": "Variant B", "subject": "Welcome!"}"#,
+    )
+    .unwrap();
+
+    let template_id = TemplateId::new("tem_1").unwrap();
+    let expected = vec![
+      TemplateChange::ModifiedVersion {
+        template_id: template_id.clone(),
+        version_id: "ver_1".to_string(),
+        name: "Original".to_string(),
+        fields: vec!["html"],
+      },
+      TemplateChange::NewVersion {
+        template_id,
+        name: "Variant B".to_string(),
+      },
+    ];
+    assert_eq!(sync.push().await.unwrap(), expected);
+
+    let meta: VersionFile =
+      serde_json::from_str(&std::fs::read_to_string(new_dir.join(VERSION_FILE)).unwrap()).unwrap();
+    assert_eq!(meta.id.as_deref(), Some("ver_2"));
+
+    list.assert();
+    get.assert();
+    update.assert();
+    create.assert();
+  }
+
+  fn write_version(dir: &Path, meta: serde_json::Value, html: &str) {
+    std::fs::create_dir_all(dir).unwrap();
+    std::fs::write(dir.join(VERSION_FILE), meta.to_string()).unwrap();
+    std::fs::write(dir.join(HTML_FILE), html).unwrap();
+  }
+
+  #[tokio::test]
+  async fn test_upload_reports_each_directory() {
+    let mut mock_server = mockito::Server::new_async().await;
+    let url = mock_server.url();
+    let dir = TempDir::new("template_upload").unwrap();
+
+    let welcome = dir.path().join("welcome");
+    std::fs::create_dir(&welcome).unwrap();
+    std::fs::write(welcome.join(TEMPLATE_FILE), r#"{"name": "Welcome"}"#).unwrap();
+    write_version(
+      &welcome.join("a"),
+      json!({"name": "Original", "subject": "Hi"}),
+      "<p>Hi</p>",
+    );
+    write_version(
+      &welcome.join("b"),
+      json!({"name": "Variant", "subject": "Hello"}),
+      "<p>Hello</p>",
+    );
+
+    let receipt = dir.path().join("receipt");
+    std::fs::create_dir(&receipt).unwrap();
+    std::fs::write(
+      receipt.join(TEMPLATE_FILE),
+      r#"{"id": "tem_2", "name": "Receipt"}"#,
+    )
+    .unwrap();
+    write_version(
+      &receipt.join("default"),
+      json!({"id": "ver_r", "name": "Default", "subject": "Receipt"}),
+      "new",
+    );
+
+    let create_template = mock_server
+      .mock("POST", "/api/v1/emails")
+      .match_body(mockito::Matcher::PartialJson(json!({"name": "Welcome"})))
+      .with_status(200)
+      .with_body(
+        json!({"id": "tem_1", "name": "Welcome", "versions": [{"id": "ver_a", "name": "Welcome"}]})
+          .to_string(),
+      )
+      .create();
+    mock_server
+      .mock("GET", "/api/v1/templates/tem_1/versions/ver_a")
+      .with_status(200)
+      .with_body(
+        json!({"id": "ver_a", "name": "Welcome", "subject": "Hi", "html": "<p>Hi</p>"}).to_string(),
+      )
+      .create();
+    let rename = mock_server
+      .mock("PUT", "/api/v1/templates/tem_1/versions/ver_a")
+      .match_body(mockito::Matcher::PartialJson(json!({"name": "Original"})))
+      .with_status(200)
+      .with_body("{}")
+      .create();
+    let create_version = mock_server
+      .mock("POST", "/api/v1/templates/tem_1/versions")
+      .with_status(200)
+      .with_body(json!({"id": "ver_b", "name": "Variant"}).to_string())
+      .create();
+    mock_server
+      .mock("GET", "/api/v1/templates/tem_2/versions/ver_r")
+      .with_status(200)
+      .with_body(
+        json!({"id": "ver_r", "name": "Default", "subject": "Receipt", "html": "old"}).to_string(),
+      )
+      .create();
+    mock_server
+      .mock("PUT", "/api/v1/templates/tem_2/versions/ver_r")
+      .with_status(500)
+      .with_body(r#"{"error": "boom"}"#)
+      .create();
+
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&url).unwrap();
+    let sync = TemplateSync::new(Api::new(config), dir.path());
+
+    let mut progress = Vec::new();
+    let results = sync
+      .upload(2, |_, update| progress.push(update))
+      .await
+      .unwrap();
+
+    assert_eq!(results.len(), 4);
+    assert_eq!(
+      progress.last(),
+      Some(&UploadProgress {
+        completed: 4,
+        total: 4
+      })
+    );
+
+    let outcome = |dir: &Path| &results.iter().find(|r| r.dir == dir).unwrap().outcome;
+    assert!(matches!(
+      outcome(&welcome),
+      Ok(Some(TemplateChange::NewTemplate { .. }))
+    ));
+    assert!(matches!(
+      outcome(&welcome.join("a")),
+      Ok(Some(TemplateChange::ModifiedVersion { .. }))
+    ));
+    assert!(matches!(
+      outcome(&welcome.join("b")),
+      Ok(Some(TemplateChange::NewVersion { .. }))
+    ));
+    assert!(outcome(&receipt.join("default")).is_err());
+
+    let meta: VersionFile =
+      serde_json::from_str(&std::fs::read_to_string(welcome.join("b").join(VERSION_FILE)).unwrap())
+        .unwrap();
+    assert_eq!(meta.id.as_deref(), Some("ver_b"));
+
+    create_template.assert();
+    rename.assert();
+    create_version.assert();
+  }
+}
@@ -0,0 +1,16 @@
+//! Helpers for testing applications that use this crate.
+//!
+//! Available with the `mock` feature. `assertions` checks the emails sent
+//! through a `mock::MockApi` or `mock::RecordingApiClient`. `fixtures`
+//! builds response bodies in SendWithUs' wire format, for canned responses
+//! of a `mock::MockApi` or a local mock server. With the `mock-server` feature, `mock_server` runs
+//! such a server and checks the requests made to it. With the `wiremock`
+//! feature, `wiremock` runs a fake SendWithUs answering every endpoint.
+
+#[cfg(feature = "mock")]
+pub mod assertions;
+pub mod fixtures;
+#[cfg(feature = "mock-server")]
+pub mod mock_server;
+#[cfg(feature = "wiremock")]
+pub mod wiremock;
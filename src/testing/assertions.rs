@@ -0,0 +1,187 @@
+//! Assertions on the emails an application sent.
+//!
+//! Each assertion takes a client that remembers its sends, a `MockApi` or a
+//! `RecordingApiClient`, and panics with a list of what was actually sent if
+//! no email matches. Tests then read as statements of the expected behavior:
+//!
+//! ```
+//! use send_with_us::ApiClient;
+//! use send_with_us::mock::RecordingApiClient;
+//! use send_with_us::testing::assertions::{assert_data_contains, assert_sent_to, assert_template_used};
+//! use send_with_us::types::{EmailOptions, Recipient};
+//! use std::collections::HashMap;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> send_with_us::Result<()> {
+//! let api = RecordingApiClient::new();
+//!
+//! let options = EmailOptions::new("tem_welcome", Recipient::new("user@example.com"))
+//!   .with_data(HashMap::from([("plan".to_string(), serde_json::json!("pro"))]));
+//! api.send_email(options).await?;
+//!
+//! assert_sent_to(&api, "user@example.com");
+//! assert_template_used(&api, "tem_welcome");
+//! assert_data_contains(&api, "plan", "pro");
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::mock::{MockApi, RecordingApiClient, addressed_to};
+use crate::types::EmailOptions;
+use serde::Serialize;
+use serde_json::json;
+
+/// A client that remembers the emails sent through it.
+pub trait SentEmails {
+  /// Returns the options of every email sent so far, in order.
+  fn sent_emails(&self) -> Vec<EmailOptions>;
+}
+
+impl SentEmails for RecordingApiClient {
+  fn sent_emails(&self) -> Vec<EmailOptions> {
+    self.sent()
+  }
+}
+
+impl SentEmails for MockApi {
+  /// Includes sends rejected by validation or a programmed error.
+  fn sent_emails(&self) -> Vec<EmailOptions> {
+    self
+      .calls_to("send_email")
+      .into_iter()
+      .filter_map(|call| serde_json::from_value(call.args["options"].clone()).ok())
+      .collect()
+  }
+}
+
+/// Asserts that an email was sent to an address, as the recipient or in CC or BCC.
+///
+/// # Arguments
+/// * `client` - Client the emails were sent through
+/// * `address` - Email address, compared case-insensitively
+///
+/// # Panics
+/// Panics if no email was sent to `address`
+#[track_caller]
+pub fn assert_sent_to(client: &impl SentEmails, address: &str) {
+  let sent = client.sent_emails();
+  if !sent.iter().any(|options| addressed_to(options, address)) {
+    panic!(
+      "expected an email sent to {}, but sent {}",
+      address,
+      describe(&sent)
+    );
+  }
+}
+
+/// Asserts that an email was sent with a template.
+///
+/// # Arguments
+/// * `client` - Client the emails were sent through
+/// * `template_id` - Template ID the email was sent with
+///
+/// # Panics
+/// Panics if no email was sent with `template_id`
+#[track_caller]
+pub fn assert_template_used(client: &impl SentEmails, template_id: &str) {
+  let sent = client.sent_emails();
+  if !sent.iter().any(|options| options.email_id == template_id) {
+    panic!(
+      "expected an email sent with template {}, but sent {}",
+      template_id,
+      describe(&sent)
+    );
+  }
+}
+
+/// Asserts that an email was sent with a value in its template data.
+///
+/// # Arguments
+/// * `client` - Client the emails were sent through
+/// * `key` - Top-level key in the email data
+/// * `value` - Value the key must hold, compared as JSON
+///
+/// # Panics
+/// Panics if no email was sent with `value` at `key`
+#[track_caller]
+pub fn assert_data_contains(client: &impl SentEmails, key: &str, value: impl Serialize) {
+  let value = json!(value);
+  let sent = client.sent_emails();
+  let found = sent.iter().any(|options| {
+    options
+      .data
+      .as_ref()
+      .and_then(|data| data.get(key))
+      .is_some_and(|actual| *actual == value)
+  });
+
+  if !found {
+    let data: Vec<_> = sent.iter().map(|options| json!(options.data)).collect();
+    panic!(
+      "expected an email sent with {} = {} in its data, but sent data {}",
+      key,
+      value,
+      json!(data)
+    );
+  }
+}
+
+/// Lists sent emails as `template -> address` for failure messages.
+fn describe(sent: &[EmailOptions]) -> String {
+  if sent.is_empty() {
+    return "no emails".to_string();
+  }
+
+  let emails: Vec<String> = sent
+    .iter()
+    .map(|options| format!("{} -> {}", options.email_id, options.recipient.address))
+    .collect();
+  format!("[{}]", emails.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::ApiClient;
+  use crate::testing::fixtures::SendReceiptFixture;
+  use crate::types::Recipient;
+  use std::collections::HashMap;
+
+  fn welcome() -> EmailOptions {
+    let mut options = EmailOptions::new("tem_welcome", Recipient::new("ada@example.com"))
+      .with_data(HashMap::from([
+        ("plan".to_string(), json!("pro")),
+        ("seats".to_string(), json!(3)),
+      ]));
+    options.bcc = Some(vec![Recipient::new("audit@example.com")]);
+    options
+  }
+
+  #[tokio::test]
+  async fn test_assertions_pass_for_both_clients() {
+    let recording = RecordingApiClient::new();
+    recording.send_email(welcome()).await.unwrap();
+
+    let mock = MockApi::new().with_response("send_email", SendReceiptFixture::new().to_json());
+    mock.send_email(welcome()).await.unwrap();
+
+    assert_sent_to(&recording, "Audit@Example.com");
+    assert_template_used(&recording, "tem_welcome");
+    assert_data_contains(&recording, "seats", 3);
+
+    assert_sent_to(&mock, "ada@example.com");
+    assert_template_used(&mock, "tem_welcome");
+    assert_data_contains(&mock, "plan", "pro");
+  }
+
+  #[tokio::test]
+  #[should_panic(
+    expected = "expected an email sent with template tem_reset, but sent [tem_welcome -> ada@example.com]"
+  )]
+  async fn test_assertion_failure_lists_sent_emails() {
+    let api = RecordingApiClient::new();
+    api.send_email(welcome()).await.unwrap();
+
+    assert_template_used(&api, "tem_reset");
+  }
+}
@@ -0,0 +1,432 @@
+//! Builders for realistic SendWithUs response bodies.
+//!
+//! Each builder starts from a complete, plausible response and lets tests
+//! override only the fields they care about. Bodies are produced by
+//! serializing this crate's models, so they stay in step with what the
+//! client parses. Setters panic on invalid IDs, like any other test setup
+//! mistake.
+
+use crate::ids::{LogId, ReceiptId, TemplateId};
+use crate::models::{EmailLog, EmailSummary, SendResponse, Template, TemplateVersion};
+use serde_json::{Value, json};
+
+/// Creation time used by fixtures unless overridden (2023-01-01T12:00:00Z).
+pub const FIXTURE_TIMESTAMP: i64 = 1672574400;
+
+/// Builds the receipt returned by `send_email`.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::testing::fixtures::SendReceiptFixture;
+///
+/// let body = SendReceiptFixture::new()
+///   .with_receipt_id("log_123")
+///   .with_locale("fr-FR")
+///   .to_json();
+///
+/// assert_eq!(body["receipt_id"], "log_123");
+/// assert_eq!(body["email"]["locale"], "fr-FR");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SendReceiptFixture {
+  response: SendResponse,
+}
+
+impl Default for SendReceiptFixture {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl SendReceiptFixture {
+  /// Creates a successful receipt for the "Welcome" template.
+  pub fn new() -> Self {
+    Self {
+      response: SendResponse {
+        success: true,
+        status: "OK".to_string(),
+        receipt_id: ReceiptId::new("log_fixture_1").expect("valid fixture receipt ID"),
+        email: EmailSummary {
+          name: "Welcome".to_string(),
+          version_name: Some("Original".to_string()),
+          locale: Some("en-US".to_string()),
+        },
+      },
+    }
+  }
+
+  /// Sets the receipt ID.
+  ///
+  /// # Panics
+  /// Panics if `receipt_id` isn't a valid receipt ID
+  pub fn with_receipt_id(mut self, receipt_id: &str) -> Self {
+    self.response.receipt_id = ReceiptId::new(receipt_id).expect("valid receipt ID");
+    self
+  }
+
+  /// Sets the name of the template sent.
+  pub fn with_template_name(mut self, name: impl Into<String>) -> Self {
+    self.response.email.name = name.into();
+    self
+  }
+
+  /// Sets the name of the template version sent.
+  pub fn with_version_name(mut self, version_name: impl Into<String>) -> Self {
+    self.response.email.version_name = Some(version_name.into());
+    self
+  }
+
+  /// Sets the locale of the template version sent.
+  pub fn with_locale(mut self, locale: impl Into<String>) -> Self {
+    self.response.email.locale = Some(locale.into());
+    self
+  }
+
+  /// Returns the receipt as the client parses it.
+  pub fn build(self) -> SendResponse {
+    self.response
+  }
+
+  /// Returns the receipt as a response body.
+  pub fn to_json(&self) -> Value {
+    to_json(&self.response)
+  }
+}
+
+/// Builds an email log entry, as returned by `log` and `logs`.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::testing::fixtures::{EmailLogFixture, customer_email_logs};
+///
+/// let log = EmailLogFixture::new()
+///   .with_recipient("ada@example.com")
+///   .with_status("bounced");
+///
+/// assert_eq!(log.to_json()["status"], "bounced");
+/// assert_eq!(customer_email_logs(vec![log])["logs"][0]["recipient_address"], "ada@example.com");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmailLogFixture {
+  log: EmailLog,
+}
+
+impl Default for EmailLogFixture {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl EmailLogFixture {
+  /// Creates a log of the "Welcome" template sent to `user@example.com`.
+  pub fn new() -> Self {
+    Self {
+      log: EmailLog {
+        id: LogId::new("log_fixture_1").expect("valid fixture log ID"),
+        created: FIXTURE_TIMESTAMP,
+        recipient_address: "user@example.com".to_string(),
+        recipient_name: Some("Test User".to_string()),
+        status: "sent".to_string(),
+        message: Some("Mandrill: Message has been successfully delivered".to_string()),
+        email_id: Some(TemplateId::new("tem_fixture").expect("valid fixture template ID")),
+        email_name: Some("Welcome".to_string()),
+        email_version: Some("Original".to_string()),
+      },
+    }
+  }
+
+  /// Sets the log ID.
+  ///
+  /// # Panics
+  /// Panics if `log_id` isn't a valid log ID
+  pub fn with_id(mut self, log_id: &str) -> Self {
+    self.log.id = LogId::new(log_id).expect("valid log ID");
+    self
+  }
+
+  /// Sets when the email was logged (Unix timestamp).
+  pub fn with_created(mut self, created: i64) -> Self {
+    self.log.created = created;
+    self
+  }
+
+  /// Sets the recipient's address.
+  pub fn with_recipient(mut self, address: impl Into<String>) -> Self {
+    self.log.recipient_address = address.into();
+    self
+  }
+
+  /// Sets the delivery status, e.g. `sent`, `opened`, or `bounced`.
+  pub fn with_status(mut self, status: impl Into<String>) -> Self {
+    self.log.status = status.into();
+    self
+  }
+
+  /// Sets the status message from the email service provider.
+  pub fn with_message(mut self, message: impl Into<String>) -> Self {
+    self.log.message = Some(message.into());
+    self
+  }
+
+  /// Sets the template the email was sent with.
+  ///
+  /// # Panics
+  /// Panics if `template_id` isn't a valid template ID
+  pub fn with_template(mut self, template_id: &str, name: impl Into<String>) -> Self {
+    self.log.email_id = Some(TemplateId::new(template_id).expect("valid template ID"));
+    self.log.email_name = Some(name.into());
+    self
+  }
+
+  /// Returns the log as the client parses it.
+  pub fn build(self) -> EmailLog {
+    self.log
+  }
+
+  /// Returns the log as a response body.
+  pub fn to_json(&self) -> Value {
+    to_json(&self.log)
+  }
+}
+
+/// Returns the body of `customer_email_log`, which wraps the logs in an object.
+///
+/// # Arguments
+/// * `logs` - Logs of the customer, newest first
+pub fn customer_email_logs(logs: Vec<EmailLogFixture>) -> Value {
+  let logs: Vec<Value> = logs.iter().map(EmailLogFixture::to_json).collect();
+  json!({ "logs": logs })
+}
+
+/// Builds a template version, as returned by `get_template_version`.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::testing::fixtures::TemplateVersionFixture;
+///
+/// let version = TemplateVersionFixture::new().with_subject("Hello {{ name }}").build();
+/// assert_eq!(version.subject.as_deref(), Some("Hello {{ name }}"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateVersionFixture {
+  version: TemplateVersion,
+}
+
+impl Default for TemplateVersionFixture {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl TemplateVersionFixture {
+  /// Creates the published "Original" version of a welcome email.
+  pub fn new() -> Self {
+    Self {
+      version: TemplateVersion {
+        id: "ver_fixture_1".to_string(),
+        name: "Original".to_string(),
+        published: true,
+        subject: Some("Welcome, {{ first_name }}!".to_string()),
+        html: Some(
+          "<html><body><p>Hi {{ first_name }}, welcome aboard.</p></body></html>".to_string(),
+        ),
+        text: Some("Hi {{ first_name }}, welcome aboard.".to_string()),
+        preheader: None,
+        amp_html: None,
+        created: Some(FIXTURE_TIMESTAMP),
+      },
+    }
+  }
+
+  /// Sets the version ID.
+  pub fn with_id(mut self, version_id: impl Into<String>) -> Self {
+    self.version.id = version_id.into();
+    self
+  }
+
+  /// Sets the version name.
+  pub fn with_name(mut self, name: impl Into<String>) -> Self {
+    self.version.name = name.into();
+    self
+  }
+
+  /// Sets whether the version is published.
+  pub fn with_published(mut self, published: bool) -> Self {
+    self.version.published = published;
+    self
+  }
+
+  /// Sets the subject line.
+  pub fn with_subject(mut self, subject: impl Into<String>) -> Self {
+    self.version.subject = Some(subject.into());
+    self
+  }
+
+  /// Sets the HTML content.
+  pub fn with_html(mut self, html: impl Into<String>) -> Self {
+    self.version.html = Some(html.into());
+    self
+  }
+
+  /// Sets the plain text content.
+  pub fn with_text(mut self, text: impl Into<String>) -> Self {
+    self.version.text = Some(text.into());
+    self
+  }
+
+  /// Sets the preheader.
+  pub fn with_preheader(mut self, preheader: impl Into<String>) -> Self {
+    self.version.preheader = Some(preheader.into());
+    self
+  }
+
+  /// Returns the version as the client parses it.
+  pub fn build(self) -> TemplateVersion {
+    self.version
+  }
+
+  /// Returns the version as a response body.
+  pub fn to_json(&self) -> Value {
+    to_json(&self.version)
+  }
+
+  /// Returns the version as listed within a template, without its content.
+  fn summary(&self) -> TemplateVersion {
+    TemplateVersion {
+      subject: None,
+      html: None,
+      text: None,
+      preheader: None,
+      amp_html: None,
+      ..self.version.clone()
+    }
+  }
+}
+
+/// Builds a template, as returned by `get_template` and listed by `list_templates`.
+///
+/// Versions are listed with their names and IDs only, like SendWithUs does.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::testing::fixtures::{TemplateFixture, TemplateVersionFixture};
+///
+/// let template = TemplateFixture::new()
+///   .with_id("tem_receipt")
+///   .with_name("Receipt")
+///   .with_version(TemplateVersionFixture::new().with_id("ver_2").with_name("Variant B"));
+///
+/// let body = template.to_json();
+/// assert_eq!(body["versions"].as_array().unwrap().len(), 2);
+/// assert_eq!(body["versions"][1]["name"], "Variant B");
+/// assert!(body["versions"][1].get("html").is_none());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateFixture {
+  template: Template,
+}
+
+impl Default for TemplateFixture {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl TemplateFixture {
+  /// Creates a "Welcome" template with one published version.
+  pub fn new() -> Self {
+    Self {
+      template: Template {
+        id: TemplateId::new("tem_fixture").expect("valid fixture template ID"),
+        name: "Welcome".to_string(),
+        tags: Vec::new(),
+        versions: vec![TemplateVersionFixture::new().summary()],
+        created: Some(FIXTURE_TIMESTAMP),
+      },
+    }
+  }
+
+  /// Sets the template ID.
+  ///
+  /// # Panics
+  /// Panics if `template_id` isn't a valid template ID
+  pub fn with_id(mut self, template_id: &str) -> Self {
+    self.template.id = TemplateId::new(template_id).expect("valid template ID");
+    self
+  }
+
+  /// Sets the template name.
+  pub fn with_name(mut self, name: impl Into<String>) -> Self {
+    self.template.name = name.into();
+    self
+  }
+
+  /// Sets the template's tags.
+  pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+    self.template.tags = tags;
+    self
+  }
+
+  /// Adds a version to the template's listing.
+  pub fn with_version(mut self, version: TemplateVersionFixture) -> Self {
+    self.template.versions.push(version.summary());
+    self
+  }
+
+  /// Removes every version from the template's listing.
+  pub fn without_versions(mut self) -> Self {
+    self.template.versions.clear();
+    self
+  }
+
+  /// Returns the template as the client parses it.
+  pub fn build(self) -> Template {
+    self.template
+  }
+
+  /// Returns the template as a response body.
+  pub fn to_json(&self) -> Value {
+    to_json(&self.template)
+  }
+}
+
+fn to_json(value: &impl serde::Serialize) -> Value {
+  serde_json::to_value(value).expect("fixtures serialize to JSON")
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+  use super::*;
+  use crate::ApiClient;
+  use crate::mock::MockApi;
+
+  #[tokio::test]
+  async fn test_fixtures_parse_as_responses() {
+    let api = MockApi::new().with_response(
+      "log",
+      EmailLogFixture::new().with_status("opened").to_json(),
+    );
+
+    let log = api
+      .log(&LogId::new("log_fixture_1").unwrap())
+      .await
+      .unwrap();
+    assert_eq!(log, EmailLogFixture::new().with_status("opened").build());
+
+    let logs: crate::models::EmailLogs =
+      serde_json::from_value(customer_email_logs(vec![EmailLogFixture::new()])).unwrap();
+    assert_eq!(logs.logs, vec![EmailLogFixture::new().build()]);
+
+    let receipt: SendResponse =
+      serde_json::from_value(SendReceiptFixture::new().to_json()).unwrap();
+    assert_eq!(receipt, SendReceiptFixture::new().build());
+
+    let template: Template = serde_json::from_value(TemplateFixture::new().to_json()).unwrap();
+    assert_eq!(template.versions[0].html, None);
+    assert_eq!(template.versions[0].name, "Original");
+  }
+}
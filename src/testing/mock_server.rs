@@ -0,0 +1,261 @@
+//! A local SendWithUs stand-in for contract tests.
+//!
+//! Available with the `mock-server` feature. A `MockServer` runs a mockito
+//! server and hands out clients pointed at it, so tests exercise the real
+//! `Api`: serialization, headers, retries, and error mapping included.
+//! Expectations describe the requests a test requires, such as "a send with
+//! template X and header Y", and answer them with fixtures.
+
+use crate::api::Api;
+use crate::config::Config;
+use crate::testing::fixtures::SendReceiptFixture;
+use mockito::{Matcher, Mock, ServerGuard};
+use serde_json::{Map, Value, json};
+use std::fmt;
+
+/// A local mock of the SendWithUs API.
+///
+/// # Examples
+///
+/// ```no_run
+/// use send_with_us::ApiClient;
+/// use send_with_us::testing::mock_server::MockServer;
+/// use send_with_us::types::{EmailOptions, Recipient};
+/// use std::collections::HashMap;
+///
+/// # #[tokio::main]
+/// # async fn main() -> send_with_us::Result<()> {
+/// let mut server = MockServer::start().await;
+/// let send = server
+///   .expect_send()
+///   .with_template("tem_welcome")
+///   .with_header("X-Campaign", "spring")
+///   .create();
+///
+/// let options = EmailOptions::new("tem_welcome", Recipient::new("user@example.com"))
+///   .with_headers(HashMap::from([("X-Campaign".to_string(), "spring".to_string())]));
+/// server.api().send_email(options).await?;
+///
+/// send.assert();
+/// # Ok(())
+/// # }
+/// ```
+pub struct MockServer {
+  server: ServerGuard,
+}
+
+impl fmt::Debug for MockServer {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("MockServer")
+      .field("url", &self.url())
+      .finish()
+  }
+}
+
+impl MockServer {
+  /// Starts a mock server on a free local port.
+  pub async fn start() -> Self {
+    Self {
+      server: mockito::Server::new_async().await,
+    }
+  }
+
+  /// Returns the base URL of the server.
+  pub fn url(&self) -> String {
+    self.server.url()
+  }
+
+  /// Returns a configuration with a test API key, pointed at the server.
+  ///
+  /// Adjust it, e.g. with a retry policy, and pass it to `Api::new`.
+  pub fn config(&self) -> Config {
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&self.url()).expect("mock server URL is valid");
+    config
+  }
+
+  /// Returns a client pointed at the server.
+  pub fn api(&self) -> Api {
+    Api::new(self.config())
+  }
+
+  /// Describes a request the server should receive.
+  ///
+  /// Matching requests are answered with `{"success": true}` unless the
+  /// expectation says otherwise.
+  ///
+  /// # Arguments
+  /// * `method` - HTTP method, e.g. `"GET"`
+  /// * `endpoint` - Endpoint relative to the API version, e.g. `"customers"`
+  pub fn expect(&mut self, method: &str, endpoint: &str) -> Expectation {
+    Expectation {
+      mock: self
+        .server
+        .mock(method, format!("/api/v1/{}", endpoint).as_str()),
+      body: Map::new(),
+      status: 200,
+      response: json!({ "success": true }),
+      times: None,
+    }
+  }
+
+  /// Describes a send the server should receive, answered with a `SendReceiptFixture`.
+  pub fn expect_send(&mut self) -> Expectation {
+    self
+      .expect("POST", "send")
+      .respond_with(SendReceiptFixture::new().to_json())
+  }
+}
+
+/// A request a `MockServer` should receive, and its response.
+///
+/// Body conditions only check the fields they name, so requests may carry
+/// other fields too. Call `create` to install the expectation.
+#[derive(Debug)]
+#[must_use = "an expectation does nothing until created"]
+pub struct Expectation {
+  mock: Mock,
+  body: Map<String, Value>,
+  status: usize,
+  response: Value,
+  times: Option<usize>,
+}
+
+impl Expectation {
+  /// Requires the JSON body to contain a value, merging objects with earlier conditions.
+  ///
+  /// # Arguments
+  /// * `body` - JSON object the body must include
+  pub fn with_body(mut self, body: Value) -> Self {
+    if let Value::Object(fields) = body {
+      merge(&mut self.body, fields);
+    }
+    self
+  }
+
+  /// Requires the email to be sent with a template.
+  pub fn with_template(self, template_id: &str) -> Self {
+    self.with_body(json!({ "email_id": template_id }))
+  }
+
+  /// Requires the email to be sent to an address.
+  pub fn with_recipient(self, address: &str) -> Self {
+    self.with_body(json!({ "recipient": { "address": address } }))
+  }
+
+  /// Requires the email data to include a value.
+  pub fn with_data(self, key: &str, value: Value) -> Self {
+    self.with_body(json!({ "email_data": { key: value } }))
+  }
+
+  /// Requires the email to carry a custom email header.
+  pub fn with_header(self, name: &str, value: &str) -> Self {
+    self.with_body(json!({ "headers": { name: value } }))
+  }
+
+  /// Requires an HTTP request header, e.g. `X-Correlation-ID`.
+  pub fn with_request_header(mut self, name: &str, value: &str) -> Self {
+    self.mock = self.mock.match_header(name, value);
+    self
+  }
+
+  /// Requires exactly this many matching requests; by default at least one.
+  pub fn times(mut self, times: usize) -> Self {
+    self.times = Some(times);
+    self
+  }
+
+  /// Answers matching requests with HTTP 200 and a body.
+  pub fn respond_with(self, body: Value) -> Self {
+    self.respond_with_status(200, body)
+  }
+
+  /// Answers matching requests with a status and body.
+  pub fn respond_with_status(mut self, status: usize, body: Value) -> Self {
+    self.status = status;
+    self.response = body;
+    self
+  }
+
+  /// Installs the expectation on the server.
+  ///
+  /// # Returns
+  /// The mock; call `assert` on it after the code under test has run
+  pub fn create(self) -> Mock {
+    let mut mock = self
+      .mock
+      .with_status(self.status)
+      .with_header("content-type", "application/json")
+      .with_body(self.response.to_string());
+
+    if !self.body.is_empty() {
+      mock = mock.match_body(Matcher::PartialJson(Value::Object(self.body)));
+    }
+
+    mock = match self.times {
+      Some(times) => mock.expect(times),
+      None => mock.expect_at_least(1),
+    };
+
+    mock.create()
+  }
+}
+
+fn merge(into: &mut Map<String, Value>, fields: Map<String, Value>) {
+  for (key, value) in fields {
+    match (into.get_mut(&key), value) {
+      (Some(Value::Object(existing)), Value::Object(value)) => merge(existing, value),
+      (_, value) => {
+        into.insert(key, value);
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::ApiClient;
+  use crate::error::Error;
+  use crate::types::{EmailOptions, Recipient};
+  use std::collections::HashMap;
+
+  #[tokio::test]
+  async fn test_expect_send() {
+    let mut server = MockServer::start().await;
+    let send = server
+      .expect_send()
+      .with_template("tem_welcome")
+      .with_recipient("ada@example.com")
+      .with_data("plan", json!("pro"))
+      .with_header("X-Campaign", "spring")
+      .times(1)
+      .create();
+
+    let options = EmailOptions::new("tem_welcome", Recipient::new("ada@example.com"))
+      .with_data(HashMap::from([
+        ("plan".to_string(), json!("pro")),
+        ("seats".to_string(), json!(3)),
+      ]))
+      .with_headers(HashMap::from([(
+        "X-Campaign".to_string(),
+        "spring".to_string(),
+      )]));
+    let receipt = server.api().send_email(options).await.unwrap();
+
+    assert_eq!(receipt, SendReceiptFixture::new().build());
+    send.assert();
+  }
+
+  #[tokio::test]
+  async fn test_unmatched_request_fails() {
+    let mut server = MockServer::start().await;
+    let send = server.expect_send().with_template("tem_other").create();
+
+    let options = EmailOptions::new("tem_welcome", Recipient::new("ada@example.com"));
+    let result = server.api().send_email(options).await;
+
+    assert!(matches!(result, Err(Error::ApiError { status: 501, .. })));
+    assert!(!send.matched());
+  }
+}
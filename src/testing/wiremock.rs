@@ -0,0 +1,426 @@
+//! A fake SendWithUs built on `wiremock`.
+//!
+//! Available with the `wiremock` feature. `FakeSendWithUs::start` runs a
+//! wiremock server answering every endpoint the client calls with fixture
+//! bodies, so integration tests get a working SendWithUs in two lines:
+//!
+//! ```no_run
+//! # use send_with_us::testing::wiremock::FakeSendWithUs;
+//! # #[tokio::main]
+//! # async fn main() {
+//! let fake = FakeSendWithUs::start().await;
+//! let api = fake.api();
+//! # }
+//! ```
+//!
+//! Like SendWithUs, the fake rejects requests without an API key and sends
+//! without a template or recipient. Tests needing other responses can mount
+//! their own mocks on `server()`. Wiremock answers with the first mock
+//! mounted at the highest priority, so give them a priority below the
+//! default of 5 to take precedence over the standard endpoints.
+
+use crate::api::Api;
+use crate::config::Config;
+use crate::testing::fixtures::{
+  EmailLogFixture, SendReceiptFixture, TemplateFixture, TemplateVersionFixture,
+};
+use ::wiremock::matchers::{method, path, path_regex};
+use ::wiremock::{Mock, MockServer, Request, ResponseTemplate};
+use serde_json::{Value, json};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Header carrying the API key on every request.
+const API_KEY_HEADER: &str = "X-SWU-API-KEY";
+
+/// A running fake of the SendWithUs API.
+///
+/// # Examples
+///
+/// ```no_run
+/// use send_with_us::ApiClient;
+/// use send_with_us::testing::wiremock::FakeSendWithUs;
+/// use send_with_us::types::{EmailOptions, Recipient};
+///
+/// # #[tokio::main]
+/// # async fn main() -> send_with_us::Result<()> {
+/// let fake = FakeSendWithUs::start().await;
+///
+/// let options = EmailOptions::new("tem_welcome", Recipient::new("user@example.com"));
+/// fake.api().send_email(options).await?;
+///
+/// let sent = fake.sent_emails().await;
+/// assert_eq!(sent[0]["email_id"], "tem_welcome");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct FakeSendWithUs {
+  server: MockServer,
+}
+
+impl FakeSendWithUs {
+  /// Starts a wiremock server with the standard endpoints mounted.
+  pub async fn start() -> Self {
+    let server = MockServer::start().await;
+    mount_standard_endpoints(&server).await;
+    Self { server }
+  }
+
+  /// Returns the base URI of the server.
+  pub fn uri(&self) -> String {
+    self.server.uri()
+  }
+
+  /// Returns the underlying wiremock server, for mounting extra mocks.
+  pub fn server(&self) -> &MockServer {
+    &self.server
+  }
+
+  /// Returns a configuration with a test API key, pointed at the server.
+  ///
+  /// Adjust it, e.g. with a retry policy, and pass it to `Api::new`.
+  pub fn config(&self) -> Config {
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&self.uri()).expect("wiremock server URI is valid");
+    config
+  }
+
+  /// Returns a client pointed at the server.
+  pub fn api(&self) -> Api {
+    Api::new(self.config())
+  }
+
+  /// Returns the JSON bodies of the sends received so far, in order.
+  ///
+  /// Rejected sends are included, so tests can check what was attempted.
+  pub async fn sent_emails(&self) -> Vec<Value> {
+    self
+      .server
+      .received_requests()
+      .await
+      .unwrap_or_default()
+      .into_iter()
+      .filter(|request| request.method.as_str() == "POST" && request.url.path() == "/api/v1/send")
+      .filter_map(|request| request.body_json().ok())
+      .collect()
+  }
+}
+
+/// Mounts mocks for every endpoint the client calls on a wiremock server.
+///
+/// Requests without an `X-SWU-API-KEY` header are answered with 401. Sends
+/// are validated and answered with a receipt echoing the template, version,
+/// and locale, with receipt IDs numbered from `log_fake_1`. Lookups echo the
+/// requested ID, listings hold one fixture, and writes answer
+/// `{"success": true}`.
+///
+/// # Arguments
+/// * `server` - Server to mount the mocks on
+pub async fn mount_standard_endpoints(server: &MockServer) {
+  Mock::given(|request: &Request| !request.headers.contains_key(API_KEY_HEADER))
+    .respond_with(error(401, "Missing API key"))
+    .with_priority(1)
+    .mount(server)
+    .await;
+
+  let receipts = AtomicU64::new(0);
+  Mock::given(method("POST"))
+    .and(path("/api/v1/send"))
+    .respond_with(move |request: &Request| send(request, &receipts))
+    .mount(server)
+    .await;
+
+  Mock::given(method("POST"))
+    .and(path("/api/v1/render"))
+    .respond_with(render)
+    .mount(server)
+    .await;
+
+  // Templates
+  mount_json(
+    server,
+    "GET",
+    "/api/v1/emails",
+    json!([TemplateFixture::new().to_json()]),
+  )
+  .await;
+  mount_json(
+    server,
+    "POST",
+    "/api/v1/emails",
+    TemplateFixture::new().to_json(),
+  )
+  .await;
+  Mock::given(method("GET"))
+    .and(path_regex(r"^/api/v1/templates/[^/]+$"))
+    .respond_with(|request: &Request| {
+      let mut template = TemplateFixture::new().to_json();
+      template["id"] = json!(segment(request, 3));
+      ResponseTemplate::new(200).set_body_json(template)
+    })
+    .mount(server)
+    .await;
+  mount_regex(server, "PUT", r"^/api/v1/templates/[^/]+$").await;
+  mount_regex(server, "DELETE", r"^/api/v1/templates/[^/]+$").await;
+  Mock::given(method("GET"))
+    .and(path_regex(r"^/api/v1/templates/[^/]+/versions$"))
+    .respond_with(
+      ResponseTemplate::new(200).set_body_json(json!([TemplateVersionFixture::new().to_json()])),
+    )
+    .mount(server)
+    .await;
+  Mock::given(method("GET"))
+    .and(path_regex(r"^/api/v1/templates/[^/]+/versions/[^/]+$"))
+    .respond_with(|request: &Request| {
+      let mut version = TemplateVersionFixture::new().to_json();
+      version["id"] = json!(segment(request, 5));
+      ResponseTemplate::new(200).set_body_json(version)
+    })
+    .mount(server)
+    .await;
+  mount_regex(server, "POST", r"^/api/v1/templates/[^/]+/versions$").await;
+  mount_regex(server, "PUT", r"^/api/v1/templates/[^/]+/versions/[^/]+$").await;
+
+  // Logs
+  mount_json(
+    server,
+    "GET",
+    "/api/v1/logs",
+    json!([EmailLogFixture::new().to_json()]),
+  )
+  .await;
+  Mock::given(method("GET"))
+    .and(path_regex(r"^/api/v1/logs/[^/]+$"))
+    .respond_with(|request: &Request| {
+      let mut log = EmailLogFixture::new().to_json();
+      log["id"] = json!(segment(request, 3));
+      ResponseTemplate::new(200).set_body_json(log)
+    })
+    .mount(server)
+    .await;
+  Mock::given(method("GET"))
+    .and(path_regex(r"^/api/v1/logs/[^/]+/events$"))
+    .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+    .mount(server)
+    .await;
+
+  // Drip campaigns
+  mount_json(server, "GET", "/api/v1/drip_campaigns", json!([])).await;
+  mount_regex(server, "GET", r"^/api/v1/drip_campaigns/[^/]+$").await;
+  mount_regex(
+    server,
+    "POST",
+    r"^/api/v1/drip_campaigns/[^/]+/(activate|deactivate)$",
+  )
+  .await;
+  Mock::given(method("GET"))
+    .and(path_regex(r"^/api/v1/drip_campaigns/[^/]+/steps$"))
+    .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+    .mount(server)
+    .await;
+  Mock::given(method("GET"))
+    .and(path_regex(r"^/api/v1/drip_campaigns/[^/]+/customers$"))
+    .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "customers": [] })))
+    .mount(server)
+    .await;
+  mount_json(server, "POST", "/api/v1/drips/unsubscribe", success()).await;
+
+  // Customers
+  mount_json(server, "POST", "/api/v1/customers", success()).await;
+  Mock::given(method("GET"))
+    .and(path_regex(r"^/api/v1/customers/[^/]+$"))
+    .respond_with(|request: &Request| {
+      ResponseTemplate::new(200).set_body_json(json!({
+        "success": true,
+        "customer": { "email": segment(request, 3), "data": {} },
+      }))
+    })
+    .mount(server)
+    .await;
+  mount_regex(server, "PUT", r"^/api/v1/customers/[^/]+$").await;
+  mount_regex(server, "DELETE", r"^/api/v1/customers/[^/]+$").await;
+  mount_regex(server, "POST", r"^/api/v1/customers/[^/]+/events$").await;
+  Mock::given(method("GET"))
+    .and(path_regex(r"^/api/v1/customers/[^/]+/logs$"))
+    .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "logs": [] })))
+    .mount(server)
+    .await;
+
+  // Unsubscribes
+  mount_json(server, "POST", "/api/v1/unsubscribes", success()).await;
+  Mock::given(method("GET"))
+    .and(path_regex(r"^/api/v1/unsubscribes/[^/]+$"))
+    .respond_with(|request: &Request| {
+      ResponseTemplate::new(200).set_body_json(json!({
+        "email_address": segment(request, 3),
+        "unsubscribed": false,
+      }))
+    })
+    .mount(server)
+    .await;
+  mount_regex(server, "DELETE", r"^/api/v1/unsubscribes/[^/]+$").await;
+}
+
+/// Validates a send and answers it with a receipt.
+fn send(request: &Request, receipts: &AtomicU64) -> ResponseTemplate {
+  let body: Value = match request.body_json() {
+    Ok(body) => body,
+    Err(_) => return error(400, "Request body is not valid JSON"),
+  };
+
+  let template_id = body["email_id"].as_str().unwrap_or_default();
+  if template_id.is_empty() {
+    return error(400, "Missing email_id");
+  }
+  if body["recipient"]["address"]
+    .as_str()
+    .unwrap_or_default()
+    .is_empty()
+  {
+    return error(400, "Missing recipient address");
+  }
+
+  let receipt_id = format!("log_fake_{}", receipts.fetch_add(1, Ordering::Relaxed) + 1);
+  let mut receipt = SendReceiptFixture::new()
+    .with_receipt_id(&receipt_id)
+    .with_template_name(template_id)
+    .to_json();
+  receipt["email"]["version_name"] = body["version_name"].clone();
+  receipt["email"]["locale"] = body["locale"].clone();
+
+  ResponseTemplate::new(200).set_body_json(receipt)
+}
+
+/// Answers a render with the requested template and locale.
+fn render(request: &Request) -> ResponseTemplate {
+  let body: Value = request.body_json().unwrap_or_default();
+
+  ResponseTemplate::new(200).set_body_json(json!({
+    "success": true,
+    "status": "OK",
+    "template": {
+      "id": body["template"],
+      "name": "Welcome",
+      "version_name": "Original",
+      "locale": body["locale"],
+    },
+    "subject": "Welcome!",
+    "html": "<h1>Welcome!</h1>",
+    "text": "Welcome!",
+  }))
+}
+
+async fn mount_json(server: &MockServer, verb: &str, endpoint: &str, body: Value) {
+  Mock::given(method(verb))
+    .and(path(endpoint))
+    .respond_with(ResponseTemplate::new(200).set_body_json(body))
+    .mount(server)
+    .await;
+}
+
+/// Mounts a write endpoint answering `{"success": true}`.
+async fn mount_regex(server: &MockServer, verb: &str, pattern: &str) {
+  Mock::given(method(verb))
+    .and(path_regex(pattern))
+    .respond_with(ResponseTemplate::new(200).set_body_json(success()))
+    .mount(server)
+    .await;
+}
+
+fn success() -> Value {
+  json!({ "success": true })
+}
+
+fn error(status: u16, message: &str) -> ResponseTemplate {
+  ResponseTemplate::new(status).set_body_json(json!({
+    "success": false,
+    "status": "error",
+    "error": message,
+  }))
+}
+
+/// Returns a segment of the request path, e.g. 3 for `{id}` in `/api/v1/logs/{id}`.
+fn segment(request: &Request, index: usize) -> String {
+  request
+    .url
+    .path_segments()
+    .and_then(|mut segments| segments.nth(index))
+    .unwrap_or_default()
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::ApiClient;
+  use crate::ids::{LogId, TemplateId};
+  use crate::types::{EmailLogQuery, EmailOptions, Recipient};
+
+  #[tokio::test]
+  async fn test_fake_answers_standard_endpoints() {
+    let fake = FakeSendWithUs::start().await;
+    let api = fake.api();
+
+    let options =
+      EmailOptions::new("tem_welcome", Recipient::new("ada@example.com")).with_locale("fr-FR");
+    let first = api.send_email(options.clone()).await.unwrap();
+    let second = api.send_email(options).await.unwrap();
+    assert_eq!(first.receipt_id.as_str(), "log_fake_1");
+    assert_eq!(second.receipt_id.as_str(), "log_fake_2");
+    assert_eq!(first.email.name, "tem_welcome");
+    assert_eq!(first.email.locale.as_deref(), Some("fr-FR"));
+
+    let template_id = TemplateId::new("tem_welcome").unwrap();
+    let template = api.get_template(&template_id).await.unwrap();
+    assert_eq!(template["id"], "tem_welcome");
+
+    let log_id = LogId::new("log_fake_1").unwrap();
+    assert_eq!(api.log(&log_id).await.unwrap().id, log_id);
+    assert_eq!(api.logs(EmailLogQuery::default()).await.unwrap().len(), 1);
+    assert!(
+      !api
+        .unsubscribe_status("ada@example.com")
+        .await
+        .unwrap()
+        .unsubscribed
+    );
+
+    let campaign_id = "dc_onboarding".parse().unwrap();
+    assert!(api.drip_campaign_details(&campaign_id).await.is_ok());
+    let version = api.get_template_version(&template_id, "ver_1").await;
+    assert_eq!(version.unwrap()["id"], "ver_1");
+    assert!(matches!(
+      api.list_templates().await,
+      Ok(Value::Array(templates)) if templates.len() == 1
+    ));
+    assert!(api.customer_get("ada@example.com").await.is_ok());
+
+    let sent = fake.sent_emails().await;
+    assert_eq!(sent.len(), 2);
+    assert_eq!(sent[0]["recipient"]["address"], "ada@example.com");
+  }
+
+  #[tokio::test]
+  async fn test_fake_rejects_invalid_requests() {
+    let fake = FakeSendWithUs::start().await;
+    let client = reqwest::Client::new();
+    let url = format!("{}/api/v1/send", fake.uri());
+
+    let unauthorized = client
+      .post(&url)
+      .json(&json!({ "email_id": "tem_welcome", "recipient": { "address": "ada@example.com" } }))
+      .send()
+      .await
+      .unwrap();
+    assert_eq!(unauthorized.status(), 401);
+
+    let invalid = client
+      .post(&url)
+      .header(API_KEY_HEADER, "test-api-key")
+      .json(&json!({ "recipient": { "address": "ada@example.com" } }))
+      .send()
+      .await
+      .unwrap();
+    assert_eq!(invalid.status(), 400);
+  }
+}
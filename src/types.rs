@@ -1,4 +1,12 @@
-use crate::attachment::Attachment;
+//! Request options for the SendWithUs API.
+//!
+//! Option structs are `#[non_exhaustive]` so new SendWithUs fields can be
+//! added without a breaking release. Create them with their `new`
+//! constructors and `with_*` methods rather than struct literals; their
+//! fields stay public for reading and updating.
+
+use crate::attachment::{Attachment, AttachmentLimits};
+use crate::error::{Error, Result, ValidationIssue, ValidationIssueKind};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -18,6 +26,7 @@ use std::collections::HashMap;
 ///   .with_name("John Doe");
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[non_exhaustive]
 pub struct Recipient {
   /// Recipient's email address
   pub address: String,
@@ -103,6 +112,7 @@ impl Recipient {
 ///   .with_reply_to("support@company.com");
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[non_exhaustive]
 pub struct Sender {
   /// Sender's email address
   pub address: String,
@@ -227,8 +237,13 @@ impl Sender {
 ///   .with_tags(vec!["welcome".to_string(), "new-user".to_string()]);
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[non_exhaustive]
 pub struct EmailOptions {
   /// Email template ID
+  ///
+  /// Unlike the IDs taken by the typed endpoints, this is a plain string, so
+  /// options can be built without failing and have every problem reported
+  /// at once by `validate`. Pass a `TemplateId` to `new` to use a checked ID.
   pub email_id: String,
 
   /// Email recipient
@@ -394,6 +409,34 @@ impl EmailOptions {
     self
   }
 
+  /// Appends a CC recipient, keeping any already added.
+  ///
+  /// # Arguments
+  /// * `recipient` - The CC recipient to add
+  ///
+  /// # Returns
+  /// Self with the CC recipient added for method chaining
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::types::{EmailOptions, Recipient};
+  ///
+  /// let notify_manager = true;
+  /// let mut options = EmailOptions::new("template-123", Recipient::new("user@example.com"))
+  ///   .add_cc(Recipient::new("support@company.com"));
+  ///
+  /// if notify_manager {
+  ///   options = options.add_cc(Recipient::new("manager@company.com"));
+  /// }
+  ///
+  /// assert_eq!(options.cc.unwrap().len(), 2);
+  /// ```
+  pub fn add_cc(mut self, recipient: Recipient) -> Self {
+    self.cc.get_or_insert_with(Vec::new).push(recipient);
+    self
+  }
+
   /// Adds BCC (blind carbon copy) recipients to the email.
   ///
   /// # Arguments
@@ -418,6 +461,18 @@ impl EmailOptions {
     self
   }
 
+  /// Appends a BCC recipient, keeping any already added.
+  ///
+  /// # Arguments
+  /// * `recipient` - The BCC recipient to add
+  ///
+  /// # Returns
+  /// Self with the BCC recipient added for method chaining
+  pub fn add_bcc(mut self, recipient: Recipient) -> Self {
+    self.bcc.get_or_insert_with(Vec::new).push(recipient);
+    self
+  }
+
   /// Adds file attachments to the email.
   ///
   /// # Arguments
@@ -447,6 +502,18 @@ impl EmailOptions {
     self
   }
 
+  /// Appends a file attachment, keeping any already added.
+  ///
+  /// # Arguments
+  /// * `file` - The attachment to add
+  ///
+  /// # Returns
+  /// Self with the attachment added for method chaining
+  pub fn add_file(mut self, file: Attachment) -> Self {
+    self.files.get_or_insert_with(Vec::new).push(file);
+    self
+  }
+
   /// Sets the ESP (Email Service Provider) account to use.
   ///
   /// Some SendWithUs configurations allow for multiple ESP integrations.
@@ -502,6 +569,36 @@ impl EmailOptions {
     self
   }
 
+  /// Adds a custom email header, keeping any already set.
+  ///
+  /// A header already set with the same name is replaced.
+  ///
+  /// # Arguments
+  /// * `name` - Header name
+  /// * `value` - Header value
+  ///
+  /// # Returns
+  /// Self with the header added for method chaining
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::types::{EmailOptions, Recipient};
+  ///
+  /// let options = EmailOptions::new("template-123", Recipient::new("user@example.com"))
+  ///   .add_header("X-Priority", "1")
+  ///   .add_header("X-Campaign", "spring");
+  ///
+  /// assert_eq!(options.headers.unwrap().len(), 2);
+  /// ```
+  pub fn add_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+    self
+      .headers
+      .get_or_insert_with(HashMap::new)
+      .insert(name.into(), value.into());
+    self
+  }
+
   /// Adds tags to the email for categorization and tracking.
   ///
   /// # Arguments
@@ -525,6 +622,18 @@ impl EmailOptions {
     self
   }
 
+  /// Appends a tag, keeping any already added.
+  ///
+  /// # Arguments
+  /// * `tag` - The tag to add
+  ///
+  /// # Returns
+  /// Self with the tag added for method chaining
+  pub fn add_tag(mut self, tag: impl Into<String>) -> Self {
+    self.tags.get_or_insert_with(Vec::new).push(tag.into());
+    self
+  }
+
   /// Sets the locale for internationalization.
   ///
   /// This can be used to select language-specific template versions.
@@ -549,6 +658,153 @@ impl EmailOptions {
     self.locale = Some(locale.into());
     self
   }
+
+  /// Checks the options for problems the API would reject.
+  ///
+  /// This checks that the attachments are within the default
+  /// `AttachmentLimits`, that a template ID is set, that every recipient,
+  /// sender, and reply-to address looks like an email address, and that
+  /// header names are valid. Apart from attachment sizes, all problems are
+  /// reported together. `Api::send_email` runs this check, with the limits
+  /// from `Config::with_attachment_limits`, before making a request.
+  ///
+  /// # Errors
+  /// Returns `Error::AttachmentTooLarge` if an attachment or all attachments
+  /// together are too large, or `Error::InvalidEmailOptions` listing every
+  /// other problem found
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::Error;
+  /// use send_with_us::types::{EmailOptions, Recipient};
+  /// use std::collections::HashMap;
+  ///
+  /// let options = EmailOptions::new("", Recipient::new("not-an-address"))
+  ///   .with_headers(HashMap::from([("X Bad".to_string(), "value".to_string())]));
+  ///
+  /// match options.validate() {
+  ///   Err(Error::InvalidEmailOptions(issues)) => assert_eq!(issues.len(), 3),
+  ///   other => panic!("unexpected result: {:?}", other),
+  /// }
+  /// ```
+  pub fn validate(&self) -> Result<()> {
+    self.validate_with_limits(&AttachmentLimits::default())
+  }
+
+  /// Checks the options like `validate`, with custom attachment size limits.
+  ///
+  /// # Arguments
+  /// * `limits` - Attachment size limits
+  ///
+  /// # Errors
+  /// Returns `Error::AttachmentTooLarge` if an attachment or all attachments
+  /// together are too large, or `Error::InvalidEmailOptions` listing every
+  /// other problem found
+  pub fn validate_with_limits(&self, limits: &AttachmentLimits) -> Result<()> {
+    if let Some(files) = &self.files {
+      limits.check(files)?;
+    }
+
+    let mut issues = Vec::new();
+
+    if self.email_id.trim().is_empty() {
+      issues.push(ValidationIssue::new(
+        "email_id",
+        ValidationIssueKind::Required,
+        "is required",
+      ));
+    }
+
+    let mut check_address = |field: &'static str, path: String, address: &str| {
+      if !is_valid_address(address) {
+        issues.push(
+          ValidationIssue::new(
+            field,
+            ValidationIssueKind::InvalidEmailAddress,
+            "is not a valid email address",
+          )
+          .with_path(path),
+        );
+      }
+    };
+
+    check_address(
+      "recipient",
+      "recipient.address".to_string(),
+      &self.recipient.address,
+    );
+
+    for (field, recipients) in [("cc", &self.cc), ("bcc", &self.bcc)] {
+      for (index, recipient) in recipients.iter().flatten().enumerate() {
+        check_address(
+          field,
+          format!("{}[{}].address", field, index),
+          &recipient.address,
+        );
+      }
+    }
+
+    if let Some(sender) = &self.sender {
+      check_address("sender", "sender.address".to_string(), &sender.address);
+
+      if let Some(reply_to) = &sender.reply_to {
+        check_address("sender", "sender.reply_to".to_string(), reply_to);
+      }
+    }
+
+    if let Some(headers) = &self.headers {
+      let mut names: Vec<&String> = headers.keys().collect();
+      names.sort();
+
+      for name in names {
+        if !is_valid_header_name(name) {
+          issues.push(
+            ValidationIssue::new(
+              "headers",
+              ValidationIssueKind::InvalidHeaderName,
+              "is not a valid header name",
+            )
+            .with_path(format!("headers[{:?}]", name)),
+          );
+        }
+      }
+    }
+
+    if issues.is_empty() {
+      Ok(())
+    } else {
+      Err(Error::InvalidEmailOptions(issues))
+    }
+  }
+}
+
+/// Returns `true` if `address` looks like a deliverable email address.
+///
+/// This is a syntax check only: a single `@` separating a non-empty local
+/// part from a domain with at least one dot, and no whitespace, control
+/// characters, or angle brackets.
+pub(crate) fn is_valid_address(address: &str) -> bool {
+  let Some((local, domain)) = address.split_once('@') else {
+    return false;
+  };
+
+  let valid_chars = address
+    .chars()
+    .all(|c| !c.is_whitespace() && !c.is_control() && !matches!(c, '<' | '>' | ',' | ';'));
+
+  valid_chars
+    && !local.is_empty()
+    && !domain.contains('@')
+    && domain.contains('.')
+    && !domain.starts_with('.')
+    && !domain.ends_with('.')
+    && !domain.contains("..")
+}
+
+/// Returns `true` if `name` is a valid email header field name (RFC 5322).
+fn is_valid_header_name(name: &str) -> bool {
+  !name.is_empty() && name.bytes().all(|b| (33..=126).contains(&b) && b != b':')
 }
 
 /// Options for creating or updating email templates in SendWithUs.
@@ -562,16 +818,16 @@ impl EmailOptions {
 /// ```
 /// use send_with_us::types::TemplateOptions;
 ///
-/// let template = TemplateOptions {
-///   name: "Welcome Email".to_string(),
-///   subject: "Welcome to Our Service".to_string(),
-///   html: "<html><body>Welcome, {{name}}!</body></html>".to_string(),
-///   text: "Welcome, {{name}}!".to_string(),
-///   preheader: Some("Welcome to our service".to_string()),
-///   amp_html: None,
-/// };
+/// let template = TemplateOptions::new(
+///   "Welcome Email",
+///   "Welcome to Our Service",
+///   "<html><body>Welcome, {{name}}!</body></html>",
+///   "Welcome, {{name}}!",
+/// )
+/// .with_preheader("Welcome to our service");
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[non_exhaustive]
 pub struct TemplateOptions {
   /// Template name (displayed in the SendWithUs dashboard)
   pub name: String,
@@ -594,6 +850,118 @@ pub struct TemplateOptions {
   pub amp_html: Option<String>,
 }
 
+impl TemplateOptions {
+  /// Creates template options with the required content.
+  ///
+  /// # Arguments
+  /// * `name` - Template name
+  /// * `subject` - Email subject line
+  /// * `html` - HTML content of the email
+  /// * `text` - Plain text content of the email
+  ///
+  /// # Returns
+  /// A new TemplateOptions instance with no preheader or AMP content
+  pub fn new(
+    name: impl Into<String>,
+    subject: impl Into<String>,
+    html: impl Into<String>,
+    text: impl Into<String>,
+  ) -> Self {
+    Self {
+      name: name.into(),
+      subject: subject.into(),
+      html: html.into(),
+      text: text.into(),
+      preheader: None,
+      amp_html: None,
+    }
+  }
+
+  /// Sets the preheader text shown in email client previews.
+  ///
+  /// # Arguments
+  /// * `preheader` - The preheader text
+  ///
+  /// # Returns
+  /// Self with the preheader set for method chaining
+  pub fn with_preheader(mut self, preheader: impl Into<String>) -> Self {
+    self.preheader = Some(preheader.into());
+    self
+  }
+
+  /// Sets the AMP HTML content for supported email clients.
+  ///
+  /// # Arguments
+  /// * `amp_html` - The AMP HTML content
+  ///
+  /// # Returns
+  /// Self with the AMP content set for method chaining
+  pub fn with_amp_html(mut self, amp_html: impl Into<String>) -> Self {
+    self.amp_html = Some(amp_html.into());
+    self
+  }
+}
+
+/// Options for updating a template's metadata without touching its versions.
+///
+/// Only the fields that are set are sent, so unset fields keep their current
+/// values on SendWithUs.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::types::TemplateUpdateOptions;
+///
+/// let update = TemplateUpdateOptions::new()
+///   .with_name("Welcome Email (2024)")
+///   .with_tags(vec!["onboarding".to_string()]);
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[non_exhaustive]
+pub struct TemplateUpdateOptions {
+  /// New template name
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub name: Option<String>,
+
+  /// New set of template tags
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub tags: Option<Vec<String>>,
+}
+
+impl TemplateUpdateOptions {
+  /// Creates update options that change nothing.
+  ///
+  /// # Returns
+  /// A new TemplateUpdateOptions instance with every field unset
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Sets a new template name.
+  ///
+  /// # Arguments
+  /// * `name` - The new template name
+  ///
+  /// # Returns
+  /// Self with the name set for method chaining
+  pub fn with_name(mut self, name: impl Into<String>) -> Self {
+    self.name = Some(name.into());
+    self
+  }
+
+  /// Replaces the template's tags.
+  ///
+  /// # Arguments
+  /// * `tags` - The new set of tags
+  ///
+  /// # Returns
+  /// Self with the tags set for method chaining
+  pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+    self.tags = Some(tags);
+    self
+  }
+}
+
 /// Options for adding recipients to a drip campaign.
 ///
 /// Drip campaigns are sequences of automated emails sent over time.
@@ -607,24 +975,18 @@ pub struct TemplateOptions {
 /// use std::collections::HashMap;
 /// use serde_json::json;
 ///
-/// let options = DripCampaignOptions {
-///   recipient_address: "customer@example.com".to_string(),
-///   email_data: None,
-///   tags: None,
-///   locale: None,
-/// };
+/// let options = DripCampaignOptions::new("customer@example.com");
 ///
 /// let mut email_data = HashMap::new();
 /// email_data.insert("name".to_string(), json!("John"));
 ///
-/// let options = DripCampaignOptions {
-///   recipient_address: "customer@example.com".to_string(),
-///   email_data: Some(email_data),
-///   tags: Some(vec!["new-user".to_string()]),
-///   locale: Some("en-US".to_string()),
-/// };
+/// let options = DripCampaignOptions::new("customer@example.com")
+///   .with_data(email_data)
+///   .with_tags(vec!["new-user".to_string()])
+///   .with_locale("en-US");
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[non_exhaustive]
 pub struct DripCampaignOptions {
   /// Email address of the recipient to add to the campaign
   pub recipient_address: String,
@@ -642,6 +1004,60 @@ pub struct DripCampaignOptions {
   pub locale: Option<String>,
 }
 
+impl DripCampaignOptions {
+  /// Creates options for adding a recipient to a drip campaign.
+  ///
+  /// # Arguments
+  /// * `recipient_address` - Email address of the recipient
+  ///
+  /// # Returns
+  /// A new DripCampaignOptions instance with no data, tags, or locale
+  pub fn new(recipient_address: impl Into<String>) -> Self {
+    Self {
+      recipient_address: recipient_address.into(),
+      email_data: None,
+      tags: None,
+      locale: None,
+    }
+  }
+
+  /// Sets the data used for email template variables.
+  ///
+  /// # Arguments
+  /// * `data` - HashMap of template variable names to values
+  ///
+  /// # Returns
+  /// Self with the email data set for method chaining
+  pub fn with_data(mut self, data: HashMap<String, serde_json::Value>) -> Self {
+    self.email_data = Some(data);
+    self
+  }
+
+  /// Sets tags for categorization and tracking.
+  ///
+  /// # Arguments
+  /// * `tags` - Vector of tag strings
+  ///
+  /// # Returns
+  /// Self with the tags set for method chaining
+  pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+    self.tags = Some(tags);
+    self
+  }
+
+  /// Sets the locale for the campaign emails.
+  ///
+  /// # Arguments
+  /// * `locale` - The locale code (e.g., "en-US", "fr-CA")
+  ///
+  /// # Returns
+  /// Self with the locale set for method chaining
+  pub fn with_locale(mut self, locale: impl Into<String>) -> Self {
+    self.locale = Some(locale.into());
+    self
+  }
+}
+
 /// Options for creating or managing customers in SendWithUs.
 ///
 /// The CustomerOptions struct is used when creating or updating customer
@@ -655,24 +1071,19 @@ pub struct DripCampaignOptions {
 /// use std::collections::HashMap;
 /// use serde_json::json;
 ///
-/// let customer = CustomerOptions {
-///   email: "customer@example.com".to_string(),
-///   data: None,
-///   locale: None,
-/// };
+/// let customer = CustomerOptions::new("customer@example.com");
 ///
 /// let mut data = HashMap::new();
 /// data.insert("first_name".to_string(), json!("John"));
 /// data.insert("last_name".to_string(), json!("Doe"));
 /// data.insert("plan".to_string(), json!("premium"));
 ///
-/// let customer = CustomerOptions {
-///   email: "customer@example.com".to_string(),
-///   data: Some(data),
-///   locale: Some("en-US".to_string()),
-/// };
+/// let customer = CustomerOptions::new("customer@example.com")
+///   .with_data(data)
+///   .with_locale("en-US");
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[non_exhaustive]
 pub struct CustomerOptions {
   /// Customer's email address (primary identifier)
   pub email: String,
@@ -686,46 +1097,417 @@ pub struct CustomerOptions {
   pub locale: Option<String>,
 }
 
-/// Options for rendering an email template without sending it.
+impl CustomerOptions {
+  /// Creates options for a customer with the given email address.
+  ///
+  /// # Arguments
+  /// * `email` - Customer's email address
+  ///
+  /// # Returns
+  /// A new CustomerOptions instance with no data or locale
+  pub fn new(email: impl Into<String>) -> Self {
+    Self {
+      email: email.into(),
+      data: None,
+      locale: None,
+    }
+  }
+
+  /// Sets custom data associated with the customer.
+  ///
+  /// # Arguments
+  /// * `data` - HashMap of data keys to values
+  ///
+  /// # Returns
+  /// Self with the data set for method chaining
+  pub fn with_data(mut self, data: HashMap<String, serde_json::Value>) -> Self {
+    self.data = Some(data);
+    self
+  }
+
+  /// Sets the customer's locale.
+  ///
+  /// # Arguments
+  /// * `locale` - The locale code (e.g., "en-US", "fr-CA")
+  ///
+  /// # Returns
+  /// Self with the locale set for method chaining
+  pub fn with_locale(mut self, locale: impl Into<String>) -> Self {
+    self.locale = Some(locale.into());
+    self
+  }
+}
+
+/// How `customer_update` treats the customer's existing `data` map.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DataUpdateMode {
+  /// Keep existing keys and overwrite only the keys provided in the update
+  #[default]
+  Merge,
+
+  /// Replace the whole data map with the one provided in the update
+  Replace,
+}
+
+/// Options for updating an existing customer record.
 ///
-/// This struct is used with the render API endpoint to preview
-/// how a template will look with specific data. This is useful
-/// for testing templates and generating email content for use
-/// in other systems.
+/// Unlike `CustomerOptions`, which creates a customer, these options only
+/// change the fields that are set. By default the provided `data` is merged
+/// into the customer's existing data; use `DataUpdateMode::Replace` to
+/// overwrite it entirely.
 ///
 /// # Examples
 ///
 /// ```
-/// use send_with_us::types::RenderOptions;
+/// use send_with_us::types::{CustomerUpdateOptions, DataUpdateMode};
 /// use std::collections::HashMap;
 /// use serde_json::json;
 ///
-/// let mut template_data = HashMap::new();
-/// template_data.insert("name".to_string(), json!("John"));
-/// template_data.insert("order_id".to_string(), json!("12345"));
+/// let mut data = HashMap::new();
+/// data.insert("plan".to_string(), json!("premium"));
+///
+/// let update = CustomerUpdateOptions::new().with_data(data.clone());
+/// assert_eq!(update.data_mode, DataUpdateMode::Merge);
 ///
-/// let options = RenderOptions {
-///   template: "template-id".to_string(),
-///   version_id: Some("version-id".to_string()),
-///   template_data,
-///   strict: true,
-///   locale: Some("en-US".to_string()),
-/// };
+/// let update = CustomerUpdateOptions::new()
+///   .with_data(data)
+///   .with_data_mode(DataUpdateMode::Replace);
 /// ```
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct RenderOptions {
-  /// ID of the template to render
-  pub template: String,
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[non_exhaustive]
+pub struct CustomerUpdateOptions {
+  /// Custom data to merge into or replace the customer's data
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub data: Option<HashMap<String, serde_json::Value>>,
 
-  /// Optional specific version of the template to render
+  /// New locale for the customer
   #[serde(skip_serializing_if = "Option::is_none")]
-  pub version_id: Option<String>,
+  pub locale: Option<String>,
 
-  /// Data to use when rendering template variables
-  pub template_data: HashMap<String, serde_json::Value>,
+  /// Whether `data` is merged with or replaces the existing data
+  #[serde(skip)]
+  pub data_mode: DataUpdateMode,
+}
+
+impl CustomerUpdateOptions {
+  /// Creates update options that change nothing.
+  ///
+  /// # Returns
+  /// A new CustomerUpdateOptions instance that merges data by default
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Sets the custom data to apply.
+  ///
+  /// # Arguments
+  /// * `data` - HashMap of data keys to values
+  ///
+  /// # Returns
+  /// Self with the data set for method chaining
+  pub fn with_data(mut self, data: HashMap<String, serde_json::Value>) -> Self {
+    self.data = Some(data);
+    self
+  }
+
+  /// Sets a new locale for the customer.
+  ///
+  /// # Arguments
+  /// * `locale` - The locale code (e.g., "en-US", "fr-CA")
+  ///
+  /// # Returns
+  /// Self with the locale set for method chaining
+  pub fn with_locale(mut self, locale: impl Into<String>) -> Self {
+    self.locale = Some(locale.into());
+    self
+  }
+
+  /// Chooses whether data is merged with or replaces the existing data.
+  ///
+  /// # Arguments
+  /// * `mode` - The data update mode
+  ///
+  /// # Returns
+  /// Self with the mode set for method chaining
+  pub fn with_data_mode(mut self, mode: DataUpdateMode) -> Self {
+    self.data_mode = mode;
+    self
+  }
+}
+
+/// Options for recording a behavioral event against a customer.
+///
+/// Customer events can be used to trigger drip campaigns and segment
+/// customers based on what they do in your application.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::types::CustomerEventOptions;
+/// use std::collections::HashMap;
+/// use serde_json::json;
+///
+/// let event = CustomerEventOptions::new("completed_checkout");
+///
+/// let mut data = HashMap::new();
+/// data.insert("order_id".to_string(), json!("12345"));
+/// data.insert("total".to_string(), json!(49.99));
+///
+/// let event = CustomerEventOptions::new("completed_checkout")
+///   .with_data(data);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[non_exhaustive]
+pub struct CustomerEventOptions {
+  /// Name of the event (e.g. "signed_up", "completed_checkout")
+  pub event_name: String,
+
+  /// Optional data describing the event
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub data: Option<HashMap<String, serde_json::Value>>,
+}
+
+impl CustomerEventOptions {
+  /// Creates new event options with the given event name.
+  ///
+  /// # Arguments
+  /// * `event_name` - Name of the event
+  ///
+  /// # Returns
+  /// A new CustomerEventOptions instance with no event data
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::types::CustomerEventOptions;
+  ///
+  /// let event = CustomerEventOptions::new("signed_up");
+  /// assert_eq!(event.event_name, "signed_up");
+  /// assert_eq!(event.data, None);
+  /// ```
+  pub fn new(event_name: impl Into<String>) -> Self {
+    Self {
+      event_name: event_name.into(),
+      data: None,
+    }
+  }
+
+  /// Sets data describing the event.
+  ///
+  /// # Arguments
+  /// * `data` - HashMap of event attribute names to values
+  ///
+  /// # Returns
+  /// Self with the event data set for method chaining
+  pub fn with_data(mut self, data: HashMap<String, serde_json::Value>) -> Self {
+    self.data = Some(data);
+    self
+  }
+}
+
+/// Filters for listing email logs.
+///
+/// Used with `logs` and `customer_email_log` to page through and filter
+/// sent emails. Timestamps are passed through to the API as given, which
+/// expects Unix timestamps in seconds. With the `chrono` feature, bounds can
+/// also be given as `chrono::DateTime` values.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::types::EmailLogQuery;
+///
+/// let query = EmailLogQuery::new()
+///   .with_count(50)
+///   .with_offset(100)
+///   .with_created_gt("1700000000");
+///
+/// assert_eq!(query.to_query_string(), "count=50&offset=100&created_gt=1700000000");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct EmailLogQuery {
+  /// Maximum number of logs to return
+  pub count: Option<u32>,
+
+  /// Number of logs to skip, for pagination
+  pub offset: Option<u32>,
+
+  /// Only include logs created after this timestamp
+  pub created_gt: Option<String>,
+
+  /// Only include logs created before this timestamp
+  pub created_lt: Option<String>,
+}
+
+impl EmailLogQuery {
+  /// Creates an empty query that applies no filters.
+  ///
+  /// # Returns
+  /// A new EmailLogQuery instance with every filter unset
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Sets the maximum number of logs to return.
+  ///
+  /// # Arguments
+  /// * `count` - Maximum number of logs
+  ///
+  /// # Returns
+  /// Self with the count set for method chaining
+  pub fn with_count(mut self, count: u32) -> Self {
+    self.count = Some(count);
+    self
+  }
+
+  /// Sets the number of logs to skip.
+  ///
+  /// # Arguments
+  /// * `offset` - Number of logs to skip
+  ///
+  /// # Returns
+  /// Self with the offset set for method chaining
+  pub fn with_offset(mut self, offset: u32) -> Self {
+    self.offset = Some(offset);
+    self
+  }
+
+  /// Only include logs created after the given timestamp.
+  ///
+  /// # Arguments
+  /// * `created_gt` - Unix timestamp in seconds
+  ///
+  /// # Returns
+  /// Self with the lower bound set for method chaining
+  pub fn with_created_gt(mut self, created_gt: impl Into<String>) -> Self {
+    self.created_gt = Some(created_gt.into());
+    self
+  }
+
+  /// Only include logs created before the given timestamp.
+  ///
+  /// # Arguments
+  /// * `created_lt` - Unix timestamp in seconds
+  ///
+  /// # Returns
+  /// Self with the upper bound set for method chaining
+  pub fn with_created_lt(mut self, created_lt: impl Into<String>) -> Self {
+    self.created_lt = Some(created_lt.into());
+    self
+  }
+
+  /// Only include logs created after the given time.
+  ///
+  /// # Arguments
+  /// * `time` - Lower bound, in any time zone
+  ///
+  /// # Returns
+  /// Self with the lower bound set for method chaining
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use chrono::{TimeZone, Utc};
+  /// use send_with_us::types::EmailLogQuery;
+  ///
+  /// let query = EmailLogQuery::new()
+  ///   .with_created_after(Utc.with_ymd_and_hms(2023, 11, 14, 22, 13, 20).unwrap());
+  ///
+  /// assert_eq!(query.created_gt.as_deref(), Some("1700000000"));
+  /// ```
+  #[cfg(feature = "chrono")]
+  pub fn with_created_after<Tz: chrono::TimeZone>(self, time: chrono::DateTime<Tz>) -> Self {
+    self.with_created_gt(time.timestamp().to_string())
+  }
+
+  /// Only include logs created before the given time.
+  ///
+  /// # Arguments
+  /// * `time` - Upper bound, in any time zone
+  ///
+  /// # Returns
+  /// Self with the upper bound set for method chaining
+  #[cfg(feature = "chrono")]
+  pub fn with_created_before<Tz: chrono::TimeZone>(self, time: chrono::DateTime<Tz>) -> Self {
+    self.with_created_lt(time.timestamp().to_string())
+  }
+
+  /// Encodes the query as a URL query string (without the leading `?`).
+  ///
+  /// # Returns
+  /// The percent-encoded query string, empty if no filters are set
+  pub fn to_query_string(&self) -> String {
+    url::form_urlencoded::Serializer::new(String::new())
+      .extend_pairs(self.query_pairs())
+      .finish()
+  }
+
+  /// Returns the set filters as unencoded query pairs.
+  pub(crate) fn query_pairs(&self) -> Vec<(&'static str, String)> {
+    let mut pairs = Vec::new();
+
+    if let Some(count) = self.count {
+      pairs.push(("count", count.to_string()));
+    }
+
+    if let Some(offset) = self.offset {
+      pairs.push(("offset", offset.to_string()));
+    }
+
+    if let Some(created_gt) = &self.created_gt {
+      pairs.push(("created_gt", created_gt.clone()));
+    }
+
+    if let Some(created_lt) = &self.created_lt {
+      pairs.push(("created_lt", created_lt.clone()));
+    }
+
+    pairs
+  }
+}
+
+/// Options for rendering an email template without sending it.
+///
+/// This struct is used with the render API endpoint to preview
+/// how a template will look with specific data. This is useful
+/// for testing templates and generating email content for use
+/// in other systems.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::types::RenderOptions;
+/// use std::collections::HashMap;
+/// use serde_json::json;
+///
+/// let mut template_data = HashMap::new();
+/// template_data.insert("name".to_string(), json!("John"));
+/// template_data.insert("order_id".to_string(), json!("12345"));
+///
+/// let options = RenderOptions::new("template-id")
+///   .with_version_id("version-id")
+///   .with_data(template_data)
+///   .with_locale("en-US")
+///   .strict();
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[non_exhaustive]
+pub struct RenderOptions {
+  /// ID of the template to render
+  pub template: String,
+
+  /// Optional specific version of the template to render
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub version_id: Option<String>,
+
+  /// Data to use when rendering template variables
+  #[serde(default)]
+  pub template_data: HashMap<String, serde_json::Value>,
 
   /// Whether to use strict mode for variable replacement
   /// (error on missing variables vs. leaving them as is)
+  #[serde(default)]
   pub strict: bool,
 
   /// Locale for template internationalization
@@ -733,6 +1515,78 @@ pub struct RenderOptions {
   pub locale: Option<String>,
 }
 
+impl RenderOptions {
+  /// Creates render options for a template with no data, in non-strict mode.
+  ///
+  /// # Arguments
+  /// * `template` - ID of the template to render
+  ///
+  /// # Returns
+  /// A new RenderOptions instance
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::types::RenderOptions;
+  ///
+  /// let options = RenderOptions::new("template-id");
+  /// assert_eq!(options.template, "template-id");
+  /// assert!(options.template_data.is_empty());
+  /// assert!(!options.strict);
+  /// ```
+  pub fn new(template: impl Into<String>) -> Self {
+    Self {
+      template: template.into(),
+      ..Self::default()
+    }
+  }
+
+  /// Sets the data used to render template variables.
+  ///
+  /// # Arguments
+  /// * `data` - HashMap of template variable names to values
+  ///
+  /// # Returns
+  /// Self with the template data set for method chaining
+  pub fn with_data(mut self, data: HashMap<String, serde_json::Value>) -> Self {
+    self.template_data = data;
+    self
+  }
+
+  /// Renders a specific version of the template instead of the active one.
+  ///
+  /// # Arguments
+  /// * `version_id` - ID of the template version
+  ///
+  /// # Returns
+  /// Self with the version set for method chaining
+  pub fn with_version_id(mut self, version_id: impl Into<String>) -> Self {
+    self.version_id = Some(version_id.into());
+    self
+  }
+
+  /// Sets the locale to render the template in.
+  ///
+  /// # Arguments
+  /// * `locale` - The locale code (e.g., "en-US", "fr-CA")
+  ///
+  /// # Returns
+  /// Self with the locale set for method chaining
+  pub fn with_locale(mut self, locale: impl Into<String>) -> Self {
+    self.locale = Some(locale.into());
+    self
+  }
+
+  /// Enables strict mode, so rendering fails if a template variable is missing from the data.
+  ///
+  /// # Returns
+  /// Self in strict mode for method chaining
+  pub fn strict(mut self) -> Self {
+    self.strict = true;
+    self
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -925,6 +1779,162 @@ mod tests {
     assert_eq!(options.locale, Some("en-US".to_string()));
   }
 
+  #[test]
+  fn test_email_options_add_methods() {
+    let options = EmailOptions::new("tem_123", Recipient::new("user@example.com"))
+      .with_cc(vec![Recipient::new("cc1@example.com")])
+      .add_cc(Recipient::new("cc2@example.com"))
+      .add_bcc(Recipient::new("bcc@example.com"))
+      .with_tags(vec!["welcome".to_string()])
+      .add_tag("new-user")
+      .add_header("X-Priority", "1")
+      .add_header("X-Priority", "2")
+      .add_file(Attachment::from_bytes(b"a", "a.txt"))
+      .add_file(Attachment::from_bytes(b"b", "b.txt"));
+
+    let cc: Vec<_> = options
+      .cc
+      .iter()
+      .flatten()
+      .map(|r| r.address.as_str())
+      .collect();
+    assert_eq!(cc, ["cc1@example.com", "cc2@example.com"]);
+    assert_eq!(options.bcc.unwrap()[0].address, "bcc@example.com");
+    assert_eq!(
+      options.tags,
+      Some(vec!["welcome".to_string(), "new-user".to_string()])
+    );
+    assert_eq!(
+      options.headers,
+      Some(HashMap::from([("X-Priority".to_string(), "2".to_string())]))
+    );
+    assert_eq!(options.files.unwrap().len(), 2);
+  }
+
+  #[test]
+  fn test_email_options_validate() {
+    let options = EmailOptions::new("tem_123", Recipient::new("user@example.com"))
+      .with_sender(Sender::new("noreply@example.com").with_reply_to("support@example.com"))
+      .with_headers(HashMap::from([(
+        "X-Campaign".to_string(),
+        "spring".to_string(),
+      )]))
+      .with_files(vec![Attachment::from_bytes(b"hello", "hello.txt")]);
+    assert!(options.validate().is_ok());
+
+    let options = EmailOptions::new(" ", Recipient::new("user@@example.com"))
+      .with_bcc(vec![Recipient::new("bcc@example")])
+      .with_sender(Sender::new("noreply@example.com").with_reply_to("support"))
+      .with_headers(HashMap::from([
+        ("Bad:Name".to_string(), "value".to_string()),
+        ("".to_string(), "value".to_string()),
+      ]));
+
+    let Err(Error::InvalidEmailOptions(issues)) = options.validate() else {
+      panic!("expected validation to fail");
+    };
+    let fields: Vec<_> = issues.iter().map(|issue| issue.field.as_str()).collect();
+    assert_eq!(
+      fields,
+      [
+        "email_id",
+        "recipient.address",
+        "bcc[0].address",
+        "sender.reply_to",
+        "headers[\"\"]",
+        "headers[\"Bad:Name\"]",
+      ]
+    );
+  }
+
+  #[test]
+  fn test_email_options_validate_attachment_limits() {
+    let options = EmailOptions::new("", Recipient::new("user@example.com"))
+      .with_files(vec![Attachment::from_bytes(&[0; 100], "report.pdf")]);
+
+    let limits = AttachmentLimits::new().with_max_size(99);
+    let Err(Error::AttachmentTooLarge {
+      filename,
+      size,
+      limit,
+    }) = options.validate_with_limits(&limits)
+    else {
+      panic!("expected the attachment to be rejected");
+    };
+    assert_eq!(filename.as_deref(), Some("report.pdf"));
+    assert_eq!((size, limit), (100, 99));
+
+    assert!(matches!(
+      options.validate(),
+      Err(Error::InvalidEmailOptions(_))
+    ));
+  }
+
+  #[test]
+  fn test_is_valid_address() {
+    assert!(is_valid_address("user@example.com"));
+    assert!(is_valid_address("first.last+tag@mail.example.co.uk"));
+    assert!(!is_valid_address("user"));
+    assert!(!is_valid_address("@example.com"));
+    assert!(!is_valid_address("user@localhost"));
+    assert!(!is_valid_address("user@example..com"));
+    assert!(!is_valid_address("user name@example.com"));
+    assert!(!is_valid_address("User <user@example.com>"));
+  }
+
+  #[test]
+  fn test_option_constructors() {
+    let template = TemplateOptions::new("Welcome", "Hi {{name}}", "<p>Hi</p>", "Hi")
+      .with_preheader("Welcome aboard")
+      .with_amp_html("<html amp></html>");
+    assert_eq!(template.name, "Welcome");
+    assert_eq!(template.subject, "Hi {{name}}");
+    assert_eq!(template.preheader, Some("Welcome aboard".to_string()));
+    assert_eq!(template.amp_html, Some("<html amp></html>".to_string()));
+
+    let data = HashMap::from([("plan".to_string(), json!("premium"))]);
+
+    let drip = DripCampaignOptions::new("customer@example.com")
+      .with_data(data.clone())
+      .with_tags(vec!["new-user".to_string()])
+      .with_locale("en-US");
+    assert_eq!(drip.recipient_address, "customer@example.com");
+    assert_eq!(drip.email_data, Some(data.clone()));
+    assert_eq!(drip.tags, Some(vec!["new-user".to_string()]));
+    assert_eq!(drip.locale, Some("en-US".to_string()));
+
+    let customer = CustomerOptions::new("customer@example.com")
+      .with_data(data.clone())
+      .with_locale("fr-CA");
+    assert_eq!(customer.email, "customer@example.com");
+    assert_eq!(customer.data, Some(data));
+    assert_eq!(customer.locale, Some("fr-CA".to_string()));
+  }
+
+  #[test]
+  fn test_render_options_builder() {
+    let options = RenderOptions::new("template-id");
+    assert_eq!(options.version_id, None);
+    assert!(options.template_data.is_empty());
+    assert!(!options.strict);
+    assert_eq!(options.locale, None);
+
+    let data = HashMap::from([("name".to_string(), json!("John"))]);
+    let options = RenderOptions::new("template-id")
+      .with_data(data.clone())
+      .with_version_id("version-id")
+      .with_locale("fr-CA")
+      .strict();
+
+    assert_eq!(options.template_data, data);
+    assert_eq!(options.version_id, Some("version-id".to_string()));
+    assert_eq!(options.locale, Some("fr-CA".to_string()));
+    assert!(options.strict);
+
+    let parsed: RenderOptions = serde_json::from_value(json!({"template": "template-id"})).unwrap();
+    assert_eq!(parsed, RenderOptions::new("template-id"));
+  }
+
   #[test]
   fn test_customer_options() {
     let options = CustomerOptions {
@@ -954,4 +1964,80 @@ mod tests {
     assert_eq!(options.data.as_ref().unwrap()["age"], 30);
     assert_eq!(options.locale, Some("en-US".to_string()));
   }
+
+  #[test]
+  fn test_customer_event_options() {
+    let event = CustomerEventOptions::new("signed_up");
+    assert_eq!(event.event_name, "signed_up");
+    assert_eq!(event.data, None);
+    assert_eq!(
+      serde_json::to_value(&event).unwrap(),
+      json!({"event_name": "signed_up"})
+    );
+
+    let data = HashMap::from([("plan".to_string(), json!("premium"))]);
+    let event = CustomerEventOptions::new("upgraded").with_data(data);
+    assert_eq!(
+      serde_json::to_value(&event).unwrap(),
+      json!({"event_name": "upgraded", "data": {"plan": "premium"}})
+    );
+  }
+
+  #[test]
+  fn test_email_log_query() {
+    let query = EmailLogQuery::new();
+    assert_eq!(query.to_query_string(), "");
+
+    let query = EmailLogQuery::new()
+      .with_count(10)
+      .with_offset(20)
+      .with_created_gt("1700000000")
+      .with_created_lt("1800000000");
+
+    assert_eq!(query.count, Some(10));
+    assert_eq!(query.offset, Some(20));
+    assert_eq!(
+      query.to_query_string(),
+      "count=10&offset=20&created_gt=1700000000&created_lt=1800000000"
+    );
+
+    let query = EmailLogQuery::new().with_created_gt("2024-01-01T00:00:00+00:00");
+    assert_eq!(
+      query.to_query_string(),
+      "created_gt=2024-01-01T00%3A00%3A00%2B00%3A00"
+    );
+  }
+
+  #[test]
+  fn test_template_update_options() {
+    let update = TemplateUpdateOptions::new();
+    assert_eq!(serde_json::to_value(&update).unwrap(), json!({}));
+
+    let update = TemplateUpdateOptions::new()
+      .with_name("Renamed")
+      .with_tags(vec!["tag1".to_string()]);
+    assert_eq!(
+      serde_json::to_value(&update).unwrap(),
+      json!({"name": "Renamed", "tags": ["tag1"]})
+    );
+  }
+
+  #[test]
+  fn test_customer_update_options() {
+    let update = CustomerUpdateOptions::new();
+    assert_eq!(update.data_mode, DataUpdateMode::Merge);
+    assert_eq!(serde_json::to_value(&update).unwrap(), json!({}));
+
+    let data = HashMap::from([("plan".to_string(), json!("premium"))]);
+    let update = CustomerUpdateOptions::new()
+      .with_data(data)
+      .with_locale("fr-CA")
+      .with_data_mode(DataUpdateMode::Replace);
+
+    assert_eq!(update.data_mode, DataUpdateMode::Replace);
+    assert_eq!(
+      serde_json::to_value(&update).unwrap(),
+      json!({"data": {"plan": "premium"}, "locale": "fr-CA"})
+    );
+  }
 }
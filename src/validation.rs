@@ -0,0 +1,139 @@
+//! `validator::Validate` implementations for option types.
+//!
+//! Available with the `validator` feature, so applications validating their
+//! own input with the `validator` crate can check emails, customers, and
+//! templates the same way. The checks are this crate's own: `EmailOptions`
+//! reports what `EmailOptions::validate` reports. Errors are keyed by the
+//! top-level field; the full path of a nested field, e.g. `cc[1].address`,
+//! is in the error's `path` parameter.
+
+use crate::error::{Error, ValidationIssue, ValidationIssueKind};
+use crate::types::{CustomerOptions, EmailOptions, TemplateOptions, is_valid_address};
+use std::borrow::Cow;
+use validator::{Validate, ValidationError, ValidationErrors};
+
+impl Validate for EmailOptions {
+  fn validate(&self) -> Result<(), ValidationErrors> {
+    match EmailOptions::validate(self) {
+      Ok(()) => Ok(()),
+      Err(Error::InvalidEmailOptions(issues)) => Err(errors(&issues)),
+      Err(error) => {
+        let mut errors = ValidationErrors::new();
+        let error =
+          ValidationError::new("attachment_size").with_message(Cow::Owned(error.to_string()));
+        errors.add("files", error);
+        Err(errors)
+      }
+    }
+  }
+}
+
+impl Validate for CustomerOptions {
+  fn validate(&self) -> Result<(), ValidationErrors> {
+    let mut issues = Vec::new();
+    if !is_valid_address(&self.email) {
+      issues.push(ValidationIssue::new(
+        "email",
+        ValidationIssueKind::InvalidEmailAddress,
+        "is not a valid email address",
+      ));
+    }
+
+    result(&issues)
+  }
+}
+
+impl Validate for TemplateOptions {
+  fn validate(&self) -> Result<(), ValidationErrors> {
+    let mut issues = Vec::new();
+    for (field, value) in [("name", &self.name), ("subject", &self.subject)] {
+      if value.trim().is_empty() {
+        issues.push(ValidationIssue::new(
+          field,
+          ValidationIssueKind::Required,
+          "is required",
+        ));
+      }
+    }
+    if self.html.trim().is_empty() && self.text.trim().is_empty() {
+      issues.push(ValidationIssue::new(
+        "html",
+        ValidationIssueKind::Required,
+        "is required without text",
+      ));
+    }
+
+    result(&issues)
+  }
+}
+
+fn result(issues: &[ValidationIssue]) -> Result<(), ValidationErrors> {
+  if issues.is_empty() {
+    Ok(())
+  } else {
+    Err(errors(issues))
+  }
+}
+
+fn errors(issues: &[ValidationIssue]) -> ValidationErrors {
+  let mut errors = ValidationErrors::new();
+  for issue in issues {
+    let mut error =
+      ValidationError::new(code(issue.kind)).with_message(Cow::Owned(issue.to_string()));
+    error.add_param(Cow::Borrowed("path"), &issue.field);
+    errors.add(issue.top_level_field(), error);
+  }
+
+  errors
+}
+
+/// Returns the `validator` error code matching an issue, as its derive would use.
+fn code(kind: ValidationIssueKind) -> &'static str {
+  match kind {
+    ValidationIssueKind::Required => "required",
+    ValidationIssueKind::InvalidEmailAddress => "email",
+    ValidationIssueKind::InvalidHeaderName => "header_name",
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::types::Recipient;
+
+  #[test]
+  fn test_errors_are_keyed_by_top_level_field() {
+    let options = EmailOptions::new("", Recipient::new("ada@example.com")).with_cc(vec![
+      Recipient::new("ada@example.com"),
+      Recipient::new("nope"),
+    ]);
+
+    let errors = Validate::validate(&options).unwrap_err();
+    let fields = errors.field_errors();
+    assert_eq!(fields["email_id"][0].code, "required");
+    assert_eq!(fields["cc"][0].code, "email");
+    assert_eq!(fields["cc"][0].params["path"], "cc[1].address");
+    assert!(!fields.contains_key("recipient"));
+
+    let mut headers = std::collections::HashMap::new();
+    headers.insert("Bad Header".to_string(), "x".to_string());
+    let options = EmailOptions::new("tem_123", Recipient::new("ada@example.com"))
+      .with_sender(crate::types::Sender::new("nope"))
+      .with_headers(headers);
+    let errors = Validate::validate(&options).unwrap_err();
+    let fields = errors.field_errors();
+    assert_eq!(fields["sender"][0].code, "email");
+    assert_eq!(fields["headers"][0].code, "header_name");
+
+    let errors = Validate::validate(&CustomerOptions::new("nope")).unwrap_err();
+    assert_eq!(errors.field_errors()["email"][0].code, "email");
+    assert!(Validate::validate(&CustomerOptions::new("ada@example.com")).is_ok());
+
+    let template = TemplateOptions::new("Welcome", "", "", "");
+    let errors = Validate::validate(&template).unwrap_err();
+    let fields = errors.field_errors();
+    assert_eq!(fields.len(), 2);
+    assert!(fields.contains_key("subject") && fields.contains_key("html"));
+    assert!(Validate::validate(&TemplateOptions::new("Welcome", "Hi", "", "Hi")).is_ok());
+  }
+}
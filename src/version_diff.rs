@@ -0,0 +1,202 @@
+//! Line diffs between template versions.
+//!
+//! `Api::diff_versions` fetches two versions of a template and compares their
+//! subject, HTML, and text, for review tooling around template changes.
+//! `VersionDiff::new` compares versions that were already fetched.
+
+use crate::models::TemplateVersion;
+use similar::{ChangeTag, TextDiff};
+
+/// A change to one line of a field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LineChange {
+  /// Line present in both versions
+  Equal(String),
+
+  /// Line only in the old version
+  Removed(String),
+
+  /// Line only in the new version
+  Added(String),
+}
+
+/// A field that differs between two versions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldDiff {
+  /// Field name: `subject`, `html`, or `text`
+  pub field: &'static str,
+
+  /// Content in the old version
+  pub old: String,
+
+  /// Content in the new version
+  pub new: String,
+}
+
+impl FieldDiff {
+  /// Returns the field's lines, each marked as kept, removed, or added.
+  pub fn changes(&self) -> Vec<LineChange> {
+    TextDiff::from_lines(&self.old, &self.new)
+      .iter_all_changes()
+      .map(|change| {
+        let line = change.value().trim_end_matches(['\r', '\n']).to_string();
+        match change.tag() {
+          ChangeTag::Equal => LineChange::Equal(line),
+          ChangeTag::Delete => LineChange::Removed(line),
+          ChangeTag::Insert => LineChange::Added(line),
+        }
+      })
+      .collect()
+  }
+
+  /// Formats the field's changes as a unified diff with three lines of context.
+  ///
+  /// # Arguments
+  /// * `old_name` - Label of the old version in the `---` header
+  /// * `new_name` - Label of the new version in the `+++` header
+  pub fn unified(&self, old_name: &str, new_name: &str) -> String {
+    TextDiff::from_lines(&self.old, &self.new)
+      .unified_diff()
+      .context_radius(3)
+      .header(
+        &format!("{}/{}", old_name, self.field),
+        &format!("{}/{}", new_name, self.field),
+      )
+      .to_string()
+  }
+}
+
+/// Differences between two versions of a template.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::models::TemplateVersion;
+/// use send_with_us::version_diff::{LineChange, VersionDiff};
+/// use serde_json::json;
+///
+/// let old: TemplateVersion = serde_json::from_value(json!({
+///   "id": "ver_1", "name": "Original", "subject": "Welcome", "html": "<p>Hi</p>\n"
+/// })).unwrap();
+/// let new: TemplateVersion = serde_json::from_value(json!({
+///   "id": "ver_2", "name": "Friendlier", "subject": "Welcome", "html": "<p>Hello!</p>\n"
+/// })).unwrap();
+///
+/// let diff = VersionDiff::new(&old, &new);
+/// assert_eq!(diff.fields.len(), 1);
+/// assert_eq!(
+///   diff.fields[0].changes(),
+///   vec![
+///     LineChange::Removed("<p>Hi</p>".to_string()),
+///     LineChange::Added("<p>Hello!</p>".to_string()),
+///   ]
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionDiff {
+  /// ID of the old version
+  pub old_version_id: String,
+
+  /// ID of the new version
+  pub new_version_id: String,
+
+  /// Fields that differ, in the order subject, html, text
+  pub fields: Vec<FieldDiff>,
+}
+
+impl VersionDiff {
+  /// Compares the subject, HTML, and text of two versions.
+  ///
+  /// Missing content is compared as empty text.
+  ///
+  /// # Arguments
+  /// * `old` - The version to compare from
+  /// * `new` - The version to compare to
+  ///
+  /// # Returns
+  /// The fields that differ
+  pub fn new(old: &TemplateVersion, new: &TemplateVersion) -> Self {
+    let fields = [
+      ("subject", &old.subject, &new.subject),
+      ("html", &old.html, &new.html),
+      ("text", &old.text, &new.text),
+    ]
+    .into_iter()
+    .map(|(field, old, new)| FieldDiff {
+      field,
+      old: old.clone().unwrap_or_default(),
+      new: new.clone().unwrap_or_default(),
+    })
+    .filter(|field| field.old != field.new)
+    .collect();
+
+    Self {
+      old_version_id: old.id.clone(),
+      new_version_id: new.id.clone(),
+      fields,
+    }
+  }
+
+  /// Returns `true` if the versions have the same subject, HTML, and text.
+  pub fn is_empty(&self) -> bool {
+    self.fields.is_empty()
+  }
+
+  /// Formats every changed field as a unified diff, labeled with the version IDs.
+  pub fn unified(&self) -> String {
+    self
+      .fields
+      .iter()
+      .map(|field| field.unified(&self.old_version_id, &self.new_version_id))
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  fn version(id: &str, subject: &str, text: Option<&str>) -> TemplateVersion {
+    serde_json::from_value(json!({
+      "id": id,
+      "name": id,
+      "subject": subject,
+      "html": "<p>Same</p>",
+      "text": text
+    }))
+    .unwrap()
+  }
+
+  #[test]
+  fn test_diff_only_includes_changed_fields() {
+    let old = version("ver_1", "Hello", Some("one\ntwo\nthree\n"));
+    let new = version("ver_2", "Hello", Some("one\n2\nthree\n"));
+
+    let diff = VersionDiff::new(&old, &new);
+    assert_eq!(diff.fields.len(), 1);
+    assert_eq!(diff.fields[0].field, "text");
+    assert_eq!(
+      diff.unified(),
+      "--- ver_1/text\n+++ ver_2/text\n@@ -1,3 +1,3 @@\n one\n-two\n+2\n three\n"
+    );
+
+    assert!(VersionDiff::new(&old, &old).is_empty());
+  }
+
+  #[test]
+  fn test_diff_treats_missing_content_as_empty() {
+    let old = version("ver_1", "Hello", None);
+    let new = version("ver_2", "Hi", Some(""));
+
+    let diff = VersionDiff::new(&old, &new);
+    assert_eq!(diff.fields.len(), 1);
+    assert_eq!(
+      diff.fields[0].changes(),
+      vec![
+        LineChange::Removed("Hello".to_string()),
+        LineChange::Added("Hi".to_string())
+      ]
+    );
+  }
+}
@@ -0,0 +1,329 @@
+//! Typed SendWithUs webhook payloads.
+//!
+//! SendWithUs posts a JSON payload to a configured URL whenever something
+//! happens to an email: it was sent, delivered, opened, clicked, bounced,
+//! dropped, or its recipient unsubscribed. `parse` turns a request body into
+//! `WebhookPayload`s, so receivers can `match` on the `Event` instead of
+//! picking fields out of raw JSON. A `WebhookVerifier` checks that requests
+//! carry a shared secret, `dispatcher` routes events to async handlers
+//! registered per event, `dedupe` keeps redelivered events from being
+//! handled twice, `replay` feeds stored payloads through a dispatcher,
+//! `correlate` fetches the log of the email an event is about, and
+//! `broadcast` fans events out to in-process subscribers.
+//!
+//! Webhook URLs themselves are configured in the SendWithUs dashboard; the
+//! API has no endpoints to manage them.
+//!
+//! With the `axum` or `actix-web` feature, the `axum` and `actix` modules
+//! provide extractors that verify and parse a webhook request, so a handler
+//! only has to `match` on its events.
+
+#[cfg(feature = "actix-web")]
+pub mod actix;
+#[cfg(feature = "axum")]
+pub mod axum;
+pub mod broadcast;
+pub mod correlate;
+pub mod dedupe;
+pub mod dispatcher;
+pub mod replay;
+
+use crate::error::{Error, Result};
+use crate::ids::{LogId, TemplateId};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fmt;
+
+/// What happened to an email, as reported by a webhook.
+///
+/// This is the event type of email logs, so a webhook event compares
+/// directly with the events of the log it is about.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::models::EventType;
+/// use send_with_us::webhooks::Event;
+///
+/// assert_eq!(Event::from("bounce"), Event::Bounced);
+/// assert_eq!(Event::from("unsubscribed"), EventType::Unsubscribed);
+/// assert_eq!(Event::Dropped.as_str(), "dropped");
+/// ```
+pub use crate::models::EventType as Event;
+
+/// A webhook notification about one email.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::webhooks::{Event, WebhookPayload};
+///
+/// let payload: WebhookPayload = serde_json::from_str(r#"{
+///   "id": "evt_123",
+///   "event": "click",
+///   "timestamp": 1700000000,
+///   "email_address": "user@example.com",
+///   "log_id": "log_abc",
+///   "swu_template_id": "tem_welcome",
+///   "url": "https://example.com/start"
+/// }"#).unwrap();
+///
+/// assert_eq!(payload.event, Event::Clicked);
+/// assert_eq!(payload.url.as_deref(), Some("https://example.com/start"));
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WebhookPayload {
+  /// Unique ID of the notification; redeliveries of an event share it
+  pub id: String,
+
+  /// What happened
+  pub event: Event,
+
+  /// When it happened (Unix timestamp)
+  pub timestamp: i64,
+
+  /// Address of the recipient the event is about
+  pub email_address: String,
+
+  /// Log entry of the send, as returned in its receipt
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub log_id: Option<LogId>,
+
+  /// Template the email was sent with
+  #[serde(
+    rename = "swu_template_id",
+    default,
+    skip_serializing_if = "Option::is_none"
+  )]
+  pub template_id: Option<TemplateId>,
+
+  /// Template version the email was sent with
+  #[serde(
+    rename = "swu_template_version_id",
+    default,
+    skip_serializing_if = "Option::is_none"
+  )]
+  pub version_id: Option<String>,
+
+  /// Link clicked, for `Event::Clicked`
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub url: Option<String>,
+
+  /// Response from the email service provider, e.g. why an email bounced
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub esp_response: Option<String>,
+
+  /// Fields not covered above, such as provider-specific details
+  #[serde(flatten)]
+  pub extra: Map<String, Value>,
+}
+
+/// A webhook body: one payload, or several delivered together.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum WebhookBody {
+  Batch(Vec<WebhookPayload>),
+  Single(WebhookPayload),
+}
+
+/// Parses the body of a webhook request.
+///
+/// # Arguments
+/// * `body` - Raw request body, a JSON object or an array of them
+///
+/// # Returns
+/// The payloads in the body, in order
+///
+/// # Errors
+/// Returns `Error::SerializationFailed` if the body isn't a webhook payload
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::webhooks::{self, Event};
+///
+/// let body = br#"{"id": "evt_1", "event": "bounce", "timestamp": 1700000000,
+///   "email_address": "user@example.com", "esp_response": "550 No such user"}"#;
+///
+/// for payload in webhooks::parse(body)? {
+///   match payload.event {
+///     Event::Bounced => println!("{} bounced", payload.email_address),
+///     _ => {}
+///   }
+/// }
+/// # Ok::<(), send_with_us::Error>(())
+/// ```
+pub fn parse(body: &[u8]) -> Result<Vec<WebhookPayload>> {
+  Ok(match serde_json::from_slice(body)? {
+    WebhookBody::Batch(payloads) => payloads,
+    WebhookBody::Single(payload) => vec![payload],
+  })
+}
+
+/// Query parameter carrying the webhook secret token.
+pub const TOKEN_PARAMETER: &str = "token";
+
+/// Header carrying the webhook secret token, for proxies that move it out of the URL.
+pub const TOKEN_HEADER: &str = "X-Webhook-Token";
+
+/// Checks that webhook requests carry a shared secret token.
+///
+/// SendWithUs posts to whatever URL is configured, so anyone who learns the
+/// URL could post fake events. Configure the webhook URL with a secret,
+/// e.g. `https://example.com/webhooks?token=SECRET`, and verify it before
+/// processing events. The token may also arrive in the `X-Webhook-Token`
+/// header.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::webhooks::WebhookVerifier;
+///
+/// let verifier = WebhookVerifier::new("s3cret");
+///
+/// assert!(verifier.verify_request(Some("token=s3cret"), None).is_ok());
+/// assert!(verifier.verify_request(Some("token=guess"), None).is_err());
+/// assert!(verifier.verify_request(None, Some("s3cret")).is_ok());
+/// ```
+#[derive(Clone)]
+pub struct WebhookVerifier {
+  secret: String,
+}
+
+impl fmt::Debug for WebhookVerifier {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("WebhookVerifier")
+      .field("secret", &"[REDACTED]")
+      .finish()
+  }
+}
+
+impl WebhookVerifier {
+  /// Creates a verifier expecting a secret token.
+  ///
+  /// # Arguments
+  /// * `secret` - Token configured in the webhook URL
+  pub fn new(secret: impl Into<String>) -> Self {
+    Self {
+      secret: secret.into(),
+    }
+  }
+
+  /// Checks a token against the secret, in constant time.
+  ///
+  /// # Errors
+  /// Returns `Error::WebhookVerificationFailed` if the token is missing or wrong
+  pub fn verify(&self, token: Option<&str>) -> Result<()> {
+    match token {
+      Some(token) if constant_time_eq(token.as_bytes(), self.secret.as_bytes()) => Ok(()),
+      _ => Err(Error::WebhookVerificationFailed),
+    }
+  }
+
+  /// Checks the token of a request, from its query string or `X-Webhook-Token` header.
+  ///
+  /// # Arguments
+  /// * `query` - Query string of the request URL, without the `?`
+  /// * `header` - Value of the `X-Webhook-Token` header
+  ///
+  /// # Errors
+  /// Returns `Error::WebhookVerificationFailed` if neither carries the secret
+  pub fn verify_request(&self, query: Option<&str>, header: Option<&str>) -> Result<()> {
+    let from_query = query.and_then(|query| {
+      url::form_urlencoded::parse(query.as_bytes())
+        .find(|(name, _)| name == TOKEN_PARAMETER)
+        .map(|(_, token)| token.into_owned())
+    });
+
+    match from_query {
+      Some(token) => self.verify(Some(&token)),
+      None => self.verify(header),
+    }
+  }
+}
+
+/// Compares two byte strings without exiting early on the first difference.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+  a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Returns the HTTP status for rejecting a webhook request that failed with `error`.
+#[cfg(any(feature = "axum", feature = "actix-web"))]
+fn rejection_status(error: &Error) -> u16 {
+  match error {
+    Error::WebhookVerificationFailed => 401,
+    _ => 400,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  #[test]
+  fn test_parse_single_and_batched_payloads() {
+    let bounce = json!({
+      "id": "evt_1",
+      "event": "bounced",
+      "timestamp": 1700000000,
+      "email_address": "ada@example.com",
+      "log_id": "log_abc",
+      "swu_template_id": "tem_welcome",
+      "swu_template_version_id": "ver_1",
+      "esp_response": "550 No such user",
+      "esp": "sendgrid"
+    });
+    let open = json!({
+      "id": "evt_2",
+      "event": "open",
+      "timestamp": 1700000600,
+      "email_address": "grace@example.com"
+    });
+
+    let single = parse(bounce.to_string().as_bytes()).unwrap();
+    assert_eq!(single.len(), 1);
+    assert_eq!(single[0].event, Event::Bounced);
+    assert_eq!(single[0].log_id.as_ref().unwrap().as_str(), "log_abc");
+    assert_eq!(
+      single[0].template_id.as_ref().unwrap().as_str(),
+      "tem_welcome"
+    );
+    assert_eq!(single[0].extra["esp"], "sendgrid");
+    assert_eq!(serde_json::to_value(&single[0]).unwrap(), bounce);
+
+    let batch = parse(json!([bounce, open]).to_string().as_bytes()).unwrap();
+    let events: Vec<&Event> = batch.iter().map(|payload| &payload.event).collect();
+    assert_eq!(events, vec![&Event::Bounced, &Event::Opened]);
+  }
+
+  #[test]
+  fn test_parse_rejects_invalid_bodies() {
+    assert!(matches!(
+      parse(b"not json"),
+      Err(Error::SerializationFailed(_))
+    ));
+    assert!(matches!(
+      parse(br#"{"event": "sent"}"#),
+      Err(Error::SerializationFailed(_))
+    ));
+  }
+
+  #[test]
+  fn test_verifier_checks_query_then_header() {
+    let verifier = WebhookVerifier::new("s3cret");
+
+    assert!(
+      verifier
+        .verify_request(Some("a=1&token=s3cret"), None)
+        .is_ok()
+    );
+    assert!(verifier.verify_request(None, Some("s3cret")).is_ok());
+    assert!(matches!(
+      verifier.verify_request(Some("token=s3cre"), Some("s3cret")),
+      Err(Error::WebhookVerificationFailed)
+    ));
+    assert!(verifier.verify_request(Some("a=1"), None).is_err());
+    assert!(!format!("{:?}", verifier).contains("s3cret"));
+  }
+}
@@ -0,0 +1,109 @@
+//! Webhook extractor for actix-web.
+//!
+//! Available with the `actix-web` feature. Add a `Webhook` argument to a
+//! handler to receive the parsed payloads of a request. If a
+//! `WebhookVerifier` was registered with `App::app_data`, requests without
+//! its secret are rejected with 401 before the body is read; bodies that
+//! aren't webhook payloads are rejected with 400.
+
+use super::{TOKEN_HEADER, WebhookPayload, WebhookVerifier, parse, rejection_status};
+use crate::error::Error;
+use actix_web::dev::Payload;
+use actix_web::error::InternalError;
+use actix_web::http::StatusCode;
+use actix_web::web::Bytes;
+use actix_web::{FromRequest, HttpRequest};
+use futures::future::LocalBoxFuture;
+
+/// The payloads of a verified webhook request.
+///
+/// # Examples
+///
+/// ```no_run
+/// use actix_web::{App, web};
+/// use send_with_us::webhooks::actix::Webhook;
+/// use send_with_us::webhooks::{Event, WebhookVerifier};
+///
+/// async fn receive(Webhook(payloads): Webhook) -> &'static str {
+///   for payload in payloads {
+///     if payload.event == Event::Bounced {
+///       println!("{} bounced", payload.email_address);
+///     }
+///   }
+///   "ok"
+/// }
+///
+/// let app = App::new()
+///   .app_data(WebhookVerifier::new("s3cret"))
+///   .route("/webhooks/sendwithus", web::post().to(receive));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Webhook(pub Vec<WebhookPayload>);
+
+impl FromRequest for Webhook {
+  type Error = actix_web::Error;
+  type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+  fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+    let verified = match req.app_data::<WebhookVerifier>() {
+      Some(verifier) => {
+        let header = req
+          .headers()
+          .get(TOKEN_HEADER)
+          .and_then(|value| value.to_str().ok());
+        verifier.verify_request(Some(req.query_string()), header)
+      }
+      None => Ok(()),
+    };
+    let body = Bytes::from_request(req, payload);
+
+    Box::pin(async move {
+      verified.map_err(rejection)?;
+      let body = body.await?;
+      parse(&body).map(Webhook).map_err(rejection)
+    })
+  }
+}
+
+fn rejection(error: Error) -> actix_web::Error {
+  let status = StatusCode::from_u16(rejection_status(&error)).unwrap_or(StatusCode::BAD_REQUEST);
+  InternalError::new(error.to_string(), status).into()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::webhooks::Event;
+  use actix_web::test::TestRequest;
+
+  const BODY: &str = r#"{"id": "evt_1", "event": "open", "timestamp": 1700000000, "email_address": "ada@example.com"}"#;
+
+  async fn extract(request: TestRequest) -> Result<Webhook, actix_web::Error> {
+    let (req, mut payload) = request.set_payload(BODY).to_http_parts();
+    Webhook::from_request(&req, &mut payload).await
+  }
+
+  #[tokio::test]
+  async fn test_extracts_verified_payloads() {
+    let verifier = WebhookVerifier::new("s3cret");
+
+    let Webhook(payloads) = extract(
+      TestRequest::post()
+        .uri("/hooks")
+        .insert_header((TOKEN_HEADER, "s3cret"))
+        .app_data(verifier.clone()),
+    )
+    .await
+    .unwrap();
+    assert_eq!(payloads[0].event, Event::Opened);
+
+    let rejected = extract(
+      TestRequest::post()
+        .uri("/hooks?token=guess")
+        .app_data(verifier),
+    )
+    .await;
+    let status = rejected.unwrap_err().as_response_error().status_code();
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+  }
+}
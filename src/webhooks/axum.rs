@@ -0,0 +1,108 @@
+//! Webhook extractor for axum.
+//!
+//! Available with the `axum` feature. Add a `Webhook` argument to a handler
+//! to receive the parsed payloads of a request. If a `WebhookVerifier` was
+//! added with an `Extension` layer, requests without its secret are
+//! rejected with 401 before the body is read; bodies that aren't webhook
+//! payloads are rejected with 400.
+
+use super::{TOKEN_HEADER, WebhookPayload, WebhookVerifier, parse, rejection_status};
+use crate::error::Error;
+use ::axum::body::Bytes;
+use ::axum::extract::{FromRequest, Request};
+use ::axum::http::StatusCode;
+
+/// The payloads of a verified webhook request.
+///
+/// # Examples
+///
+/// ```no_run
+/// use axum::routing::post;
+/// use axum::{Extension, Router};
+/// use send_with_us::webhooks::axum::Webhook;
+/// use send_with_us::webhooks::{Event, WebhookVerifier};
+///
+/// async fn receive(Webhook(payloads): Webhook) {
+///   for payload in payloads {
+///     match payload.event {
+///       Event::Bounced => println!("{} bounced", payload.email_address),
+///       Event::Unsubscribed => println!("{} unsubscribed", payload.email_address),
+///       _ => {}
+///     }
+///   }
+/// }
+///
+/// let app: Router = Router::new()
+///   .route("/webhooks/sendwithus", post(receive))
+///   .layer(Extension(WebhookVerifier::new("s3cret")));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Webhook(pub Vec<WebhookPayload>);
+
+impl<S> FromRequest<S> for Webhook
+where
+  S: Send + Sync,
+{
+  type Rejection = (StatusCode, String);
+
+  async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+    if let Some(verifier) = req.extensions().get::<WebhookVerifier>() {
+      let header = req
+        .headers()
+        .get(TOKEN_HEADER)
+        .and_then(|value| value.to_str().ok());
+      verifier
+        .verify_request(req.uri().query(), header)
+        .map_err(rejection)?;
+    }
+
+    let body = Bytes::from_request(req, state)
+      .await
+      .map_err(|rejection| (rejection.status(), rejection.body_text()))?;
+
+    parse(&body).map(Webhook).map_err(rejection)
+  }
+}
+
+fn rejection(error: Error) -> (StatusCode, String) {
+  let status = StatusCode::from_u16(rejection_status(&error)).unwrap_or(StatusCode::BAD_REQUEST);
+  (status, error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::webhooks::Event;
+  use ::axum::body::Body;
+
+  const BODY: &str = r#"{"id": "evt_1", "event": "open", "timestamp": 1700000000, "email_address": "ada@example.com"}"#;
+
+  fn request(uri: &str, verifier: Option<WebhookVerifier>) -> Request {
+    let mut req = Request::post(uri).body(Body::from(BODY)).unwrap();
+    if let Some(verifier) = verifier {
+      req.extensions_mut().insert(verifier);
+    }
+    req
+  }
+
+  #[tokio::test]
+  async fn test_extracts_verified_payloads() {
+    let verifier = WebhookVerifier::new("s3cret");
+
+    let Webhook(payloads) =
+      Webhook::from_request(request("/hooks?token=s3cret", Some(verifier.clone())), &())
+        .await
+        .unwrap();
+    assert_eq!(payloads[0].event, Event::Opened);
+
+    let unverified = Webhook::from_request(request("/hooks", None), &()).await;
+    assert!(unverified.is_ok());
+
+    let rejected = Webhook::from_request(request("/hooks?token=guess", Some(verifier)), &()).await;
+    assert_eq!(rejected.unwrap_err().0, StatusCode::UNAUTHORIZED);
+
+    let invalid = Request::post("/hooks").body(Body::from("{}")).unwrap();
+    let invalid = Webhook::from_request(invalid, &()).await;
+    assert_eq!(invalid.unwrap_err().0, StatusCode::BAD_REQUEST);
+  }
+}
@@ -0,0 +1,178 @@
+//! Fan-out of webhook events to in-process subscribers.
+//!
+//! A `WebhookBroadcast` publishes parsed payloads on a
+//! `tokio::sync::broadcast` channel, so several parts of an application,
+//! e.g. a suppression list and an analytics pipeline, can each consume email
+//! events without knowing about the HTTP endpoint that received them.
+
+use super::{WebhookPayload, parse};
+use crate::error::Result;
+use futures::future::{Ready, ready};
+use futures::stream::{self, BoxStream, StreamExt};
+use tokio::sync::broadcast;
+
+/// Publishes webhook payloads to any number of subscribers.
+///
+/// Cloning is cheap and every clone publishes on the same channel. Each
+/// subscriber buffers up to `capacity` payloads; one that falls further
+/// behind misses the oldest of them rather than slowing down the others.
+/// Payloads published while nobody is subscribed are dropped.
+///
+/// # Examples
+///
+/// ```
+/// use futures::StreamExt;
+/// use send_with_us::webhooks::broadcast::WebhookBroadcast;
+///
+/// # #[tokio::main]
+/// # async fn main() -> send_with_us::Result<()> {
+/// let broadcast = WebhookBroadcast::new(64);
+/// let mut events = broadcast.stream();
+///
+/// broadcast.publish_body(
+///   br#"{"id": "evt_1", "event": "open", "timestamp": 1700000000, "email_address": "ada@example.com"}"#,
+/// )?;
+///
+/// let payload = events.next().await.unwrap();
+/// assert_eq!(payload.id, "evt_1");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct WebhookBroadcast {
+  sender: broadcast::Sender<WebhookPayload>,
+}
+
+impl WebhookBroadcast {
+  /// Creates a broadcast buffering up to `capacity` payloads per subscriber.
+  ///
+  /// # Panics
+  /// Panics if `capacity` is zero
+  pub fn new(capacity: usize) -> Self {
+    let (sender, _) = broadcast::channel(capacity);
+    Self { sender }
+  }
+
+  /// Subscribes to payloads published from now on.
+  ///
+  /// The receiver reports `RecvError::Lagged` when it fell behind; use
+  /// `stream` to skip over missed payloads instead.
+  pub fn subscribe(&self) -> broadcast::Receiver<WebhookPayload> {
+    self.sender.subscribe()
+  }
+
+  /// Subscribes to payloads published from now on, as a stream.
+  ///
+  /// Payloads missed because the subscriber fell behind are skipped. The
+  /// stream ends once every `WebhookBroadcast` handle has been dropped.
+  pub fn stream(&self) -> BoxStream<'static, WebhookPayload> {
+    stream::unfold(self.subscribe(), |mut receiver| async move {
+      loop {
+        match receiver.recv().await {
+          Ok(payload) => return Some((payload, receiver)),
+          Err(broadcast::error::RecvError::Lagged(_)) => continue,
+          Err(broadcast::error::RecvError::Closed) => return None,
+        }
+      }
+    })
+    .boxed()
+  }
+
+  /// Returns how many subscribers are listening.
+  pub fn subscriber_count(&self) -> usize {
+    self.sender.receiver_count()
+  }
+
+  /// Publishes a payload to every subscriber.
+  ///
+  /// # Returns
+  /// The number of subscribers the payload was sent to
+  pub fn publish(&self, payload: WebhookPayload) -> usize {
+    self.sender.send(payload).unwrap_or_default()
+  }
+
+  /// Parses a webhook body and publishes its payloads in order.
+  ///
+  /// # Arguments
+  /// * `body` - Raw request body
+  ///
+  /// # Returns
+  /// The number of payloads in the body
+  ///
+  /// # Errors
+  /// Returns `Error::SerializationFailed` if the body isn't a webhook payload
+  pub fn publish_body(&self, body: &[u8]) -> Result<usize> {
+    let payloads = parse(body)?;
+    let count = payloads.len();
+    for payload in payloads {
+      self.publish(payload);
+    }
+
+    Ok(count)
+  }
+
+  /// Returns a handler publishing every payload, for `WebhookDispatcher::on_any`.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use send_with_us::webhooks::broadcast::WebhookBroadcast;
+  /// use send_with_us::webhooks::dispatcher::WebhookDispatcher;
+  ///
+  /// let broadcast = WebhookBroadcast::new(64);
+  /// let dispatcher = WebhookDispatcher::new().on_any(broadcast.handler());
+  /// ```
+  pub fn handler(&self) -> impl Fn(WebhookPayload) -> Ready<Result<()>> + Send + Sync + 'static {
+    let broadcast = self.clone();
+    move |payload| {
+      broadcast.publish(payload);
+      ready(Ok(()))
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::webhooks::dispatcher::WebhookDispatcher;
+  use serde_json::json;
+
+  #[tokio::test]
+  async fn test_every_subscriber_receives_dispatched_events() {
+    let broadcast = WebhookBroadcast::new(8);
+    let dispatcher = WebhookDispatcher::new().on_any(broadcast.handler());
+    let first = broadcast.stream();
+    let mut second = broadcast.subscribe();
+    assert_eq!(broadcast.subscriber_count(), 2);
+
+    let body = json!([
+      { "id": "evt_1", "event": "sent", "timestamp": 1700000000, "email_address": "ada@example.com" },
+      { "id": "evt_2", "event": "bounce", "timestamp": 1700000001, "email_address": "ada@example.com" },
+    ]);
+    dispatcher
+      .dispatch(body.to_string().as_bytes())
+      .await
+      .unwrap();
+    assert_eq!(second.recv().await.unwrap().id, "evt_1");
+    assert_eq!(second.recv().await.unwrap().id, "evt_2");
+
+    drop(dispatcher);
+    drop(broadcast);
+    let ids: Vec<String> = first.map(|payload| payload.id).collect().await;
+    assert_eq!(ids, vec!["evt_1", "evt_2"]);
+  }
+
+  #[tokio::test]
+  async fn test_lagging_stream_skips_missed_payloads() {
+    let broadcast = WebhookBroadcast::new(1);
+    let events = broadcast.stream();
+    for id in ["evt_1", "evt_2", "evt_3"] {
+      let body = json!({ "id": id, "event": "open", "timestamp": 1700000000, "email_address": "ada@example.com" });
+      broadcast.publish_body(body.to_string().as_bytes()).unwrap();
+    }
+
+    drop(broadcast);
+    let ids: Vec<String> = events.map(|payload| payload.id).collect().await;
+    assert_eq!(ids, vec!["evt_3"]);
+  }
+}
@@ -0,0 +1,146 @@
+//! Correlation of webhook events with the emails they are about.
+//!
+//! A webhook payload says what happened but little about the email itself.
+//! `correlate` fetches the email's log and its event history, so code
+//! handling a bounce or complaint has the template, recipient, and every
+//! earlier event at hand in one call.
+
+use super::WebhookPayload;
+use crate::api::ApiClient;
+use crate::error::{Error, Result};
+use crate::models::{EmailLog, LogEvent};
+
+/// A webhook event together with the log of its email.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorrelatedEvent {
+  /// The webhook event
+  pub payload: WebhookPayload,
+
+  /// Log of the email the event is about
+  pub log: EmailLog,
+
+  /// Events recorded for the email so far, including earlier ones
+  pub events: Vec<LogEvent>,
+}
+
+/// Fetches the log and events of the email a webhook event is about.
+///
+/// The log and its events are requested concurrently.
+///
+/// # Arguments
+/// * `client` - Client used to fetch the log
+/// * `payload` - Webhook event to correlate
+///
+/// # Returns
+/// The event with the log and event history of its email
+///
+/// # Errors
+/// Returns `Error::MissingLogId` if the payload has no `log_id`, or the
+/// error of either request
+///
+/// # Examples
+///
+/// ```no_run
+/// use send_with_us::Api;
+/// use send_with_us::models::LogEventsExt;
+/// use send_with_us::webhooks::correlate::correlate;
+/// use send_with_us::webhooks::dispatcher::WebhookDispatcher;
+/// use std::sync::Arc;
+///
+/// let api = Arc::new(Api::with_api_key("api-key"));
+/// let dispatcher = WebhookDispatcher::new().on_bounced(move |payload| {
+///   let api = api.clone();
+///   async move {
+///     let bounce = correlate(&*api, payload).await?;
+///     println!(
+///       "{} bounced {:?}, opened before: {}",
+///       bounce.log.recipient_address,
+///       bounce.log.email_name,
+///       bounce.events.opened_after(0),
+///     );
+///     Ok(())
+///   }
+/// });
+/// ```
+pub async fn correlate(
+  client: &impl ApiClient,
+  payload: WebhookPayload,
+) -> Result<CorrelatedEvent> {
+  let log_id = payload
+    .log_id
+    .clone()
+    .ok_or_else(|| Error::MissingLogId(payload.id.clone()))?;
+
+  let (log, events) =
+    futures::future::try_join(client.log(&log_id), client.log_events(&log_id)).await?;
+
+  Ok(CorrelatedEvent {
+    payload,
+    log,
+    events,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::api::Api;
+  use crate::config::Config;
+  use crate::models::EventType;
+  use serde_json::json;
+
+  fn payload(log_id: Option<&str>) -> WebhookPayload {
+    serde_json::from_value(json!({
+      "id": "evt_1",
+      "event": "bounce",
+      "timestamp": 1700000900,
+      "email_address": "ada@example.com",
+      "log_id": log_id,
+    }))
+    .unwrap()
+  }
+
+  #[tokio::test]
+  async fn test_correlate_fetches_log_and_events() {
+    let mut server = mockito::Server::new_async().await;
+    let log = server
+      .mock("GET", "/api/v1/logs/log_abc")
+      .with_status(200)
+      .with_body(
+        json!({
+          "id": "log_abc",
+          "created": 1700000000,
+          "recipient_address": "ada@example.com",
+          "status": "bounced",
+          "email_name": "Welcome"
+        })
+        .to_string(),
+      )
+      .create();
+    let events = server
+      .mock("GET", "/api/v1/logs/log_abc/events")
+      .with_status(200)
+      .with_body(
+        json!([
+          { "type": "sent", "created": 1700000000 },
+          { "type": "bounce", "created": 1700000900, "message": "550 No such user" }
+        ])
+        .to_string(),
+      )
+      .create();
+
+    let mut config = Config::new("test-api-key");
+    config.url = url::Url::parse(&server.url()).unwrap();
+    let api = Api::new(config);
+
+    let correlated = correlate(&api, payload(Some("log_abc"))).await.unwrap();
+    assert_eq!(correlated.log.email_name.as_deref(), Some("Welcome"));
+    assert_eq!(correlated.events[1].event_type, EventType::Bounced);
+    assert_eq!(correlated.payload.id, "evt_1");
+    log.assert();
+    events.assert();
+
+    let missing = correlate(&api, payload(None)).await;
+    assert!(matches!(missing, Err(Error::MissingLogId(id)) if id == "evt_1"));
+  }
+}
@@ -0,0 +1,207 @@
+//! Deduplication of redelivered webhook events.
+//!
+//! SendWithUs redelivers an event when its webhook doesn't answer in time or
+//! answers with an error, so the same event can arrive more than once. A
+//! `DedupeStore` remembers which event IDs were processed; with one set via
+//! `WebhookDispatcher::with_dedupe_store`, handlers run once per event.
+//! `MemoryDedupeStore` remembers IDs for the life of the process, and with
+//! the `sqlite` feature, `SqliteDedupeStore` keeps them in a database shared
+//! across restarts.
+
+use crate::error::Result;
+use async_trait::async_trait;
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+/// Storage for the IDs of processed webhook events.
+///
+/// Claims must be atomic: when two deliveries of an event race, exactly one
+/// claim may succeed. Implement this trait to share IDs between instances
+/// of a webhook receiver, e.g. in Redis or a database table.
+#[async_trait]
+pub trait DedupeStore: Send + Sync {
+  /// Claims an event for processing.
+  ///
+  /// # Returns
+  /// `true` if the event wasn't claimed before and should be processed
+  async fn claim(&self, event_id: &str) -> Result<bool>;
+
+  /// Releases a claim after processing failed, so a redelivery is processed.
+  async fn release(&self, event_id: &str) -> Result<()>;
+}
+
+/// A dedupe store keeping event IDs in memory.
+///
+/// IDs are forgotten when the process exits, so redeliveries after a restart
+/// are processed again.
+///
+/// # Examples
+///
+/// ```
+/// use send_with_us::webhooks::dedupe::{DedupeStore, MemoryDedupeStore};
+///
+/// # #[tokio::main]
+/// # async fn main() -> send_with_us::Result<()> {
+/// let store = MemoryDedupeStore::new();
+///
+/// assert!(store.claim("evt_1").await?);
+/// assert!(!store.claim("evt_1").await?);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct MemoryDedupeStore {
+  claimed: Mutex<HashSet<String>>,
+}
+
+impl MemoryDedupeStore {
+  /// Creates a store with no events claimed.
+  pub fn new() -> Self {
+    Self::default()
+  }
+}
+
+#[async_trait]
+impl DedupeStore for MemoryDedupeStore {
+  async fn claim(&self, event_id: &str) -> Result<bool> {
+    Ok(
+      self
+        .claimed
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(event_id.to_string()),
+    )
+  }
+
+  async fn release(&self, event_id: &str) -> Result<()> {
+    self
+      .claimed
+      .lock()
+      .unwrap_or_else(|e| e.into_inner())
+      .remove(event_id);
+    Ok(())
+  }
+}
+
+#[cfg(feature = "sqlite")]
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS webhook_events (
+  id TEXT PRIMARY KEY,
+  claimed_at INTEGER NOT NULL
+);
+";
+
+/// A dedupe store keeping event IDs in a SQLite database.
+///
+/// Available with the `sqlite` feature. IDs survive restarts, and receivers
+/// on one host can share the database file.
+///
+/// # Examples
+///
+/// ```no_run
+/// use send_with_us::webhooks::dedupe::SqliteDedupeStore;
+/// use send_with_us::webhooks::dispatcher::WebhookDispatcher;
+///
+/// # fn run() -> send_with_us::Result<()> {
+/// let dispatcher = WebhookDispatcher::new()
+///   .with_dedupe_store(SqliteDedupeStore::open("webhooks.db")?)
+///   .on_bounced(|payload| async move {
+///     println!("{} bounced", payload.email_address);
+///     Ok(())
+///   });
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "sqlite")]
+#[derive(Debug)]
+pub struct SqliteDedupeStore {
+  connection: Mutex<rusqlite::Connection>,
+}
+
+#[cfg(feature = "sqlite")]
+impl SqliteDedupeStore {
+  /// Opens the store in the SQLite database at `path`, creating its table if needed.
+  ///
+  /// # Errors
+  /// Returns an error if the database cannot be opened or initialized
+  pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self> {
+    Self::with_connection(rusqlite::Connection::open(path)?)
+  }
+
+  /// Creates a store kept in memory, e.g. for tests.
+  ///
+  /// # Errors
+  /// Returns an error if the database cannot be initialized
+  pub fn in_memory() -> Result<Self> {
+    Self::with_connection(rusqlite::Connection::open_in_memory()?)
+  }
+
+  fn with_connection(connection: rusqlite::Connection) -> Result<Self> {
+    connection.execute_batch(SCHEMA)?;
+
+    Ok(Self {
+      connection: Mutex::new(connection),
+    })
+  }
+
+  fn connection(&self) -> std::sync::MutexGuard<'_, rusqlite::Connection> {
+    self.connection.lock().unwrap_or_else(|e| e.into_inner())
+  }
+}
+
+#[cfg(feature = "sqlite")]
+#[async_trait]
+impl DedupeStore for SqliteDedupeStore {
+  async fn claim(&self, event_id: &str) -> Result<bool> {
+    let claimed_at = std::time::SystemTime::now()
+      .duration_since(std::time::UNIX_EPOCH)
+      .map(|elapsed| elapsed.as_secs() as i64)
+      .unwrap_or_default();
+    let inserted = self.connection().execute(
+      "INSERT OR IGNORE INTO webhook_events (id, claimed_at) VALUES (?1, ?2)",
+      rusqlite::params![event_id, claimed_at],
+    )?;
+
+    Ok(inserted == 1)
+  }
+
+  async fn release(&self, event_id: &str) -> Result<()> {
+    self.connection().execute(
+      "DELETE FROM webhook_events WHERE id = ?1",
+      rusqlite::params![event_id],
+    )?;
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  async fn check_claims(store: &dyn DedupeStore) {
+    assert!(store.claim("evt_1").await.unwrap());
+    assert!(!store.claim("evt_1").await.unwrap());
+    assert!(store.claim("evt_2").await.unwrap());
+
+    store.release("evt_1").await.unwrap();
+    assert!(store.claim("evt_1").await.unwrap());
+  }
+
+  #[tokio::test]
+  async fn test_memory_store_claims_once() {
+    check_claims(&MemoryDedupeStore::new()).await;
+  }
+
+  #[cfg(feature = "sqlite")]
+  #[tokio::test]
+  async fn test_sqlite_store_claims_once_across_connections() {
+    let dir = tempdir::TempDir::new("dedupe").unwrap();
+    let path = dir.path().join("webhooks.db");
+
+    check_claims(&SqliteDedupeStore::open(&path).unwrap()).await;
+    check_claims(&SqliteDedupeStore::in_memory().unwrap()).await;
+
+    let reopened = SqliteDedupeStore::open(&path).unwrap();
+    assert!(!reopened.claim("evt_2").await.unwrap());
+  }
+}
@@ -0,0 +1,391 @@
+//! Routing of webhook events to registered handlers.
+//!
+//! A `WebhookDispatcher` holds async handlers registered per event, such as
+//! `on_bounced` or `on_opened`. Feed it raw webhook bodies and it verifies
+//! them, parses the payloads, and calls the handlers for each event, so an
+//! application's webhook endpoint reduces to one `dispatch_request` call.
+
+use super::dedupe::DedupeStore;
+use super::{Event, WebhookPayload, WebhookVerifier, parse};
+use crate::error::Result;
+use futures::future::BoxFuture;
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+
+type Handler = Arc<dyn Fn(WebhookPayload) -> BoxFuture<'static, Result<()>> + Send + Sync>;
+
+/// Routes webhook payloads to async handlers by event.
+///
+/// Handlers for an event run in the order they were registered, after any
+/// `on_any` handlers. Payloads are handled one at a time in the order they
+/// arrived, and the first handler error stops the dispatch, so the webhook
+/// endpoint can answer with an error and SendWithUs will redeliver.
+///
+/// # Examples
+///
+/// ```no_run
+/// use send_with_us::webhooks::WebhookVerifier;
+/// use send_with_us::webhooks::dispatcher::WebhookDispatcher;
+///
+/// # async fn run(query: Option<&str>, body: &[u8]) -> send_with_us::Result<()> {
+/// let dispatcher = WebhookDispatcher::new()
+///   .with_verifier(WebhookVerifier::new("s3cret"))
+///   .on_bounced(|payload| async move {
+///     println!("suppressing {}", payload.email_address);
+///     Ok(())
+///   })
+///   .on_opened(|payload| async move {
+///     println!("{} opened {:?}", payload.email_address, payload.template_id);
+///     Ok(())
+///   });
+///
+/// dispatcher.dispatch_request(query, None, body).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct WebhookDispatcher {
+  verifier: Option<WebhookVerifier>,
+  dedupe: Option<Arc<dyn DedupeStore>>,
+  handlers: HashMap<Event, Vec<Handler>>,
+  any: Vec<Handler>,
+}
+
+impl fmt::Debug for WebhookDispatcher {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let events: Vec<&Event> = self.handlers.keys().collect();
+    f.debug_struct("WebhookDispatcher")
+      .field("verifier", &self.verifier)
+      .field("dedupe", &self.dedupe.is_some())
+      .field("events", &events)
+      .field("any", &self.any.len())
+      .finish()
+  }
+}
+
+impl WebhookDispatcher {
+  /// Creates a dispatcher with no handlers and no verifier.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Verifies requests passed to `dispatch_request` with a shared secret.
+  ///
+  /// # Arguments
+  /// * `verifier` - Verifier holding the webhook secret
+  ///
+  /// # Returns
+  /// Self with the verifier set for method chaining
+  pub fn with_verifier(mut self, verifier: WebhookVerifier) -> Self {
+    self.verifier = Some(verifier);
+    self
+  }
+
+  /// Runs the handlers once per event ID, skipping redeliveries.
+  ///
+  /// An event is claimed in the store before its handlers run and released
+  /// if one fails, so a redelivery after a failure is processed again. If
+  /// the process dies while handlers run, the event stays claimed.
+  ///
+  /// # Arguments
+  /// * `store` - Store remembering processed event IDs
+  ///
+  /// # Returns
+  /// Self with deduplication set for method chaining
+  pub fn with_dedupe_store(mut self, store: impl DedupeStore + 'static) -> Self {
+    self.dedupe = Some(Arc::new(store));
+    self
+  }
+
+  /// Registers a handler for an event.
+  ///
+  /// # Arguments
+  /// * `event` - Event to handle, e.g. `Event::Other("spam".into())`
+  /// * `handler` - Async function called with each payload of the event
+  ///
+  /// # Returns
+  /// Self with the handler registered for method chaining
+  pub fn on<F, Fut>(mut self, event: Event, handler: F) -> Self
+  where
+    F: Fn(WebhookPayload) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+  {
+    self.handlers.entry(event).or_default().push(boxed(handler));
+    self
+  }
+
+  /// Registers a handler for every event, before any event-specific handlers.
+  pub fn on_any<F, Fut>(mut self, handler: F) -> Self
+  where
+    F: Fn(WebhookPayload) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+  {
+    self.any.push(boxed(handler));
+    self
+  }
+
+  /// Registers a handler for `Event::Sent`.
+  pub fn on_sent<F, Fut>(self, handler: F) -> Self
+  where
+    F: Fn(WebhookPayload) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+  {
+    self.on(Event::Sent, handler)
+  }
+
+  /// Registers a handler for `Event::Delivered`.
+  pub fn on_delivered<F, Fut>(self, handler: F) -> Self
+  where
+    F: Fn(WebhookPayload) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+  {
+    self.on(Event::Delivered, handler)
+  }
+
+  /// Registers a handler for `Event::Opened`.
+  pub fn on_opened<F, Fut>(self, handler: F) -> Self
+  where
+    F: Fn(WebhookPayload) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+  {
+    self.on(Event::Opened, handler)
+  }
+
+  /// Registers a handler for `Event::Clicked`.
+  pub fn on_clicked<F, Fut>(self, handler: F) -> Self
+  where
+    F: Fn(WebhookPayload) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+  {
+    self.on(Event::Clicked, handler)
+  }
+
+  /// Registers a handler for `Event::Bounced`.
+  pub fn on_bounced<F, Fut>(self, handler: F) -> Self
+  where
+    F: Fn(WebhookPayload) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+  {
+    self.on(Event::Bounced, handler)
+  }
+
+  /// Registers a handler for `Event::Dropped`.
+  pub fn on_dropped<F, Fut>(self, handler: F) -> Self
+  where
+    F: Fn(WebhookPayload) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+  {
+    self.on(Event::Dropped, handler)
+  }
+
+  /// Registers a handler for `Event::Unsubscribed`.
+  pub fn on_unsubscribed<F, Fut>(self, handler: F) -> Self
+  where
+    F: Fn(WebhookPayload) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+  {
+    self.on(Event::Unsubscribed, handler)
+  }
+
+  /// Verifies a webhook request, then dispatches its body.
+  ///
+  /// Without a verifier, this is the same as `dispatch`.
+  ///
+  /// # Arguments
+  /// * `query` - Query string of the request URL, without the `?`
+  /// * `header` - Value of the `X-Webhook-Token` header
+  /// * `body` - Raw request body
+  ///
+  /// # Returns
+  /// The number of payloads dispatched
+  ///
+  /// # Errors
+  /// Returns `Error::WebhookVerificationFailed` if the request lacks the
+  /// secret, and otherwise the errors of `dispatch`
+  pub async fn dispatch_request(
+    &self,
+    query: Option<&str>,
+    header: Option<&str>,
+    body: &[u8],
+  ) -> Result<usize> {
+    if let Some(verifier) = &self.verifier {
+      verifier.verify_request(query, header)?;
+    }
+    self.dispatch(body).await
+  }
+
+  /// Parses a webhook body and dispatches each payload, without verification.
+  ///
+  /// # Arguments
+  /// * `body` - Raw webhook body, a JSON object or an array of them
+  ///
+  /// # Returns
+  /// The number of payloads dispatched
+  ///
+  /// # Errors
+  /// Returns `Error::SerializationFailed` if the body isn't a webhook
+  /// payload, or the first error returned by a handler
+  pub async fn dispatch(&self, body: &[u8]) -> Result<usize> {
+    let payloads = parse(body)?;
+    let count = payloads.len();
+    for payload in payloads {
+      self.dispatch_payload(payload).await?;
+    }
+    Ok(count)
+  }
+
+  /// Calls the handlers registered for a payload's event.
+  ///
+  /// # Returns
+  /// The number of handlers called, 0 for an event already processed
+  ///
+  /// # Errors
+  /// Returns the first error returned by a handler; later handlers aren't
+  /// called. Errors of the dedupe store are returned too.
+  pub async fn dispatch_payload(&self, payload: WebhookPayload) -> Result<usize> {
+    let claimed = match &self.dedupe {
+      Some(dedupe) => dedupe.claim(&payload.id).await?,
+      None => true,
+    };
+    if !claimed {
+      return Ok(0);
+    }
+
+    let event_id = payload.id.clone();
+    let result = self.run_handlers(payload).await;
+    if let (Err(_), Some(dedupe)) = (&result, &self.dedupe) {
+      dedupe.release(&event_id).await?;
+    }
+    result
+  }
+
+  async fn run_handlers(&self, payload: WebhookPayload) -> Result<usize> {
+    let handlers: Vec<&Handler> = self
+      .any
+      .iter()
+      .chain(self.handlers.get(&payload.event).into_iter().flatten())
+      .collect();
+
+    for handler in &handlers {
+      handler(payload.clone()).await?;
+    }
+    Ok(handlers.len())
+  }
+}
+
+fn boxed<F, Fut>(handler: F) -> Handler
+where
+  F: Fn(WebhookPayload) -> Fut + Send + Sync + 'static,
+  Fut: Future<Output = Result<()>> + Send + 'static,
+{
+  Arc::new(move |payload| Box::pin(handler(payload)))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::error::Error;
+  use crate::webhooks::dedupe::MemoryDedupeStore;
+  use serde_json::json;
+  use std::sync::Mutex;
+
+  fn body() -> Vec<u8> {
+    json!([
+      { "id": "evt_1", "event": "bounce", "timestamp": 1700000000, "email_address": "ada@example.com" },
+      { "id": "evt_2", "event": "open", "timestamp": 1700000600, "email_address": "grace@example.com" },
+      { "id": "evt_3", "event": "spam", "timestamp": 1700000900, "email_address": "ada@example.com" }
+    ])
+    .to_string()
+    .into_bytes()
+  }
+
+  #[tokio::test]
+  async fn test_routes_payloads_to_handlers() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let record = |label: &'static str| {
+      let seen = seen.clone();
+      move |payload: WebhookPayload| {
+        let seen = seen.clone();
+        async move {
+          seen
+            .lock()
+            .unwrap()
+            .push(format!("{} {}", label, payload.id));
+          Ok(())
+        }
+      }
+    };
+
+    let dispatcher = WebhookDispatcher::new()
+      .on_bounced(record("bounced"))
+      .on_opened(record("opened"))
+      .on_any(record("any"))
+      .on(Event::Other("spam".to_string()), record("spam"));
+
+    assert_eq!(dispatcher.dispatch(&body()).await.unwrap(), 3);
+    assert_eq!(
+      *seen.lock().unwrap(),
+      vec![
+        "any evt_1",
+        "bounced evt_1",
+        "any evt_2",
+        "opened evt_2",
+        "any evt_3",
+        "spam evt_3"
+      ]
+    );
+  }
+
+  #[tokio::test]
+  async fn test_verification_and_handler_errors_stop_dispatch() {
+    let calls = Arc::new(Mutex::new(0));
+    let counted = calls.clone();
+    let dispatcher = WebhookDispatcher::new()
+      .with_verifier(WebhookVerifier::new("s3cret"))
+      .on_any(move |_| {
+        let counted = counted.clone();
+        async move {
+          *counted.lock().unwrap() += 1;
+          Err(Error::Unexpected("handler failed".to_string()))
+        }
+      });
+
+    let rejected = dispatcher
+      .dispatch_request(Some("token=guess"), None, &body())
+      .await;
+    assert!(matches!(rejected, Err(Error::WebhookVerificationFailed)));
+    assert_eq!(*calls.lock().unwrap(), 0);
+
+    let failed = dispatcher
+      .dispatch_request(Some("token=s3cret"), None, &body())
+      .await;
+    assert!(matches!(failed, Err(Error::Unexpected(_))));
+    assert_eq!(*calls.lock().unwrap(), 1);
+  }
+
+  #[tokio::test]
+  async fn test_dedupe_store_skips_redeliveries() {
+    let calls = Arc::new(Mutex::new(0));
+    let counted = calls.clone();
+    let dispatcher = WebhookDispatcher::new()
+      .with_dedupe_store(MemoryDedupeStore::new())
+      .on_bounced(move |_| {
+        let counted = counted.clone();
+        async move {
+          let mut calls = counted.lock().unwrap();
+          *calls += 1;
+          if *calls == 1 {
+            return Err(Error::Unexpected("handler failed".to_string()));
+          }
+          Ok(())
+        }
+      });
+
+    assert!(dispatcher.dispatch(&body()).await.is_err());
+    assert_eq!(dispatcher.dispatch(&body()).await.unwrap(), 3);
+    assert_eq!(dispatcher.dispatch(&body()).await.unwrap(), 3);
+    assert_eq!(*calls.lock().unwrap(), 2);
+  }
+}
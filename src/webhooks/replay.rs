@@ -0,0 +1,233 @@
+//! Replay of stored webhook payloads.
+//!
+//! When a webhook receiver was down, SendWithUs eventually gives up on
+//! redelivering its events. If the payloads were kept elsewhere, e.g. by a
+//! proxy or an exported log, `replay` feeds them through a
+//! `WebhookDispatcher` to backfill what was missed. Give the dispatcher a
+//! dedupe store so events that were already handled are skipped.
+
+use super::dispatcher::WebhookDispatcher;
+use super::parse;
+use crate::error::{Error, Result};
+use std::path::{Path, PathBuf};
+
+/// Outcome of a replay.
+#[derive(Debug, Default)]
+pub struct ReplaySummary {
+  /// Payloads dispatched successfully
+  pub payloads: usize,
+
+  /// Bodies that couldn't be parsed and payloads whose handlers failed
+  pub failures: Vec<ReplayFailure>,
+}
+
+impl ReplaySummary {
+  /// Returns `true` if every stored payload was dispatched.
+  pub fn is_success(&self) -> bool {
+    self.failures.is_empty()
+  }
+}
+
+/// A stored body or payload that failed to replay.
+#[derive(Debug)]
+pub struct ReplayFailure {
+  /// Where the payload was stored, e.g. `events.ndjson:12`
+  pub source: String,
+
+  /// Why it failed
+  pub error: Error,
+}
+
+/// Replays the webhook payloads stored at a path.
+///
+/// A file holds either one webhook body, a JSON object or array, or with a
+/// `.ndjson` or `.jsonl` extension one body per line. A directory is
+/// replayed file by file in name order, skipping files without a `.json`,
+/// `.ndjson`, or `.jsonl` extension. Failures don't stop the replay; they
+/// are collected in the summary so the rest of the backlog still gets
+/// processed.
+///
+/// # Arguments
+/// * `dispatcher` - Dispatcher routing the payloads to handlers
+/// * `path` - File or directory of stored payloads
+///
+/// # Returns
+/// How many payloads were dispatched, and which failed
+///
+/// # Errors
+/// Returns `Error::FileAccessFailed` if the path can't be read
+///
+/// # Examples
+///
+/// ```no_run
+/// use send_with_us::webhooks::dedupe::MemoryDedupeStore;
+/// use send_with_us::webhooks::dispatcher::WebhookDispatcher;
+/// use send_with_us::webhooks::replay;
+///
+/// # async fn run() -> send_with_us::Result<()> {
+/// let dispatcher = WebhookDispatcher::new()
+///   .with_dedupe_store(MemoryDedupeStore::new())
+///   .on_bounced(|payload| async move {
+///     println!("{} bounced", payload.email_address);
+///     Ok(())
+///   });
+///
+/// let summary = replay::replay(&dispatcher, "/var/log/webhooks").await?;
+/// for failure in &summary.failures {
+///   eprintln!("{}: {}", failure.source, failure.error);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn replay(
+  dispatcher: &WebhookDispatcher,
+  path: impl AsRef<Path>,
+) -> Result<ReplaySummary> {
+  let path = path.as_ref();
+  let mut summary = ReplaySummary::default();
+
+  if tokio::fs::metadata(path).await?.is_dir() {
+    for file in stored_files(path).await? {
+      replay_file(dispatcher, &file, &mut summary).await?;
+    }
+  } else {
+    replay_file(dispatcher, path, &mut summary).await?;
+  }
+
+  Ok(summary)
+}
+
+/// Returns the files of a directory holding payloads, in name order.
+async fn stored_files(dir: &Path) -> Result<Vec<PathBuf>> {
+  let mut entries = tokio::fs::read_dir(dir).await?;
+  let mut files = Vec::new();
+  while let Some(entry) = entries.next_entry().await? {
+    let path = entry.path();
+    let stored = path
+      .extension()
+      .is_some_and(|ext| ext == "json" || is_line_delimited(&path));
+    if stored && entry.file_type().await?.is_file() {
+      files.push(path);
+    }
+  }
+
+  files.sort();
+  Ok(files)
+}
+
+async fn replay_file(
+  dispatcher: &WebhookDispatcher,
+  path: &Path,
+  summary: &mut ReplaySummary,
+) -> Result<()> {
+  let contents = tokio::fs::read(path).await?;
+  let name = path.display().to_string();
+
+  if is_line_delimited(path) {
+    let lines = contents.split(|byte| *byte == b'\n').enumerate();
+    for (index, line) in lines {
+      if !line.trim_ascii().is_empty() {
+        let source = format!("{}:{}", name, index + 1);
+        replay_body(dispatcher, source, line, summary).await;
+      }
+    }
+  } else {
+    replay_body(dispatcher, name, &contents, summary).await;
+  }
+
+  Ok(())
+}
+
+async fn replay_body(
+  dispatcher: &WebhookDispatcher,
+  source: String,
+  body: &[u8],
+  summary: &mut ReplaySummary,
+) {
+  let payloads = match parse(body) {
+    Ok(payloads) => payloads,
+    Err(error) => {
+      summary.failures.push(ReplayFailure { source, error });
+      return;
+    }
+  };
+
+  for payload in payloads {
+    let id = payload.id.clone();
+    match dispatcher.dispatch_payload(payload).await {
+      Ok(_) => summary.payloads += 1,
+      Err(error) => summary.failures.push(ReplayFailure {
+        source: format!("{} ({})", source, id),
+        error,
+      }),
+    }
+  }
+}
+
+fn is_line_delimited(path: &Path) -> bool {
+  path
+    .extension()
+    .is_some_and(|ext| ext == "ndjson" || ext == "jsonl")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::webhooks::WebhookPayload;
+  use serde_json::json;
+  use std::sync::{Arc, Mutex};
+  use tempdir::TempDir;
+
+  fn payload(id: &str, event: &str) -> serde_json::Value {
+    json!({ "id": id, "event": event, "timestamp": 1700000000, "email_address": "ada@example.com" })
+  }
+
+  #[tokio::test]
+  async fn test_replays_files_and_directories() {
+    let dir = TempDir::new("replay").unwrap();
+    std::fs::write(
+      dir.path().join("a.json"),
+      json!([payload("evt_1", "sent"), payload("evt_2", "bounce")]).to_string(),
+    )
+    .unwrap();
+    std::fs::write(
+      dir.path().join("b.ndjson"),
+      format!(
+        "{}\n\nnot json\n{}\n",
+        payload("evt_3", "open"),
+        payload("evt_4", "click")
+      ),
+    )
+    .unwrap();
+    std::fs::write(dir.path().join("notes.txt"), "ignored").unwrap();
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let recorded = seen.clone();
+    let dispatcher = WebhookDispatcher::new().on_any(move |payload: WebhookPayload| {
+      let recorded = recorded.clone();
+      async move {
+        recorded.lock().unwrap().push(payload.id);
+        Ok(())
+      }
+    });
+
+    let summary = replay(&dispatcher, dir.path()).await.unwrap();
+    assert_eq!(summary.payloads, 4);
+    assert_eq!(
+      *seen.lock().unwrap(),
+      vec!["evt_1", "evt_2", "evt_3", "evt_4"]
+    );
+    assert_eq!(summary.failures.len(), 1);
+    assert!(summary.failures[0].source.ends_with("b.ndjson:3"));
+    assert!(!summary.is_success());
+
+    let summary = replay(&dispatcher, dir.path().join("a.json"))
+      .await
+      .unwrap();
+    assert_eq!(summary.payloads, 2);
+    assert!(summary.is_success());
+
+    let missing = replay(&dispatcher, dir.path().join("missing.json")).await;
+    assert!(matches!(missing, Err(Error::FileAccessFailed(_))));
+  }
+}